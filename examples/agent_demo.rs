@@ -1,6 +1,7 @@
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tokio::time::{sleep, Duration};
-use tracing::{info, warn};
+use tracing::info;
 use twitch_chat_scraper::{
     agents::{Agent, ScrapingAgent},
     browser::{BrowserManager, StealthConfig},
@@ -14,13 +15,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     // setting up browser manager with stealth
     let stealth_config = StealthConfig::default();
-    let proxy_list = Vec::new(); // No proxies for demo
     let browser_manager = Arc::new(
-        BrowserManager::new(1, stealth_config, proxy_list).await?
+        BrowserManager::new(1, stealth_config).await?
     );
 
     // creating a scraping agent
-    let mut agent = ScrapingAgent::new()?
+    let (chat_message_broadcaster, _) = broadcast::channel(1000);
+    let agent = ScrapingAgent::new((1000, 5000), chat_message_broadcaster)?
         .with_browser_manager(browser_manager);
 
     info!("Created agent with ID: {}", agent.id);
@@ -40,7 +41,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         while let Some(message) = message_stream.recv().await {
             message_count += 1;
             info!("Received message #{}: {} from {}: {}", 
-                  message_count, message.id, message.username, message.message);
+                  message_count, message.id, message.user.username, message.message.text);
             
             // stopping after 10 messages for demo
             if message_count >= 10 {
@@ -6,21 +6,24 @@ use tracing::{info, warn, error};
 use twitch_chat_scraper::agents::AgentOrchestrator;
 use twitch_chat_scraper::browser::BrowserManager;
 use twitch_chat_scraper::browser::stealth::StealthConfig;
-use twitch_chat_scraper::config::{Config, FileConfigManager, ConfigManager};
+use twitch_chat_scraper::config::{Config, FileConfigManager};
+use twitch_chat_scraper::webhooks::WebhookManager;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::init();
+async fn main() -> twitch_chat_scraper::Result<()> {
+    tracing_subscriber::fmt::init();
 
     info!("Starting Twitch Chat Scraper Orchestrator Demo");
 
     // creating config
-    let mut config = Config::default();
-    config.streamers = vec![
-        "shroud".to_string(),
-        "ninja".to_string(),
-        "pokimane".to_string(),
-    ];
+    let mut config = Config {
+        streamers: vec![
+            "shroud".to_string(),
+            "ninja".to_string(),
+            "pokimane".to_string(),
+        ],
+        ..Config::default()
+    };
     config.agents.max_concurrent = 3;
 
     // setting up stealth config
@@ -28,10 +31,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // creating browser manager
     info!("Creating browser manager...");
-    let browser_manager = match BrowserManager::new(
+    let browser_manager = match BrowserManager::with_binary_path(
         config.agents.max_concurrent,
         stealth_config,
-        vec![], // No proxies for demo
+        config.browser.binary_path.clone(),
     ).await {
         Ok(manager) => Arc::new(manager),
         Err(e) => {
@@ -42,8 +45,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // creating orchestrator
     info!("Creating agent orchestrator...");
+    let webhook_manager = WebhookManager::from_config(&config.monitoring)?;
     let mut orchestrator = AgentOrchestrator::new(config, browser_manager);
 
+    // forwarding chat to any webhooks set under [monitoring]
+    if webhook_manager.has_providers() {
+        orchestrator = orchestrator.with_webhook_manager(Arc::new(webhook_manager));
+    }
+
     // setting up config manager
     let config_path = std::path::PathBuf::from("config.toml");
     let config_manager = Arc::new(FileConfigManager::new(config_path));
@@ -112,8 +121,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // testing config update
     info!("Testing configuration update...");
-    let mut new_config = Config::default();
-    new_config.streamers = vec!["xqc".to_string(), "summit1g".to_string()];
+    let mut new_config = Config {
+        streamers: vec!["lirik".to_string(), "summit1g".to_string()],
+        ..Config::default()
+    };
     new_config.agents.max_concurrent = 2;
 
     if let Err(e) = orchestrator.update_config(new_config).await {
@@ -132,10 +143,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // stopping the orchestrator
     info!("Stopping orchestrator...");
-    if let Err(e) = orchestrator.stop().await {
-        error!("Failed to stop orchestrator: {}", e);
-    } else {
-        info!("Orchestrator stopped successfully");
+    match orchestrator.stop().await {
+        Ok(summary) => info!("Orchestrator stopped successfully: {}", summary),
+        Err(e) => error!("Failed to stop orchestrator: {}", e),
     }
 
     // stopping the monitor
@@ -1,4 +1,5 @@
-use twitch_chat_scraper::{DataProcessor, TwitchChatParser};
+use twitch_chat_scraper::parser::data_processor::DataProcessor;
+use twitch_chat_scraper::parser::html_parser::TwitchChatParser;
 
 const SAMPLE_TWITCH_HTML: &str = r#"
 <div class="Layout-sc-1xcs6mc-0 fHdBNk chat-line__no-background">
@@ -45,7 +46,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=== Twitch Chat Parser Example ===\n");
 
     // let's create a parser
-    let parser = TwitchChatParser::new()?;
+    let parser = TwitchChatParser::new(None)?;
     println!("✓ Created TwitchChatParser");
 
     let messages = parser.parse_chat_html(SAMPLE_TWITCH_HTML, "examplestreamer")?;
@@ -54,10 +54,7 @@ async fn main() -> Result<()> {
             MessageContent {
                 text: "Great gameplay!".to_string(),
                 emotes: vec![],
-                fragments: vec![MessageFragment {
-                    fragment_type: "text".to_string(),
-                    content: "Great gameplay!".to_string(),
-                }],
+                fragments: vec![MessageFragment::new("text", "Great gameplay!")],
             },
             StreamContext {
                 viewer_count: Some(15000),
@@ -78,14 +75,8 @@ async fn main() -> Result<()> {
                 text: "Nice shot!".to_string(),
                 emotes: vec!["Kappa".to_string()],
                 fragments: vec![
-                    MessageFragment {
-                        fragment_type: "text".to_string(),
-                        content: "Nice shot! ".to_string(),
-                    },
-                    MessageFragment {
-                        fragment_type: "emote".to_string(),
-                        content: "Kappa".to_string(),
-                    },
+                    MessageFragment::new("text", "Nice shot! "),
+                    MessageFragment::new("emote", "Kappa"),
                 ],
             },
             StreamContext {
@@ -106,10 +97,7 @@ async fn main() -> Result<()> {
             MessageContent {
                 text: "Hello from another stream!".to_string(),
                 emotes: vec![],
-                fragments: vec![MessageFragment {
-                    fragment_type: "text".to_string(),
-                    content: "Hello from another stream!".to_string(),
-                }],
+                fragments: vec![MessageFragment::new("text", "Hello from another stream!")],
             },
             StreamContext {
                 viewer_count: Some(8000),
@@ -135,12 +123,12 @@ async fn main() -> Result<()> {
 
     println!("\n📊 Storage Statistics:");
     println!("JSON Storage:");
-    println!("  - Total messages: {}", json_stats.total_messages);
+    println!("  - Total messages: {}", json_stats.total_stored);
     println!("  - Files created: {}", json_stats.files_created);
     println!("  - Disk usage: {} bytes", json_stats.disk_usage);
 
     println!("CSV Storage:");
-    println!("  - Total messages: {}", csv_stats.total_messages);
+    println!("  - Total messages: {}", csv_stats.total_stored);
     println!("  - Files created: {}", csv_stats.files_created);
     println!("  - Disk usage: {} bytes", csv_stats.disk_usage);
 
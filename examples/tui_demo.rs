@@ -1,5 +1,5 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -12,13 +12,12 @@ use std::{
     io,
     time::{Duration, Instant},
 };
-use tokio::time::sleep;
 use uuid::Uuid;
 
 use twitch_chat_scraper::tui::{
     Action, AgentInfo, Dashboard, LogEntry, LogLevel, SystemMetrics, TUIMonitor,
 };
-use twitch_chat_scraper::agents::{AgentMetrics, AgentStatus};
+use twitch_chat_scraper::agents::AgentStatus;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -32,19 +31,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let mut dashboard = Dashboard::new();
     
     // adding sample log entries
-    dashboard.add_log(LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: LogLevel::Info,
-        message: "TUI Demo started".to_string(),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, "TUI Demo started", None));
     
-    dashboard.add_log(LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: LogLevel::Info,
-        message: "Initializing agents...".to_string(),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, "Initializing agents...", None));
 
     // running the app
     let res = run_app(&mut terminal, &mut dashboard).await;
@@ -77,59 +66,39 @@ async fn run_app<B: ratatui::backend::Backend>(
     let sample_agents = vec![
         AgentInfo {
             id: Uuid::new_v4(),
-            streamer: "shroud".to_string(),
+            channel: "shroud".to_string(),
             status: AgentStatus::Running,
-            metrics: AgentMetrics {
-                messages_scraped: 1250,
-                uptime: Duration::from_secs(3600),
-                error_count: 2,
-                last_message_time: Some(chrono::Utc::now() - chrono::Duration::seconds(30)),
-                network_latency: Duration::from_millis(45),
-                memory_usage: 128 * 1024 * 1024, // 128 MB
-                status: AgentStatus::Running,
-            },
+            uptime: Duration::from_secs(3600),
+            messages_per_second: 0.35,
+            error_count: 2,
+            alert_id: None,
         },
         AgentInfo {
             id: Uuid::new_v4(),
-            streamer: "ninja".to_string(),
+            channel: "ninja".to_string(),
             status: AgentStatus::Running,
-            metrics: AgentMetrics {
-                messages_scraped: 890,
-                uptime: Duration::from_secs(2400),
-                error_count: 0,
-                last_message_time: Some(chrono::Utc::now() - chrono::Duration::seconds(5)),
-                network_latency: Duration::from_millis(32),
-                memory_usage: 95 * 1024 * 1024, // 95 MB
-                status: AgentStatus::Running,
-            },
+            uptime: Duration::from_secs(2400),
+            messages_per_second: 0.37,
+            error_count: 0,
+            alert_id: None,
         },
         AgentInfo {
             id: Uuid::new_v4(),
-            streamer: "pokimane".to_string(),
+            channel: "pokimane".to_string(),
             status: AgentStatus::Error("Connection timeout".to_string()),
-            metrics: AgentMetrics {
-                messages_scraped: 456,
-                uptime: Duration::from_secs(1800),
-                error_count: 5,
-                last_message_time: Some(chrono::Utc::now() - chrono::Duration::minutes(10)),
-                network_latency: Duration::from_millis(120),
-                memory_usage: 87 * 1024 * 1024, // 87 MB
-                status: AgentStatus::Error("Connection timeout".to_string()),
-            },
+            uptime: Duration::from_secs(1800),
+            messages_per_second: 0.25,
+            error_count: 5,
+            alert_id: None,
         },
         AgentInfo {
             id: Uuid::new_v4(),
-            streamer: "xqc".to_string(),
+            channel: "xqc".to_string(),
             status: AgentStatus::Starting,
-            metrics: AgentMetrics {
-                messages_scraped: 0,
-                uptime: Duration::from_secs(30),
-                error_count: 0,
-                last_message_time: None,
-                network_latency: Duration::from_millis(0),
-                memory_usage: 45 * 1024 * 1024, // 45 MB
-                status: AgentStatus::Starting,
-            },
+            uptime: Duration::from_secs(30),
+            messages_per_second: 0.0,
+            error_count: 0,
+            alert_id: None,
         },
     ];
 
@@ -139,41 +108,8 @@ async fn run_app<B: ratatui::backend::Backend>(
         // handling events
         if event::poll(Duration::from_millis(250))? {
             if let Event::Key(key) = event::read()? {
-                match dashboard.handle_input(Event::Key(key)) {
-                    Ok(Action::Quit) => return Ok(()),
-                    Ok(Action::Refresh) => {
-                        dashboard.add_log(LogEntry {
-                            timestamp: chrono::Utc::now(),
-                            level: LogLevel::Info,
-                            message: "Manual refresh triggered".to_string(),
-                            agent_id: None,
-                        });
-                    }
-                    Ok(Action::StopAgent(agent_id)) => {
-                        dashboard.add_log(LogEntry {
-                            timestamp: chrono::Utc::now(),
-                            level: LogLevel::Warning,
-                            message: format!("Stop agent requested for {}", agent_id),
-                            agent_id: Some(agent_id),
-                        });
-                    }
-                    Ok(Action::RestartAgent(agent_id)) => {
-                        dashboard.add_log(LogEntry {
-                            timestamp: chrono::Utc::now(),
-                            level: LogLevel::Info,
-                            message: format!("Restart agent requested for {}", agent_id),
-                            agent_id: Some(agent_id),
-                        });
-                    }
-                    Ok(Action::ShowHelp) => {
-                        dashboard.add_log(LogEntry {
-                            timestamp: chrono::Utc::now(),
-                            level: LogLevel::Debug,
-                            message: "Help popup shown".to_string(),
-                            agent_id: None,
-                        });
-                    }
-                    _ => {}
+                if let Ok(Action::Quit) = dashboard.handle_input(Event::Key(key)) {
+                    return Ok(());
                 }
             }
         }
@@ -200,7 +136,7 @@ async fn run_app<B: ratatui::backend::Backend>(
             dashboard.update_metrics(system_metrics);
             
             // Occasionally add log entries
-            if rand::random::<u8>() % 20 == 0 {
+            if rand::random::<u8>().is_multiple_of(20) {
                 let log_levels = [LogLevel::Info, LogLevel::Warning, LogLevel::Error, LogLevel::Debug];
                 let messages = [
                     "New chat message processed",
@@ -211,16 +147,16 @@ async fn run_app<B: ratatui::backend::Backend>(
                     "Configuration updated",
                 ];
                 
-                dashboard.add_log(LogEntry {
-                    timestamp: chrono::Utc::now(),
-                    level: log_levels[rand::random::<usize>() % log_levels.len()],
-                    message: messages[rand::random::<usize>() % messages.len()].to_string(),
-                    agent_id: if rand::random::<bool>() { 
-                        Some(sample_agents[rand::random::<usize>() % sample_agents.len()].id) 
-                    } else { 
-                        None 
-                    },
-                });
+                let agent_id = if rand::random::<bool>() {
+                    Some(sample_agents[rand::random::<usize>() % sample_agents.len()].id)
+                } else {
+                    None
+                };
+                dashboard.add_log(LogEntry::new(
+                    log_levels[rand::random::<usize>() % log_levels.len()],
+                    messages[rand::random::<usize>() % messages.len()],
+                    agent_id,
+                ));
             }
             
             last_tick = Instant::now();
@@ -1,21 +1,33 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock};
 use tokio::time::{interval, sleep, Instant};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Level};
 use uuid::Uuid;
 use rand::Rng;
 
+use crate::agents::agent_log::AgentLogFile;
+use crate::agents::batch::{BatchPublisher, BatchSettings};
 use crate::browser::{BrowserManager, BrowserInstanceId};
 use crate::error::{Result, ScrapingError};
 use crate::parser::chat_message::ChatMessage;
+use crate::parser::data_processor::{EmoteSet, Redactor};
 use crate::parser::html_parser::TwitchChatParser;
 
 pub type AgentId = Uuid;
 
+/// UUIDv5 namespace for agent ids derived from streamer names
+const AGENT_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6c1f_2b4e_93a7_4d0e_b5c8_1e2f_7a9d_3c60);
+
+/// The same UUIDv5 for a streamer every time, so an agent keeps its id across restarts
+pub fn stable_agent_id(streamer: &str) -> AgentId {
+    Uuid::new_v5(&AGENT_ID_NAMESPACE, streamer.to_lowercase().as_bytes())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AgentStatus {
     Idle,
@@ -23,6 +35,7 @@ pub enum AgentStatus {
     Running,
     Stopping,
     Stopped,
+    Paused,
     Error(String),
 }
 
@@ -35,10 +48,79 @@ pub struct AgentMetrics {
     pub network_latency: Duration,
     pub memory_usage: u64,
     pub status: AgentStatus,
+    #[serde(default)]
+    pub proxy: Option<String>, // the proxy this agent's browser is pinned to
 }
 
 pub type MessageStream = tokio::sync::mpsc::Receiver<ChatMessage>;
 
+/// Routes parsed messages to the storage queue and the broadcaster (directly or
+/// batched), holding anything not yet sent until it is flushed
+pub(crate) struct MessageDispatcher {
+    message_broadcaster: broadcast::Sender<ChatMessage>,
+    batch_publisher: Option<BatchPublisher>,
+    storage_sender: Option<mpsc::Sender<ChatMessage>>,
+    redactor: Option<Arc<Redactor>>,
+    emotes: Option<watch::Receiver<Arc<EmoteSet>>>,
+    paused: bool,
+}
+
+impl MessageDispatcher {
+    /// Send one message, with third-party emotes tagged and then scrubbed
+    /// first when those are on
+    pub(crate) async fn dispatch(&mut self, mut chat_message: ChatMessage) {
+        // nothing goes out while the agent is paused
+        if self.paused {
+            debug!("Dropping message from {} while paused", chat_message.streamer);
+            return;
+        }
+
+        if let Some(ref emotes) = self.emotes {
+            emotes.borrow().enrich_message(&mut chat_message);
+        }
+        if let Some(ref redactor) = self.redactor {
+            redactor.redact_message(&mut chat_message);
+        }
+
+        // Storage gets every message; this waits while the queue is full
+        if let Some(ref storage_sender) = self.storage_sender {
+            if storage_sender.send(chat_message.clone()).await.is_err() {
+                warn!("Storage queue closed, message not stored");
+            }
+        }
+
+        // Queue into the current batch when batching is on
+        if let Some(ref mut publisher) = self.batch_publisher {
+            publisher.push(chat_message);
+            return;
+        }
+
+        // Send message (non-blocking)
+        if self.message_broadcaster.send(chat_message).is_err() {
+            // This is not an error condition - just means no one is listening
+            warn!("No receivers for message broadcast, continuing");
+        }
+    }
+
+    /// Send a partial batch whose window has elapsed
+    pub(crate) fn publish_due(&mut self) {
+        if let Some(ref mut publisher) = self.batch_publisher {
+            publisher.publish_due();
+        }
+    }
+
+    /// Send everything still held back, used when the agent stops
+    pub(crate) fn flush(&mut self) {
+        if let Some(ref mut publisher) = self.batch_publisher {
+            publisher.publish_all();
+        }
+    }
+
+    fn batch_window(&self) -> Option<Duration> {
+        self.batch_publisher.as_ref().map(|publisher| publisher.window())
+    }
+}
+
 #[async_trait]
 pub trait Agent {
     async fn start(&mut self, streamer: &str) -> Result<()>;
@@ -60,7 +142,15 @@ pub struct ScrapingAgent {
     pub parser: TwitchChatParser,
     pub shutdown_signal: Option<mpsc::Sender<()>>,
     pub monitoring_task: Option<tokio::task::JoinHandle<()>>,
+    pub agent_log: Option<Arc<AgentLogFile>>,
+    log_output: Option<(PathBuf, u64)>, // output dir and rotation size for the agent log
+    redactor: Option<Arc<Redactor>>,
+    emotes: Option<watch::Receiver<Arc<EmoteSet>>>,
+    batching: Option<(BatchSettings, broadcast::Sender<Vec<ChatMessage>>)>,
+    storage_sender: Option<mpsc::Sender<ChatMessage>>,
+    dispatcher: Option<Arc<Mutex<MessageDispatcher>>>,
     delay_range: (u64, u64),
+    proxy: Option<String>, // pinned once the browser starts, asked for again after a restart
 }
 
 impl ScrapingAgent {
@@ -68,7 +158,7 @@ impl ScrapingAgent {
         delay_range: (u64, u64),
         chat_message_broadcaster: broadcast::Sender<ChatMessage>,
     ) -> Result<Self> {
-        let parser = TwitchChatParser::new()
+        let parser = TwitchChatParser::new(None)
             .map_err(|e| ScrapingError::AgentError(format!("Failed to create parser: {}", e)))?;
 
         Ok(Self {
@@ -83,6 +173,7 @@ impl ScrapingAgent {
                 network_latency: Duration::from_millis(0),
                 memory_usage: 0,
                 status: AgentStatus::Idle,
+                proxy: None,
             })),
             browser_manager: None,
             browser_instance_id: None,
@@ -91,19 +182,127 @@ impl ScrapingAgent {
             parser,
             shutdown_signal: None,
             monitoring_task: None,
+            agent_log: None,
+            log_output: None,
+            redactor: None,
+            emotes: None,
+            batching: None,
+            storage_sender: None,
+            dispatcher: None,
             delay_range,
+            proxy: None,
         })
     }
 
+    /// Replace the random id, e.g. with `stable_agent_id`
+    pub fn with_id(mut self, id: AgentId) -> Self {
+        self.id = id;
+        self
+    }
+
     pub fn with_browser_manager(mut self, browser_manager: Arc<BrowserManager>) -> Self {
         self.browser_manager = Some(browser_manager);
         self
     }
 
+    /// Ask for this proxy when the browser starts, e.g. the one a restarted agent
+    /// had. Another is picked if it has since gone bad.
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// The proxy this agent is pinned to, if any
+    pub fn proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// Tee this agent's events into `output_dir/<streamer>/agent.log` once it starts
+    pub fn with_log_output(mut self, output_dir: PathBuf, rotation_size: u64) -> Self {
+        self.log_output = Some((output_dir, rotation_size));
+        self
+    }
+
+    /// Scrub message text with this redactor before messages are broadcast
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(Arc::new(redactor));
+        self
+    }
+
+    /// Parse chat with this parser instead of the one built on the default selectors
+    pub fn with_parser(mut self, parser: TwitchChatParser) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    /// Tag BTTV/FFZ/7TV emotes in messages before they are broadcast, using
+    /// whichever set `emotes` holds when the message arrives
+    pub fn with_emotes(mut self, emotes: watch::Receiver<Arc<EmoteSet>>) -> Self {
+        self.emotes = Some(emotes);
+        self
+    }
+
+    /// Coalesce messages into batches on `batch_broadcaster` instead of
+    /// broadcasting them one at a time
+    pub fn with_batching(
+        mut self,
+        settings: BatchSettings,
+        batch_broadcaster: broadcast::Sender<Vec<ChatMessage>>,
+    ) -> Self {
+        self.batching = Some((settings, batch_broadcaster));
+        self
+    }
+
+    /// Also send every message into the bounded storage queue, waiting for room
+    /// when it is full so a slow writer throttles extraction instead of losing data
+    pub fn with_storage_sender(mut self, storage_sender: mpsc::Sender<ChatMessage>) -> Self {
+        self.storage_sender = Some(storage_sender);
+        self
+    }
+
+    /// Dispatcher shared by the monitoring task and `stop`, created on first use
+    pub(crate) fn dispatcher(&mut self) -> Result<Arc<Mutex<MessageDispatcher>>> {
+        if let Some(ref dispatcher) = self.dispatcher {
+            return Ok(dispatcher.clone());
+        }
+
+        let message_broadcaster = self
+            .message_broadcaster
+            .clone()
+            .ok_or_else(|| ScrapingError::AgentError("No message broadcaster available".to_string()))?;
+        let dispatcher = Arc::new(Mutex::new(MessageDispatcher {
+            message_broadcaster,
+            batch_publisher: self
+                .batching
+                .clone()
+                .map(|(settings, sender)| BatchPublisher::new(settings, sender)),
+            storage_sender: self.storage_sender.clone(),
+            redactor: self.redactor.clone(),
+            emotes: self.emotes.clone(),
+            paused: false,
+        }));
+        self.dispatcher = Some(dispatcher.clone());
+        Ok(dispatcher)
+    }
+
+    /// Write an event to the per-agent log, if enabled
+    pub fn log_event(&self, level: Level, message: &str) {
+        if let Some(ref agent_log) = self.agent_log {
+            agent_log.log(level, message);
+        }
+    }
+
     pub async fn initialize_browser(&mut self) -> Result<()> {
         if let Some(ref browser_manager) = self.browser_manager {
-            let instance_id = browser_manager.create_browser_instance().await?;
+            let instance_id = browser_manager.create_browser_instance_with_proxy(self.proxy.as_deref()).await?;
             self.browser_instance_id = Some(instance_id);
+
+            // the pin sticks for the agent's lifetime, even if the asked-for proxy was swapped
+            self.proxy = browser_manager
+                .get_browser_instance(instance_id)
+                .await
+                .and_then(|instance| instance.proxy);
+            self.metrics.write().await.proxy = self.proxy.clone();
             tracing::info!(
                 "Initialized browser instance {} for agent {}",
                 instance_id,
@@ -150,6 +349,11 @@ impl ScrapingAgent {
     }
 
     pub async fn set_status(&self, status: AgentStatus) {
+        match status {
+            AgentStatus::Error(ref e) => self.log_event(Level::ERROR, &format!("status changed to Error: {}", e)),
+            ref other => self.log_event(Level::INFO, &format!("status changed to {:?}", other)),
+        }
+
         let mut current_status = self.status.write().await;
         *current_status = status.clone();
 
@@ -157,6 +361,62 @@ impl ScrapingAgent {
         metrics.status = status;
     }
 
+    /// How long the agent has gone without a message since it was (re)started
+    pub async fn idle_duration(&self) -> Duration {
+        let since_start = match self.start_time {
+            Some(start_time) => start_time.elapsed(),
+            None => return Duration::from_secs(0),
+        };
+
+        let metrics = self.metrics.read().await;
+        match metrics.last_message_time {
+            Some(last_message_time) => (Utc::now() - last_message_time)
+                .to_std()
+                .unwrap_or_default()
+                .min(since_start),
+            None => since_start,
+        }
+    }
+
+    /// Park the agent on a dead channel, releasing its browser instance
+    pub async fn go_idle(&mut self) -> Result<()> {
+        info!("Agent {} going idle, releasing browser instance", self.id);
+        self.log_event(Level::INFO, "no messages within idle timeout, releasing browser");
+
+        self.stop_message_monitoring().await?;
+        self.cleanup_browser().await?;
+
+        self.set_status(AgentStatus::Idle).await;
+        self.start_time = None;
+        Ok(())
+    }
+
+    /// Stop extracting and sending messages while keeping the browser on the channel
+    pub async fn pause(&mut self) -> Result<()> {
+        let status = self.get_status().await;
+        if status != AgentStatus::Running {
+            return Err(ScrapingError::AgentError(format!("Agent {} can't be paused while {}", self.id, status)).into());
+        }
+
+        info!("Pausing agent {}", self.id);
+        self.dispatcher()?.lock().await.paused = true;
+        self.set_status(AgentStatus::Paused).await;
+        Ok(())
+    }
+
+    /// Pick extraction back up on a paused agent
+    pub async fn resume(&mut self) -> Result<()> {
+        let status = self.get_status().await;
+        if status != AgentStatus::Paused {
+            return Err(ScrapingError::AgentError(format!("Agent {} can't be resumed while {}", self.id, status)).into());
+        }
+
+        info!("Resuming agent {}", self.id);
+        self.dispatcher()?.lock().await.paused = false;
+        self.set_status(AgentStatus::Running).await;
+        Ok(())
+    }
+
     /// Start the real-time message extraction loop
     async fn start_message_monitoring(&mut self, streamer: String) -> Result<()> {
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel(1);
@@ -166,20 +426,25 @@ impl ScrapingAgent {
             .browser_manager
             .clone()
             .ok_or_else(|| ScrapingError::AgentError("No browser manager available".to_string()))?;
-        let browser_instance_id = self
+        let mut browser_instance_id = self
             .browser_instance_id
             .ok_or_else(|| ScrapingError::AgentError("No browser instance available".to_string()))?;
-        let message_broadcaster = self
-            .message_broadcaster
-            .clone()
-            .ok_or_else(|| ScrapingError::AgentError("No message broadcaster available".to_string()))?;
+        let mut replaced_rx = browser_manager.subscribe_replacements();
+        let mut replacements_open = true;
+        let dispatcher = self.dispatcher()?;
+        let batch_window = dispatcher.lock().await.batch_window();
 
-        let parser = TwitchChatParser::new()
-            .map_err(|e| ScrapingError::AgentError(format!("Failed to create parser: {}", e)))?;
+        let parser = self.parser.clone();
         let status = self.status.clone();
         let metrics = self.metrics.clone();
         let agent_id = self.id;
         let delay_range = self.delay_range;
+        let agent_log = self.agent_log.clone();
+        let log_event = move |level: Level, message: String| {
+            if let Some(ref agent_log) = agent_log {
+                agent_log.log(level, &message);
+            }
+        };
 
         // Spawn the monitoring task
         let monitoring_task = tokio::spawn(async move {
@@ -189,6 +454,12 @@ impl ScrapingAgent {
             );
 
             let mut extraction_interval = interval(Duration::from_millis(1000));    // checking for new messages every 1000ms
+            // flushing partial batches once their window is up
+            let mut batch_flush_interval = interval(
+                batch_window
+                    .map(|window| window.max(Duration::from_millis(1)))
+                    .unwrap_or(Duration::from_secs(1)),
+            );
             let mut last_html_hash = String::new();
             let mut consecutive_errors = 0;
             const MAX_CONSECUTIVE_ERRORS: u32 = 10;
@@ -205,9 +476,56 @@ impl ScrapingAgent {
                 tokio::select! {
                     _ = shutdown_rx.recv() => {
                         info!("Received shutdown signal for agent {}", agent_id);
+                        // one last pass so chat since the previous tick isn't lost
+                        if let Some(browser_instance) = browser_manager.get_browser_instance(browser_instance_id).await {
+                            if let Err(e) = Self::extract_and_process_messages(
+                                &browser_instance,
+                                &parser,
+                                &streamer,
+                                &mut last_html_hash,
+                                &dispatcher,
+                                &metrics
+                            ).await {
+                                debug!("Final extraction for agent {} failed: {}", agent_id, e);
+                            }
+                        }
                         break;
                     }
+                    _ = batch_flush_interval.tick(), if batch_window.is_some() => {
+                        dispatcher.lock().await.publish_due();
+                    }
+                    replaced = replaced_rx.recv(), if replacements_open => {
+                        if matches!(replaced, Err(broadcast::error::RecvError::Closed)) {
+                            replacements_open = false;
+                            continue;
+                        }
+                        // the pool swapped out a crashed instance, ours if the chain leads somewhere new
+                        let current = browser_manager.resolve_instance_id(browser_instance_id).await;
+                        if current == browser_instance_id {
+                            continue;
+                        }
+                        warn!("Browser instance {} for agent {} crashed, moving to {}", browser_instance_id, agent_id, current);
+                        log_event(Level::WARN, format!("browser instance crashed, re-navigating on {}", current));
+                        browser_instance_id = current;
+                        last_html_hash.clear();
+
+                        let navigated = match browser_manager.get_browser_instance(current).await {
+                            Some(browser_instance) => browser_instance.navigate_to_twitch_stream(&streamer).await,
+                            None => Err(ScrapingError::BrowserError("Replacement browser instance not found".to_string()).into()),
+                        };
+                        if let Err(e) = navigated {
+                            error!("Agent {} could not re-navigate after a browser crash: {}", agent_id, e);
+                            log_event(Level::ERROR, format!("re-navigation failed, stopping monitoring: {}", e));
+                            *status.write().await = AgentStatus::Error(format!("Browser error: {}", e));
+                            break;
+                        }
+                    }
                     _ = extraction_interval.tick() => {
+                        // the page stays open while paused, it just isn't read
+                        if *status.read().await == AgentStatus::Paused {
+                            continue;
+                        }
+
                         // Get browser instance and extract messages
                         if let Some(browser_instance) = browser_manager.get_browser_instance(browser_instance_id).await {
                             match Self::extract_and_process_messages(
@@ -215,7 +533,7 @@ impl ScrapingAgent {
                                 &parser,
                                 &streamer,
                                 &mut last_html_hash,
-                                &message_broadcaster,
+                                &dispatcher,
                                 &metrics
                             ).await {
                                 Ok(message_count) => {
@@ -228,14 +546,16 @@ impl ScrapingAgent {
                                     consecutive_errors += 1;
                                     warn!("Error extracting messages for agent {}: {} (consecutive errors: {})",
                                           agent_id, e, consecutive_errors);
+                                    log_event(Level::WARN, format!("extraction error: {} (consecutive errors: {})", e, consecutive_errors));
 
                                     if let Some(ScrapingError::BrowserError(_)) = e.downcast_ref::<ScrapingError>() {
                                         if let Some(browser_instance) = browser_manager.get_browser_instance(browser_instance_id).await {
-                                            if let Some(_proxy) = browser_instance.proxy.clone() {
-                                                // browser_manager.report_bad_proxy(proxy).await;
+                                            if let Some(proxy) = browser_instance.proxy.clone() {
+                                                browser_manager.report_bad_proxy(proxy).await;
                                             }
                                         }
                                         error!("Browser error for agent {}, setting to error state", agent_id);
+                                        log_event(Level::ERROR, format!("browser error, stopping monitoring: {}", e));
                                         let mut status_guard = status.write().await;
                                         *status_guard = AgentStatus::Error(format!("Browser error: {}", e));
                                         break; // Break from monitoring loop, orchestrator will restart
@@ -249,6 +569,7 @@ impl ScrapingAgent {
                                     // If too many consecutive errors, set agent to error state
                                     if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
                                         error!("Too many consecutive errors for agent {}, setting to error state", agent_id);
+                                        log_event(Level::ERROR, format!("too many consecutive errors, stopping monitoring: {}", e));
                                         let mut status_guard = status.write().await;
                                         *status_guard = AgentStatus::Error(format!("Too many consecutive errors: {}", e));
                                         break;
@@ -261,6 +582,7 @@ impl ScrapingAgent {
                             }
                         } else {
                             error!("Browser instance not found for agent {}", agent_id);
+                            log_event(Level::ERROR, "browser instance not found, stopping monitoring".to_string());
                             let mut status_guard = status.write().await;
                             *status_guard = AgentStatus::Error("Browser instance not found".to_string());
                             break;
@@ -269,6 +591,9 @@ impl ScrapingAgent {
                 }
             }
 
+            // don't drop messages still waiting in a batch
+            dispatcher.lock().await.flush();
+
             info!("Message monitoring stopped for agent {}", agent_id);
         });
 
@@ -282,7 +607,7 @@ impl ScrapingAgent {
         parser: &TwitchChatParser,
         streamer: &str,
         last_html_hash: &mut String,
-        message_broadcaster: &broadcast::Sender<ChatMessage>,
+        dispatcher: &Mutex<MessageDispatcher>,
         metrics: &Arc<RwLock<AgentMetrics>>,
     ) -> Result<u64> {
         let start_time = Instant::now();
@@ -301,18 +626,13 @@ impl ScrapingAgent {
         let parsed_messages = parser.parse_chat_html(&html, streamer)?;
         let message_count = parsed_messages.len() as u64;
 
-        // sending parsed messages directly
+        // sending parsed messages on, scrubbed first if redaction is on
+        let mut dispatcher = dispatcher.lock().await;
         for chat_message in parsed_messages {
-            // Send message (non-blocking)
-            if let Err(e) = message_broadcaster.send(chat_message) {
-                match e {
-                    broadcast::error::SendError(_) => {
-                        warn!("No receivers for message broadcast, continuing");
-                        // This is not an error condition - just means no one is listening
-                    }
-                }
-            }
+            dispatcher.dispatch(chat_message).await;
         }
+        dispatcher.publish_due();
+        drop(dispatcher);
 
         // updating metrics
         if message_count > 0 {
@@ -339,6 +659,11 @@ impl ScrapingAgent {
             }
         }
 
+        // the task flushes on exit, but it may have ended early or never run
+        if let Some(ref dispatcher) = self.dispatcher {
+            dispatcher.lock().await.flush();
+        }
+
         Ok(())
     }
 }
@@ -348,6 +673,11 @@ impl Agent for ScrapingAgent {
     async fn start(&mut self, streamer: &str) -> Result<()> {
         info!("Starting agent {} for streamer {}", self.id, streamer);
 
+        if let Some((ref output_dir, rotation_size)) = self.log_output {
+            self.agent_log = Some(Arc::new(AgentLogFile::new(output_dir, streamer, self.id, rotation_size)));
+        }
+        self.log_event(Level::INFO, &format!("starting on streamer {}", streamer));
+
         self.set_status(AgentStatus::Starting).await;
         self.streamer = Some(streamer.to_string());
         self.start_time = Some(Instant::now());
@@ -397,8 +727,9 @@ impl Agent for ScrapingAgent {
                             "Failed to navigate to Twitch stream for agent {}: {}",
                             self.id, e
                         );
-                        if let Some(_proxy) = browser_instance.proxy.clone() {
-                            // browser_manager.report_bad_proxy(proxy).await;
+                        self.log_event(Level::ERROR, &format!("navigation failed: {}", e));
+                        if let Some(proxy) = browser_instance.proxy.clone() {
+                            browser_manager.report_bad_proxy(proxy).await;
                         }
                         self.set_status(AgentStatus::Error(format!("Navigation failed: {}", e)))
                             .await;
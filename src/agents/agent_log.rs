@@ -0,0 +1,90 @@
+use chrono::Utc;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{warn, Level};
+
+use crate::agents::AgentId;
+use crate::error::{Result, ScrapingError};
+
+/// Dedicated log file for a single agent, written next to its message files
+/// at `output_dir/<streamer>/agent.log` and rotated by size.
+pub struct AgentLogFile {
+    agent_id: AgentId,
+    path: PathBuf,
+    rotation_size: u64,
+    write_lock: Mutex<()>,
+}
+
+impl AgentLogFile {
+    pub fn new(output_dir: &Path, streamer: &str, agent_id: AgentId, rotation_size: u64) -> Self {
+        Self {
+            agent_id,
+            path: output_dir.join(streamer).join("agent.log"),
+            rotation_size,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path the current log is moved to when it fills up
+    pub fn rotated_path(&self) -> PathBuf {
+        self.path.with_extension("log.1")
+    }
+
+    /// Append a line to the agent log, warning through tracing if the write fails
+    pub fn log(&self, level: Level, message: &str) {
+        if let Err(e) = self.write_line(level, message) {
+            warn!("Failed to write agent log for {}: {}", self.agent_id, e);
+        }
+    }
+
+    pub fn write_line(&self, level: Level, message: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to create agent log directory: {}", e)))?;
+        }
+
+        self.rotate_if_needed()?;
+
+        let line = format!(
+            "{} {:<5} agent={} {}\n",
+            Utc::now().to_rfc3339(),
+            level,
+            self.agent_id,
+            message
+        );
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to open agent log: {}", e)))?;
+        file.write_all(line.as_bytes())
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to write agent log: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Move the log aside once it reaches the rotation size, keeping one old file.
+    /// A rotation size of 0 ("unlimited") lets the log grow.
+    fn rotate_if_needed(&self) -> Result<()> {
+        let size = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(_) => return Ok(()),
+        };
+
+        if self.rotation_size > 0 && size >= self.rotation_size {
+            fs::rename(&self.path, self.rotated_path())
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to rotate agent log: {}", e)))?;
+        }
+
+        Ok(())
+    }
+}
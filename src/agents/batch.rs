@@ -0,0 +1,121 @@
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::parser::chat_message::ChatMessage;
+
+/// How an agent coalesces messages before broadcasting them
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSettings {
+    /// Send as soon as this many messages are pending
+    pub max_size: usize,
+    /// Send whatever is pending once the oldest message has waited this long
+    pub window: Duration,
+}
+
+/// Collects chat messages into batches by count or time window
+pub struct MessageBatcher {
+    settings: BatchSettings,
+    pending: Vec<ChatMessage>,
+    first_pending_at: Option<Instant>,
+}
+
+impl MessageBatcher {
+    pub fn new(settings: BatchSettings) -> Self {
+        Self {
+            pending: Vec::with_capacity(settings.max_size),
+            settings,
+            first_pending_at: None,
+        }
+    }
+
+    /// Queue a message, returning a full batch when the size limit is hit
+    pub fn push(&mut self, message: ChatMessage) -> Option<Vec<ChatMessage>> {
+        if self.pending.is_empty() {
+            self.first_pending_at = Some(Instant::now());
+        }
+        self.pending.push(message);
+
+        if self.pending.len() >= self.settings.max_size {
+            return self.flush();
+        }
+        None
+    }
+
+    /// Take the pending batch if its window has elapsed
+    pub fn take_if_due(&mut self) -> Option<Vec<ChatMessage>> {
+        match self.first_pending_at {
+            Some(first) if first.elapsed() >= self.settings.window => self.flush(),
+            _ => None,
+        }
+    }
+
+    /// Take whatever is pending, regardless of size or window
+    pub fn flush(&mut self) -> Option<Vec<ChatMessage>> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        self.first_pending_at = None;
+        Some(std::mem::replace(
+            &mut self.pending,
+            Vec::with_capacity(self.settings.max_size),
+        ))
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn window(&self) -> Duration {
+        self.settings.window
+    }
+}
+
+/// Publishes batches from a `MessageBatcher` onto a broadcast channel
+pub struct BatchPublisher {
+    batcher: MessageBatcher,
+    sender: broadcast::Sender<Vec<ChatMessage>>,
+}
+
+impl BatchPublisher {
+    pub fn new(settings: BatchSettings, sender: broadcast::Sender<Vec<ChatMessage>>) -> Self {
+        Self {
+            batcher: MessageBatcher::new(settings),
+            sender,
+        }
+    }
+
+    /// Queue a message, sending a batch if it filled up
+    pub fn push(&mut self, message: ChatMessage) {
+        if let Some(batch) = self.batcher.push(message) {
+            self.send(batch);
+        }
+    }
+
+    /// Send the pending batch if its window elapsed
+    pub fn publish_due(&mut self) {
+        if let Some(batch) = self.batcher.take_if_due() {
+            self.send(batch);
+        }
+    }
+
+    /// Send everything still pending, used on shutdown
+    pub fn publish_all(&mut self) {
+        if let Some(batch) = self.batcher.flush() {
+            self.send(batch);
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.batcher.window()
+    }
+
+    fn send(&self, batch: Vec<ChatMessage>) {
+        let batch_len = batch.len();
+        if self.sender.send(batch).is_err() {
+            // nobody listening isn't an error
+            debug!("No receivers for batch of {} messages", batch_len);
+        }
+    }
+}
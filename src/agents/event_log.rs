@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use crate::agents::{AgentId, AgentMessage};
+
+/// Events kept per agent; older ones are dropped first
+pub const MAX_EVENTS_PER_AGENT: usize = 100;
+/// Agents whose history is kept, including ones that have since been stopped
+const MAX_TRACKED_AGENTS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentEventKind {
+    Status,
+    Error,
+    Alert,
+    RateLimited,
+}
+
+/// One entry in an agent's recent history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentEvent {
+    pub timestamp: DateTime<Utc>,
+    pub kind: AgentEventKind,
+    pub message: String,
+}
+
+/// Ring buffers of recent status changes, errors and alerts per agent
+#[derive(Default)]
+pub struct AgentEventLog {
+    events: HashMap<AgentId, VecDeque<AgentEvent>>,
+}
+
+impl AgentEventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the parts of an orchestrator message worth debugging with;
+    /// chat and metrics traffic is ignored
+    pub fn record(&mut self, message: &AgentMessage) {
+        let (agent_id, kind, text) = match message {
+            AgentMessage::StatusUpdate { agent_id, status } => {
+                (*agent_id, AgentEventKind::Status, format!("status changed to {}", status))
+            }
+            AgentMessage::Error { agent_id, error } => (*agent_id, AgentEventKind::Error, error.clone()),
+            AgentMessage::ResourceAlert { agent_id, alert } => (*agent_id, AgentEventKind::Alert, alert.clone()),
+            AgentMessage::RateLimited { agent_id, streamer, pause } => (
+                *agent_id,
+                AgentEventKind::RateLimited,
+                format!("rate limited on {}, pausing for {}s", streamer, pause.as_secs()),
+            ),
+            AgentMessage::StreamerFailed { agent_id, streamer, restart_attempts } => (
+                *agent_id,
+                AgentEventKind::Alert,
+                format!("gave up on {} after {} restarts", streamer, restart_attempts),
+            ),
+            AgentMessage::ChatMessage { .. } | AgentMessage::MetricsUpdate { .. } => return,
+        };
+
+        // system-wide alerts use the nil id and don't belong to any agent
+        if agent_id.is_nil() {
+            return;
+        }
+
+        self.push(agent_id, AgentEvent {
+            timestamp: Utc::now(),
+            kind,
+            message: text,
+        });
+    }
+
+    pub fn push(&mut self, agent_id: AgentId, event: AgentEvent) {
+        if !self.events.contains_key(&agent_id) && self.events.len() >= MAX_TRACKED_AGENTS {
+            self.evict_stalest();
+        }
+
+        let events = self.events.entry(agent_id).or_default();
+        events.push_back(event);
+        if events.len() > MAX_EVENTS_PER_AGENT {
+            events.pop_front();
+        }
+    }
+
+    /// Up to `limit` most recent events for an agent, oldest first
+    pub fn recent(&self, agent_id: AgentId, limit: usize) -> Option<Vec<AgentEvent>> {
+        let events = self.events.get(&agent_id)?;
+        let skip = events.len().saturating_sub(limit);
+        Some(events.iter().skip(skip).cloned().collect())
+    }
+
+    fn evict_stalest(&mut self) {
+        let stalest = self.events
+            .iter()
+            .min_by_key(|(_, events)| events.back().map(|event| event.timestamp))
+            .map(|(agent_id, _)| *agent_id);
+        if let Some(agent_id) = stalest {
+            self.events.remove(&agent_id);
+        }
+    }
+}
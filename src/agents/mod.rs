@@ -1,4 +1,7 @@
 pub mod agent;
+pub mod agent_log;
+pub mod batch;
+pub mod event_log;
 pub mod orchestrator;
 
 #[cfg(test)]
@@ -7,8 +10,11 @@ mod tests;
 #[cfg(test)]
 mod orchestrator_test;
 
-pub use agent::{Agent, ScrapingAgent, AgentStatus, AgentMetrics, AgentId};
+pub use agent::{Agent, ScrapingAgent, AgentStatus, AgentMetrics, AgentId, stable_agent_id};
+pub use agent_log::AgentLogFile;
+pub use batch::{BatchPublisher, BatchSettings, MessageBatcher};
+pub use event_log::{AgentEvent, AgentEventKind, AgentEventLog};
 pub use crate::parser::chat_message::ChatMessage;
 pub use orchestrator::{
-    AgentOrchestrator, SystemMetrics, AgentAssignment, OrchestratorStatus, AgentMessage
+    AgentOrchestrator, SystemMetrics, AgentAssignment, FailedStreamer, OrchestratorStatus, AgentMessage, ShutdownSummary
 };
\ No newline at end of file
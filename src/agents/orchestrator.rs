@@ -1,17 +1,28 @@
-use crate::error::{Result, ScrapingError};
+use crate::error::{RecoveryStrategy, Result, ScrapingError};
 use crate::parser::chat_message::ChatMessage;
+use crate::parser::data_processor::{EmoteSet, Redactor};
+use crate::parser::emotes::EmoteCache;
+use crate::parser::html_parser::TwitchChatParser;
+use crate::parser::quality_metrics::{ChannelQuality, ChannelQualityTracker};
+use crate::storage::{StorageManager, SummaryWriter};
 use rand::Rng;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use sysinfo::{CpuExt, System, SystemExt};
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
 use tokio::time::{interval, sleep, Instant};
 use tracing::{debug, error, info, warn};
 
-use crate::agents::{Agent, AgentId, AgentMetrics, AgentStatus, ScrapingAgent};
+use crate::agents::{
+    Agent, AgentEvent, AgentEventKind, AgentEventLog, AgentId, AgentMetrics, AgentStatus,
+    BatchSettings, ScrapingAgent, stable_agent_id,
+};
+use crate::agents::event_log::MAX_EVENTS_PER_AGENT;
 use crate::browser::BrowserManager;
-use crate::config::{Config, ConfigManager};
+use crate::config::{AgentConfig, Config, ConfigManager, FileConfigManager};
+use crate::webhooks::WebhookManager;
 
 /// System resource metrics for dynamic scaling decisions
 /// System resource metrics for dynamic scaling decisions
@@ -22,6 +33,8 @@ pub struct SystemMetrics {
     pub memory_total: u64,
     pub active_agents: usize,
     pub total_messages_scraped: u64,
+    pub per_streamer_messages: HashMap<String, u64>, // messages scraped by the current agents, per channel
+    pub storage_queue_depth: usize, // messages waiting in the storage ingest queue
     #[serde(with = "humantime_serde")]
     pub timestamp: SystemTime,
 }
@@ -37,18 +50,145 @@ pub struct AgentAssignment {
     pub retry_attempts: u32,
     #[serde(with = "humantime_serde")]
     pub last_failure: Option<SystemTime>,
+    #[serde(with = "humantime_serde")]
+    pub idle_since: Option<SystemTime>, // set while the channel is offline/silent
+    pub proxy: Option<String>, // pinned for the agent's lifetime and asked for again on restart
+}
+
+/// A streamer whose agent kept failing after `agents.retry_attempts` restarts
+/// and was abandoned. It isn't scraped again until it's removed from and re-added
+/// to the streamer list.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FailedStreamer {
+    pub streamer: String,
+    pub restart_attempts: u32,
+    pub last_error: String,
+    #[serde(with = "humantime_serde")]
+    pub failed_at: SystemTime,
+}
+
+/// An agent set up by `prepare_agent`. Starting it takes seconds, so callers
+/// that share the orchestrator behind a lock start it with the lock released
+/// and hand it back to `install_agent`.
+struct PendingAgent {
+    agent: ScrapingAgent,
+    streamer: String,
+    priority: u8,
+    retry_attempts: u32,
+    delay_range: (u64, u64),
+    message_broadcaster: broadcast::Sender<AgentMessage>,
+    while_running: bool, // prepared after `start`, so a `stop` in the meantime leaves it orphaned
+}
+
+impl PendingAgent {
+    /// Wait out the staggered startup delay, then start the agent, retrying
+    /// transient failures with backoff
+    async fn start(&mut self) -> Result<()> {
+        let agent_id = self.agent.id;
+        let startup_delay = rand::thread_rng().gen_range(100..=2000); // 0.1 to 2 seconds
+        info!(
+            "Agent {} delaying for {}ms before startup",
+            agent_id, startup_delay
+        );
+        sleep(Duration::from_millis(startup_delay)).await;
+
+        if let Err(e) = AgentOrchestrator::start_with_retries(
+            &mut self.agent,
+            &self.streamer,
+            self.retry_attempts,
+            self.delay_range,
+            &self.message_broadcaster,
+        ).await {
+            let _ = self.message_broadcaster.send(AgentMessage::Error {
+                agent_id,
+                error: format!("failed to start for {}: {}", self.streamer, e),
+            });
+            return Err(e);
+        }
+
+        info!("Agent {} started successfully for streamer {}", agent_id, self.streamer);
+        let _ = self.message_broadcaster.send(AgentMessage::StatusUpdate {
+            agent_id,
+            status: AgentStatus::Running,
+        });
+        Ok(())
+    }
+}
+
+/// A restart begun by `begin_restart`. The old agent is out of the orchestrator
+/// but still has to be stopped and its replacement started, neither of which
+/// needs the orchestrator lock.
+struct PendingRestart {
+    agent_id: AgentId,
+    old_agent: Option<ScrapingAgent>,
+    assignment: AgentAssignment,
+    after_failure: bool,
+    replacement: PendingAgent,
+}
+
+impl PendingRestart {
+    /// Stop the old agent, then start its replacement
+    async fn run(&mut self) -> Result<()> {
+        if let Some(mut old_agent) = self.old_agent.take() {
+            old_agent.stop().await?;
+            info!("Stopped agent {} for streamer {}", self.agent_id, self.assignment.streamer);
+            let _ = self.replacement.message_broadcaster.send(AgentMessage::StatusUpdate {
+                agent_id: self.agent_id,
+                status: AgentStatus::Stopped,
+            });
+        }
+        self.replacement.start().await
+    }
 }
 
 /// Orchestrator status and statistics
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct OrchestratorStatus {
     pub active_agents: usize,
+    pub idle_agents: usize,
     pub total_agents_spawned: u64,
     pub system_metrics: SystemMetrics,
     pub agent_assignments: Vec<AgentAssignment>,
     pub error_count: u32,
     #[serde(with = "humantime_serde")]
     pub uptime: Duration,
+    pub failed_streamers: Vec<FailedStreamer>, // abandoned after running out of restarts
+}
+
+/// What `stop` wound down, reported to the operator on exit
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct ShutdownSummary {
+    pub agents_stopped: usize,
+    pub agents_failed: usize,      // agents whose stop returned an error
+    pub messages_flushed: u64,     // persisted by the storage writer over the whole run
+    pub messages_unwritten: u64,   // failed writes plus anything left queued when the writer timed out
+    pub files_closed: usize,
+}
+
+impl std::fmt::Display for ShutdownSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.messages_unwritten == 0 {
+            write!(f, "All {} messages persisted", self.messages_flushed)?;
+        } else {
+            write!(
+                f,
+                "{} messages persisted, {} could not be written",
+                self.messages_flushed, self.messages_unwritten
+            )?;
+        }
+        write!(f, ", {} files closed, {} agents stopped", self.files_closed, self.agents_stopped)?;
+        if self.agents_failed > 0 {
+            write!(f, ", {} agents failed to stop cleanly", self.agents_failed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Running totals from the storage writer, read back on shutdown
+#[derive(Debug, Default)]
+struct StorageCounters {
+    written: AtomicU64,
+    failed: AtomicU64,
 }
 
 /// Inter-agent communication message types
@@ -64,7 +204,7 @@ pub enum AgentMessage {
     },
     ChatMessage {
         agent_id: AgentId,
-        message: ChatMessage,
+        message: Box<ChatMessage>, // boxed, it dwarfs every other variant
     },
     ResourceAlert {
         agent_id: AgentId,
@@ -74,26 +214,65 @@ pub enum AgentMessage {
         agent_id: AgentId,
         error: String,
     },
+    RateLimited {
+        agent_id: AgentId,
+        streamer: String,
+        pause: Duration,
+    },
+    StreamerFailed {
+        agent_id: AgentId,
+        streamer: String,
+        restart_attempts: u32,
+    },
 }
 
 pub struct AgentOrchestrator {
     // Core state
     agents: Arc<RwLock<HashMap<AgentId, ScrapingAgent>>>,
     pub agent_assignments: Arc<RwLock<HashMap<AgentId, AgentAssignment>>>,
+    failed_streamers: Arc<RwLock<HashMap<String, FailedStreamer>>>,
+    starting_streamers: HashSet<String>, // agents prepared but not installed yet, started with the lock released
+    streamer_rates: HashMap<String, f64>, // last measured messages/minute, kept after an agent stops
+    message_rates: MessageRateWindow,
     browser_manager: Arc<BrowserManager>,
 
     // Configuration and limits
     config: Arc<RwLock<Config>>,
     max_concurrent: usize,
+    monitor_interval: Duration, // `monitoring.monitor_interval`, read once at startup
+    scale_interval: Duration,   // `monitoring.scale_interval`, read once at startup
+    channel_url: String,        // base of the channel pages probed for being live
 
     // Communication channels
     message_broadcaster: broadcast::Sender<AgentMessage>,
     chat_message_broadcaster: broadcast::Sender<ChatMessage>,
+    chat_batch_broadcaster: broadcast::Sender<Vec<ChatMessage>>,
     shutdown_signal: Option<broadcast::Sender<()>>,
 
+    // Errored agents found by the recovery task, restarted by `drive_recovery`
+    recovery_tx: mpsc::Sender<AgentId>,
+    recovery_rx: Option<mpsc::Receiver<AgentId>>,
+
+    // Configs reloaded by the config watcher, applied by `drive_config_reloads`
+    config_reload_tx: watch::Sender<Option<Config>>,
+    config_reload_rx: Option<watch::Receiver<Option<Config>>>,
+
+    // Storage ingest
+    storage_manager: Option<Arc<dyn StorageManager + Send + Sync>>,
+    storage_sender: Option<mpsc::Sender<ChatMessage>>,
+    storage_counters: Arc<StorageCounters>,
+
+    // Lifecycle notifications
+    webhook_manager: Option<Arc<WebhookManager>>,
+
+    // Third-party emote lists, created the first time an agent needs them
+    emote_cache: Option<Arc<EmoteCache>>,
+
     // System monitoring
     system: Arc<RwLock<System>>,
     system_metrics: Arc<RwLock<SystemMetrics>>,
+    channel_quality: Arc<RwLock<HashMap<String, ChannelQuality>>>,
+    agent_events: Arc<RwLock<AgentEventLog>>,
 
     // Statistics
     total_agents_spawned: Arc<RwLock<u64>>,
@@ -102,16 +281,276 @@ pub struct AgentOrchestrator {
 
     // Background tasks
     monitoring_task: Option<tokio::task::JoinHandle<()>>,
-    scaling_task: Option<tokio::task::JoinHandle<()>>,
     config_watcher_task: Option<tokio::task::JoinHandle<()>>,
     agent_recovery_task: Option<tokio::task::JoinHandle<()>>,
+    idle_monitor_task: Option<tokio::task::JoinHandle<()>>,
+    channel_quality_task: Option<tokio::task::JoinHandle<()>>,
+    summary_task: Option<tokio::task::JoinHandle<()>>,
+    storage_task: Option<tokio::task::JoinHandle<()>>,
+    agent_event_task: Option<tokio::task::JoinHandle<()>>,
+    error_alert_task: Option<tokio::task::JoinHandle<()>>,
+    lifecycle_task: Option<tokio::task::JoinHandle<()>>,
+    browser_health_task: Option<tokio::task::JoinHandle<()>>,
+    webhook_forward_task: Option<tokio::task::JoinHandle<()>>,
+    webhook_flush_task: Option<tokio::task::JoinHandle<()>>,
+}
+
+/// Delay before retrying a failed start: a random pick from `delay_range`, doubled
+/// for every failed attempt and capped at a minute
+pub(crate) fn startup_backoff(attempt: u32, delay_range: (u64, u64)) -> Duration {
+    let base = rand::thread_rng().gen_range(delay_range.0..=delay_range.1.max(delay_range.0));
+    let factor = 1u64 << attempt.saturating_sub(1).min(6);
+    Duration::from_millis(base.saturating_mul(factor).min(60_000))
+}
+
+/// How long after its last failure a streamer's agent may be restarted again:
+/// 5s after the first restart, doubling with every restart since, capped at 5 minutes
+pub(crate) fn restart_backoff(retry_attempts: u32) -> Duration {
+    const BASE: Duration = Duration::from_secs(5);
+    const MAX: Duration = Duration::from_secs(300);
+    if retry_attempts == 0 {
+        return Duration::ZERO;
+    }
+    BASE.saturating_mul(1 << (retry_attempts - 1).min(16)).min(MAX)
+}
+
+/// The failure record for `assignment` once it has used up `max_restarts`,
+/// or None while it may still be restarted
+pub(crate) fn give_up_on(assignment: &AgentAssignment, max_restarts: u32, last_error: &str) -> Option<FailedStreamer> {
+    if assignment.retry_attempts < max_restarts {
+        return None;
+    }
+    Some(FailedStreamer {
+        streamer: assignment.streamer.clone(),
+        restart_attempts: assignment.retry_attempts,
+        last_error: last_error.to_string(),
+        failed_at: SystemTime::now(),
+    })
+}
+
+impl AgentAssignment {
+    /// Whether the restart backoff since `last_failure` has run out
+    pub fn restart_due(&self, now: SystemTime) -> bool {
+        match self.last_failure {
+            Some(last_failure) => now
+                .duration_since(last_failure)
+                .is_ok_and(|elapsed| elapsed >= restart_backoff(self.retry_attempts)),
+            None => true,
+        }
+    }
+}
+
+/// Whether a running agent that has scraped messages before has now gone
+/// `stale_after` without one. Agents that never got a message are on quiet
+/// channels and left to the idle monitor.
+pub(crate) fn is_stalled(metrics: &AgentMetrics, stale_after: Duration, now: chrono::DateTime<chrono::Utc>) -> bool {
+    if stale_after.is_zero() || metrics.status != AgentStatus::Running {
+        return false;
+    }
+    let Some(last_message_time) = metrics.last_message_time else {
+        return false;
+    };
+    (now - last_message_time).to_std().is_ok_and(|silent| silent >= stale_after)
+}
+
+/// Errored agents waiting for `drive_recovery`. The recovery task finds any
+/// that don't fit again on its next check.
+const RECOVERY_QUEUE_SIZE: usize = 64;
+
+/// Channel pages probed to tell whether a stream is live
+const TWITCH_CHANNEL_URL: &str = "https://www.twitch.tv";
+
+/// Agents younger than this haven't scraped long enough for their rate to mean much
+const MIN_RATE_UPTIME: Duration = Duration::from_secs(300);
+
+/// Span message rates are measured over, so the scaler follows what a channel
+/// is doing now rather than its average since the agent started
+const RATE_WINDOW: Duration = Duration::from_secs(300);
+
+/// One agent's throughput as the scaler sees it
+#[derive(Debug, Clone)]
+pub(crate) struct AgentLoad {
+    pub(crate) agent_id: AgentId,
+    pub(crate) streamer: String,
+    pub(crate) priority: u8,
+    pub(crate) messages_per_minute: f64,
+    pub(crate) uptime: Duration,
+}
+
+impl AgentLoad {
+    pub(crate) fn new(assignment: &AgentAssignment, metrics: &AgentMetrics, messages_per_minute: f64) -> Self {
+        Self {
+            agent_id: assignment.agent_id,
+            streamer: assignment.streamer.clone(),
+            priority: assignment.priority,
+            messages_per_minute,
+            uptime: metrics.uptime,
+        }
+    }
+
+    fn is_measured(&self) -> bool {
+        self.uptime >= MIN_RATE_UPTIME
+    }
+}
+
+/// Message counts the scaler has seen per agent over the last `RATE_WINDOW`
+#[derive(Debug, Default)]
+pub(crate) struct MessageRateWindow {
+    samples: HashMap<AgentId, VecDeque<(Instant, u64)>>,
+}
+
+impl MessageRateWindow {
+    /// Record the agent's message count and return its messages per minute over
+    /// the window. Until a second sample comes in that's the lifetime average.
+    pub(crate) fn record(&mut self, agent_id: AgentId, metrics: &AgentMetrics, now: Instant) -> f64 {
+        let samples = self.samples.entry(agent_id).or_default();
+        samples.push_back((now, metrics.messages_scraped));
+        // keep the newest sample at or past the window's start
+        while samples.len() > 2 && now.duration_since(samples[1].0) >= RATE_WINDOW {
+            samples.pop_front();
+        }
+
+        let (oldest_at, oldest_count) = samples[0];
+        let window = now.duration_since(oldest_at);
+        if window.is_zero() {
+            let minutes = metrics.uptime.as_secs_f64() / 60.0;
+            return if minutes > 0.0 { metrics.messages_scraped as f64 / minutes } else { 0.0 };
+        }
+        metrics.messages_scraped.saturating_sub(oldest_count) as f64 / (window.as_secs_f64() / 60.0)
+    }
+
+    /// Forget agents that are gone
+    pub(crate) fn retain(&mut self, agents: &HashSet<AgentId>) {
+        self.samples.retain(|agent_id, _| agents.contains(agent_id));
+    }
+}
+
+/// Moves agent slots toward busy channels: hot channels are kept when resources
+/// run short, and cold ones give their slot to streamers waiting for one
+pub(crate) struct ThroughputPolicy {
+    hot_rate: f64,
+    cold_rate: f64,
+}
+
+impl ThroughputPolicy {
+    pub(crate) fn new(hot_rate: f64, cold_rate: f64) -> Self {
+        Self { hot_rate, cold_rate }
+    }
+
+    pub(crate) fn from_config(agents: &AgentConfig) -> Self {
+        Self::new(agents.hot_message_rate, agents.cold_message_rate)
+    }
+
+    fn is_hot(&self, load: &AgentLoad) -> bool {
+        self.hot_rate > 0.0 && load.is_measured() && load.messages_per_minute >= self.hot_rate
+    }
+
+    /// Agent to stop when CPU or memory run short: the quietest one that isn't
+    /// hot, the lowest priority one on a tie
+    pub(crate) fn shed_candidate(&self, loads: &[AgentLoad]) -> Option<AgentId> {
+        loads
+            .iter()
+            .filter(|load| !self.is_hot(load))
+            .min_by(|a, b| {
+                a.messages_per_minute
+                    .total_cmp(&b.messages_per_minute)
+                    .then(b.priority.cmp(&a.priority))
+            })
+            .map(|load| load.agent_id)
+    }
+
+    /// Cold agent to stop so `waiting_rate`'s streamer can have its slot. A
+    /// streamer that hasn't been measured yet (None) gets a chance; one known
+    /// to be quieter than the candidate doesn't.
+    pub(crate) fn reclaim_candidate(&self, loads: &[AgentLoad], waiting_rate: Option<f64>) -> Option<AgentId> {
+        loads
+            .iter()
+            .filter(|load| load.is_measured() && load.messages_per_minute < self.cold_rate)
+            .filter(|load| waiting_rate.is_none_or(|rate| rate > load.messages_per_minute))
+            .min_by(|a, b| a.messages_per_minute.total_cmp(&b.messages_per_minute))
+            .map(|load| load.agent_id)
+    }
+
+    /// Streamers waiting for an agent, busiest known rate first, then in configured
+    /// order. Unmeasured streamers rank just above channels known to be cold.
+    pub(crate) fn spawn_order(&self, mut waiting: Vec<(String, u8)>, known_rates: &HashMap<String, f64>) -> Vec<(String, u8)> {
+        waiting.sort_by(|(a, a_priority), (b, b_priority)| {
+            let a_rate = known_rates.get(a).copied().unwrap_or(self.cold_rate);
+            let b_rate = known_rates.get(b).copied().unwrap_or(self.cold_rate);
+            b_rate.total_cmp(&a_rate).then(a_priority.cmp(b_priority))
+        });
+        waiting
+    }
+}
+
+/// Decides when an agent's errors are worth an alert. The cooldown is kept per
+/// streamer so the agent replacing a restarted one doesn't alert straight away.
+pub(crate) struct ErrorAlertPolicy {
+    max_errors: u32,
+    max_errors_per_minute: f64,
+    cooldown: Duration,
+    last_alert: HashMap<String, Instant>,
+}
+
+impl ErrorAlertPolicy {
+    pub(crate) fn new(max_errors: u32, max_errors_per_minute: f64, cooldown: Duration) -> Self {
+        Self {
+            max_errors,
+            max_errors_per_minute,
+            cooldown,
+            last_alert: HashMap::new(),
+        }
+    }
+
+    /// Pick up threshold changes from a config reload, keeping the cooldowns
+    pub(crate) fn update_thresholds(&mut self, agents: &AgentConfig) {
+        self.max_errors = agents.error_alert_threshold;
+        self.max_errors_per_minute = agents.error_rate_alert_threshold;
+        self.cooldown = FileConfigManager::parse_time_to_duration(&agents.error_alert_cooldown)
+            .unwrap_or(self.cooldown);
+    }
+
+    /// Why this agent should be alerted on, or None if it's under both thresholds
+    /// or its streamer was alerted on recently
+    pub(crate) fn check(&mut self, streamer: &str, error_count: u32, uptime: Duration) -> Option<String> {
+        if let Some(alerted_at) = self.last_alert.get(streamer) {
+            if alerted_at.elapsed() < self.cooldown {
+                return None;
+            }
+        }
+
+        // rates over the first minute are too noisy to act on
+        let per_minute = if uptime >= Duration::from_secs(60) {
+            error_count as f64 * 60.0 / uptime.as_secs_f64()
+        } else {
+            0.0
+        };
+
+        let reason = if self.max_errors > 0 && error_count >= self.max_errors {
+            format!("{} errors", error_count)
+        } else if self.max_errors_per_minute > 0.0 && per_minute >= self.max_errors_per_minute {
+            format!("{:.1} errors/min", per_minute)
+        } else {
+            return None;
+        };
+
+        self.last_alert.insert(streamer.to_string(), Instant::now());
+        Some(reason)
+    }
 }
 
 impl AgentOrchestrator {
     pub fn new(config: Config, browser_manager: Arc<BrowserManager>) -> Self {
         let max_concurrent = config.agents.max_concurrent;
+        let monitor_interval = FileConfigManager::parse_time_to_duration(&config.monitoring.monitor_interval)
+            .unwrap_or(Duration::from_secs(5));
+        let scale_interval = FileConfigManager::parse_time_to_duration(&config.monitoring.scale_interval)
+            .unwrap_or(Duration::from_secs(30));
         let (message_broadcaster, _) = broadcast::channel(10000);
         let (chat_message_broadcaster, _) = broadcast::channel(10000);
+        let (chat_batch_broadcaster, _) = broadcast::channel(1000);
+        let (recovery_tx, recovery_rx) = mpsc::channel(RECOVERY_QUEUE_SIZE);
+        let (config_reload_tx, config_reload_rx) = watch::channel(None);
 
         let mut system = System::new_all();
         system.refresh_all();
@@ -122,30 +561,81 @@ impl AgentOrchestrator {
             memory_total: system.total_memory(),
             active_agents: 0,
             total_messages_scraped: 0,
+            per_streamer_messages: HashMap::new(),
+            storage_queue_depth: 0,
             timestamp: SystemTime::now(),
         };
 
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
             agent_assignments: Arc::new(RwLock::new(HashMap::new())),
+            failed_streamers: Arc::new(RwLock::new(HashMap::new())),
+            starting_streamers: HashSet::new(),
+            streamer_rates: HashMap::new(),
+            message_rates: MessageRateWindow::default(),
             browser_manager,
             config: Arc::new(RwLock::new(config)),
             max_concurrent,
+            monitor_interval,
+            scale_interval,
+            channel_url: TWITCH_CHANNEL_URL.to_string(),
             message_broadcaster,
             chat_message_broadcaster,
+            chat_batch_broadcaster,
             shutdown_signal: None,
+            recovery_tx,
+            recovery_rx: Some(recovery_rx),
+            config_reload_tx,
+            config_reload_rx: Some(config_reload_rx),
+            storage_manager: None,
+            storage_sender: None,
+            storage_counters: Arc::new(StorageCounters::default()),
+            webhook_manager: None,
+            emote_cache: None,
             system: Arc::new(RwLock::new(system)),
             system_metrics: Arc::new(RwLock::new(initial_metrics)),
+            channel_quality: Arc::new(RwLock::new(HashMap::new())),
+            agent_events: Arc::new(RwLock::new(AgentEventLog::new())),
             total_agents_spawned: Arc::new(RwLock::new(0)),
             error_count: Arc::new(RwLock::new(0)),
-                    start_time: Instant::now(),
+            start_time: Instant::now(),
             monitoring_task: None,
-            scaling_task: None,
             config_watcher_task: None,
             agent_recovery_task: None,
+            idle_monitor_task: None,
+            channel_quality_task: None,
+            summary_task: None,
+            storage_task: None,
+            agent_event_task: None,
+            error_alert_task: None,
+            lifecycle_task: None,
+            browser_health_task: None,
+            webhook_forward_task: None,
+            webhook_flush_task: None,
         }
     }
 
+    /// Persist scraped messages through this storage manager, fed by a bounded
+    /// queue sized by `output.ingest_capacity`
+    pub fn with_storage_manager(mut self, storage_manager: Arc<dyn StorageManager + Send + Sync>) -> Self {
+        self.storage_manager = Some(storage_manager);
+        self
+    }
+
+    /// Forward scraped chat through these webhooks, and post startup, shutdown
+    /// and agent error alerts when `monitoring.lifecycle_notifications` is on
+    pub fn with_webhook_manager(mut self, webhook_manager: Arc<WebhookManager>) -> Self {
+        self.webhook_manager = Some(webhook_manager);
+        self
+    }
+
+    /// Probe channels for being live under this base instead of twitch.tv
+    #[cfg(test)]
+    pub(crate) fn with_channel_url(mut self, channel_url: &str) -> Self {
+        self.channel_url = channel_url.to_string();
+        self
+    }
+
     /// Start the orchestrator with all background tasks
     pub async fn start(
         &mut self,
@@ -155,63 +645,163 @@ impl AgentOrchestrator {
 
         let (shutdown_tx, shutdown_rx1) = broadcast::channel(1);
         let shutdown_rx2 = shutdown_tx.subscribe();
-        let shutdown_rx3 = shutdown_tx.subscribe();
 
         // Store the broadcast sender for shutdown signaling
         self.shutdown_signal = Some(shutdown_tx.clone());
 
+        // Start storage writer before anything that reads the queue depth
+        if self.storage_manager.is_some() {
+            self.start_storage_writer().await?;
+        }
+
+        // Start per-agent event history before anything can emit events
+        self.start_agent_event_tracking(shutdown_tx.subscribe()).await?;
+
         // Start system monitoring task
         self.start_system_monitoring(shutdown_rx1).await?;
 
-        // Start dynamic scaling task
-        self.start_dynamic_scaling(shutdown_rx2).await?;
-
         // Start configuration watcher task
-        self.start_config_watcher(config_manager, shutdown_rx3)
+        self.start_config_watcher(config_manager, shutdown_rx2)
             .await?;
 
         // Start agent recovery task
         self.start_agent_recovery(shutdown_tx.subscribe()).await?;
 
+        // Start idle channel monitor task
+        self.start_idle_monitor(shutdown_tx.subscribe()).await?;
+
+        // Start error threshold alerting task
+        self.start_error_alerting(shutdown_tx.subscribe()).await?;
+
+        // Start channel quality tracking task
+        self.start_channel_quality_tracking(shutdown_tx.subscribe()).await?;
+
+        // Start browser health checks
+        self.start_browser_health_checks(shutdown_tx.subscribe()).await?;
+
+        // Start forwarding chat to webhooks if any are configured
+        self.start_webhook_forwarding(shutdown_tx.subscribe()).await?;
+
+        // Start agent error notifications if enabled
+        if self.config.read().await.monitoring.lifecycle_notifications {
+            self.start_lifecycle_notifications(shutdown_tx.subscribe()).await?;
+        }
+
+        // Start hourly summary writer if enabled
+        if self.config.read().await.output.summaries {
+            self.start_summary_writer(shutdown_tx.subscribe()).await?;
+        }
+
         // Distribute agents across configured streamers
         self.distribute_agents().await?;
 
+        let channels = self.agent_assignments.read().await.len();
+        self.notify_lifecycle("info", "Scraper started", &format!("Scraper started monitoring {} channels", channels)).await;
+
         info!("Agent Orchestrator started successfully");
         Ok(())
     }
 
-    /// Stop the orchestrator and all agents
-    pub async fn stop(&mut self) -> Result<()> {
+    /// Stop the orchestrator and all agents, draining and flushing storage
+    pub async fn stop(&mut self) -> Result<ShutdownSummary> {
         info!("Stopping Agent Orchestrator");
 
+        let channels = self.agent_assignments.read().await.len();
+        self.notify_lifecycle("info", "Shutting down", &format!("Shutting down, stopping {} agents", channels)).await;
+
         // Send shutdown signal to all background tasks
         if let Some(shutdown_tx) = self.shutdown_signal.take() {
             let _ = shutdown_tx.send(());
         }
 
         // Stop all agents
-        self.stop_all_agents().await?;
+        let (agents_stopped, agents_failed) = self.stop_all_agents().await?;
+
+        // Agents are gone, closing the queue lets the writer drain and exit
+        let queued = self.storage_sender.take().map(|sender| Self::queue_depth(&sender)).unwrap_or(0) as u64;
+        let progress_before = self.storage_progress();
+        let mut messages_left_queued = 0;
+        if let Some(task) = self.storage_task.take() {
+            if tokio::time::timeout(Duration::from_secs(10), task).await.is_err() {
+                messages_left_queued = queued.saturating_sub(self.storage_progress() - progress_before);
+                warn!("Storage writer did not drain within 10s, {} messages still queued", messages_left_queued);
+            }
+        }
+
+        let files_closed = match self.storage_manager {
+            Some(ref storage_manager) => storage_manager.flush_all().await.unwrap_or_else(|e| {
+                warn!("Failed to flush storage: {}", e);
+                0
+            }),
+            None => 0,
+        };
 
         // Wait for background tasks to complete
         if let Some(task) = self.monitoring_task.take() {
             let _ = task.await;
         }
-        if let Some(task) = self.scaling_task.take() {
-            let _ = task.await;
-        }
         if let Some(task) = self.config_watcher_task.take() {
             let _ = task.await;
         }
         if let Some(task) = self.agent_recovery_task.take() {
             let _ = task.await;
         }
+        if let Some(task) = self.idle_monitor_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.error_alert_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.lifecycle_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.webhook_forward_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.webhook_flush_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.channel_quality_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.browser_health_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.summary_task.take() {
+            let _ = task.await;
+        }
+        if let Some(task) = self.agent_event_task.take() {
+            let _ = task.await;
+        }
 
-        info!("Agent Orchestrator stopped");
-        Ok(())
+        let summary = ShutdownSummary {
+            agents_stopped,
+            agents_failed,
+            messages_flushed: self.storage_counters.written.load(Ordering::Relaxed),
+            messages_unwritten: self.storage_counters.failed.load(Ordering::Relaxed) + messages_left_queued,
+            files_closed,
+        };
+        info!("Agent Orchestrator stopped: {}", summary);
+        Ok(summary)
+    }
+
+    /// Messages the storage writer has finished with, written or not
+    fn storage_progress(&self) -> u64 {
+        self.storage_counters.written.load(Ordering::Relaxed) + self.storage_counters.failed.load(Ordering::Relaxed)
     }
 
     /// Distribute agents across configured streamers based on priority
     pub async fn distribute_agents(&mut self) -> Result<()> {
+        for mut pending in self.begin_distribution().await? {
+            let started = pending.start().await;
+            self.finish_distribution(pending, started).await;
+        }
+        Ok(())
+    }
+
+    /// The part of `distribute_agents` that stops agents of removed streamers
+    /// and sets up the missing ones, returning the agents to start
+    async fn begin_distribution(&mut self) -> Result<Vec<PendingAgent>> {
         let config = self.config.read().await;
         let streamers = config.streamers.clone();
         let max_concurrent = config.agents.max_concurrent;
@@ -219,6 +809,10 @@ impl AgentOrchestrator {
 
         info!("Distributing agents across {} streamers: {:?}", streamers.len(), streamers);
 
+        // a streamer dropped from the list gets a fresh start if it's added back
+        self.failed_streamers.write().await.retain(|streamer, _| streamers.contains(streamer));
+        let failed_streamers: HashSet<String> = self.failed_streamers.read().await.keys().cloned().collect();
+
         // stopping existing agents not in new streamer list
         let current_assignments = self.agent_assignments.read().await.clone();
         for (agent_id, assignment) in current_assignments {
@@ -239,6 +833,7 @@ impl AgentOrchestrator {
         };
 
         let mut assigned_count = 0;
+        let mut pending_agents = Vec::new();
 
         // assigning agents to streamers
         for (index, streamer) in streamers.iter().enumerate() {
@@ -246,20 +841,22 @@ impl AgentOrchestrator {
                 break;
             }
 
+            if failed_streamers.contains(streamer) {
+                debug!("Skipping streamer {}, it failed too many times", streamer);
+                continue;
+            }
+
             // checking if we have agent for this streamer
             let assignments = self.agent_assignments.read().await;
-            let has_agent = assignments.values().any(|a| a.streamer == *streamer);
+            let has_agent = assignments.values().any(|a| a.streamer == *streamer) || self.starting_streamers.contains(streamer);
             drop(assignments);
 
             if !has_agent {
                 info!("No existing agent for streamer {}, spawning new one", streamer);
                 let priority = index as u8; // Earlier streamers get higher priority (lower number)
-                match self.spawn_agent(streamer, priority).await {
-                    Ok(agent_id) => {
-                        info!(
-                            "Successfully assigned agent {} to streamer {} with priority {}",
-                            agent_id, streamer, priority
-                        );
+                match self.prepare_agent(streamer, priority, None).await {
+                    Ok(pending) => {
+                        pending_agents.push(pending);
                         assigned_count += 1;
                     }
                     Err(e) => {
@@ -277,13 +874,100 @@ impl AgentOrchestrator {
             "Agent distribution complete: {} agents assigned",
             assigned_count
         );
-        Ok(())
+        Ok(pending_agents)
+    }
+
+    /// The part of `distribute_agents` that installs an agent it started
+    async fn finish_distribution(&mut self, pending: PendingAgent, started: Result<()>) {
+        let (streamer, priority) = (pending.streamer.clone(), pending.priority);
+        match self.install_started(pending, started).await {
+            Ok(agent_id) => info!(
+                "Successfully assigned agent {} to streamer {} with priority {}",
+                agent_id, streamer, priority
+            ),
+            Err(e) => {
+                error!("Failed to assign agent to streamer {}: {}", streamer, e);
+                self.increment_error_count().await;
+            }
+        }
+    }
+
+    /// Try `agent.start` up to `retry_attempts + 1` times, each attempt bounded by a 30s timeout
+    /// A rate limited attempt waits out the limit instead of the usual backoff.
+    async fn start_with_retries(
+        agent: &mut ScrapingAgent,
+        streamer: &str,
+        retry_attempts: u32,
+        delay_range: (u64, u64),
+        message_broadcaster: &broadcast::Sender<AgentMessage>,
+    ) -> Result<()> {
+        let max_attempts = retry_attempts + 1;
+        let mut attempt = 1;
+
+        loop {
+            info!("Starting agent {} for streamer {} (attempt {}/{})", agent.id, streamer, attempt, max_attempts);
+            let error: Box<dyn std::error::Error + Send + Sync> =
+                match tokio::time::timeout(Duration::from_secs(30), agent.start(streamer)).await {
+                    Ok(Ok(_)) => return Ok(()),
+                    Ok(Err(e)) => e,
+                    Err(_) => ScrapingError::AgentError(format!("Agent startup timed out for {}", streamer)).into(),
+                };
+
+            // some failures won't go away by trying again, e.g. no browser installed
+            let retryable = !matches!(
+                error.downcast_ref::<ScrapingError>().map(ScrapingError::recovery_strategy),
+                Some(RecoveryStrategy::StopAgent)
+            );
+            if !retryable || attempt >= max_attempts {
+                error!(
+                    "Agent {} failed to start for streamer {} after {} attempt(s): {}",
+                    agent.id, streamer, attempt, error
+                );
+                return Err(error);
+            }
+
+            if let Some(pause) = ScrapingError::rate_limit_pause(error.as_ref()) {
+                warn!(
+                    "Agent {} rate limited starting streamer {} (attempt {}/{}), pausing for {}s",
+                    agent.id, streamer, attempt, max_attempts, pause.as_secs()
+                );
+                let _ = message_broadcaster.send(AgentMessage::RateLimited {
+                    agent_id: agent.id,
+                    streamer: streamer.to_string(),
+                    pause,
+                });
+                sleep(pause).await;
+                attempt += 1;
+                continue;
+            }
+
+            let backoff = startup_backoff(attempt, delay_range);
+            warn!(
+                "Agent {} failed to start for streamer {} (attempt {}/{}): {}, retrying in {}ms",
+                agent.id, streamer, attempt, max_attempts, error, backoff.as_millis()
+            );
+            sleep(backoff).await;
+            attempt += 1;
+        }
     }
 
     /// Spawn a new agent for a specific streamer with priority
     pub async fn spawn_agent(&mut self, streamer: &str, priority: u8) -> Result<AgentId> {
+        self.spawn_agent_on_proxy(streamer, priority, None).await
+    }
+
+    /// Spawn an agent that asks for `proxy` first, so a restarted agent keeps its outbound IP
+    async fn spawn_agent_on_proxy(&mut self, streamer: &str, priority: u8, proxy: Option<String>) -> Result<AgentId> {
+        let mut pending = self.prepare_agent(streamer, priority, proxy).await?;
+        let started = pending.start().await;
+        self.install_started(pending, started).await
+    }
+
+    /// Set up an agent for `streamer` without starting it. It counts against
+    /// `max_concurrent` until it's installed or discarded.
+    async fn prepare_agent(&mut self, streamer: &str, priority: u8, proxy: Option<String>) -> Result<PendingAgent> {
         let agents = self.agents.read().await;
-        if agents.len() >= self.max_concurrent {
+        if agents.len() + self.starting_streamers.len() >= self.max_concurrent {
             return Err(ScrapingError::ResourceLimit(
                 "Maximum concurrent agents reached".to_string(),
             )
@@ -293,47 +977,143 @@ impl AgentOrchestrator {
 
         let config = self.config.read().await;
         let delay_range = config.agents.delay_range;
+        let retry_attempts = config.agents.retry_attempts;
+        let stable_ids = config.agents.stable_ids;
+        let log_output = if config.monitoring.per_agent_logs {
+            Some((
+                config.output.directory.clone(),
+                FileConfigManager::parse_size_to_bytes(&config.output.rotation_size)?,
+            ))
+        } else {
+            None
+        };
+        let redactor = Redactor::from_config(&config.redaction)?;
+        let parser = TwitchChatParser::new(Some(&config.parser.selectors))?;
+        let emote_cache_ttl = if config.parser.third_party_emotes {
+            Some(FileConfigManager::parse_time_to_duration(&config.parser.emote_cache_ttl)?)
+        } else {
+            None
+        };
+        let batch_settings = if config.agents.batch_size > 1 {
+            Some(BatchSettings {
+                max_size: config.agents.batch_size,
+                window: Duration::from_millis(config.agents.batch_window_ms),
+            })
+        } else {
+            None
+        };
         drop(config);
 
         let mut agent =
             ScrapingAgent::new(delay_range, self.chat_message_broadcaster.clone())?;
-        let agent_id = agent.id;
 
-        // Configure agent with browser manager
-        agent = agent.with_browser_manager(self.browser_manager.clone());
+        // Keep the same id for a streamer across restarts when enabled
+        if stable_ids {
+            let stable_id = stable_agent_id(streamer);
+            if self.agents.read().await.contains_key(&stable_id) {
+                return Err(ScrapingError::AgentError(format!("An agent for {} is already running", streamer)).into());
+            }
+            agent = agent.with_id(stable_id);
+        }
 
-        // staggering startup delay
-        let startup_delay = rand::thread_rng().gen_range(100..=2000); // 0.1 to 2 seconds
-        info!(
-            "Agent {} delaying for {}ms before startup",
-            agent_id, startup_delay
-        );
-        sleep(Duration::from_millis(startup_delay)).await;
+        // Configure agent with browser manager and the configured chat selectors
+        agent = agent
+            .with_browser_manager(self.browser_manager.clone())
+            .with_proxy(proxy)
+            .with_parser(parser);
 
-        // Start the agent with timeout
-        info!("Starting agent {} for streamer {}", agent_id, streamer);
-        match tokio::time::timeout(Duration::from_secs(30), agent.start(streamer)).await {
-            Ok(Ok(_)) => {
-                info!("Agent {} started successfully for streamer {}", agent_id, streamer);
-            }
-            Ok(Err(e)) => {
-                error!("Agent {} failed to start for streamer {}: {}", agent_id, streamer, e);
-                return Err(e);
-            }
-            Err(_) => {
-                error!("Agent {} startup timed out for streamer {}", agent_id, streamer);
-                return Err(ScrapingError::AgentError(format!("Agent startup timed out for {}", streamer)).into());
+        // Tee agent events into its own log file when enabled
+        if let Some((output_dir, rotation_size)) = log_output {
+            agent = agent.with_log_output(output_dir, rotation_size);
+        }
+
+        // Scrub PII before messages leave the agent when enabled
+        if let Some(redactor) = redactor {
+            agent = agent.with_redactor(redactor);
+        }
+
+        // Tag BTTV/FFZ/7TV emotes when enabled, fetched in the background so
+        // slow emote providers don't hold up the spawn
+        if let Some(ttl) = emote_cache_ttl {
+            let emote_cache = match self.emote_cache {
+                Some(ref emote_cache) => emote_cache.clone(),
+                None => {
+                    let emote_cache = Arc::new(EmoteCache::new(ttl)?);
+                    self.emote_cache = Some(emote_cache.clone());
+                    emote_cache
+                }
+            };
+            let (emotes_tx, emotes_rx) = watch::channel(Arc::new(EmoteSet::new()));
+            let streamer = streamer.to_string();
+            tokio::spawn(async move {
+                let _ = emotes_tx.send(emote_cache.get(&streamer).await);
+            });
+            agent = agent.with_emotes(emotes_rx);
+        }
+
+        // Coalesce messages into batches when a batch size is configured
+        if let Some(batch_settings) = batch_settings {
+            agent = agent.with_batching(batch_settings, self.chat_batch_broadcaster.clone());
+        }
+
+        // Feed the storage queue when a storage manager is attached
+        if let Some(ref storage_sender) = self.storage_sender {
+            agent = agent.with_storage_sender(storage_sender.clone());
+        }
+
+        self.starting_streamers.insert(streamer.to_string());
+        Ok(PendingAgent {
+            agent,
+            streamer: streamer.to_string(),
+            priority,
+            retry_attempts,
+            delay_range,
+            message_broadcaster: self.message_broadcaster.clone(),
+            while_running: self.shutdown_signal.is_some(),
+        })
+    }
+
+    /// Install a prepared agent once it's started, or drop it if it didn't start
+    async fn install_started(&mut self, pending: PendingAgent, started: Result<()>) -> Result<AgentId> {
+        match started {
+            Ok(()) => self.install_agent(pending).await,
+            Err(e) => {
+                self.discard_pending(&pending);
+                Err(e)
             }
         }
+    }
+
+    /// Drop a prepared agent that failed to start
+    fn discard_pending(&mut self, pending: &PendingAgent) {
+        self.starting_streamers.remove(&pending.streamer);
+    }
+
+    /// Track a started agent and give it an assignment
+    async fn install_agent(&mut self, pending: PendingAgent) -> Result<AgentId> {
+        let PendingAgent { mut agent, streamer, priority, while_running, .. } = pending;
+        let agent_id = agent.id;
+        self.starting_streamers.remove(&streamer);
+
+        // the orchestrator stopped while the agent started unlocked, don't leave it running
+        if while_running && self.shutdown_signal.is_none() {
+            agent.stop().await?;
+            return Err(ScrapingError::AgentError(format!(
+                "Orchestrator stopped while agent {} was starting for {}",
+                agent_id, streamer
+            )).into());
+        }
 
         // create assignment record
         let assignment = AgentAssignment {
             agent_id,
-            streamer: streamer.to_string(),
+            streamer: streamer.clone(),
             assigned_at: SystemTime::now(),
             priority,
             retry_attempts: 0,
             last_failure: None,
+            idle_since: None,
+            proxy: agent.proxy().map(str::to_string),
         };
 
         // store agent and assignment
@@ -389,6 +1169,49 @@ impl AgentOrchestrator {
         Ok(())
     }
 
+    /// Pause a running agent, keeping its browser. Returns false if there is no such agent.
+    pub async fn pause_agent(&mut self, agent_id: AgentId) -> Result<bool> {
+        let mut agents = self.agents.write().await;
+        let Some(agent) = agents.get_mut(&agent_id) else {
+            return Ok(false);
+        };
+        agent.pause().await?;
+
+        let _ = self.message_broadcaster.send(AgentMessage::StatusUpdate {
+            agent_id,
+            status: AgentStatus::Paused,
+        });
+        Ok(true)
+    }
+
+    /// Resume a paused agent. Returns false if there is no such agent.
+    pub async fn resume_agent(&mut self, agent_id: AgentId) -> Result<bool> {
+        let mut agents = self.agents.write().await;
+        let Some(agent) = agents.get_mut(&agent_id) else {
+            return Ok(false);
+        };
+        agent.resume().await?;
+
+        let _ = self.message_broadcaster.send(AgentMessage::StatusUpdate {
+            agent_id,
+            status: AgentStatus::Running,
+        });
+        Ok(true)
+    }
+
+    /// Track an agent that's already set up, as if `spawn_agent` had started it
+    #[cfg(test)]
+    pub(crate) async fn insert_agent(&self, agent: ScrapingAgent, assignment: AgentAssignment) {
+        self.agent_assignments.write().await.insert(agent.id, assignment);
+        self.agents.write().await.insert(agent.id, agent);
+    }
+
+    /// The sending side of the storage ingest queue, as agents get it
+    #[cfg(test)]
+    pub(crate) fn storage_sender(&self) -> Option<mpsc::Sender<ChatMessage>> {
+        self.storage_sender.clone()
+    }
+
     /// Get status of a specific agent
     pub async fn get_agent_status(&self, agent_id: AgentId) -> Option<AgentStatus> {
         let agents = self.agents.read().await;
@@ -409,6 +1232,16 @@ impl AgentOrchestrator {
         }
     }
 
+    /// How often system metrics are sampled
+    pub fn monitor_interval(&self) -> Duration {
+        self.monitor_interval
+    }
+
+    /// How often agents are scaled to the load
+    pub fn scale_interval(&self) -> Duration {
+        self.scale_interval
+    }
+
     /// Get list of active agent IDs
     pub async fn get_active_agents(&self) -> Vec<AgentId> {
         let agents = self.agents.read().await;
@@ -417,8 +1250,16 @@ impl AgentOrchestrator {
 
     /// Get comprehensive orchestrator status
     pub async fn get_status(&self) -> OrchestratorStatus {
-        let _agents = self.agents.read().await;
-        let _assignments = self.agent_assignments.read().await;
+        let (total_agents, idle_agents) = {
+            let agents = self.agents.read().await;
+            let mut idle_agents = 0;
+            for agent in agents.values() {
+                if agent.get_status().await == AgentStatus::Idle {
+                    idle_agents += 1;
+                }
+            }
+            (agents.len(), idle_agents)
+        };
         let system_metrics = self.system_metrics.read().await.clone();
         let agent_assignments: Vec<AgentAssignment> = self
             .agent_assignments
@@ -429,15 +1270,39 @@ impl AgentOrchestrator {
             .collect();
 
         OrchestratorStatus {
-            active_agents: self.agents.read().await.len(),
+            active_agents: total_agents - idle_agents,
+            idle_agents,
             total_agents_spawned: *self.total_agents_spawned.read().await,
             system_metrics,
             agent_assignments,
             error_count: *self.error_count.read().await,
             uptime: self.start_time.elapsed(),
+            failed_streamers: self.get_failed_streamers().await,
         }
     }
 
+    /// Streamers abandoned after running out of restarts, most recent first
+    pub async fn get_failed_streamers(&self) -> Vec<FailedStreamer> {
+        let mut failed: Vec<FailedStreamer> = self.failed_streamers.read().await.values().cloned().collect();
+        failed.sort_by_key(|failed| std::cmp::Reverse(failed.failed_at));
+        failed
+    }
+
+    /// Get a copy of the configuration currently in effect
+    pub async fn get_config(&self) -> Config {
+        self.config.read().await.clone()
+    }
+
+    /// Get emote-only and caps ratios for every streamer over the recent window
+    pub async fn get_channel_quality(&self) -> HashMap<String, ChannelQuality> {
+        self.channel_quality.read().await.clone()
+    }
+
+    /// Get emote-only and caps ratios for one streamer over the recent window
+    pub async fn get_streamer_channel_quality(&self, streamer: &str) -> Option<ChannelQuality> {
+        self.channel_quality.read().await.get(streamer).cloned()
+    }
+
     /// Subscribe to inter-agent communication messages
     pub fn subscribe_to_messages(&self) -> broadcast::Receiver<AgentMessage> {
         self.message_broadcaster.subscribe()
@@ -448,25 +1313,59 @@ impl AgentOrchestrator {
         self.chat_message_broadcaster.subscribe()
     }
 
+    /// Subscribe to batched chat messages, sent by agents when `batch_size` > 1
+    pub fn subscribe_to_chat_batches(&self) -> broadcast::Receiver<Vec<ChatMessage>> {
+        self.chat_batch_broadcaster.subscribe()
+    }
+
+    /// Recent status changes, errors and alerts for an agent, oldest first
+    pub async fn get_agent_events(&self, agent_id: AgentId, limit: usize) -> Option<Vec<AgentEvent>> {
+        self.agent_events.read().await.recent(agent_id, limit)
+    }
+
+    /// Messages currently waiting in the storage ingest queue
+    pub fn get_storage_queue_depth(&self) -> usize {
+        self.storage_sender.as_ref().map_or(0, Self::queue_depth)
+    }
+
+    fn queue_depth(sender: &mpsc::Sender<ChatMessage>) -> usize {
+        sender.max_capacity() - sender.capacity()
+    }
+
     /// Stop all agents
-    pub async fn stop_all_agents(&mut self) -> Result<()> {
+    /// Stop every agent, returning how many stopped cleanly and how many errored
+    pub async fn stop_all_agents(&mut self) -> Result<(usize, usize)> {
         let agent_ids: Vec<AgentId> = {
             let agents = self.agents.read().await;
             agents.keys().cloned().collect()
         };
 
+        let (mut stopped, mut failed) = (0, 0);
         for agent_id in agent_ids {
             if let Err(e) = self.stop_agent(agent_id).await {
                 warn!("Error stopping agent {}: {}", agent_id, e);
                 self.increment_error_count().await;
+                failed += 1;
+            } else {
+                stopped += 1;
             }
         }
 
-        Ok(())
+        Ok((stopped, failed))
     }
 
     /// Update configuration and redistribute agents if needed
     pub async fn update_config(&mut self, new_config: Config) -> Result<()> {
+        for mut pending in self.begin_config_update(new_config).await? {
+            let started = pending.start().await;
+            self.finish_distribution(pending, started).await;
+        }
+        Ok(())
+    }
+
+    /// The part of `update_config` that swaps the config in, returning the
+    /// agents to start for streamers that were added
+    async fn begin_config_update(&mut self, new_config: Config) -> Result<Vec<PendingAgent>> {
         info!("Updating orchestrator configuration");
 
         let old_streamers = {
@@ -488,60 +1387,454 @@ impl AgentOrchestrator {
         // Redistribute agents if streamer list changed
         if old_streamers != new_streamers {
             info!("Streamer list changed, redistributing agents");
-            self.distribute_agents().await?;
+            return self.begin_distribution().await;
         }
 
-        Ok(())
+        Ok(Vec::new())
+    }
+
+    /// Start scraping `streamer` straight away and add it to the in-memory streamer
+    /// list, returning its agent id. A streamer that already has an agent keeps it.
+    /// The config file isn't touched, so reloading it from disk drops the streamer again.
+    pub async fn add_streamer(&mut self, streamer: &str) -> Result<AgentId> {
+        let streamer = streamer.trim().to_lowercase();
+        if !FileConfigManager::is_valid_streamer_name(&streamer) {
+            return Err(ScrapingError::ConfigError(format!("Invalid streamer name '{}'", streamer)).into());
+        }
+
+        let existing = self.agent_assignments.read().await
+            .values()
+            .find(|assignment| assignment.streamer == streamer)
+            .map(|assignment| assignment.agent_id);
+        if let Some(agent_id) = existing {
+            return Ok(agent_id);
+        }
+
+        let priority = {
+            let config = self.config.read().await;
+            config.streamers.iter()
+                .position(|configured| *configured == streamer)
+                .unwrap_or(config.streamers.len()) as u8
+        };
+        let agent_id = self.spawn_agent(&streamer, priority).await?;
+
+        self.failed_streamers.write().await.remove(&streamer);
+        let mut config = self.config.write().await;
+        if !config.streamers.contains(&streamer) {
+            config.streamers.push(streamer.clone());
+        }
+        info!("Added streamer {} with agent {}", streamer, agent_id);
+        Ok(agent_id)
+    }
+
+    /// Stop scraping `streamer` and drop it from the in-memory streamer list.
+    /// Returns false if it wasn't being scraped.
+    pub async fn remove_streamer(&mut self, streamer: &str) -> Result<bool> {
+        let streamer = streamer.trim().to_lowercase();
+
+        let agent_ids: Vec<AgentId> = self.agent_assignments.read().await
+            .values()
+            .filter(|assignment| assignment.streamer == streamer)
+            .map(|assignment| assignment.agent_id)
+            .collect();
+        let configured = {
+            let mut config = self.config.write().await;
+            let before = config.streamers.len();
+            config.streamers.retain(|configured| *configured != streamer);
+            config.streamers.len() != before
+        };
+        self.failed_streamers.write().await.remove(&streamer);
+
+        for agent_id in &agent_ids {
+            self.stop_agent(*agent_id).await?;
+        }
+
+        if configured || !agent_ids.is_empty() {
+            info!("Removed streamer {}", streamer);
+        }
+        Ok(configured || !agent_ids.is_empty())
     }
 
-    /// Restart a failed agent
+    /// Restart a failed agent, counting it against `agents.retry_attempts`
     pub async fn restart_agent(&mut self, agent_id: AgentId) -> Result<()> {
+        self.restart(agent_id, true).await
+    }
+
+    /// Restart an agent on the operator's request. It isn't a failure, so the
+    /// streamer's restart count and backoff are left alone.
+    pub async fn manual_restart_agent(&mut self, agent_id: AgentId) -> Result<()> {
+        self.restart(agent_id, false).await
+    }
+
+    async fn restart(&mut self, agent_id: AgentId, after_failure: bool) -> Result<()> {
+        let mut restart = self.begin_restart(agent_id, after_failure).await?;
+        let started = restart.run().await;
+        self.finish_restart(restart, started).await
+    }
+
+    /// Take an agent and its assignment out of the orchestrator and prepare its
+    /// replacement. The slow part is left to `PendingRestart::run`.
+    async fn begin_restart(&mut self, agent_id: AgentId, after_failure: bool) -> Result<PendingRestart> {
         let assignment = {
             let mut assignments = self.agent_assignments.write().await;
             assignments.remove(&agent_id)
         };
+        let Some(mut assignment) = assignment else {
+            return Err(ScrapingError::AgentError(format!("Agent {} not found for restart", agent_id)).into());
+        };
 
-        if let Some(mut assignment) = assignment {
-            info!(
-                "Restarting agent {} for streamer {}",
-                agent_id, assignment.streamer
-            );
-
-            // stop the existing agent
-            self.stop_agent(agent_id).await?;
+        info!(
+            "Restarting agent {} for streamer {}",
+            agent_id, assignment.streamer
+        );
+        let old_agent = self.agents.write().await.remove(&agent_id);
 
-            // increment retry attempts and update last failure
+        // increment retry attempts and update last failure
+        if after_failure {
             assignment.retry_attempts += 1;
             assignment.last_failure = Some(SystemTime::now());
+        }
 
-            // spawn new agent for same streamer
-            let new_agent_id = self
-                .spawn_agent(&assignment.streamer, assignment.priority)
-                .await?;
+        // new agent for same streamer, on the same proxy if it's still healthy
+        match self.prepare_agent(&assignment.streamer, assignment.priority, assignment.proxy.clone()).await {
+            Ok(replacement) => Ok(PendingRestart {
+                agent_id,
+                old_agent,
+                assignment,
+                after_failure,
+                replacement,
+            }),
+            Err(e) => {
+                if let Some(mut old_agent) = old_agent {
+                    if let Err(stop_error) = old_agent.stop().await {
+                        warn!("Error stopping agent {}: {}", agent_id, stop_error);
+                    }
+                }
+                self.keep_failed_restart(assignment, e.as_ref()).await?;
+                Err(e)
+            }
+        }
+    }
 
-            // update assignment with new agent id
-            assignment.agent_id = new_agent_id;
-            let mut assignments = self.agent_assignments.write().await;
-            assignments.insert(new_agent_id, assignment);
+    /// Install the replacement `PendingRestart::run` started, or keep the
+    /// streamer errored if it didn't
+    async fn finish_restart(&mut self, restart: PendingRestart, started: Result<()>) -> Result<()> {
+        let PendingRestart { agent_id, mut assignment, after_failure, replacement, .. } = restart;
+        if let Err(e) = started {
+            self.discard_pending(&replacement);
+            self.keep_failed_restart(assignment, e.as_ref()).await?;
+            return Err(e);
+        }
+        let new_agent_id = self.install_agent(replacement).await?;
 
-            Ok(())
+        // link the two histories so a flapping streamer can be followed
+        let now = chrono::Utc::now();
+        let reason = if after_failure {
+            format!("attempt {}", assignment.retry_attempts)
         } else {
-            Err(ScrapingError::AgentError(format!("Agent {} not found for restart", agent_id)).into())
+            "manual".to_string()
+        };
+        let mut agent_events = self.agent_events.write().await;
+        if new_agent_id == agent_id {
+            // stable ids: the history just carries on
+            agent_events.push(agent_id, AgentEvent {
+                timestamp: now,
+                kind: AgentEventKind::Status,
+                message: format!("restarted ({})", reason),
+            });
+        } else {
+            agent_events.push(agent_id, AgentEvent {
+                timestamp: now,
+                kind: AgentEventKind::Status,
+                message: format!("restarted as agent {} ({})", new_agent_id, reason),
+            });
+            agent_events.push(new_agent_id, AgentEvent {
+                timestamp: now,
+                kind: AgentEventKind::Status,
+                message: format!("replaces agent {}", agent_id),
+            });
+        }
+        drop(agent_events);
+
+        // update assignment with new agent id and whichever proxy it ended up on
+        assignment.agent_id = new_agent_id;
+        let mut assignments = self.agent_assignments.write().await;
+        assignment.proxy = assignments.get(&new_agent_id).and_then(|new| new.proxy.clone());
+        assignments.insert(new_agent_id, assignment);
+
+        Ok(())
+    }
+
+    /// Track a streamer whose restart failed as an errored agent under its old
+    /// id, so recovery backs off and tries again until it gives up on it
+    async fn keep_failed_restart(&mut self, assignment: AgentAssignment, error: &(dyn std::error::Error + Send + Sync)) -> Result<()> {
+        let delay_range = self.config.read().await.agents.delay_range;
+        let agent = ScrapingAgent::new(delay_range, self.chat_message_broadcaster.clone())?.with_id(assignment.agent_id);
+        agent.set_status(AgentStatus::Error(format!("restart failed: {}", error))).await;
+
+        self.agents.write().await.insert(assignment.agent_id, agent);
+        self.agent_assignments.write().await.insert(assignment.agent_id, assignment);
+        Ok(())
+    }
+
+    /// Restart an errored agent, or stop it for good once its streamer has used
+    /// up `agents.retry_attempts` restarts
+    pub async fn recover_agent(&mut self, agent_id: AgentId) -> Result<()> {
+        let Some(mut restart) = self.begin_recovery(agent_id).await? else {
+            return Ok(());
+        };
+        let started = restart.run().await;
+        self.finish_recovery(restart, started).await
+    }
+
+    /// The part of `recover_agent` that decides what to do with the agent,
+    /// returning the restart to run if there is one
+    async fn begin_recovery(&mut self, agent_id: AgentId) -> Result<Option<PendingRestart>> {
+        // a stable id can come back healthy under the same id it was queued with
+        let Some(AgentStatus::Error(last_error)) = self.get_agent_status(agent_id).await else {
+            return Ok(None);
+        };
+        let Some(assignment) = self.agent_assignments.read().await.get(&agent_id).cloned() else {
+            // already restarted or stopped since the request was queued
+            debug!("Agent {} no longer assigned, skipping recovery", agent_id);
+            return Ok(None);
+        };
+        let max_restarts = self.config.read().await.agents.retry_attempts;
+
+        if let Some(failed) = give_up_on(&assignment, max_restarts, &last_error) {
+            error!(
+                "Agent {} for streamer {} failed again after {} restarts, giving up: {}",
+                agent_id, failed.streamer, failed.restart_attempts, failed.last_error
+            );
+            self.stop_agent(agent_id).await?;
+            let _ = self.message_broadcaster.send(AgentMessage::StreamerFailed {
+                agent_id,
+                streamer: failed.streamer.clone(),
+                restart_attempts: failed.restart_attempts,
+            });
+            self.notify_lifecycle(
+                "critical",
+                "Streamer abandoned",
+                &format!(
+                    "Gave up on {} after {} restarts: {}",
+                    failed.streamer, failed.restart_attempts, failed.last_error
+                ),
+            ).await;
+            self.failed_streamers.write().await.insert(failed.streamer.clone(), failed);
+            return Ok(None);
+        }
+
+        warn!(
+            "Agent {} for streamer {} is in error state, restarting (restart {}/{})",
+            agent_id, assignment.streamer, assignment.retry_attempts + 1, max_restarts
+        );
+        match self.begin_restart(agent_id, true).await {
+            Ok(restart) => Ok(Some(restart)),
+            Err(e) => Err(self.recovery_failed(agent_id, &assignment.streamer, e).await),
+        }
+    }
+
+    /// The part of `recover_agent` that installs the restarted agent
+    async fn finish_recovery(&mut self, restart: PendingRestart, started: Result<()>) -> Result<()> {
+        let (agent_id, streamer) = (restart.agent_id, restart.assignment.streamer.clone());
+        match self.finish_restart(restart, started).await {
+            Ok(()) => Ok(()),
+            Err(e) => Err(self.recovery_failed(agent_id, &streamer, e).await),
         }
     }
 
-    /// Scale agents based on system resources and demand
+    /// Count and report a restart that didn't work, handing the error back
+    async fn recovery_failed(
+        &mut self,
+        agent_id: AgentId,
+        streamer: &str,
+        error: Box<dyn std::error::Error + Send + Sync>,
+    ) -> Box<dyn std::error::Error + Send + Sync> {
+        self.increment_error_count().await;
+        let _ = self.message_broadcaster.send(AgentMessage::Error {
+            agent_id,
+            error: format!("failed to restart for {}: {}", streamer, error),
+        });
+        error
+    }
+
+    /// Restart the agents the recovery task finds in error state. Runs until the
+    /// orchestrator is dropped, so spawn it next to whatever holds the orchestrator.
+    pub fn drive_recovery(orchestrator: &Arc<RwLock<AgentOrchestrator>>) -> tokio::task::JoinHandle<()> {
+        let orchestrator = Arc::downgrade(orchestrator);
+        tokio::spawn(async move {
+            let recovery_rx = match orchestrator.upgrade() {
+                Some(orchestrator) => orchestrator.write().await.recovery_rx.take(),
+                None => None,
+            };
+            let Some(mut recovery_rx) = recovery_rx else {
+                warn!("Agent recovery is already being driven");
+                return;
+            };
+
+            while let Some(agent_id) = recovery_rx.recv().await {
+                let Some(orchestrator) = orchestrator.upgrade() else {
+                    break;
+                };
+                // only deciding and installing take the lock, the restart itself
+                // can take a while and shouldn't hold up the dashboard or shutdown
+                let begun = orchestrator.write().await.begin_recovery(agent_id).await;
+                let result = match begun {
+                    Ok(Some(mut restart)) => {
+                        let started = restart.run().await;
+                        orchestrator.write().await.finish_recovery(restart, started).await
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    error!("Failed to recover agent {}: {}", agent_id, e);
+                }
+            }
+        })
+    }
+
+    /// Scale agents to the load every `monitoring.scale_interval` until the
+    /// orchestrator stops or is dropped. Spawn it next to whatever holds the
+    /// orchestrator, like `drive_recovery`.
+    pub fn drive_scaling(orchestrator: &Arc<RwLock<AgentOrchestrator>>) -> tokio::task::JoinHandle<()> {
+        let orchestrator = Arc::downgrade(orchestrator);
+        tokio::spawn(async move {
+            let started = match orchestrator.upgrade() {
+                Some(orchestrator) => {
+                    let orchestrator = orchestrator.read().await;
+                    orchestrator.shutdown_signal.as_ref().map(|signal| (signal.subscribe(), orchestrator.scale_interval))
+                }
+                None => None,
+            };
+            let Some((mut shutdown_rx, scale_interval)) = started else {
+                warn!("Orchestrator isn't running, agents won't be scaled");
+                return;
+            };
+
+            let mut interval = interval(scale_interval);
+            // the first tick is immediate, leave startup to `start`
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        debug!("Agent scaling received shutdown signal");
+                        break;
+                    }
+                    _ = interval.tick() => {
+                        let Some(orchestrator) = orchestrator.upgrade() else {
+                            break;
+                        };
+                        // a new agent starts with the lock released, like recovery restarts
+                        let begun = orchestrator.write().await.begin_scaling().await;
+                        match begun {
+                            Ok(Some(mut pending)) => {
+                                let started = pending.start().await;
+                                orchestrator.write().await.finish_scale_up(pending, started).await;
+                            }
+                            Ok(None) => {}
+                            Err(e) => error!("Failed to scale agents: {}", e),
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Apply the configs the config watcher reloads, redistributing agents when
+    /// the streamer list changed. Runs until the orchestrator is dropped, spawn
+    /// it next to whatever holds the orchestrator, like `drive_recovery`.
+    pub fn drive_config_reloads(orchestrator: &Arc<RwLock<AgentOrchestrator>>) -> tokio::task::JoinHandle<()> {
+        let orchestrator = Arc::downgrade(orchestrator);
+        tokio::spawn(async move {
+            let config_reload_rx = match orchestrator.upgrade() {
+                Some(orchestrator) => orchestrator.write().await.config_reload_rx.take(),
+                None => None,
+            };
+            let Some(mut config_reload_rx) = config_reload_rx else {
+                warn!("Config reloads are already being driven");
+                return;
+            };
+
+            while config_reload_rx.changed().await.is_ok() {
+                let Some(new_config) = config_reload_rx.borrow_and_update().clone() else {
+                    continue;
+                };
+                let Some(orchestrator) = orchestrator.upgrade() else {
+                    break;
+                };
+                // agents for added streamers start with the lock released, like recovery restarts
+                let begun = orchestrator.write().await.begin_config_update(new_config).await;
+                match begun {
+                    Ok(pending_agents) => {
+                        for mut pending in pending_agents {
+                            let started = pending.start().await;
+                            orchestrator.write().await.finish_distribution(pending, started).await;
+                        }
+                    }
+                    Err(e) => error!("Failed to apply reloaded configuration: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Throughput of every assigned agent, over the recent window
+    async fn agent_loads(&mut self) -> Vec<AgentLoad> {
+        let agents = self.agents.read().await;
+        let assignments = self.agent_assignments.read().await;
+        let now = Instant::now();
+        let mut loads = Vec::new();
+        for (agent_id, agent) in agents.iter() {
+            if let Some(assignment) = assignments.get(agent_id) {
+                let metrics = agent.get_metrics().await;
+                let messages_per_minute = self.message_rates.record(*agent_id, &metrics, now);
+                loads.push(AgentLoad::new(assignment, &metrics, messages_per_minute));
+            }
+        }
+        self.message_rates.retain(&agents.keys().copied().collect());
+        loads
+    }
+
+    /// Messages scraped so far by each streamer's agents
+    pub(crate) async fn messages_per_streamer(
+        agents: &RwLock<HashMap<AgentId, ScrapingAgent>>,
+        agent_assignments: &RwLock<HashMap<AgentId, AgentAssignment>>,
+    ) -> HashMap<String, u64> {
+        let agents = agents.read().await;
+        let assignments = agent_assignments.read().await;
+        let mut per_streamer = HashMap::new();
+        for (agent_id, agent) in agents.iter() {
+            if let Some(assignment) = assignments.get(agent_id) {
+                *per_streamer.entry(assignment.streamer.clone()).or_insert(0) += agent.get_metrics().await.messages_scraped;
+            }
+        }
+        per_streamer
+    }
+
+    /// Scale agents based on system resources and where the chat traffic is
     pub async fn scale_agents(&mut self) -> Result<()> {
+        if let Some(mut pending) = self.begin_scaling().await? {
+            let started = pending.start().await;
+            self.finish_scale_up(pending, started).await;
+        }
+        Ok(())
+    }
+
+    /// The part of `scale_agents` that decides, returning the agent to start
+    /// when it scales up
+    async fn begin_scaling(&mut self) -> Result<Option<PendingAgent>> {
         let system_metrics = self.system_metrics.read().await.clone();
         let config = self.config.read().await;
         let max_concurrent = config.agents.max_concurrent;
         let streamers = config.streamers.clone();
+        let policy = ThroughputPolicy::from_config(&config.agents);
         drop(config);
 
-        let current_agents = {
-            let agents = self.agents.read().await;
-            agents.len()
-        };
+        let loads = self.agent_loads().await;
+        for load in loads.iter().filter(|load| load.is_measured()) {
+            self.streamer_rates.insert(load.streamer.clone(), load.messages_per_minute);
+        }
+        let current_agents = loads.len();
 
         let memory_usage_percent =
             (system_metrics.memory_usage as f64 / system_metrics.memory_total as f64) * 100.0;
@@ -553,46 +1846,66 @@ impl AgentOrchestrator {
                 system_metrics.cpu_usage, memory_usage_percent
             );
 
-            // Find the lowest priority agent to stop
-            let agent_id_to_stop = {
-                let assignments = self.agent_assignments.read().await;
-                assignments
-                    .iter()
-                    .max_by_key(|(_, assignment)| assignment.priority)
-                    .map(|(id, _)| *id)
-            };
-
-            if let Some(agent_id) = agent_id_to_stop {
-                self.stop_agent(agent_id).await?;
+            // shed the quietest channel, busy ones keep their agents
+            if let Some(load) = policy.shed_candidate(&loads).and_then(|agent_id| loads.iter().find(|load| load.agent_id == agent_id)) {
+                let _ = self.message_broadcaster.send(AgentMessage::ResourceAlert {
+                    agent_id: load.agent_id,
+                    alert: format!(
+                        "Resource usage high (CPU: {:.1}%, Memory: {:.1}%), stopping the agent on {} at {:.1} msg/min",
+                        system_metrics.cpu_usage, memory_usage_percent, load.streamer, load.messages_per_minute
+                    ),
+                });
+                self.stop_agent(load.agent_id).await?;
             }
         }
         // Scale up if resources are available and we have unassigned streamers
-        else if system_metrics.cpu_usage < 60.0
-            && memory_usage_percent < 70.0
-            && current_agents < max_concurrent
-        {
+        else if system_metrics.cpu_usage < 60.0 && memory_usage_percent < 70.0 {
             // Find streamers without agents
-            let assignments = self.agent_assignments.read().await;
-            let assigned_streamers: Vec<String> =
-                assignments.values().map(|a| a.streamer.clone()).collect();
-            drop(assignments);
+            let assigned_streamers: HashSet<String> = loads
+                .iter()
+                .map(|load| load.streamer.clone())
+                .chain(self.starting_streamers.iter().cloned())
+                .collect();
+            let failed_streamers = self.failed_streamers.read().await;
+            let waiting: Vec<(String, u8)> = streamers
+                .iter()
+                .enumerate()
+                .filter(|(_, streamer)| !assigned_streamers.contains(*streamer) && !failed_streamers.contains_key(*streamer))
+                .map(|(index, streamer)| (streamer.clone(), index as u8))
+                .collect();
+            drop(failed_streamers);
+
+            // Only add one agent at a time
+            if let Some((streamer, priority)) = policy.spawn_order(waiting, &self.streamer_rates).into_iter().next() {
+                if current_agents >= max_concurrent {
+                    // every slot is taken, a cold channel makes room for a possibly busier one
+                    let Some(agent_id) = policy.reclaim_candidate(&loads, self.streamer_rates.get(&streamer).copied()) else {
+                        return Ok(None);
+                    };
+                    info!("Agent {} is on a quiet channel, handing its slot to streamer {}", agent_id, streamer);
+                    self.stop_agent(agent_id).await?;
+                }
 
-            for (index, streamer) in streamers.iter().enumerate() {
-                if !assigned_streamers.contains(streamer) && current_agents < max_concurrent {
-                    info!(
-                        "Resources available, scaling up agent for streamer {}",
-                        streamer
-                    );
-                    let priority = index as u8;
-                    if let Err(e) = self.spawn_agent(streamer, priority).await {
-                        warn!("Failed to scale up agent for streamer {}: {}", streamer, e);
-                    }
-                    break; // Only add one agent at a time
+                info!(
+                    "Resources available, scaling up agent for streamer {}",
+                    streamer
+                );
+                match self.prepare_agent(&streamer, priority, None).await {
+                    Ok(pending) => return Ok(Some(pending)),
+                    Err(e) => warn!("Failed to scale up agent for streamer {}: {}", streamer, e),
                 }
             }
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    /// The part of `scale_agents` that installs the agent it started
+    async fn finish_scale_up(&mut self, pending: PendingAgent, started: Result<()>) {
+        let streamer = pending.streamer.clone();
+        if let Err(e) = self.install_started(pending, started).await {
+            warn!("Failed to scale up agent for streamer {}: {}", streamer, e);
+        }
     }
 
     /// Get agent performance metrics for load balancing
@@ -619,8 +1932,15 @@ impl AgentOrchestrator {
                 0.0
             };
 
+            let restart_due = self
+                .agent_assignments
+                .read()
+                .await
+                .get(&agent_id)
+                .is_none_or(|assignment| assignment.restart_due(SystemTime::now()));
+
             // if error rate too high, restart agent
-            if error_rate > 0.1 {
+            if error_rate > 0.1 && restart_due {
                 // More than 0.1 errors per second
                 warn!(
                     "Agent {} has high error rate ({:.2}/sec), restarting",
@@ -647,9 +1967,13 @@ impl AgentOrchestrator {
         let system_metrics = self.system_metrics.clone();
         let agents = self.agents.clone();
         let message_broadcaster = self.message_broadcaster.clone();
+        let agent_assignments = self.agent_assignments.clone();
+        // weak so the monitor doesn't keep the storage queue open on shutdown
+        let storage_sender = self.storage_sender.as_ref().map(|sender| sender.downgrade());
+        let monitor_interval = self.monitor_interval;
 
         let monitoring_task = tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(5)); // Update every 5 seconds
+            let mut interval = interval(monitor_interval);
 
             loop {
                 tokio::select! {
@@ -687,6 +2011,12 @@ impl AgentOrchestrator {
                             }
                             total
                         };
+                        let per_streamer_messages = Self::messages_per_streamer(&agents, &agent_assignments).await;
+
+                        let storage_queue_depth = storage_sender
+                            .as_ref()
+                            .and_then(|sender| sender.upgrade())
+                            .map_or(0, |sender| Self::queue_depth(&sender));
 
                         let metrics = SystemMetrics {
                             cpu_usage,
@@ -694,6 +2024,8 @@ impl AgentOrchestrator {
                             memory_total,
                             active_agents,
                             total_messages_scraped: total_messages,
+                            per_streamer_messages,
+                            storage_queue_depth,
                             timestamp: SystemTime::now(),
                         };
 
@@ -727,69 +2059,43 @@ impl AgentOrchestrator {
         Ok(())
     }
 
-    /// Start dynamic scaling background task
-    async fn start_dynamic_scaling(
+    /// Start the task pinging browser instances and recreating crashed ones
+    async fn start_browser_health_checks(
         &mut self,
         mut shutdown_rx: broadcast::Receiver<()>,
     ) -> Result<()> {
-        let system_metrics = self.system_metrics.clone();
-        let agents = self.agents.clone();
-        let config = self.config.clone();
-        let message_broadcaster = self.message_broadcaster.clone();
+        let browser_manager = self.browser_manager.clone();
 
-        let scaling_task = tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(30)); // Check every 30 seconds
+        let browser_health_task = tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(15)); // Ping every 15 seconds
 
             loop {
                 tokio::select! {
                     _ = shutdown_rx.recv() => {
-                        debug!("Dynamic scaling task received shutdown signal");
+                        debug!("Browser health task received shutdown signal");
                         break;
                     }
                     _ = interval.tick() => {
-                        let metrics = system_metrics.read().await.clone();
-                        let config_guard = config.read().await;
-                        let max_concurrent = config_guard.agents.max_concurrent;
-                        drop(config_guard);
-
-                        let current_agents = {
-                            let agents_guard = agents.read().await;
-                            agents_guard.len()
-                        };
-
-                        // scaling decision logic
-                        let memory_usage_percent = (metrics.memory_usage as f64 / metrics.memory_total as f64) * 100.0;
-
-                        // Scale down if resource usage is too high
-                        if (metrics.cpu_usage > 90.0 || memory_usage_percent > 90.0) && current_agents > 1 {
-                            let _ = message_broadcaster.send(AgentMessage::ResourceAlert {
-                                agent_id: uuid::Uuid::nil(),
-                                alert: format!("Resource usage critical - consider scaling down agents. CPU: {:.1}%, Memory: {:.1}%",
-                                              metrics.cpu_usage, memory_usage_percent),
-                            });
-                        }
-
-                        // scale up if resources available
-                        else if metrics.cpu_usage < 50.0 && memory_usage_percent < 60.0 && current_agents < max_concurrent {
-                            debug!("Resources available for scaling up: CPU: {:.1}%, Memory: {:.1}%, Agents: {}/{}",
-                                  metrics.cpu_usage, memory_usage_percent, current_agents, max_concurrent);
+                        let replaced = browser_manager.replace_dead_instances().await;
+                        if replaced > 0 {
+                            info!("Recreated {} crashed browser instances", replaced);
                         }
                     }
                 }
             }
         });
 
-        self.scaling_task = Some(scaling_task);
+        self.browser_health_task = Some(browser_health_task);
         Ok(())
     }
 
     /// Start configuration watcher background task
-    async fn start_config_watcher(
+    pub(crate) async fn start_config_watcher(
         &mut self,
         config_manager: Arc<dyn ConfigManager + Send + Sync>,
         mut shutdown_rx: broadcast::Receiver<()>,
     ) -> Result<()> {
-        let config = self.config.clone();
+        let config_reload_tx = self.config_reload_tx.clone();
         let message_broadcaster = self.message_broadcaster.clone();
 
         let config_watcher_task = tokio::spawn(async move {
@@ -804,11 +2110,8 @@ impl AgentOrchestrator {
                             if let Some(new_config) = new_config {
                                 info!("Configuration updated, applying changes");
 
-                                // update stored configuration
-                                {
-                                    let mut config_guard = config.write().await;
-                                    *config_guard = new_config;
-                                }
+                                // applied by `drive_config_reloads`, only the latest one matters
+                                let _ = config_reload_tx.send(Some(new_config));
 
                                 // broadcast configuration update
                                 let _ = message_broadcaster.send(AgentMessage::ResourceAlert {
@@ -833,48 +2136,758 @@ impl AgentOrchestrator {
     }
 
     /// Start agent recovery background task
-    async fn start_agent_recovery(
+    pub(crate) async fn start_agent_recovery(
+        &mut self,
+        shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to create probe client: {}", e)))?;
+
+        let agent_recovery_task = tokio::spawn(Self::watch_for_errored_agents(
+            self.agents.clone(),
+            self.agent_assignments.clone(),
+            self.config.clone(),
+            client,
+            self.channel_url.clone(),
+            Duration::from_secs(15), // Check every 15 seconds
+            self.recovery_tx.clone(),
+            self.message_broadcaster.clone(),
+            shutdown_rx,
+        ));
+
+        self.agent_recovery_task = Some(agent_recovery_task);
+        Ok(())
+    }
+
+    /// Queue every agent in error state whose restart backoff has run out for
+    /// `recover_agent`, after first erroring running agents that stalled on a live
+    /// channel. The task only holds shared handles, so the restart itself happens
+    /// in `drive_recovery`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn watch_for_errored_agents(
+        agents: Arc<RwLock<HashMap<AgentId, ScrapingAgent>>>,
+        agent_assignments: Arc<RwLock<HashMap<AgentId, AgentAssignment>>>,
+        config: Arc<RwLock<Config>>,
+        client: reqwest::Client,
+        channel_url: String,
+        period: Duration,
+        recovery_tx: mpsc::Sender<AgentId>,
+        message_broadcaster: broadcast::Sender<AgentMessage>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) {
+        let mut interval = interval(period);
+
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.recv() => {
+                    debug!("Agent recovery task received shutdown signal");
+                    break;
+                }
+                _ = interval.tick() => {
+                    let stale_after = FileConfigManager::parse_time_to_duration(&config.read().await.agents.stale_after)
+                        .unwrap_or_default();
+                    Self::error_stalled_agents(&agents, &agent_assignments, &client, &channel_url, stale_after, &message_broadcaster).await;
+
+                    let mut agents_to_restart = Vec::new();
+                    let agents_guard = agents.read().await;
+                    for (agent_id, agent) in agents_guard.iter() {
+                        let status = agent.get_status().await;
+                        if let AgentStatus::Error(_) = status {
+                            agents_to_restart.push(*agent_id);
+                        }
+                    }
+                    drop(agents_guard);
+
+                    // back off from streamers that keep failing instead of hammering them
+                    let now = SystemTime::now();
+                    let assignments = agent_assignments.read().await;
+                    agents_to_restart.retain(|agent_id| match assignments.get(agent_id) {
+                        Some(assignment) if !assignment.restart_due(now) => {
+                            debug!("Agent {} restart backoff not over yet, skipping", agent_id);
+                            false
+                        }
+                        _ => true,
+                    });
+                    drop(assignments);
+
+                    // a full queue means recovery is behind, whatever's left is found again next tick
+                    for agent_id in agents_to_restart {
+                        debug!("Agent {} is in error state, queueing restart", agent_id);
+                        match recovery_tx.try_send(agent_id) {
+                            Ok(()) => {}
+                            Err(mpsc::error::TrySendError::Full(_)) => {
+                                debug!("Agent recovery queue is full, retrying next check");
+                                break;
+                            }
+                            Err(mpsc::error::TrySendError::Closed(_)) => return,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Put running agents that stopped getting messages into error state, but
+    /// only once a probe shows their channel is still live
+    async fn error_stalled_agents(
+        agents: &Arc<RwLock<HashMap<AgentId, ScrapingAgent>>>,
+        agent_assignments: &Arc<RwLock<HashMap<AgentId, AgentAssignment>>>,
+        client: &reqwest::Client,
+        channel_url: &str,
+        stale_after: Duration,
+        message_broadcaster: &broadcast::Sender<AgentMessage>,
+    ) {
+        if stale_after.is_zero() {
+            return;
+        }
+
+        let now = chrono::Utc::now();
+        let mut stalled = Vec::new();
+        {
+            let agents_guard = agents.read().await;
+            let assignments = agent_assignments.read().await;
+            for (agent_id, agent) in agents_guard.iter() {
+                if is_stalled(&agent.get_metrics().await, stale_after, now) {
+                    if let Some(assignment) = assignments.get(agent_id) {
+                        stalled.push((*agent_id, assignment.streamer.clone()));
+                    }
+                }
+            }
+        }
+
+        for (agent_id, streamer) in stalled {
+            match Self::probe_stream_live(client, channel_url, &streamer).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    debug!("Agent {} is quiet but {} is offline, leaving it to the idle monitor", agent_id, streamer);
+                    continue;
+                }
+                Err(e) => {
+                    debug!("Failed to probe stream {} for stalled agent {}: {}", streamer, agent_id, e);
+                    continue;
+                }
+            }
+
+            let reason = format!("no messages for {}s while {} is live", stale_after.as_secs(), streamer);
+            warn!("Agent {} looks stalled: {}", agent_id, reason);
+            if let Some(agent) = agents.read().await.get(&agent_id) {
+                agent.set_status(AgentStatus::Error(format!("stalled, {}", reason))).await;
+            }
+            let _ = message_broadcaster.send(AgentMessage::ResourceAlert {
+                agent_id,
+                alert: format!("Agent looks stalled, {}; restarting it", reason),
+            });
+        }
+    }
+
+    /// Start idle channel monitor background task
+    async fn start_idle_monitor(
         &mut self,
         mut shutdown_rx: broadcast::Receiver<()>,
     ) -> Result<()> {
         let agents = self.agents.clone();
-        let _agent_assignments = self.agent_assignments.clone();
-        let _message_broadcaster = self.message_broadcaster.clone();
+        let agent_assignments = self.agent_assignments.clone();
+        let config = self.config.clone();
+        let message_broadcaster = self.message_broadcaster.clone();
+        let channel_url = self.channel_url.clone();
 
-        let agent_recovery_task = tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to create probe client: {}", e)))?;
+
+        let idle_monitor_task = tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(15)); // Check every 15 seconds
+            let mut last_probe: HashMap<AgentId, Instant> = HashMap::new();
+            let mut paused_until: HashMap<AgentId, Instant> = HashMap::new(); // rate limited probes
 
             loop {
                 tokio::select! {
                     _ = shutdown_rx.recv() => {
-                        debug!("Agent recovery task received shutdown signal");
+                        debug!("Idle monitor task received shutdown signal");
                         break;
                     }
                     _ = interval.tick() => {
-                        let mut agents_to_restart = Vec::new();
-                        let agents_guard = agents.read().await;
-                        for (agent_id, agent) in agents_guard.iter() {
-                            let status = agent.get_status().await;
-                            if let AgentStatus::Error(_) = status {
-                                agents_to_restart.push(*agent_id);
+                        let (idle_timeout, probe_interval) = {
+                            let config_guard = config.read().await;
+                            (
+                                FileConfigManager::parse_time_to_duration(&config_guard.agents.idle_timeout)
+                                    .unwrap_or_default(),
+                                FileConfigManager::parse_time_to_duration(&config_guard.agents.idle_probe_interval)
+                                    .unwrap_or(Duration::from_secs(120)),
+                            )
+                        };
+
+                        // a zero timeout disables idling
+                        if idle_timeout.is_zero() {
+                            continue;
+                        }
+
+                        // park running agents that have gone quiet
+                        {
+                            let mut agents_guard = agents.write().await;
+                            for (agent_id, agent) in agents_guard.iter_mut() {
+                                if agent.get_status().await != AgentStatus::Running {
+                                    continue;
+                                }
+
+                                let idle_for = agent.idle_duration().await;
+                                if idle_for < idle_timeout {
+                                    continue;
+                                }
+
+                                info!(
+                                    "Agent {} has seen no messages for {:?}, marking channel idle",
+                                    agent_id, idle_for
+                                );
+                                if let Err(e) = agent.go_idle().await {
+                                    warn!("Failed to idle agent {}: {}", agent_id, e);
+                                    let _ = message_broadcaster.send(AgentMessage::Error {
+                                        agent_id: *agent_id,
+                                        error: format!("failed to go idle: {}", e),
+                                    });
+                                    continue;
+                                }
+
+                                if let Some(assignment) = agent_assignments.write().await.get_mut(agent_id) {
+                                    assignment.idle_since = Some(SystemTime::now());
+                                }
+                                last_probe.insert(*agent_id, Instant::now());
+
+                                let _ = message_broadcaster.send(AgentMessage::StatusUpdate {
+                                    agent_id: *agent_id,
+                                    status: AgentStatus::Idle,
+                                });
                             }
                         }
-                        drop(agents_guard);
 
-                        for agent_id in agents_to_restart {
-                            warn!("Agent {} is in error state, attempting to restart", agent_id);
-                            // this is simplified restart, real would need more logic
-                            // to manage agents and streamers.
+                        // probe idle channels and bring back the ones that went live
+                        let due_for_probe: Vec<(AgentId, String)> = {
+                            let assignments = agent_assignments.read().await;
+                            assignments
+                                .values()
+                                .filter(|a| a.idle_since.is_some())
+                                .filter(|a| paused_until.get(&a.agent_id).is_none_or(|until| Instant::now() >= *until))
+                                .filter(|a| {
+                                    last_probe
+                                        .get(&a.agent_id)
+                                        .map(|probed_at| probed_at.elapsed() >= probe_interval)
+                                        .unwrap_or(true)
+                                })
+                                .map(|a| (a.agent_id, a.streamer.clone()))
+                                .collect()
+                        };
+
+                        for (agent_id, streamer) in due_for_probe {
+                            last_probe.insert(agent_id, Instant::now());
+
+                            match Self::probe_stream_live(&client, &channel_url, &streamer).await {
+                                Ok(true) => {
+                                    info!("Stream {} is live again, resuming agent {}", streamer, agent_id);
+                                    Self::resume_idle_agent(&agents, &agent_assignments, &message_broadcaster, agent_id, &streamer).await;
+                                    last_probe.remove(&agent_id);
+                                }
+                                Ok(false) => {
+                                    debug!("Stream {} still offline, agent {} stays idle", streamer, agent_id);
+                                }
+                                Err(e) => match ScrapingError::rate_limit_pause(e.as_ref()) {
+                                    Some(pause) => {
+                                        warn!("Rate limited probing {}, pausing probes for {}s", streamer, pause.as_secs());
+                                        paused_until.insert(agent_id, Instant::now() + pause);
+                                        let _ = message_broadcaster.send(AgentMessage::RateLimited {
+                                            agent_id,
+                                            streamer: streamer.clone(),
+                                            pause,
+                                        });
+                                    }
+                                    None => debug!("Failed to probe stream {}: {}", streamer, e),
+                                },
+                            }
                         }
+
+                        // forget probe times for agents that no longer exist
+                        let assignments = agent_assignments.read().await;
+                        last_probe.retain(|agent_id, _| assignments.contains_key(agent_id));
+                        paused_until.retain(|agent_id, until| assignments.contains_key(agent_id) && Instant::now() < *until);
                     }
                 }
             }
         });
 
-        self.agent_recovery_task = Some(agent_recovery_task);
+        self.idle_monitor_task = Some(idle_monitor_task);
+        Ok(())
+    }
+
+    /// Start the task raising a `ResourceAlert` when an agent's errors cross the
+    /// configured count or rate threshold
+    async fn start_error_alerting(
+        &mut self,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let agents = self.agents.clone();
+        let agent_assignments = self.agent_assignments.clone();
+        let agent_events = self.agent_events.clone();
+        let config = self.config.clone();
+        let message_broadcaster = self.message_broadcaster.clone();
+
+        let error_alert_task = tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(15)); // Check every 15 seconds
+            let mut policy = ErrorAlertPolicy::new(0, 0.0, Duration::from_secs(900));
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        debug!("Error alert task received shutdown signal");
+                        break;
+                    }
+                    _ = interval.tick() => {
+                        policy.update_thresholds(&config.read().await.agents);
+
+                        let mut agent_errors = Vec::new();
+                        {
+                            let agents_guard = agents.read().await;
+                            for (agent_id, agent) in agents_guard.iter() {
+                                let metrics = agent.get_metrics().await;
+                                agent_errors.push((*agent_id, metrics.error_count, metrics.uptime, metrics.status));
+                            }
+                        }
+
+                        for (agent_id, error_count, uptime, status) in agent_errors {
+                            let Some(streamer) = agent_assignments.read().await
+                                .get(&agent_id)
+                                .map(|assignment| assignment.streamer.clone()) else {
+                                continue;
+                            };
+                            let Some(reason) = policy.check(&streamer, error_count, uptime) else {
+                                continue;
+                            };
+
+                            let last_error = agent_events.read().await
+                                .recent(agent_id, MAX_EVENTS_PER_AGENT)
+                                .and_then(|events| events.into_iter().rev().find(|e| e.kind == AgentEventKind::Error))
+                                .map(|event| event.message)
+                                .or(match status {
+                                    AgentStatus::Error(error) => Some(error),
+                                    _ => None,
+                                })
+                                .unwrap_or_else(|| "none recorded".to_string());
+
+                            warn!("Agent {} for {} crossed the error threshold: {}", agent_id, streamer, reason);
+                            let _ = message_broadcaster.send(AgentMessage::ResourceAlert {
+                                agent_id,
+                                alert: format!("Agent for {} has {}, last error: {}", streamer, reason, last_error),
+                            });
+                        }
+                    }
+                }
+            }
+        });
+
+        self.error_alert_task = Some(error_alert_task);
         Ok(())
     }
 
+    /// Send a lifecycle alert if notifications are enabled and a webhook is configured
+    async fn notify_lifecycle(&self, level: &str, title: &str, message: &str) {
+        let Some(ref webhook_manager) = self.webhook_manager else {
+            return;
+        };
+        if self.config.read().await.monitoring.lifecycle_notifications {
+            let _ = webhook_manager.send_alert(level, title, message).await;
+        }
+    }
+
+    /// Start the task alerting once per agent when it enters the error state, and
+    /// on every rate limit pause
+    async fn start_lifecycle_notifications(
+        &mut self,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let Some(webhook_manager) = self.webhook_manager.clone() else {
+            return Ok(());
+        };
+        let agents = self.agents.clone();
+        let agent_assignments = self.agent_assignments.clone();
+        let mut message_rx = self.message_broadcaster.subscribe();
+
+        let lifecycle_task = tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(15)); // Check every 15 seconds
+            let mut reported: HashSet<AgentId> = HashSet::new();
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        debug!("Lifecycle notification task received shutdown signal");
+                        break;
+                    }
+                    message = message_rx.recv() => {
+                        match message {
+                            Ok(AgentMessage::RateLimited { streamer, pause, .. }) => {
+                                let _ = webhook_manager.send_alert(
+                                    "rate_limited",
+                                    "Rate limited",
+                                    &format!("Twitch rate limited {}, pausing for {}s", streamer, pause.as_secs()),
+                                ).await;
+                            }
+                            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                    _ = interval.tick() => {
+                        let mut errored = Vec::new();
+                        {
+                            let agents_guard = agents.read().await;
+                            for (agent_id, agent) in agents_guard.iter() {
+                                if let AgentStatus::Error(error) = agent.get_status().await {
+                                    errored.push((*agent_id, error));
+                                }
+                            }
+                        }
+
+                        // forget agents that recovered or were stopped so a later failure alerts again
+                        reported.retain(|agent_id| errored.iter().any(|(id, _)| id == agent_id));
+
+                        for (agent_id, error) in errored {
+                            if !reported.insert(agent_id) {
+                                continue;
+                            }
+                            let streamer = agent_assignments.read().await
+                                .get(&agent_id)
+                                .map(|assignment| assignment.streamer.clone())
+                                .unwrap_or_else(|| agent_id.to_string());
+                            let _ = webhook_manager.send_alert(
+                                "warning",
+                                "Agent error",
+                                &format!("Agent {} entered error state: {}", streamer, error),
+                            ).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        self.lifecycle_task = Some(lifecycle_task);
+        Ok(())
+    }
+
+    /// Start the task feeding every scraped message to the webhook manager, and
+    /// the batch flusher when batching is on. Does nothing without providers.
+    async fn start_webhook_forwarding(&mut self, shutdown_rx: broadcast::Receiver<()>) -> Result<()> {
+        let Some(webhook_manager) = self.webhook_manager.clone() else {
+            return Ok(());
+        };
+        if !webhook_manager.has_providers() {
+            return Ok(());
+        }
+
+        let flush_shutdown_rx = shutdown_rx.resubscribe();
+        let chat_rx = self.chat_message_broadcaster.subscribe();
+        let batch_rx = self.chat_batch_broadcaster.subscribe();
+        self.webhook_forward_task = Some(tokio::spawn(Self::forward_to_webhooks(
+            webhook_manager.clone(),
+            chat_rx,
+            batch_rx,
+            shutdown_rx,
+        )));
+        self.webhook_flush_task = webhook_manager.start_background_delivery(flush_shutdown_rx);
+        Ok(())
+    }
+
+    /// Hand each broadcast message to the webhook manager until shutdown. A slow
+    /// webhook makes the receivers lag, the skipped messages are logged and dropped.
+    pub(crate) async fn forward_to_webhooks(
+        webhook_manager: Arc<WebhookManager>,
+        mut chat_rx: broadcast::Receiver<ChatMessage>,
+        mut batch_rx: broadcast::Receiver<Vec<ChatMessage>>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) {
+        let mut batches_open = true;
+        loop {
+            let messages = tokio::select! {
+                _ = shutdown_rx.recv() => {
+                    debug!("Webhook forwarding task received shutdown signal");
+                    break;
+                }
+                message = chat_rx.recv() => match message {
+                    Ok(message) => vec![message],
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Webhook forwarding fell behind, dropped {} messages", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                batch = batch_rx.recv(), if batches_open => match batch {
+                    Ok(batch) => batch,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Webhook forwarding fell behind, dropped {} batches", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        batches_open = false;
+                        continue;
+                    }
+                },
+            };
+
+            for message in &messages {
+                if let Err(e) = webhook_manager.queue_message(message).await {
+                    warn!("Failed to forward message {} to webhooks: {}", message.id, e);
+                }
+            }
+        }
+    }
+
+    /// Start channel quality tracking background task
+    async fn start_channel_quality_tracking(
+        &mut self,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let channel_quality = self.channel_quality.clone();
+        let mut chat_rx = self.chat_message_broadcaster.subscribe();
+        let mut batch_rx = self.chat_batch_broadcaster.subscribe();
+
+        let channel_quality_task = tokio::spawn(async move {
+            let mut tracker = ChannelQualityTracker::new(chrono::Duration::minutes(5)); // 5 minute rolling window
+            let mut interval = interval(Duration::from_secs(10)); // Publish every 10 seconds
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        debug!("Channel quality task received shutdown signal");
+                        break;
+                    }
+                    message = chat_rx.recv() => {
+                        match message {
+                            Ok(message) => tracker.record_message(&message),
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                debug!("Channel quality tracker lagged, skipped {} messages", skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                    batch = batch_rx.recv() => {
+                        match batch {
+                            Ok(batch) => {
+                                for message in &batch {
+                                    tracker.record_message(message);
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                debug!("Channel quality tracker lagged, skipped {} batches", skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                    _ = interval.tick() => {
+                        tracker.prune(chrono::Utc::now());
+                        let mut channel_quality_guard = channel_quality.write().await;
+                        *channel_quality_guard = tracker.all_channel_quality();
+                    }
+                }
+            }
+        });
+
+        self.channel_quality_task = Some(channel_quality_task);
+        Ok(())
+    }
+
+    /// Start the task recording agent events into the per-agent history
+    async fn start_agent_event_tracking(
+        &mut self,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let agent_events = self.agent_events.clone();
+        let mut message_rx = self.message_broadcaster.subscribe();
+
+        let agent_event_task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        debug!("Agent event task received shutdown signal");
+                        break;
+                    }
+                    message = message_rx.recv() => {
+                        match message {
+                            Ok(message) => agent_events.write().await.record(&message),
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                debug!("Agent event tracker lagged, skipped {} messages", skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                }
+            }
+        });
+
+        self.agent_event_task = Some(agent_event_task);
+        Ok(())
+    }
+
+    /// Start the task draining the storage ingest queue into the storage manager
+    pub(crate) async fn start_storage_writer(&mut self) -> Result<()> {
+        let storage_manager = self
+            .storage_manager
+            .clone()
+            .ok_or_else(|| ScrapingError::StorageError("No storage manager configured".to_string()))?;
+        let capacity = self.config.read().await.output.ingest_capacity;
+        let error_count = self.error_count.clone();
+        let storage_counters = self.storage_counters.clone();
+
+        storage_manager.setup_rotation().await?;
+
+        let (storage_tx, mut storage_rx) = mpsc::channel::<ChatMessage>(capacity);
+        self.storage_sender = Some(storage_tx);
+
+        let storage_task = tokio::spawn(async move {
+            // runs until every sender is dropped, so queued messages are always written
+            while let Some(message) = storage_rx.recv().await {
+                let mut batch = vec![message];
+                while batch.len() < capacity {
+                    match storage_rx.try_recv() {
+                        Ok(message) => batch.push(message),
+                        Err(_) => break,
+                    }
+                }
+
+                let batch_len = batch.len();
+                match storage_manager.store_messages(batch).await {
+                    Ok(()) => {
+                        storage_counters.written.fetch_add(batch_len as u64, Ordering::Relaxed);
+                    }
+                    Err(e) => {
+                        error!("Failed to store {} messages: {}", batch_len, e);
+                        storage_counters.failed.fetch_add(batch_len as u64, Ordering::Relaxed);
+                        *error_count.write().await += 1;
+                    }
+                }
+            }
+
+            debug!("Storage writer drained and stopped");
+        });
+
+        self.storage_task = Some(storage_task);
+        Ok(())
+    }
+
+    /// Start hourly summary writer background task
+    async fn start_summary_writer(
+        &mut self,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let config = self.config.read().await;
+        let timezone: chrono_tz::Tz = config.output.timezone.parse()
+            .map_err(|e| ScrapingError::ConfigError(format!("Invalid timezone '{}': {}", config.output.timezone, e)))?;
+        let mut writer = SummaryWriter::new(config.output.directory.clone(), timezone);
+        drop(config);
+
+        let mut chat_rx = self.chat_message_broadcaster.subscribe();
+        let mut batch_rx = self.chat_batch_broadcaster.subscribe();
+
+        let summary_task = tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(30)); // Close finished hours every 30 seconds
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        debug!("Summary writer received shutdown signal");
+                        break;
+                    }
+                    message = chat_rx.recv() => {
+                        match message {
+                            Ok(message) => {
+                                if let Err(e) = writer.record(&message) {
+                                    warn!("Failed to write hourly summary: {}", e);
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                debug!("Summary writer lagged, skipped {} messages", skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                    batch = batch_rx.recv() => {
+                        match batch {
+                            Ok(batch) => {
+                                for message in &batch {
+                                    if let Err(e) = writer.record(message) {
+                                        warn!("Failed to write hourly summary: {}", e);
+                                    }
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                debug!("Summary writer lagged, skipped {} batches", skipped);
+                            }
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                    _ = interval.tick() => {
+                        if let Err(e) = writer.flush_due(chrono::Utc::now()) {
+                            warn!("Failed to write hourly summary: {}", e);
+                        }
+                    }
+                }
+            }
+
+            // write out the hours still in progress
+            match writer.flush_all() {
+                Ok(written) => debug!("Wrote {} partial hourly summaries on shutdown", written),
+                Err(e) => warn!("Failed to write hourly summaries on shutdown: {}", e),
+            }
+        });
+
+        self.summary_task = Some(summary_task);
+        Ok(())
+    }
+
+    /// Check whether a streamer's channel page reports a live broadcast
+    async fn probe_stream_live(client: &reqwest::Client, channel_url: &str, streamer: &str) -> Result<bool> {
+        let url = format!("{}/{}", channel_url, streamer);
+        let response = client.get(&url).send().await?;
+        if let Some(rate_limited) = ScrapingError::from_rate_limited_response(&response) {
+            return Err(rate_limited.into());
+        }
+        let body = response.text().await?;
+        Ok(body.contains("\"isLiveBroadcast\":true") || body.contains("\"isLive\":true"))
+    }
+
+    /// Restart an idle agent on its streamer once the channel is back
+    async fn resume_idle_agent(
+        agents: &Arc<RwLock<HashMap<AgentId, ScrapingAgent>>>,
+        agent_assignments: &Arc<RwLock<HashMap<AgentId, AgentAssignment>>>,
+        message_broadcaster: &broadcast::Sender<AgentMessage>,
+        agent_id: AgentId,
+        streamer: &str,
+    ) {
+        // take the agent out so the map isn't locked while the browser starts
+        let agent = agents.write().await.remove(&agent_id);
+        let Some(mut agent) = agent else {
+            return;
+        };
+
+        let status = match tokio::time::timeout(Duration::from_secs(30), agent.start(streamer)).await {
+            Ok(Ok(_)) => AgentStatus::Running,
+            Ok(Err(e)) => {
+                warn!("Failed to resume idle agent {} for {}: {}", agent_id, streamer, e);
+                agent.get_status().await
+            }
+            Err(_) => {
+                warn!("Resuming idle agent {} for {} timed out", agent_id, streamer);
+                let status = AgentStatus::Error("Resume from idle timed out".to_string());
+                agent.set_status(status.clone()).await;
+                status
+            }
+        };
+
+        agents.write().await.insert(agent_id, agent);
+        if let Some(assignment) = agent_assignments.write().await.get_mut(&agent_id) {
+            assignment.idle_since = None;
+        }
+
+        let _ = message_broadcaster.send(AgentMessage::StatusUpdate { agent_id, status });
+    }
+
     /// Increment error counter
     async fn increment_error_count(&self) {
         let mut error_count = self.error_count.write().await;
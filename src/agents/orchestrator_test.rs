@@ -1,25 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::agents::{AgentOrchestrator};
     use crate::config::{Config, FileConfigManager, ConfigManager};
-    use std::sync::Arc;
     use tempfile::tempdir;
 
-    // create a mock orchestrator without browser manager for testing
-    fn create_mock_orchestrator() -> AgentOrchestrator {
-        let config = Config::default();
-        
-        // create a mock browser manager - we'll use a placeholder arc
-        // in a real test environment, we'd use a mock browser manager
-        let mock_browser_manager = Arc::new(
-            // this will fail in tests, but that's expected for unit tests
-            // in integration tests, we'd use a real browser manager
-            unsafe { std::mem::zeroed() }
-        );
-        
-        AgentOrchestrator::new(config, mock_browser_manager)
-    }
-
     #[tokio::test]
     async fn test_orchestrator_creation() {
         // test basic orchestrator creation without browser dependencies
@@ -31,11 +14,32 @@ mod tests {
         assert_eq!(config.streamers, vec!["shroud", "ninja"]); // Default streamers
     }
 
+    #[tokio::test]
+    async fn test_custom_monitoring_intervals() {
+        use crate::agents::orchestrator::AgentOrchestrator;
+        use crate::browser::BrowserManager;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let defaults = AgentOrchestrator::new(Config::default(), Arc::new(BrowserManager::without_browser(1)));
+        assert_eq!(defaults.monitor_interval(), Duration::from_secs(5));
+        assert_eq!(defaults.scale_interval(), Duration::from_secs(30));
+
+        let mut config = Config::default();
+        config.monitoring.monitor_interval = "1m".to_string();
+        config.monitoring.scale_interval = "10m".to_string();
+        let orchestrator = AgentOrchestrator::new(config, Arc::new(BrowserManager::without_browser(1)));
+        assert_eq!(orchestrator.monitor_interval(), Duration::from_secs(60));
+        assert_eq!(orchestrator.scale_interval(), Duration::from_secs(600));
+    }
+
     #[tokio::test]
     async fn test_config_update() {
         // test configuration update logic without browser dependencies
-        let mut config = Config::default();
-        config.streamers = vec!["newstreamer".to_string()];
+        let mut config = Config {
+            streamers: vec!["newstreamer".to_string()],
+            ..Config::default()
+        };
         config.agents.max_concurrent = 3;
         
         // Verify config values
@@ -63,7 +67,7 @@ mod tests {
     #[tokio::test]
     async fn test_system_metrics_structure() {
         use crate::agents::SystemMetrics;
-        use std::time::Instant;
+        use std::time::SystemTime;
         
         // test systemmetrics structure
         let metrics = SystemMetrics {
@@ -72,7 +76,9 @@ mod tests {
             memory_total: 8 * 1024 * 1024 * 1024, // 8GB
             active_agents: 3,
             total_messages_scraped: 1000,
-            timestamp: Instant::now(),
+            per_streamer_messages: std::collections::HashMap::new(),
+            storage_queue_depth: 0,
+            timestamp: SystemTime::now(),
         };
         
         assert_eq!(metrics.cpu_usage, 50.0);
@@ -85,15 +91,19 @@ mod tests {
     #[tokio::test]
     async fn test_agent_assignment_structure() {
         use crate::agents::AgentAssignment;
-        use std::time::Instant;
+        use std::time::SystemTime;
         use uuid::Uuid;
         
         // test agentassignment structure
         let assignment = AgentAssignment {
             agent_id: Uuid::new_v4(),
             streamer: "teststreamer".to_string(),
-            assigned_at: Instant::now(),
+            assigned_at: SystemTime::now(),
             priority: 1,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
         };
         
         assert_eq!(assignment.streamer, "teststreamer");
@@ -103,7 +113,7 @@ mod tests {
     #[tokio::test]
     async fn test_orchestrator_status_structure() {
         use crate::agents::{OrchestratorStatus, SystemMetrics};
-        use std::time::{Duration, Instant};
+        use std::time::{Duration, SystemTime};
         
         // test orchestratorstatus structure
         let system_metrics = SystemMetrics {
@@ -112,19 +122,24 @@ mod tests {
             memory_total: 16 * 1024 * 1024 * 1024, // 16GB
             active_agents: 2,
             total_messages_scraped: 500,
-            timestamp: Instant::now(),
+            per_streamer_messages: std::collections::HashMap::new(),
+            storage_queue_depth: 0,
+            timestamp: SystemTime::now(),
         };
         
         let status = OrchestratorStatus {
             active_agents: 2,
+            idle_agents: 1,
             total_agents_spawned: 5,
             system_metrics,
             agent_assignments: vec![],
             error_count: 1,
             uptime: Duration::from_secs(3600), // 1 hour
+            failed_streamers: vec![],
         };
         
         assert_eq!(status.active_agents, 2);
+        assert_eq!(status.idle_agents, 1);
         assert_eq!(status.total_agents_spawned, 5);
         assert_eq!(status.error_count, 1);
         assert_eq!(status.uptime.as_secs(), 3600);
@@ -157,26 +172,23 @@ mod tests {
         // verify message types can be created
         match status_update {
             AgentMessage::StatusUpdate { agent_id: _, status } => {
-                match status {
-                    AgentStatus::Running => assert!(true),
-                    _ => assert!(false, "Expected Running status"),
-                }
+                assert!(matches!(status, AgentStatus::Running), "Expected Running status");
             }
-            _ => assert!(false, "Expected StatusUpdate message"),
+            _ => panic!("Expected StatusUpdate message"),
         }
         
         match resource_alert {
             AgentMessage::ResourceAlert { agent_id: _, alert } => {
                 assert_eq!(alert, "High CPU usage");
             }
-            _ => assert!(false, "Expected ResourceAlert message"),
+            _ => panic!("Expected ResourceAlert message"),
         }
         
         match error_message {
             AgentMessage::Error { agent_id: _, error } => {
                 assert_eq!(error, "Connection failed");
             }
-            _ => assert!(false, "Expected Error message"),
+            _ => panic!("Expected Error message"),
         }
     }
 
@@ -206,4 +218,774 @@ mod tests {
         invalid_config.agents.delay_range = (5000, 1000);
         assert!(manager.validate_config(&invalid_config).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_startup_backoff() {
+        use crate::agents::orchestrator::startup_backoff;
+        use std::time::Duration;
+
+        // first retry waits somewhere in the delay range
+        let first = startup_backoff(1, (1000, 2000));
+        assert!(first >= Duration::from_millis(1000) && first <= Duration::from_millis(2000));
+
+        // later retries double
+        assert_eq!(startup_backoff(3, (500, 500)), Duration::from_millis(2000));
+
+        // and never exceed a minute
+        assert_eq!(startup_backoff(10, (30000, 30000)), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_error_alert_policy() {
+        use crate::agents::orchestrator::ErrorAlertPolicy;
+        use std::time::Duration;
+
+        let mut policy = ErrorAlertPolicy::new(10, 0.0, Duration::from_secs(900));
+        assert!(policy.check("shroud", 9, Duration::from_secs(30)).is_none());
+        assert_eq!(policy.check("shroud", 10, Duration::from_secs(30)), Some("10 errors".to_string()));
+
+        // the restarted agent for the same channel stays quiet during the cooldown
+        assert!(policy.check("shroud", 12, Duration::from_secs(30)).is_none());
+        assert!(policy.check("ninja", 12, Duration::from_secs(30)).is_some());
+
+        // rate threshold only kicks in after the first minute
+        let mut policy = ErrorAlertPolicy::new(0, 2.0, Duration::ZERO);
+        assert!(policy.check("shroud", 5, Duration::from_secs(30)).is_none());
+        assert_eq!(policy.check("shroud", 5, Duration::from_secs(120)), Some("2.5 errors/min".to_string()));
+        assert!(policy.check("shroud", 5, Duration::from_secs(120)).is_some());
+    }
+
+    #[test]
+    fn test_shutdown_summary_message() {
+        use crate::agents::ShutdownSummary;
+
+        let clean = ShutdownSummary {
+            agents_stopped: 3,
+            agents_failed: 0,
+            messages_flushed: 1200,
+            messages_unwritten: 0,
+            files_closed: 3,
+        };
+        assert_eq!(clean.to_string(), "All 1200 messages persisted, 3 files closed, 3 agents stopped");
+
+        let lossy = ShutdownSummary {
+            agents_failed: 1,
+            messages_unwritten: 40,
+            ..clean
+        };
+        assert_eq!(
+            lossy.to_string(),
+            "1200 messages persisted, 40 could not be written, 3 files closed, 3 agents stopped, 1 agents failed to stop cleanly"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_storage_queue_applies_backpressure() {
+        use crate::agents::orchestrator::AgentOrchestrator;
+        use crate::browser::BrowserManager;
+        use crate::error::Result;
+        use crate::parser::fixtures::chat_message;
+        use crate::parser::ChatMessage;
+        use crate::storage::{MemoryStorageManager, StorageManager, StorageStats};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio::sync::Semaphore;
+
+        // stores one batch per permit, so the writer stalls until the test lets it go
+        struct GatedStorage {
+            gate: Semaphore,
+            stored: MemoryStorageManager,
+        }
+
+        #[async_trait::async_trait]
+        impl StorageManager for GatedStorage {
+            async fn store_messages(&self, messages: Vec<ChatMessage>) -> Result<()> {
+                self.gate.acquire().await?.forget();
+                self.stored.store_messages(messages).await
+            }
+
+            async fn setup_rotation(&self) -> Result<()> {
+                Ok(())
+            }
+
+            async fn get_storage_stats(&self) -> Result<StorageStats> {
+                self.stored.get_storage_stats().await
+            }
+        }
+
+        let storage = Arc::new(GatedStorage { gate: Semaphore::new(0), stored: MemoryStorageManager::new() });
+        let mut config = Config::default();
+        config.output.ingest_capacity = 2;
+        let mut orchestrator = AgentOrchestrator::new(config, Arc::new(BrowserManager::without_browser(1)))
+            .with_storage_manager(storage.clone());
+        orchestrator.start_storage_writer().await.unwrap();
+        let sender = orchestrator.storage_sender().unwrap();
+
+        // the writer takes the first batch and blocks on it, the next two fill the queue
+        for text in ["one", "two"] {
+            sender.send(chat_message(text)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        for text in ["three", "four"] {
+            sender.send(chat_message(text)).await.unwrap();
+        }
+        assert_eq!(orchestrator.get_storage_queue_depth(), 2);
+
+        // another message waits for room instead of being dropped
+        let waiting_sender = sender.clone();
+        let waiting = tokio::spawn(async move { waiting_sender.send(chat_message("five")).await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        assert!(storage.stored.is_empty().await);
+
+        storage.gate.add_permits(10);
+        waiting.await.unwrap().unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while storage.stored.len().await < 5 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("every queued message is stored once the writer catches up");
+        let texts: Vec<String> = storage.stored.messages().await.into_iter().map(|message| message.message.text).collect();
+        assert_eq!(texts, ["one", "two", "three", "four", "five"]);
+    }
+
+    #[tokio::test]
+    async fn test_errored_agents_queued_for_restart() {
+        use crate::agents::{AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio::sync::{broadcast, mpsc, RwLock};
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let crashed = ScrapingAgent::new((100, 200), chat_tx.clone()).unwrap();
+        crashed.set_status(AgentStatus::Error("page crashed".to_string())).await;
+        let healthy = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        healthy.set_status(AgentStatus::Running).await;
+        let crashed_id = crashed.id;
+
+        let agents = Arc::new(RwLock::new(HashMap::from([(crashed.id, crashed), (healthy.id, healthy)])));
+        let (recovery_tx, mut recovery_rx) = mpsc::channel(8);
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let (message_tx, _) = broadcast::channel(10);
+        let task = tokio::spawn(AgentOrchestrator::watch_for_errored_agents(
+            agents,
+            Arc::new(RwLock::new(HashMap::new())),
+            Arc::new(RwLock::new(Config::default())),
+            reqwest::Client::new(),
+            "http://127.0.0.1:9".to_string(),
+            Duration::from_millis(20),
+            recovery_tx,
+            message_tx,
+            shutdown_rx,
+        ));
+
+        let queued = tokio::time::timeout(Duration::from_secs(1), recovery_rx.recv()).await.unwrap();
+        assert_eq!(queued, Some(crashed_id));
+
+        let _ = shutdown_tx.send(());
+        task.await.unwrap();
+        while let Ok(agent_id) = recovery_rx.try_recv() {
+            assert_eq!(agent_id, crashed_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_errored_agent_recovered_by_orchestrator() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use std::sync::Arc;
+        use std::time::SystemTime;
+        use tokio::sync::broadcast;
+
+        let mut config = Config::default();
+        config.agents.retry_attempts = 2;
+        let mut orchestrator = AgentOrchestrator::new(config, Arc::new(BrowserManager::without_browser(1)));
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        agent.set_status(AgentStatus::Error("page crashed".to_string())).await;
+        let agent_id = agent.id;
+        orchestrator.insert_agent(agent, AgentAssignment {
+            agent_id,
+            streamer: "shroud".to_string(),
+            assigned_at: SystemTime::now(),
+            priority: 0,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
+        }).await;
+
+        // no browser to restart on, so the streamer stays errored with one restart used
+        assert!(orchestrator.recover_agent(agent_id).await.is_err());
+        let Some(AgentStatus::Error(error)) = orchestrator.get_agent_status(agent_id).await else {
+            panic!("a failed restart should leave the streamer in error state");
+        };
+        assert!(error.starts_with("restart failed"), "{}", error);
+        let assignment = orchestrator.agent_assignments.read().await.get(&agent_id).cloned().unwrap();
+        assert_eq!(assignment.streamer, "shroud");
+        assert_eq!(assignment.retry_attempts, 1);
+        assert!(assignment.last_failure.is_some());
+
+        assert!(orchestrator.get_failed_streamers().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_restart_keeps_the_agents_proxy() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use std::sync::Arc;
+        use std::time::SystemTime;
+        use tokio::sync::broadcast;
+
+        let proxies = vec!["10.0.0.1:8080".to_string(), "10.0.0.2:8080".to_string(), "10.0.0.3:8080".to_string()];
+        let browser_manager = Arc::new(BrowserManager::without_browser(3).with_proxy_list(proxies.clone()));
+        let mut config = Config::default();
+        config.agents.retry_attempts = 0;
+        let mut orchestrator = AgentOrchestrator::new(config, browser_manager.clone());
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap().with_proxy(Some(proxies[1].clone()));
+        agent.set_status(AgentStatus::Error("page crashed".to_string())).await;
+        let agent_id = agent.id;
+        orchestrator.insert_agent(agent, AgentAssignment {
+            agent_id,
+            streamer: "shroud".to_string(),
+            assigned_at: SystemTime::now(),
+            priority: 0,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: Some(proxies[1].clone()),
+        }).await;
+
+        // rotation moves on between restarts, the streamer stays pinned to its proxy
+        for _ in 0..2 {
+            assert!(browser_manager.choose_proxy(None).await.is_some());
+            assert!(orchestrator.restart_agent(agent_id).await.is_err());
+            let assignment = orchestrator.agent_assignments.read().await.get(&agent_id).cloned().unwrap();
+            assert_eq!(assignment.proxy.as_deref(), Some(proxies[1].as_str()));
+            assert_eq!(browser_manager.choose_proxy(assignment.proxy.as_deref()).await, Some(proxies[1].clone()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manual_restart_keeps_the_restart_count() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use std::sync::Arc;
+        use std::time::SystemTime;
+        use tokio::sync::broadcast;
+
+        let mut config = Config::default();
+        config.agents.retry_attempts = 0;
+        let mut orchestrator = AgentOrchestrator::new(config, Arc::new(BrowserManager::without_browser(1)));
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        agent.set_status(AgentStatus::Running).await;
+        let agent_id = agent.id;
+        orchestrator.insert_agent(agent, AgentAssignment {
+            agent_id,
+            streamer: "shroud".to_string(),
+            assigned_at: SystemTime::now(),
+            priority: 0,
+            retry_attempts: 1,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
+        }).await;
+
+        // an operator's restart isn't a failure, even when it can't start
+        assert!(orchestrator.manual_restart_agent(agent_id).await.is_err());
+        let assignment = orchestrator.agent_assignments.read().await.get(&agent_id).cloned().unwrap();
+        assert_eq!(assignment.retry_attempts, 1);
+        assert!(assignment.last_failure.is_none());
+
+        assert!(orchestrator.restart_agent(agent_id).await.is_err());
+        let assignment = orchestrator.agent_assignments.read().await.get(&agent_id).cloned().unwrap();
+        assert_eq!(assignment.retry_attempts, 2);
+        assert!(assignment.last_failure.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_stalled_agent_restarted_by_orchestrator() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use axum::{routing::get, Router};
+        use std::sync::Arc;
+        use std::time::{Duration, SystemTime};
+        use tokio::sync::{broadcast, RwLock};
+
+        // stand-in for twitch, every channel is live
+        let app = Router::new().route("/:streamer", get(|| async { r#"{"isLive":true}"# }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let channel_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let mut config = Config::default();
+        config.agents.stale_after = "1m".to_string();
+        let orchestrator = AgentOrchestrator::new(config, Arc::new(BrowserManager::without_browser(1)))
+            .with_channel_url(&channel_url);
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        agent.set_status(AgentStatus::Running).await;
+        agent.metrics.write().await.last_message_time = Some(chrono::Utc::now() - chrono::Duration::minutes(5));
+        let agent_id = agent.id;
+        orchestrator.insert_agent(agent, AgentAssignment {
+            agent_id,
+            streamer: "shroud".to_string(),
+            assigned_at: SystemTime::now(),
+            priority: 0,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
+        }).await;
+
+        let orchestrator = Arc::new(RwLock::new(orchestrator));
+        let recovery = AgentOrchestrator::drive_recovery(&orchestrator);
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        orchestrator.write().await.start_agent_recovery(shutdown_rx).await.unwrap();
+
+        // the stall is caught on the first check and the agent restarted, which
+        // fails without a browser and leaves the streamer waiting for its next restart
+        let assignment = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let assignment = orchestrator.read().await.agent_assignments.read().await.get(&agent_id).cloned();
+                if let Some(assignment) = assignment.filter(|assignment| assignment.retry_attempts > 0) {
+                    return assignment;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }).await.expect("stalled agent was never restarted");
+        assert_eq!(assignment.streamer, "shroud");
+        assert_eq!(assignment.retry_attempts, 1);
+        let Some(AgentStatus::Error(error)) = orchestrator.read().await.get_agent_status(agent_id).await else {
+            panic!("a failed restart should leave the streamer in error state");
+        };
+        assert!(error.starts_with("restart failed"), "{}", error);
+
+        let _ = shutdown_tx.send(());
+        recovery.abort();
+    }
+
+    #[tokio::test]
+    async fn test_recovery_restart_does_not_hold_orchestrator_lock() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use std::sync::Arc;
+        use std::time::{Duration, SystemTime};
+        use tokio::sync::{broadcast, RwLock};
+
+        let orchestrator = AgentOrchestrator::new(Config::default(), Arc::new(BrowserManager::without_browser(1)));
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        agent.set_status(AgentStatus::Error("page crashed".to_string())).await;
+        let agent_id = agent.id;
+        orchestrator.insert_agent(agent, AgentAssignment {
+            agent_id,
+            streamer: "shroud".to_string(),
+            assigned_at: SystemTime::now(),
+            priority: 0,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
+        }).await;
+
+        let orchestrator = Arc::new(RwLock::new(orchestrator));
+        let recovery = AgentOrchestrator::drive_recovery(&orchestrator);
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        orchestrator.write().await.start_agent_recovery(shutdown_rx).await.unwrap();
+
+        // the restart waits out a startup delay of at least 100ms, readers
+        // like the dashboard must get in the whole time
+        let assignment = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let orchestrator = tokio::time::timeout(Duration::from_millis(50), orchestrator.read())
+                    .await
+                    .expect("orchestrator stayed locked during the restart");
+                let assignment = orchestrator.agent_assignments.read().await.get(&agent_id).cloned();
+                drop(orchestrator);
+                if let Some(assignment) = assignment.filter(|assignment| assignment.retry_attempts > 0) {
+                    return assignment;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.expect("errored agent was never restarted");
+        assert_eq!(assignment.streamer, "shroud");
+        let Some(AgentStatus::Error(error)) = orchestrator.read().await.get_agent_status(agent_id).await else {
+            panic!("a failed restart should leave the streamer in error state");
+        };
+        assert!(error.starts_with("restart failed"), "{}", error);
+
+        let _ = shutdown_tx.send(());
+        recovery.abort();
+    }
+
+    #[tokio::test]
+    async fn test_streamers_file_edit_changes_assignments() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use std::sync::Arc;
+        use std::time::{Duration, SystemTime};
+        use tokio::sync::{broadcast, RwLock};
+
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let streamers_path = temp_dir.path().join("streamers.txt");
+        std::fs::write(&streamers_path, "shroud\npokimane\n").unwrap();
+        let config_manager = Arc::new(FileConfigManager::new(config_path));
+        config_manager.save_config(&Config {
+            streamers_file: Some("streamers.txt".into()),
+            ..Config::default()
+        }).await.unwrap();
+        let config = config_manager.load_config().await.unwrap();
+        assert_eq!(config.streamers, vec!["shroud", "pokimane"]);
+
+        let orchestrator = AgentOrchestrator::new(config, Arc::new(BrowserManager::without_browser(1)));
+        for streamer in ["shroud", "pokimane"] {
+            let (chat_tx, _) = broadcast::channel(10);
+            let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+            agent.set_status(AgentStatus::Running).await;
+            let agent_id = agent.id;
+            orchestrator.insert_agent(agent, AgentAssignment {
+                agent_id,
+                streamer: streamer.to_string(),
+                assigned_at: SystemTime::now(),
+                priority: 0,
+                retry_attempts: 0,
+                last_failure: None,
+                idle_since: None,
+                proxy: None,
+            }).await;
+        }
+
+        let orchestrator = Arc::new(RwLock::new(orchestrator));
+        let reloads = AgentOrchestrator::drive_config_reloads(&orchestrator);
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        orchestrator.write().await.start_config_watcher(config_manager, shutdown_rx).await.unwrap();
+
+        // the watcher starts in the background, save again if it missed the
+        // edit, but not before the reload debounce is over
+        let streamers = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                std::fs::write(&streamers_path, "shroud\n").unwrap();
+                for _ in 0..30 {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    let streamers: Vec<String> = orchestrator.read().await.agent_assignments.read().await
+                        .values()
+                        .map(|assignment| assignment.streamer.clone())
+                        .collect();
+                    if streamers.len() == 1 {
+                        return streamers;
+                    }
+                }
+            }
+        }).await.expect("editing the streamers file never changed the assignments");
+        assert_eq!(streamers, vec!["shroud"]);
+
+        let _ = shutdown_tx.send(());
+        reloads.abort();
+    }
+
+    #[test]
+    fn test_restart_backoff() {
+        use crate::agents::orchestrator::restart_backoff;
+        use crate::agents::AgentAssignment;
+        use std::time::{Duration, SystemTime};
+        use uuid::Uuid;
+
+        // first restart is immediate, then the delay doubles until the cap
+        assert_eq!(restart_backoff(0), Duration::ZERO);
+        assert_eq!(restart_backoff(1), Duration::from_secs(5));
+        assert_eq!(restart_backoff(2), Duration::from_secs(10));
+        assert_eq!(restart_backoff(3), Duration::from_secs(20));
+        assert_eq!(restart_backoff(7), Duration::from_secs(300));
+        assert_eq!(restart_backoff(u32::MAX), Duration::from_secs(300));
+
+        let now = SystemTime::now();
+        let mut assignment = AgentAssignment {
+            agent_id: Uuid::new_v4(),
+            streamer: "shroud".to_string(),
+            assigned_at: now,
+            priority: 0,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
+        };
+        assert!(assignment.restart_due(now));
+
+        assignment.retry_attempts = 3;
+        assignment.last_failure = Some(now - Duration::from_secs(15));
+        assert!(!assignment.restart_due(now));
+        assignment.last_failure = Some(now - Duration::from_secs(20));
+        assert!(assignment.restart_due(now));
+    }
+
+    #[tokio::test]
+    async fn test_streamer_fails_after_retries_exhausted() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, AgentStatus, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use std::sync::Arc;
+        use std::time::SystemTime;
+        use tokio::sync::broadcast;
+
+        let mut config = Config::default();
+        config.agents.retry_attempts = 3;
+        let mut orchestrator = AgentOrchestrator::new(config, Arc::new(BrowserManager::without_browser(1)));
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        agent.set_status(AgentStatus::Error("page crashed".to_string())).await;
+        let agent_id = agent.id;
+        orchestrator.insert_agent(agent, AgentAssignment {
+            agent_id,
+            streamer: "shroud".to_string(),
+            assigned_at: SystemTime::now(),
+            priority: 0,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
+        }).await;
+
+        // every restart fails without a browser, until the orchestrator stops trying
+        let mut restarts = 0;
+        while orchestrator.recover_agent(agent_id).await.is_err() {
+            restarts += 1;
+            assert!(restarts <= 3, "recovery should give up after the configured restarts");
+        }
+
+        assert_eq!(restarts, 3);
+        assert!(orchestrator.get_agent_status(agent_id).await.is_none());
+        let status = orchestrator.get_status().await;
+        assert_eq!(status.failed_streamers.len(), 1);
+        let failed = &status.failed_streamers[0];
+        assert_eq!(failed.streamer, "shroud");
+        assert_eq!(failed.restart_attempts, 3);
+        assert!(failed.last_error.starts_with("restart failed"), "{}", failed.last_error);
+    }
+
+    #[test]
+    fn test_throughput_policy_prioritizes_hot_streamers() {
+        use crate::agents::orchestrator::{AgentLoad, ThroughputPolicy};
+        use std::collections::HashMap;
+        use std::time::Duration;
+        use uuid::Uuid;
+
+        let load = |streamer: &str, priority: u8, messages_per_minute: f64| AgentLoad {
+            agent_id: Uuid::new_v4(),
+            streamer: streamer.to_string(),
+            priority,
+            messages_per_minute,
+            uptime: Duration::from_secs(600),
+        };
+        // the busy channel was configured last, so priority alone would shed it first
+        let loads = vec![
+            load("quietone", 0, 0.5),
+            load("steady", 1, 20.0),
+            load("busychannel", 2, 900.0),
+        ];
+        let policy = ThroughputPolicy::new(60.0, 1.0);
+
+        assert_eq!(policy.shed_candidate(&loads), Some(loads[0].agent_id));
+        let without_quiet = &loads[1..];
+        assert_eq!(policy.shed_candidate(without_quiet), Some(loads[1].agent_id));
+        assert_eq!(policy.shed_candidate(&loads[2..]), None);
+
+        // a cold channel makes room for an unmeasured or busier streamer, not a quieter one
+        assert_eq!(policy.reclaim_candidate(&loads, None), Some(loads[0].agent_id));
+        assert_eq!(policy.reclaim_candidate(&loads, Some(300.0)), Some(loads[0].agent_id));
+        assert_eq!(policy.reclaim_candidate(&loads, Some(0.1)), None);
+        assert_eq!(policy.reclaim_candidate(without_quiet, None), None);
+
+        // just-started agents aren't judged on their rate yet
+        let fresh = AgentLoad { uptime: Duration::from_secs(30), ..load("newchannel", 3, 0.0) };
+        assert_eq!(policy.reclaim_candidate(std::slice::from_ref(&fresh), None), None);
+
+        let known_rates = HashMap::from([("busychannel".to_string(), 900.0), ("quietone".to_string(), 0.5)]);
+        let waiting = vec![("quietone".to_string(), 0), ("unknown".to_string(), 1), ("busychannel".to_string(), 2)];
+        let order: Vec<String> = policy.spawn_order(waiting, &known_rates).into_iter().map(|(streamer, _)| streamer).collect();
+        assert_eq!(order, vec!["busychannel", "unknown", "quietone"]);
+    }
+
+    #[tokio::test]
+    async fn test_message_rate_follows_recent_traffic() {
+        use crate::agents::orchestrator::MessageRateWindow;
+        use crate::agents::{Agent, ScrapingAgent};
+        use std::collections::HashSet;
+        use std::time::Duration;
+        use tokio::sync::broadcast;
+        use tokio::time::Instant;
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        let mut rates = MessageRateWindow::default();
+        let start = Instant::now();
+        let mut metrics = agent.get_metrics().await;
+
+        // an hour of busy chat, 600 msg/min
+        metrics.uptime = Duration::from_secs(3600);
+        metrics.messages_scraped = 36_000;
+        assert_eq!(rates.record(agent.id, &metrics, start), 600.0);
+
+        // then the channel goes nearly quiet, 10 msg/min sampled every minute
+        for minute in 1..=10 {
+            metrics.messages_scraped += 10;
+            let rate = rates.record(agent.id, &metrics, start + Duration::from_secs(60 * minute));
+            assert_eq!(rate, 10.0, "minute {}", minute);
+        }
+        // the lifetime average would still call it busy
+        assert!(metrics.messages_scraped as f64 / 70.0 > 500.0);
+
+        rates.retain(&HashSet::new());
+        assert_eq!(rates.record(agent.id, &metrics, start + Duration::from_secs(660)), metrics.messages_scraped as f64 / 60.0);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_agent_detection() {
+        use crate::agents::orchestrator::is_stalled;
+        use crate::agents::{Agent, AgentStatus, ScrapingAgent};
+        use chrono::Utc;
+        use std::time::Duration;
+        use tokio::sync::broadcast;
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let agent = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        agent.set_status(AgentStatus::Running).await;
+        let stale_after = Duration::from_secs(300);
+        let now = Utc::now();
+
+        // a channel that never produced anything is quiet, not stalled
+        assert!(!is_stalled(&agent.get_metrics().await, stale_after, now));
+
+        agent.update_message_metrics(10).await;
+        assert!(!is_stalled(&agent.get_metrics().await, stale_after, now));
+
+        agent.metrics.write().await.last_message_time = Some(now - chrono::Duration::minutes(6));
+        let metrics = agent.get_metrics().await;
+        assert!(is_stalled(&metrics, stale_after, now));
+        assert!(!is_stalled(&metrics, Duration::ZERO, now));
+
+        agent.set_status(AgentStatus::Idle).await;
+        assert!(!is_stalled(&agent.get_metrics().await, stale_after, now));
+    }
+
+    #[tokio::test]
+    async fn test_per_streamer_message_counts() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, ScrapingAgent};
+        use std::collections::HashMap;
+        use std::time::SystemTime;
+        use tokio::sync::{broadcast, RwLock};
+
+        let (chat_tx, _) = broadcast::channel(10);
+        let shroud = ScrapingAgent::new((100, 200), chat_tx.clone()).unwrap();
+        shroud.update_message_metrics(120).await;
+        let ninja = ScrapingAgent::new((100, 200), chat_tx.clone()).unwrap();
+        ninja.update_message_metrics(30).await;
+        ninja.update_message_metrics(5).await;
+        // an agent without an assignment isn't counted anywhere
+        let unassigned = ScrapingAgent::new((100, 200), chat_tx).unwrap();
+        unassigned.update_message_metrics(99).await;
+
+        let assignment = |agent: &ScrapingAgent, streamer: &str| AgentAssignment {
+            agent_id: agent.id,
+            streamer: streamer.to_string(),
+            assigned_at: SystemTime::now(),
+            priority: 0,
+            retry_attempts: 0,
+            last_failure: None,
+            idle_since: None,
+            proxy: None,
+        };
+        let assignments = RwLock::new(HashMap::from([
+            (shroud.id, assignment(&shroud, "shroud")),
+            (ninja.id, assignment(&ninja, "ninja")),
+        ]));
+        let agents = RwLock::new(HashMap::from([(shroud.id, shroud), (ninja.id, ninja), (unassigned.id, unassigned)]));
+
+        let per_streamer = AgentOrchestrator::messages_per_streamer(&agents, &assignments).await;
+        assert_eq!(per_streamer, HashMap::from([("shroud".to_string(), 120), ("ninja".to_string(), 35)]));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_messages_reach_webhooks() {
+        use crate::agents::AgentOrchestrator;
+        use crate::error::Result;
+        use crate::parser::fixtures::chat_message;
+        use crate::parser::ChatMessage;
+        use crate::webhooks::{WebhookManager, WebhookProvider};
+        use std::sync::Arc;
+        use std::time::Duration;
+        use tokio::sync::{broadcast, Mutex};
+
+        struct MockProvider {
+            received: Arc<Mutex<Vec<String>>>,
+        }
+
+        #[async_trait::async_trait]
+        impl WebhookProvider for MockProvider {
+            async fn send_message(&self, message: &ChatMessage) -> Result<()> {
+                self.received.lock().await.push(message.message.text.clone());
+                Ok(())
+            }
+
+            async fn send_alert(&self, _level: &str, _title: &str, _message: &str) -> Result<()> {
+                Ok(())
+            }
+        }
+
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let mut webhook_manager = WebhookManager::new();
+        webhook_manager.add_provider(Box::new(MockProvider { received: received.clone() }));
+
+        let (chat_tx, chat_rx) = broadcast::channel(2);
+        let (batch_tx, batch_rx) = broadcast::channel::<Vec<ChatMessage>>(2);
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+        // overflow the channel before the forwarder runs: the oldest are dropped, not fatal
+        for text in ["lost 1", "lost 2", "kept 1", "kept 2"] {
+            chat_tx.send(chat_message(text)).unwrap();
+        }
+        let forwarder = tokio::spawn(AgentOrchestrator::forward_to_webhooks(
+            Arc::new(webhook_manager),
+            chat_rx,
+            batch_rx,
+            shutdown_rx,
+        ));
+
+        let wait_for = |count: usize| {
+            let received = received.clone();
+            async move {
+                for _ in 0..50 {
+                    if received.lock().await.len() >= count {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            }
+        };
+        wait_for(2).await;
+        assert_eq!(*received.lock().await, vec!["kept 1", "kept 2"]);
+
+        chat_tx.send(chat_message("live")).unwrap();
+        wait_for(3).await;
+        batch_tx.send(vec![chat_message("batched 1"), chat_message("batched 2")]).unwrap();
+        wait_for(5).await;
+        assert_eq!(*received.lock().await, vec!["kept 1", "kept 2", "live", "batched 1", "batched 2"]);
+
+        shutdown_tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), forwarder).await.unwrap().unwrap();
+    }
+}
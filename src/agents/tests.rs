@@ -1,15 +1,25 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::agents::agent::*;
+    use crate::parser::ChatMessage;
+    use crate::agents::agent_log::AgentLogFile;
+    use crate::agents::batch::{BatchPublisher, BatchSettings, MessageBatcher};
     use crate::browser::{BrowserManager, StealthConfig};
     use std::sync::Arc;
-    use tokio::time::{timeout, Duration};
+    use tokio::sync::broadcast;
+    use tokio::time::{timeout, Duration, Instant};
     use uuid::Uuid;
     use chrono::Utc;
 
+    fn create_test_agent() -> ScrapingAgent {
+        let (chat_message_broadcaster, _) = broadcast::channel(100);
+        ScrapingAgent::new((1000, 5000), chat_message_broadcaster).expect("Failed to create agent")
+    }
+
     #[tokio::test]
     async fn test_agent_creation() {
-        let agent = ScrapingAgent::new().expect("Failed to create agent");
+        let agent = create_test_agent();
         
         assert!(agent.id != Uuid::nil());
         assert_eq!(agent.streamer, None);
@@ -28,15 +38,13 @@ mod tests {
     #[tokio::test]
     async fn test_agent_with_browser_manager() {
         let stealth_config = StealthConfig::default();
-        let proxy_list = Vec::new();
-        let browser_manager = Arc::new(
-            BrowserManager::new(1, stealth_config, proxy_list)
-                .await
-                .expect("Failed to create browser manager")
-        );
+        let browser_manager = match BrowserManager::new(1, stealth_config).await {
+            Ok(manager) => Arc::new(manager),
+            // chrome isn't available everywhere the tests run
+            Err(_) => return,
+        };
         
-        let agent = ScrapingAgent::new()
-            .expect("Failed to create agent")
+        let agent = create_test_agent()
             .with_browser_manager(browser_manager.clone());
         
         assert!(agent.browser_manager.is_some());
@@ -48,7 +56,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_agent_status_transitions() {
-        let agent = ScrapingAgent::new().expect("Failed to create agent");
+        let agent = create_test_agent();
         
         // test initial status
         let status = agent.get_status().await;
@@ -70,7 +78,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_message_stream() {
-        let agent = ScrapingAgent::new().expect("Failed to create agent");
+        let agent = create_test_agent();
         let mut message_stream = agent.message_stream();
         
         // get the broadcaster to send a test message
@@ -87,10 +95,7 @@ mod tests {
                 crate::parser::chat_message::MessageContent {
                     text: "Hello, world!".to_string(),
                     emotes: vec![],
-                    fragments: vec![crate::parser::chat_message::MessageFragment {
-                        fragment_type: "text".to_string(),
-                        content: "Hello, world!".to_string(),
-                    }],
+                    fragments: vec![crate::parser::chat_message::MessageFragment::new("text", "Hello, world!")],
                 },
                 crate::parser::chat_message::StreamContext::default(),
             );
@@ -113,7 +118,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_metrics_updates() {
-        let agent = ScrapingAgent::new().expect("Failed to create agent");
+        let agent = create_test_agent();
         
         // test initial metrics
         let metrics = agent.get_metrics().await;
@@ -135,10 +140,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_uptime_calculation() {
-        let mut agent = ScrapingAgent::new().expect("Failed to create agent");
+        let mut agent = create_test_agent();
         
         // set start time
-        agent.start_time = Some(Utc::now() - chrono::Duration::seconds(10));
+        agent.start_time = Some(Instant::now() - Duration::from_secs(10));
         
         // update uptime
         agent.update_uptime().await;
@@ -150,7 +155,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_browser_initialization_without_manager() {
-        let mut agent = ScrapingAgent::new().expect("Failed to create agent");
+        let mut agent = create_test_agent();
         
         // try to initialize browser without browser manager
         let result = agent.initialize_browser().await;
@@ -164,10 +169,310 @@ mod tests {
 
     #[tokio::test]
     async fn test_cleanup_browser_without_instance() {
-        let mut agent = ScrapingAgent::new().expect("Failed to create agent");
+        let mut agent = create_test_agent();
         
         // try to cleanup browser without browser instance
         let result = agent.cleanup_browser().await;
         assert!(result.is_ok()); // Should succeed even without instance
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_idle_duration() {
+        let mut agent = create_test_agent();
+
+        // not started, never idle
+        assert_eq!(agent.idle_duration().await, Duration::from_secs(0));
+
+        // started with no messages yet, idle since start
+        agent.start_time = Some(Instant::now() - Duration::from_secs(60));
+        assert!(agent.idle_duration().await >= Duration::from_secs(59));
+
+        // a recent message resets the idle clock
+        agent.update_message_metrics(1).await;
+        assert!(agent.idle_duration().await < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_go_idle_without_browser() {
+        let mut agent = create_test_agent();
+        agent.set_status(AgentStatus::Running).await;
+        agent.start_time = Some(Instant::now());
+
+        agent.go_idle().await.unwrap();
+
+        assert!(matches!(agent.get_status().await, AgentStatus::Idle));
+        assert!(agent.start_time.is_none());
+        assert!(agent.browser_instance_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_agent_log_written_on_status_change() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut agent = create_test_agent();
+        agent.agent_log = Some(Arc::new(AgentLogFile::new(temp_dir.path(), "teststreamer", agent.id, 1024 * 1024)));
+
+        agent.set_status(AgentStatus::Running).await;
+        agent.set_status(AgentStatus::Error("Connection lost".to_string())).await;
+
+        let log_path = temp_dir.path().join("teststreamer").join("agent.log");
+        let contents = std::fs::read_to_string(log_path).unwrap();
+        assert!(contents.contains("status changed to Running"));
+        assert!(contents.contains("ERROR"));
+        assert!(contents.contains("Connection lost"));
+        assert!(contents.contains(&agent.id.to_string()));
+    }
+
+    #[test]
+    fn test_agent_log_rotation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let agent_log = AgentLogFile::new(temp_dir.path(), "teststreamer", Uuid::new_v4(), 64);
+
+        for i in 0..5 {
+            agent_log.write_line(tracing::Level::INFO, &format!("event number {}", i)).unwrap();
+        }
+
+        assert!(agent_log.rotated_path().exists());
+        let current_size = std::fs::metadata(agent_log.path()).unwrap().len();
+        assert!(current_size < 64 * 2);
+    }
+
+    #[tokio::test]
+    async fn test_agent_without_log_output() {
+        let agent = create_test_agent();
+        assert!(agent.agent_log.is_none());
+
+        // logging without a log file is a no-op
+        agent.log_event(tracing::Level::INFO, "nothing to see here");
+    }
+
+    fn create_batch_message(text: &str) -> ChatMessage {
+        use crate::parser::{ChatUser, MessageContent, StreamContext};
+
+        ChatMessage::new(
+            "teststreamer".to_string(),
+            Utc::now(),
+            ChatUser {
+                username: "testuser".to_string(),
+                display_name: "testuser".to_string(),
+                color: None,
+                badges: vec![],
+            },
+            MessageContent {
+                text: text.to_string(),
+                emotes: vec![],
+                fragments: vec![],
+            },
+            StreamContext::default(),
+        )
+    }
+
+    #[test]
+    fn test_batcher_flushes_on_size() {
+        let mut batcher = MessageBatcher::new(BatchSettings {
+            max_size: 3,
+            window: Duration::from_secs(60),
+        });
+
+        assert!(batcher.push(create_batch_message("one")).is_none());
+        assert!(batcher.push(create_batch_message("two")).is_none());
+        let batch = batcher.push(create_batch_message("three")).unwrap();
+
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch[0].message.text, "one");
+        assert_eq!(batcher.pending_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batcher_flushes_on_window() {
+        let mut batcher = MessageBatcher::new(BatchSettings {
+            max_size: 100,
+            window: Duration::from_millis(10),
+        });
+
+        assert!(batcher.take_if_due().is_none());
+        batcher.push(create_batch_message("one"));
+        assert!(batcher.take_if_due().is_none());
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(batcher.take_if_due().unwrap().len(), 1);
+        assert!(batcher.flush().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_publisher_sends_batches() {
+        let (tx, mut rx) = broadcast::channel(10);
+        let mut publisher = BatchPublisher::new(
+            BatchSettings {
+                max_size: 2,
+                window: Duration::from_secs(60),
+            },
+            tx,
+        );
+
+        publisher.push(create_batch_message("one"));
+        publisher.push(create_batch_message("two"));
+        publisher.push(create_batch_message("three"));
+        publisher.publish_all();
+
+        assert_eq!(rx.recv().await.unwrap().len(), 2);
+        assert_eq!(rx.recv().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stop_flushes_pending_messages() {
+        let (chat_tx, _) = broadcast::channel(100);
+        let (batch_tx, mut batch_rx) = broadcast::channel(10);
+        let mut agent = ScrapingAgent::new((1000, 5000), chat_tx)
+            .expect("Failed to create agent")
+            .with_batching(
+                BatchSettings {
+                    max_size: 10,
+                    window: Duration::from_secs(60),
+                },
+                batch_tx,
+            );
+
+        let dispatcher = agent.dispatcher().unwrap();
+        for text in ["one", "two", "three"] {
+            dispatcher.lock().await.dispatch(create_batch_message(text)).await;
+        }
+
+        // nothing is sent while the batch is neither full nor due
+        assert!(batch_rx.try_recv().is_err());
+
+        agent.stop().await.unwrap();
+
+        let batch = batch_rx.try_recv().expect("pending messages were not flushed on stop");
+        let texts: Vec<&str> = batch.iter().map(|m| m.message.text.as_str()).collect();
+        assert_eq!(texts, vec!["one", "two", "three"]);
+    }
+
+    #[tokio::test]
+    async fn test_emotes_fetched_after_spawn_are_used() {
+        use crate::parser::data_processor::{EmoteProvider, EmoteSet};
+
+        let (chat_tx, mut chat_rx) = broadcast::channel(100);
+        let (emotes_tx, emotes_rx) = tokio::sync::watch::channel(Arc::new(EmoteSet::new()));
+        let mut agent = ScrapingAgent::new((1000, 5000), chat_tx)
+            .expect("Failed to create agent")
+            .with_emotes(emotes_rx);
+        let dispatcher = agent.dispatcher().unwrap();
+
+        // still fetching: messages go out untagged rather than waiting
+        dispatcher.lock().await.dispatch(create_batch_message("catJAM")).await;
+        assert!(chat_rx.try_recv().unwrap().message.emotes.is_empty());
+
+        let mut emotes = EmoteSet::new();
+        emotes.insert("catJAM".to_string(), EmoteProvider::Bttv);
+        emotes_tx.send(Arc::new(emotes)).unwrap();
+        dispatcher.lock().await.dispatch(create_batch_message("catJAM")).await;
+        assert_eq!(chat_rx.try_recv().unwrap().message.emotes, vec!["catJAM"]);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_agent() {
+        let (chat_tx, mut chat_rx) = broadcast::channel(100);
+        let mut agent = ScrapingAgent::new((1000, 5000), chat_tx).expect("Failed to create agent");
+        let dispatcher = agent.dispatcher().unwrap();
+
+        // only a running agent can be paused
+        assert!(agent.pause().await.is_err());
+        assert!(agent.resume().await.is_err());
+
+        agent.set_status(AgentStatus::Running).await;
+        agent.pause().await.unwrap();
+        assert_eq!(agent.get_status().await, AgentStatus::Paused);
+        assert_eq!(agent.get_metrics().await.status, AgentStatus::Paused);
+
+        dispatcher.lock().await.dispatch(create_batch_message("while paused")).await;
+        assert!(chat_rx.try_recv().is_err());
+
+        agent.resume().await.unwrap();
+        assert_eq!(agent.get_status().await, AgentStatus::Running);
+
+        dispatcher.lock().await.dispatch(create_batch_message("after resume")).await;
+        assert_eq!(chat_rx.try_recv().unwrap().message.text, "after resume");
+        assert!(chat_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_agent_keeps_proxy_across_restart() {
+        let proxies = vec!["10.0.0.1:8080".to_string(), "10.0.0.2:8080".to_string(), "10.0.0.3:8080".to_string()];
+        let browser_manager = BrowserManager::without_browser(3).with_proxy_list(proxies.clone());
+
+        let pinned = browser_manager.choose_proxy(None).await.unwrap();
+        assert_eq!(pinned, proxies[0]);
+        // rotation moved on, but a restarted agent asks for its pin and gets it back
+        assert_eq!(browser_manager.choose_proxy(None).await.unwrap(), proxies[1]);
+        assert_eq!(browser_manager.choose_proxy(Some(&pinned)).await, Some(pinned.clone()));
+
+        let agent = create_test_agent().with_proxy(Some(pinned.clone()));
+        assert_eq!(agent.proxy(), Some(pinned.as_str()));
+
+        // once the pin goes bad a different proxy is handed out
+        browser_manager.report_bad_proxy(pinned.clone()).await;
+        let replacement = browser_manager.choose_proxy(Some(&pinned)).await.unwrap();
+        assert_ne!(replacement, pinned);
+
+        // a pin that isn't in the proxy list any more isn't honoured
+        let unknown = browser_manager.choose_proxy(Some("10.9.9.9:1")).await.unwrap();
+        assert!(proxies.contains(&unknown));
+    }
+
+    #[test]
+    fn test_agent_event_log_ring_buffer() {
+        use crate::agents::event_log::MAX_EVENTS_PER_AGENT;
+        use crate::agents::{AgentEventKind, AgentEventLog, AgentMessage};
+
+        let mut event_log = AgentEventLog::new();
+        let agent_id = Uuid::new_v4();
+
+        event_log.record(&AgentMessage::StatusUpdate { agent_id, status: AgentStatus::Running });
+        event_log.record(&AgentMessage::Error { agent_id, error: "browser crashed".to_string() });
+        event_log.record(&AgentMessage::ChatMessage { agent_id, message: Box::new(create_batch_message("ignored")) });
+        event_log.record(&AgentMessage::ResourceAlert { agent_id: Uuid::nil(), alert: "High CPU usage".to_string() });
+
+        let events = event_log.recent(agent_id, 10).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, AgentEventKind::Status);
+        assert_eq!(events[1].message, "browser crashed");
+        assert_eq!(event_log.recent(agent_id, 1).unwrap()[0].kind, AgentEventKind::Error);
+
+        let limited_id = Uuid::new_v4();
+        event_log.record(&AgentMessage::RateLimited {
+            agent_id: limited_id,
+            streamer: "shroud".to_string(),
+            pause: std::time::Duration::from_secs(90),
+        });
+        let limited = event_log.recent(limited_id, 10).unwrap();
+        assert_eq!(limited[0].kind, AgentEventKind::RateLimited);
+        assert_eq!(limited[0].message, "rate limited on shroud, pausing for 90s");
+        event_log.record(&AgentMessage::StreamerFailed {
+            agent_id: limited_id,
+            streamer: "shroud".to_string(),
+            restart_attempts: 3,
+        });
+        let failed = event_log.recent(limited_id, 1).unwrap();
+        assert_eq!(failed[0].kind, AgentEventKind::Alert);
+        assert_eq!(failed[0].message, "gave up on shroud after 3 restarts");
+        assert!(event_log.recent(Uuid::nil(), 10).is_none());
+
+        for i in 0..MAX_EVENTS_PER_AGENT + 5 {
+            event_log.record(&AgentMessage::Error { agent_id, error: format!("error {}", i) });
+        }
+        let events = event_log.recent(agent_id, usize::MAX).unwrap();
+        assert_eq!(events.len(), MAX_EVENTS_PER_AGENT);
+        assert_eq!(events.last().unwrap().message, format!("error {}", MAX_EVENTS_PER_AGENT + 4));
+    }
+
+    #[test]
+    fn test_stable_agent_ids() {
+        let id = stable_agent_id("shroud");
+        assert_eq!(id, stable_agent_id("Shroud"));
+        assert_ne!(id, stable_agent_id("ninja"));
+        assert_eq!(id.get_version_num(), 5);
+
+        let agent = create_test_agent().with_id(id);
+        assert_eq!(agent.id, id);
+    }
+}
@@ -10,20 +10,31 @@ use std::sync::Arc;
 
 use crate::config::Config;
 
+/// Reject requests without `Authorization: Bearer <api_token>` once a token is
+/// configured; with no token every request is let through
 pub async fn auth_middleware(
     State(config): State<Arc<Config>>,
     req: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
     let token = req.headers()
-        .typed_get::<Authorization<Bearer>>()
-        .and_then(|auth| Some(auth.token().to_string()));
+        .typed_get::<Authorization<Bearer>>().map(|auth| auth.token().to_string());
 
     if let Some(api_token) = &config.monitoring.api_token {
-        if token.is_none() || &token.unwrap() != api_token {
+        if !token.is_some_and(|token| tokens_match(&token, api_token)) {
             return Err(StatusCode::UNAUTHORIZED);
         }
     }
 
     Ok(next.run(req).await)
 }
+
+/// Compare every byte instead of stopping at the first mismatch, so response
+/// times don't give away how much of a guessed token was right
+fn tokens_match(given: &str, expected: &str) -> bool {
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+    if given.len() != expected.len() {
+        return false;
+    }
+    given.iter().zip(expected).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
@@ -0,0 +1,96 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::agents::AgentOrchestrator;
+use crate::api::ApiResponse;
+use crate::config::{Config, ConfigManager};
+
+/// Placeholder shown instead of the api token and webhook URLs; sending it back
+/// keeps the current value
+pub const REDACTED_TOKEN: &str = "[REDACTED]";
+
+fn secrets(config: &mut Config) -> [&mut Option<String>; 4] {
+    config.monitoring.secrets_mut().map(|(_, secret)| secret)
+}
+
+fn redact_secrets(config: &mut Config) {
+    for secret in secrets(config) {
+        if secret.is_some() {
+            *secret = Some(REDACTED_TOKEN.to_string());
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ConfigApiState {
+    orchestrator: Arc<RwLock<AgentOrchestrator>>,
+    config_manager: Arc<dyn ConfigManager + Send + Sync>,
+}
+
+pub fn create_config_router(
+    orchestrator: Arc<RwLock<AgentOrchestrator>>,
+    config_manager: Arc<dyn ConfigManager + Send + Sync>,
+) -> Router<Arc<RwLock<AgentOrchestrator>>> {
+    let state = ConfigApiState {
+        orchestrator,
+        config_manager,
+    };
+
+    Router::new()
+        .route("/config", get(get_config).put(update_config))
+        .with_state(state)
+}
+
+async fn get_config(State(state): State<ConfigApiState>) -> Json<ApiResponse<Config>> {
+    let mut config = state.orchestrator.read().await.get_config().await;
+    redact_secrets(&mut config);
+    Json(ApiResponse::success(config))
+}
+
+async fn update_config(
+    State(state): State<ConfigApiState>,
+    Json(mut new_config): Json<Config>,
+) -> (StatusCode, Json<ApiResponse<Config>>) {
+    // a round-tripped GET response still carries the placeholders
+    let mut current = state.orchestrator.read().await.get_config().await;
+    for (secret, current_secret) in secrets(&mut new_config).into_iter().zip(secrets(&mut current)) {
+        if secret.as_deref() == Some(REDACTED_TOKEN) {
+            *secret = current_secret.take();
+        }
+    }
+
+    if let Err(e) = state.config_manager.validate_config(&new_config) {
+        warn!("Rejected configuration update: {}", e);
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(format!("Invalid configuration: {}", e))),
+        );
+    }
+    new_config.normalize_streamers();
+
+    if let Err(e) = state.config_manager.save_config(&new_config).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to save configuration: {}", e))),
+        );
+    }
+
+    let mut orchestrator = state.orchestrator.write().await;
+    if let Err(e) = orchestrator.update_config(new_config.clone()).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Failed to apply configuration: {}", e))),
+        );
+    }
+
+    info!("Configuration updated through the API");
+    redact_secrets(&mut new_config);
+    (StatusCode::OK, Json(ApiResponse::success(new_config)))
+}
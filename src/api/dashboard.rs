@@ -1,18 +1,85 @@
 use axum::{
+    extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
     extract::State,
+    Extension,
     http::StatusCode,
-    response::{Html, IntoResponse},
+    response::{Html, IntoResponse, Response},
     routing::get,
     Router,
 };
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::time::{timeout, Instant};
+use tracing::{debug, warn};
 use crate::agents::AgentOrchestrator;
+use crate::config::{FileConfigManager, MonitorConfig};
+use crate::error::Result as ScraperResult;
 
-pub fn create_dashboard_router() -> Router<Arc<RwLock<AgentOrchestrator>>> {
+/// How often `/ws/metrics` pushes a status snapshot
+const METRICS_PUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Heartbeat and client cap for the dashboard WebSocket routes, shared by every connection
+#[derive(Debug, Clone)]
+pub struct WsLimits {
+    pub ping_interval: Duration,
+    pub pong_timeout: Duration,
+    pub max_clients: usize,
+    clients: Arc<AtomicUsize>,
+}
+
+impl WsLimits {
+    pub fn new(ping_interval: Duration, pong_timeout: Duration, max_clients: usize) -> Self {
+        Self {
+            ping_interval,
+            pong_timeout,
+            max_clients,
+            clients: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn from_config(config: &MonitorConfig) -> ScraperResult<Self> {
+        Ok(Self::new(
+            FileConfigManager::parse_time_to_duration(&config.ws_ping_interval)?,
+            FileConfigManager::parse_time_to_duration(&config.ws_pong_timeout)?,
+            config.ws_max_clients,
+        ))
+    }
+
+    /// Connected clients right now
+    pub fn clients(&self) -> usize {
+        self.clients.load(Ordering::SeqCst)
+    }
+
+    /// Take a client slot, `None` when the cap is reached. The slot is
+    /// given back when the returned guard drops.
+    pub fn try_acquire(&self) -> Option<ClientSlot> {
+        self.clients
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |clients| {
+                (clients < self.max_clients).then_some(clients + 1)
+            })
+            .ok()
+            .map(|_| ClientSlot(self.clients.clone()))
+    }
+}
+
+/// One connected WebSocket client, counted against `WsLimits::max_clients`
+#[derive(Debug)]
+pub struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn create_dashboard_router(ws_limits: WsLimits) -> Router<Arc<RwLock<AgentOrchestrator>>> {
     Router::new()
         .route("/", get(dashboard_html))
         .route("/api/stats", get(dashboard_stats))
+        .route("/ws/metrics", get(metrics_ws))
+        .layer(Extension(ws_limits))
 }
 
 async fn dashboard_html() -> impl IntoResponse {
@@ -33,4 +100,122 @@ async fn dashboard_stats(
         "error_count": status.error_count,
         "timestamp": chrono::Utc::now()
     })))
-}
\ No newline at end of file
+}
+/// Upgrade to a WebSocket that receives an `OrchestratorStatus` snapshot every second
+async fn metrics_ws(
+    ws: WebSocketUpgrade,
+    State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
+    Extension(ws_limits): Extension<WsLimits>,
+) -> Response {
+    // taken before upgrading so a burst of connections can't overshoot the cap
+    let slot = ws_limits.try_acquire();
+
+    // each client gets its own push loop, which ends when the client goes away
+    ws.on_failed_upgrade(|e| warn!("Metrics WebSocket upgrade failed: {}", e))
+        .on_upgrade(move |mut socket| async move {
+            match slot {
+                Some(_slot) => push_metrics(socket, orchestrator, &ws_limits).await,
+                None => {
+                    debug!("Rejecting metrics WebSocket client, {} already connected", ws_limits.max_clients);
+                    let _ = socket.send(Message::Close(Some(CloseFrame {
+                        code: close_code::AGAIN,
+                        reason: "too many clients, try again later".into(),
+                    }))).await;
+                }
+            }
+        })
+}
+
+async fn push_metrics(
+    mut socket: WebSocket,
+    orchestrator: Arc<RwLock<AgentOrchestrator>>,
+    ws_limits: &WsLimits,
+) {
+    let mut interval = tokio::time::interval(METRICS_PUSH_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut heartbeat = tokio::time::interval_at(Instant::now() + ws_limits.ping_interval, ws_limits.ping_interval);
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut awaiting_pong: Option<Instant> = None;
+
+    loop {
+        let pong_deadline = async {
+            match awaiting_pong {
+                Some(sent) => tokio::time::sleep_until(sent + ws_limits.pong_timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        // a client that stops reading fills its socket buffer, so sends get the pong timeout too
+        let outgoing = tokio::select! {
+            _ = interval.tick() => {
+                let status = orchestrator.read().await.get_status().await;
+                match serde_json::to_string(&status) {
+                    Ok(payload) => Message::Text(payload),
+                    Err(e) => {
+                        warn!("Failed to serialize orchestrator status: {}", e);
+                        continue;
+                    }
+                }
+            }
+            _ = heartbeat.tick() => {
+                awaiting_pong.get_or_insert_with(Instant::now);
+                Message::Ping(Vec::new())
+            }
+            _ = pong_deadline => {
+                debug!("Metrics WebSocket client missed its pong, closing");
+                let _ = timeout(ws_limits.pong_timeout, socket.send(Message::Close(Some(CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "heartbeat timeout".into(),
+                })))).await;
+                break;
+            }
+            incoming = socket.recv() => {
+                // clients only ever send control frames; their pings are answered by the socket itself
+                match incoming {
+                    Some(Ok(Message::Pong(_))) => {
+                        awaiting_pong = None;
+                        continue;
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                }
+            }
+        };
+
+        match timeout(ws_limits.pong_timeout, socket.send(outgoing)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(_)) => break,
+            Err(_) => {
+                debug!("Metrics WebSocket client stopped reading, dropping it");
+                break;
+            }
+        }
+    }
+
+    debug!("Metrics WebSocket client disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ws_client_slots() {
+        let limits = WsLimits::new(Duration::from_secs(30), Duration::from_secs(10), 2);
+
+        let first = limits.try_acquire().unwrap();
+        let second = limits.clone().try_acquire().unwrap();
+        assert_eq!(limits.clients(), 2);
+        assert!(limits.try_acquire().is_none());
+
+        // a disconnect frees its slot for the next client
+        drop(first);
+        assert_eq!(limits.clients(), 1);
+        let third = limits.try_acquire();
+        assert!(third.is_some());
+
+        drop(second);
+        drop(third);
+        assert_eq!(limits.clients(), 0);
+    }
+}
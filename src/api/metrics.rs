@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use crate::agents::OrchestratorStatus;
+
+/// Content type of the Prometheus text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Render the orchestrator status in the Prometheus text exposition format
+pub fn render_prometheus(status: &OrchestratorStatus) -> String {
+    let metrics = &status.system_metrics;
+    let mut out = String::new();
+
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(Option<&str>, f64)]| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (streamer, value) in samples {
+            match streamer {
+                Some(streamer) => {
+                    let _ = writeln!(out, "{}{{streamer=\"{}\"}} {}", name, escape_label(streamer), value);
+                }
+                None => {
+                    let _ = writeln!(out, "{} {}", name, value);
+                }
+            }
+        }
+    };
+
+    metric("scraper_active_agents", "gauge", "Agents currently scraping a channel.", &[(None, status.active_agents as f64)]);
+    metric("scraper_idle_agents", "gauge", "Agents parked on an offline or silent channel.", &[(None, status.idle_agents as f64)]);
+    metric("scraper_agents_spawned_total", "counter", "Agents spawned since startup.", &[(None, status.total_agents_spawned as f64)]);
+    metric("scraper_errors_total", "counter", "Agent errors since startup.", &[(None, status.error_count as f64)]);
+    metric(
+        "scraper_failed_streamers",
+        "gauge",
+        "Streamers abandoned after running out of restarts.",
+        &[(None, status.failed_streamers.len() as f64)],
+    );
+    // summed over the running agents, so these drop when an agent stops or restarts
+    metric(
+        "scraper_messages_scraped",
+        "gauge",
+        "Chat messages scraped by the current agents.",
+        &[(None, metrics.total_messages_scraped as f64)],
+    );
+
+    // sorted so scrapes diff cleanly
+    let mut per_streamer: Vec<(&String, &u64)> = metrics.per_streamer_messages.iter().collect();
+    per_streamer.sort();
+    let samples: Vec<(Option<&str>, f64)> = per_streamer
+        .into_iter()
+        .map(|(streamer, count)| (Some(streamer.as_str()), *count as f64))
+        .collect();
+    metric("scraper_streamer_messages_scraped", "gauge", "Chat messages scraped per channel by its current agents.", &samples);
+
+    metric("scraper_cpu_usage_percent", "gauge", "Host CPU usage.", &[(None, metrics.cpu_usage as f64)]);
+    metric("scraper_memory_used_bytes", "gauge", "Host memory in use.", &[(None, metrics.memory_usage as f64)]);
+    metric("scraper_memory_total_bytes", "gauge", "Host memory installed.", &[(None, metrics.memory_total as f64)]);
+    metric(
+        "scraper_storage_queue_depth",
+        "gauge",
+        "Messages waiting in the storage ingest queue.",
+        &[(None, metrics.storage_queue_depth as f64)],
+    );
+    metric("scraper_uptime_seconds", "gauge", "Time since the orchestrator started.", &[(None, status.uptime.as_secs_f64())]);
+
+    out
+}
+
+/// Label values escape backslashes, quotes and newlines
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::SystemMetrics;
+    use std::collections::{HashMap, HashSet};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_render_prometheus() {
+        let status = OrchestratorStatus {
+            active_agents: 2,
+            idle_agents: 1,
+            total_agents_spawned: 4,
+            system_metrics: SystemMetrics {
+                cpu_usage: 12.5,
+                memory_usage: 1024,
+                memory_total: 4096,
+                active_agents: 3,
+                total_messages_scraped: 150,
+                per_streamer_messages: HashMap::from([("shroud".to_string(), 120), ("ninja".to_string(), 30)]),
+                storage_queue_depth: 7,
+                timestamp: SystemTime::now(),
+            },
+            agent_assignments: vec![],
+            error_count: 3,
+            uptime: Duration::from_secs(90),
+            failed_streamers: vec![],
+        };
+        let text = render_prometheus(&status);
+
+        assert!(text.contains("scraper_active_agents 2\n"));
+        assert!(text.contains("scraper_messages_scraped 150\n"));
+        assert!(text.contains("scraper_streamer_messages_scraped{streamer=\"ninja\"} 30\n"));
+        assert!(text.contains("scraper_streamer_messages_scraped{streamer=\"shroud\"} 120\n"));
+        assert!(text.contains("scraper_cpu_usage_percent 12.5\n"));
+        assert!(text.contains("scraper_errors_total 3\n"));
+        assert!(text.contains("scraper_failed_streamers 0\n"));
+
+        // every sample belongs to a declared family and has a numeric value
+        let mut declared = HashSet::new();
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = rest.split_once(' ').unwrap();
+                assert!(["gauge", "counter"].contains(&kind), "bad type in {:?}", line);
+                assert!(declared.insert(name.to_string()), "{} declared twice", name);
+            } else if !line.starts_with("# HELP ") {
+                let (series, value) = line.rsplit_once(' ').unwrap();
+                let name = series.split('{').next().unwrap();
+                assert!(declared.contains(name), "{} has no TYPE", name);
+                assert!(name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'), "bad name {:?}", name);
+                value.parse::<f64>().unwrap_or_else(|_| panic!("bad value in {:?}", line));
+            }
+        }
+
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
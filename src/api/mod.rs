@@ -1,16 +1,21 @@
 pub mod auth;
+pub mod config;
 pub mod dashboard;
+pub mod metrics;
+pub mod rate_limit;
 pub mod stream;
 
-use axum::{extract::State, response::Json, routing::{get, post}, Router};
+use axum::{extract::State, http::{header, StatusCode}, middleware, response::Json, routing::{delete, get, post}, Router};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::info;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tracing::{info, warn};
 
-use crate::agents::{AgentId, AgentOrchestrator, AgentStatus, AgentMetrics, OrchestratorStatus};
-use crate::error::Result;
-use crate::config::Config;
+use crate::agents::{AgentEvent, AgentId, AgentOrchestrator, AgentStatus, AgentMetrics, OrchestratorStatus};
+use crate::agents::event_log::MAX_EVENTS_PER_AGENT;
+use crate::error::{Result, ScrapingError};
+use crate::config::{Config, ConfigManager};
 
 #[derive(Serialize)]
 pub struct ApiResponse<T> {
@@ -40,29 +45,99 @@ impl<T> ApiResponse<T> {
 pub async fn start_api_server(
     orchestrator: Arc<RwLock<AgentOrchestrator>>,
     config: Arc<Config>,
+    config_manager: Arc<dyn ConfigManager + Send + Sync>,
 ) -> Result<()> {
-    let stream_router = stream::create_stream_router(orchestrator.clone(), config.clone()).await;
+    let app = create_api_router(orchestrator, config.clone(), config_manager).await;
+
+    let addr = format!("0.0.0.0:{}", config.monitoring.api_port);
+    info!("API server listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    info!("API server listening on {}", listener.local_addr().unwrap());
+    // client addresses are what the rate limit is keyed on
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
+
+    Ok(())
+}
+
+/// Every API route, ready to serve
+pub async fn create_api_router(
+    orchestrator: Arc<RwLock<AgentOrchestrator>>,
+    config: Arc<Config>,
+    config_manager: Arc<dyn ConfigManager + Send + Sync>,
+) -> Router {
+    let stream_router = stream::create_stream_router(orchestrator.clone()).await;
+    let config_router = config::create_config_router(orchestrator.clone(), config_manager);
 
     let app = Router::new()
         .route("/status", get(get_orchestrator_status))
+        .route("/metrics", get(get_prometheus_metrics))
         .route("/agents", get(list_agents))
         .route("/agents/:id/status", get(get_agent_status))
         .route("/agents/:id/metrics", get(get_agent_metrics))
+        .route("/agents/:id/logs", get(get_agent_logs))
         .route("/agents/:id/start", post(start_agent))
         .route("/agents/:id/stop", post(stop_agent))
         .route("/agents/:id/restart", post(restart_agent))
+        .route("/agents/:id/pause", post(pause_agent))
+        .route("/agents/:id/resume", post(resume_agent))
         .route("/agents", post(create_agent))
+        .route("/streamers", post(add_streamer))
+        .route("/streamers/:name", delete(remove_streamer))
         .merge(stream_router)
-        .with_state(orchestrator);
+        .merge(config_router)
+        .with_state(orchestrator)
+        .layer(middleware::from_fn_with_state(config.clone(), auth::auth_middleware))
+        // limited before auth so token guessing is throttled too
+        .layer(middleware::from_fn_with_state(
+            Arc::new(rate_limit::RateLimiter::new(config.monitoring.api_rate_limit)),
+            rate_limit::rate_limit_middleware,
+        ));
 
-    let addr = format!("0.0.0.0:{}", config.monitoring.api_port);
-    info!("API server listening on {}", addr);
+    // cors goes on the outside so preflight requests never reach the auth middleware
+    match build_cors_layer(&config.monitoring.cors_allowed_origins) {
+        Some(cors) => app.layer(cors),
+        None => app,
+    }
+}
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    info!("API server listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap();
+/// Status code for a failed orchestrator call: bad input is the client's fault,
+/// a full agent pool is a conflict, anything else is on us
+fn error_status(error: &(dyn std::error::Error + Send + Sync + 'static)) -> StatusCode {
+    match error.downcast_ref::<ScrapingError>() {
+        Some(ScrapingError::ConfigError(_)) => StatusCode::BAD_REQUEST,
+        Some(ScrapingError::ResourceLimit(_)) => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
 
-    Ok(())
+/// Build the CORS layer from the configured origins, `None` when CORS is disabled
+fn build_cors_layer(allowed_origins: &[String]) -> Option<CorsLayer> {
+    if allowed_origins.is_empty() {
+        return None;
+    }
+
+    if allowed_origins.iter().any(|origin| origin == "*") {
+        return Some(CorsLayer::permissive());
+    }
+
+    let origins: Vec<_> = allowed_origins
+        .iter()
+        .filter_map(|origin| match origin.parse() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Ignoring invalid CORS origin '{}': {}", origin, e);
+                None
+            }
+        })
+        .collect();
+
+    Some(
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods(Any)
+            .allow_headers(Any),
+    )
 }
 
 pub async fn start_dashboard_server(
@@ -70,7 +145,8 @@ pub async fn start_dashboard_server(
     config: Arc<Config>,
 ) -> Result<()> {
     let dashboard_port = config.monitoring.dashboard_port.unwrap_or(8888);
-    let app = dashboard::create_dashboard_router().with_state(orchestrator);
+    let ws_limits = dashboard::WsLimits::from_config(&config.monitoring)?;
+    let app = dashboard::create_dashboard_router(ws_limits).with_state(orchestrator);
 
     let addr = format!("0.0.0.0:{}", dashboard_port);
     info!("Dashboard server listening on http://{}", addr);
@@ -90,44 +166,125 @@ pub struct CreateAgentRequest {
 async fn create_agent(
     State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
     Json(payload): Json<CreateAgentRequest>,
-) -> Json<ApiResponse<AgentId>> {
+) -> (StatusCode, Json<ApiResponse<AgentId>>) {
     let mut orchestrator_guard = orchestrator.write().await;
     match orchestrator_guard.spawn_agent(&payload.streamer, payload.priority.unwrap_or(0)).await {
-        Ok(agent_id) => Json(ApiResponse::success(agent_id)),
-        Err(e) => Json(ApiResponse::error(format!("Failed to create agent: {}", e))),
+        Ok(agent_id) => (StatusCode::OK, Json(ApiResponse::success(agent_id))),
+        Err(e) => (error_status(e.as_ref()), Json(ApiResponse::error(format!("Failed to create agent: {}", e)))),
+    }
+}
+
+fn agent_not_found<T>(agent_id: AgentId) -> (StatusCode, Json<ApiResponse<T>>) {
+    (StatusCode::NOT_FOUND, Json(ApiResponse::error(format!("Agent {} not found", agent_id))))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddStreamerRequest {
+    streamer: String,
+}
+
+async fn add_streamer(
+    State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
+    Json(payload): Json<AddStreamerRequest>,
+) -> (StatusCode, Json<ApiResponse<AgentId>>) {
+    let mut orchestrator_guard = orchestrator.write().await;
+    match orchestrator_guard.add_streamer(&payload.streamer).await {
+        Ok(agent_id) => (StatusCode::OK, Json(ApiResponse::success(agent_id))),
+        Err(e) => (
+            error_status(e.as_ref()),
+            Json(ApiResponse::error(format!("Failed to add streamer {}: {}", payload.streamer, e))),
+        ),
+    }
+}
+
+async fn remove_streamer(
+    State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    let mut orchestrator_guard = orchestrator.write().await;
+    match orchestrator_guard.remove_streamer(&name).await {
+        Ok(true) => (StatusCode::OK, Json(ApiResponse::success(format!("Streamer {} removed", name)))),
+        Ok(false) => (StatusCode::NOT_FOUND, Json(ApiResponse::error(format!("Streamer {} is not being scraped", name)))),
+        Err(e) => (
+            error_status(e.as_ref()),
+            Json(ApiResponse::error(format!("Failed to remove streamer {}: {}", name, e))),
+        ),
     }
 }
 
 async fn start_agent(
     State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
     axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
-) -> Json<ApiResponse<String>> {
+) -> (StatusCode, Json<ApiResponse<String>>) {
     let mut orchestrator_guard = orchestrator.write().await;
-    match orchestrator_guard.restart_agent(agent_id).await { // Restarting is effectively starting if stopped
-        Ok(_) => Json(ApiResponse::success(format!("Agent {} started/restarted successfully", agent_id))),
-        Err(e) => Json(ApiResponse::error(format!("Failed to start/restart agent {}: {}", agent_id, e))),
+    if orchestrator_guard.get_agent_status(agent_id).await.is_none() {
+        return agent_not_found(agent_id);
+    }
+    match orchestrator_guard.manual_restart_agent(agent_id).await { // Restarting is effectively starting if stopped
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::success(format!("Agent {} started/restarted successfully", agent_id)))),
+        Err(e) => (
+            error_status(e.as_ref()),
+            Json(ApiResponse::error(format!("Failed to start/restart agent {}: {}", agent_id, e))),
+        ),
     }
 }
 
 async fn stop_agent(
     State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
     axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
-) -> Json<ApiResponse<String>> {
+) -> (StatusCode, Json<ApiResponse<String>>) {
     let mut orchestrator_guard = orchestrator.write().await;
+    if orchestrator_guard.get_agent_status(agent_id).await.is_none() {
+        return agent_not_found(agent_id);
+    }
     match orchestrator_guard.stop_agent(agent_id).await {
-        Ok(_) => Json(ApiResponse::success(format!("Agent {} stopped successfully", agent_id))),
-        Err(e) => Json(ApiResponse::error(format!("Failed to stop agent {}: {}", agent_id, e))),
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::success(format!("Agent {} stopped successfully", agent_id)))),
+        Err(e) => (
+            error_status(e.as_ref()),
+            Json(ApiResponse::error(format!("Failed to stop agent {}: {}", agent_id, e))),
+        ),
     }
 }
 
 async fn restart_agent(
     State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
     axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
-) -> Json<ApiResponse<String>> {
+) -> (StatusCode, Json<ApiResponse<String>>) {
     let mut orchestrator_guard = orchestrator.write().await;
-    match orchestrator_guard.restart_agent(agent_id).await {
-        Ok(_) => Json(ApiResponse::success(format!("Agent {} restarted successfully", agent_id))),
-        Err(e) => Json(ApiResponse::error(format!("Failed to restart agent {}: {}", agent_id, e))),
+    if orchestrator_guard.get_agent_status(agent_id).await.is_none() {
+        return agent_not_found(agent_id);
+    }
+    match orchestrator_guard.manual_restart_agent(agent_id).await {
+        Ok(_) => (StatusCode::OK, Json(ApiResponse::success(format!("Agent {} restarted successfully", agent_id)))),
+        Err(e) => (
+            error_status(e.as_ref()),
+            Json(ApiResponse::error(format!("Failed to restart agent {}: {}", agent_id, e))),
+        ),
+    }
+}
+
+async fn pause_agent(
+    State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
+    axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    let mut orchestrator_guard = orchestrator.write().await;
+    match orchestrator_guard.pause_agent(agent_id).await {
+        Ok(true) => (StatusCode::OK, Json(ApiResponse::success(format!("Agent {} paused", agent_id)))),
+        Ok(false) => agent_not_found(agent_id),
+        // the only way pausing fails is an agent that isn't running
+        Err(e) => (StatusCode::CONFLICT, Json(ApiResponse::error(format!("Failed to pause agent {}: {}", agent_id, e)))),
+    }
+}
+
+async fn resume_agent(
+    State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
+    axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    let mut orchestrator_guard = orchestrator.write().await;
+    match orchestrator_guard.resume_agent(agent_id).await {
+        Ok(true) => (StatusCode::OK, Json(ApiResponse::success(format!("Agent {} resumed", agent_id)))),
+        Ok(false) => agent_not_found(agent_id),
+        Err(e) => (StatusCode::CONFLICT, Json(ApiResponse::error(format!("Failed to resume agent {}: {}", agent_id, e)))),
     }
 }
 
@@ -139,6 +296,14 @@ async fn get_orchestrator_status(
     Json(ApiResponse::success(status))
 }
 
+async fn get_prometheus_metrics(
+    State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
+) -> ([(header::HeaderName, &'static str); 1], String) {
+    let orchestrator_guard = orchestrator.read().await;
+    let status = orchestrator_guard.get_status().await;
+    ([(header::CONTENT_TYPE, metrics::PROMETHEUS_CONTENT_TYPE)], metrics::render_prometheus(&status))
+}
+
 async fn list_agents(
     State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
 ) -> Json<ApiResponse<Vec<AgentId>>> {
@@ -150,21 +315,269 @@ async fn list_agents(
 async fn get_agent_status(
     State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
     axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
-) -> Json<ApiResponse<AgentStatus>> {
+) -> (StatusCode, Json<ApiResponse<AgentStatus>>) {
     let orchestrator_guard = orchestrator.read().await;
     match orchestrator_guard.get_agent_status(agent_id).await {
-        Some(status) => Json(ApiResponse::success(status)),
-        None => Json(ApiResponse::error(format!("Agent {} not found", agent_id))),
+        Some(status) => (StatusCode::OK, Json(ApiResponse::success(status))),
+        None => agent_not_found(agent_id),
     }
 }
 
 async fn get_agent_metrics(
     State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
     axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
-) -> Json<ApiResponse<AgentMetrics>> {
+) -> (StatusCode, Json<ApiResponse<AgentMetrics>>) {
     let orchestrator_guard = orchestrator.read().await;
     match orchestrator_guard.get_agent_metrics(agent_id).await {
-        Some(metrics) => Json(ApiResponse::success(metrics)),
-        None => Json(ApiResponse::error(format!("Agent {} not found", agent_id))),
+        Some(metrics) => (StatusCode::OK, Json(ApiResponse::success(metrics))),
+        None => agent_not_found(agent_id),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AgentLogsQuery {
+    limit: Option<usize>,
+}
+
+async fn get_agent_logs(
+    State(orchestrator): State<Arc<RwLock<AgentOrchestrator>>>,
+    axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
+    axum::extract::Query(query): axum::extract::Query<AgentLogsQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<AgentEvent>>>) {
+    let limit = query.limit.unwrap_or(50).min(MAX_EVENTS_PER_AGENT);
+    let orchestrator_guard = orchestrator.read().await;
+    match orchestrator_guard.get_agent_events(agent_id, limit).await {
+        Some(events) => (StatusCode::OK, Json(ApiResponse::success(events))),
+        None => (StatusCode::NOT_FOUND, Json(ApiResponse::error(format!("No events recorded for agent {}", agent_id)))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::BrowserManager;
+    use crate::config::FileConfigManager;
+    use serde_json::{json, Value};
+    use tempfile::tempdir;
+
+    /// Serve the API for an orchestrator that can't launch browsers, returning its base url
+    async fn serve_test_api(config: Config, config_path: std::path::PathBuf) -> (String, Arc<RwLock<AgentOrchestrator>>) {
+        let orchestrator = Arc::new(RwLock::new(AgentOrchestrator::new(
+            config.clone(),
+            Arc::new(BrowserManager::without_browser(1)),
+        )));
+        let config_manager = Arc::new(FileConfigManager::new(config_path));
+        let app = create_api_router(orchestrator.clone(), Arc::new(config), config_manager).await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await.unwrap();
+        });
+        (format!("http://{}", addr), orchestrator)
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_streamers() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config::default();
+        config.agents.retry_attempts = 0;
+        let (base_url, orchestrator) = serve_test_api(config, temp_dir.path().join("config.toml")).await;
+        let client = reqwest::Client::new();
+
+        let response = client.post(format!("{}/streamers", base_url))
+            .json(&json!({"streamer": "not a name!"}))
+            .send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["success"], false);
+
+        // no browser here, so the agent can't start and the streamer isn't kept
+        let response = client.post(format!("{}/streamers", base_url))
+            .json(&json!({"streamer": "xqcow"}))
+            .send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!orchestrator.read().await.get_config().await.streamers.contains(&"xqcow".to_string()));
+
+        let response = client.delete(format!("{}/streamers/Shroud", base_url)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(orchestrator.read().await.get_config().await.streamers, vec!["ninja"]);
+
+        let response = client.delete(format!("{}/streamers/shroud", base_url)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_missing_agent_is_404() {
+        let temp_dir = tempdir().unwrap();
+        let (base_url, _orchestrator) = serve_test_api(Config::default(), temp_dir.path().join("config.toml")).await;
+        let client = reqwest::Client::new();
+        let agent_id = uuid::Uuid::new_v4();
+
+        for path in ["status", "metrics", "logs"] {
+            let response = client.get(format!("{}/agents/{}/{}", base_url, agent_id, path)).send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND, "GET {}", path);
+            let body: Value = response.json().await.unwrap();
+            assert_eq!(body["success"], false);
+        }
+        for path in ["start", "stop", "restart", "pause", "resume"] {
+            let response = client.post(format!("{}/agents/{}/{}", base_url, agent_id, path)).send().await.unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND, "POST {}", path);
+        }
+
+        let response = client.get(format!("{}/agents/not-an-id/status", base_url)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_api_token_required_when_set() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config::default();
+        config.monitoring.api_token = Some("s3cret".to_string());
+        let (base_url, _orchestrator) = serve_test_api(config, temp_dir.path().join("config.toml")).await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(format!("{}/status", base_url)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        let response = client.get(format!("{}/config", base_url)).bearer_auth("s3cre").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        let response = client.post(format!("{}/streamers", base_url))
+            .bearer_auth("wrong!")
+            .json(&json!({"streamer": "xqcow"}))
+            .send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let response = client.get(format!("{}/status", base_url)).bearer_auth("s3cret").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let response = client.get(format!("{}/config", base_url)).bearer_auth("s3cret").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_no_api_token_leaves_routes_open() {
+        let temp_dir = tempdir().unwrap();
+        let (base_url, _orchestrator) = serve_test_api(Config::default(), temp_dir.path().join("config.toml")).await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(format!("{}/status", base_url)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let response = client.get(format!("{}/agents", base_url)).bearer_auth("anything").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_metrics_route() {
+        let temp_dir = tempdir().unwrap();
+        let (base_url, _orchestrator) = serve_test_api(Config::default(), temp_dir.path().join("config.toml")).await;
+
+        let response = reqwest::get(format!("{}/metrics", base_url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], metrics::PROMETHEUS_CONTENT_TYPE);
+        let body = response.text().await.unwrap();
+        assert!(body.contains("# TYPE scraper_active_agents gauge"));
+        assert!(body.contains("scraper_messages_scraped 0"));
+    }
+
+    #[tokio::test]
+    async fn test_api_rate_limit() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config::default();
+        config.monitoring.api_rate_limit = 5;
+        let (base_url, _orchestrator) = serve_test_api(config, temp_dir.path().join("config.toml")).await;
+        let client = reqwest::Client::new();
+
+        let mut statuses = Vec::new();
+        for _ in 0..20 {
+            statuses.push(client.get(format!("{}/status", base_url)).send().await.unwrap().status());
+        }
+        assert_eq!(statuses[0], reqwest::StatusCode::OK);
+        assert!(statuses.contains(&reqwest::StatusCode::TOO_MANY_REQUESTS));
+
+        // only the stream routes are exempt, not everything sharing their prefix
+        let response = client.post(format!("{}/streamers", base_url))
+            .json(&json!({"streamer": "not a name!"}))
+            .send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+        // the stream status route is exempt
+        let response = client.get(format!("{}/stream/status", base_url)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_cors_disabled_by_default() {
+        let temp_dir = tempdir().unwrap();
+        let (base_url, _orchestrator) = serve_test_api(Config::default(), temp_dir.path().join("config.toml")).await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(format!("{}/status", base_url))
+            .header("Origin", "https://dashboard.local")
+            .send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(response.headers().get("access-control-allow-origin").is_none());
+
+        // "*" lets any origin in
+        let mut config = Config::default();
+        config.monitoring.cors_allowed_origins = vec!["*".to_string()];
+        let (base_url, _orchestrator) = serve_test_api(config, temp_dir.path().join("config.toml")).await;
+        let response = client.get(format!("{}/status", base_url))
+            .header("Origin", "https://dashboard.local")
+            .send().await.unwrap();
+        assert_eq!(response.headers().get("access-control-allow-origin").unwrap(), "*");
+    }
+
+    #[tokio::test]
+    async fn test_update_config() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let mut config = Config::default();
+        config.monitoring.discord_webhook_url = Some("https://discord.com/api/webhooks/1/abc".to_string());
+        let (base_url, orchestrator) = serve_test_api(config, config_path.clone()).await;
+        let client = reqwest::Client::new();
+
+        let body: Value = client.get(format!("{}/config", base_url)).send().await.unwrap().json().await.unwrap();
+        assert_eq!(body["data"]["streamers"], json!(["shroud", "ninja"]));
+        assert_eq!(body["data"]["monitoring"]["discord_webhook_url"], config::REDACTED_TOKEN);
+
+        // edit the GET response and send it back
+        let mut new_config = body["data"].clone();
+        new_config["streamers"] = json!(["XQCow"]);
+        let response = client.put(format!("{}/config", base_url)).json(&new_config).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["streamers"], json!(["xqcow"]));
+        assert_eq!(body["data"]["monitoring"]["discord_webhook_url"], config::REDACTED_TOKEN);
+
+        let orchestrator_guard = orchestrator.read().await;
+        let current = orchestrator_guard.get_config().await;
+        assert_eq!(current.streamers, vec!["xqcow"]);
+        assert_eq!(current.monitoring.discord_webhook_url.as_deref(), Some("https://discord.com/api/webhooks/1/abc"));
+        // the new streamer got an agent spawned for it, which fails without a browser
+        assert_eq!(orchestrator_guard.get_status().await.error_count, 1);
+        drop(orchestrator_guard);
+        let saved = FileConfigManager::new(config_path).load_config().await.unwrap();
+        assert_eq!(saved.streamers, vec!["xqcow"]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_update_rejected() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let (base_url, orchestrator) = serve_test_api(Config::default(), config_path.clone()).await;
+
+        let mut invalid = Config::default();
+        invalid.agents.max_concurrent = 0;
+        invalid.streamers = vec!["xqcow".to_string()];
+        let response = reqwest::Client::new().put(format!("{}/config", base_url)).json(&invalid).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["success"], false);
+        assert!(body["error"].as_str().unwrap().contains("max_concurrent"));
+
+        let current = orchestrator.read().await.get_config().await;
+        assert_eq!(current.streamers, vec!["shroud", "ninja"]);
+        assert_eq!(current.agents.max_concurrent, 5);
+        assert_eq!(orchestrator.read().await.get_status().await.error_count, 0);
+        assert!(!config_path.exists());
     }
 }
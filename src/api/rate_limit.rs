@@ -0,0 +1,90 @@
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Clients tracked before quiet ones are forgotten
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token bucket per client IP: `per_second` requests a second, with bursts of
+/// up to a second's worth. A limit of 0 lets everything through.
+pub struct RateLimiter {
+    per_second: u32,
+    clients: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            per_second,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Spend one of `client`'s tokens, false if it has none left
+    pub fn allow(&self, client: IpAddr) -> bool {
+        if self.per_second == 0 {
+            return true;
+        }
+
+        let now = Instant::now();
+        let capacity = self.per_second as f64;
+        let mut clients = self.clients.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if clients.len() >= MAX_TRACKED_CLIENTS {
+            // a client quiet for a second has a full bucket again, same as a new one
+            clients.retain(|_, bucket| now.duration_since(bucket.refilled_at) < Duration::from_secs(1));
+        }
+
+        let bucket = clients.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Answer 429 once a client goes over `monitoring.api_rate_limit`. The SSE
+/// stream holds one long request open, so it isn't counted.
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let path = req.uri().path();
+    if path == "/stream" || path.starts_with("/stream/") {
+        return Ok(next.run(req).await);
+    }
+
+    // without connect info (e.g. a plain `serve`) every client shares one bucket
+    let client = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    if !limiter.allow(client) {
+        return Err(StatusCode::TOO_MANY_REQUESTS);
+    }
+    Ok(next.run(req).await)
+}
@@ -0,0 +1,65 @@
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, EnableParams, EventRequestPaused, FailRequestParams, RequestPattern,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+use chromiumoxide::page::Page;
+use futures::StreamExt;
+use tracing::debug;
+
+use crate::error::{Result, ScrapingError};
+
+/// Resources chat scraping never looks at: emotes, avatars, the player and styling
+pub const BLOCKED_RESOURCE_TYPES: [ResourceType; 4] = [
+    ResourceType::Image,
+    ResourceType::Font,
+    ResourceType::Media,
+    ResourceType::Stylesheet,
+];
+
+/// `Fetch.enable` pausing only the blocked resource types, everything else
+/// loads without a round trip through us
+pub fn resource_blocking_params() -> EnableParams {
+    let patterns = BLOCKED_RESOURCE_TYPES
+        .iter()
+        .map(|resource_type| RequestPattern::builder().url_pattern("*").resource_type(resource_type.clone()).build())
+        .collect::<Vec<_>>();
+    EnableParams::builder().patterns(patterns).build()
+}
+
+/// The abort for a paused request of a blocked type, None if it should go through
+pub fn blocked_request(event: &EventRequestPaused) -> Option<FailRequestParams> {
+    if BLOCKED_RESOURCE_TYPES.contains(&event.resource_type) {
+        Some(FailRequestParams::new(event.request_id.clone(), ErrorReason::BlockedByClient))
+    } else {
+        None
+    }
+}
+
+/// Abort image, font, media and stylesheet requests on `page` for as long as it's open
+pub async fn install_resource_blocking(page: &Page) -> Result<()> {
+    // listen before enabling so the first paused request isn't missed
+    let mut paused = page
+        .event_listener::<EventRequestPaused>()
+        .await
+        .map_err(|e| ScrapingError::BrowserError(format!("Failed to listen for paused requests: {}", e)))?;
+
+    page.execute(resource_blocking_params())
+        .await
+        .map_err(|e| ScrapingError::BrowserError(format!("Failed to enable request interception: {}", e)))?;
+
+    let page = page.clone();
+    tokio::spawn(async move {
+        // ends once the page is closed and the event stream with it
+        while let Some(event) = paused.next().await {
+            let result = match blocked_request(&event) {
+                Some(fail) => page.execute(fail).await.map(|_| ()),
+                None => page.execute(ContinueRequestParams::new(event.request_id.clone())).await.map(|_| ()),
+            };
+            if let Err(e) = result {
+                debug!("Failed to settle paused request {}: {}", event.request.url, e);
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -1,15 +1,20 @@
 use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::detection::DetectionOptions;
 use chromiumoxide::page::Page;
+use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
 use chromiumoxide::cdp::browser_protocol::emulation::SetUserAgentOverrideParams;
+use chromiumoxide::cdp::browser_protocol::target::{CreateBrowserContextParams, CreateTargetParams};
 use futures::StreamExt;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 use std::time::Instant;
 
+use crate::browser::intercept::install_resource_blocking;
 use crate::browser::stealth::{StealthConfig, UserAgentGenerator, FingerprintRandomizer, BrowserFingerprint, generate_video_disable_script, generate_stealth_script};
 use crate::error::{Result, ScrapingError};
 
@@ -22,6 +27,7 @@ pub struct BrowserInstance {
     pub fingerprint: BrowserFingerprint,
     pub user_agent: String,
     pub proxy: Option<String>,
+    pub browser_context_id: Option<BrowserContextId>, // own context when the page goes through a proxy
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -94,6 +100,14 @@ impl BrowserInstance {
         }
     }
 
+    /// Ping the page over CDP, false once the tab or the browser behind it is gone
+    pub async fn is_alive(&self) -> bool {
+        matches!(
+            tokio::time::timeout(Duration::from_secs(5), self.page.evaluate("1")).await,
+            Ok(Ok(_))
+        )
+    }
+
     pub async fn close(self) -> Result<()> {
         self.page
             .close()
@@ -107,7 +121,8 @@ impl BrowserInstance {
 
 pub struct BrowserPool {
     instances: Arc<RwLock<HashMap<BrowserInstanceId, BrowserInstance>>>,
-    browser: Arc<Browser>,
+    browser: RwLock<Option<Arc<Browser>>>, // None only for pools built by `without_browser` in tests
+    binary_path: Option<PathBuf>, // for relaunching after a crash
     stealth_config: StealthConfig,
     user_agent_generator: UserAgentGenerator,
     fingerprint_randomizer: FingerprintRandomizer,
@@ -115,15 +130,74 @@ pub struct BrowserPool {
     proxy_list: Vec<String>,
     proxy_index: Arc<Mutex<usize>>,
     bad_proxies: Arc<RwLock<HashMap<String, Instant>>>,
+    replacements: Arc<RwLock<HashMap<BrowserInstanceId, BrowserInstanceId>>>, // dead instance -> the one that took over
+    replaced_tx: broadcast::Sender<(BrowserInstanceId, BrowserInstanceId)>,
+}
+
+/// Environment variable pointing at the Chrome/Chromium binary
+pub const BROWSER_PATH_ENV: &str = "BROWSER_PATH";
+
+/// Find the browser binary: explicit path first, then `BROWSER_PATH`, then the
+/// usual install locations. A missing binary is a `BrowserNotFound` error.
+pub fn resolve_browser_binary(configured_path: Option<&Path>) -> Result<PathBuf> {
+    let explicit_path = configured_path
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::var_os(BROWSER_PATH_ENV).map(PathBuf::from));
+
+    if let Some(path) = explicit_path {
+        if path.is_file() {
+            return Ok(path);
+        }
+        return Err(ScrapingError::BrowserNotFound(format!(
+            "browser binary not found at {}, install Chrome/Chromium or fix {} / browser.binary_path",
+            path.display(),
+            BROWSER_PATH_ENV
+        )).into());
+    }
+
+    chromiumoxide::detection::default_executable(DetectionOptions::default()).map_err(|e| {
+        ScrapingError::BrowserNotFound(format!(
+            "could not find Chrome/Chromium ({}), install it or set {} / browser.binary_path",
+            e, BROWSER_PATH_ENV
+        )).into()
+    })
 }
 
 impl BrowserPool {
     pub async fn new(max_instances: usize, stealth_config: StealthConfig) -> Result<Self> {
-        let browser = Self::create_browser(&stealth_config).await?;
+        Self::with_binary_path(max_instances, stealth_config, None).await
+    }
+
+    pub async fn with_binary_path(
+        max_instances: usize,
+        stealth_config: StealthConfig,
+        binary_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        // check for the binary before anything else so a missing install fails once, clearly
+        let binary_path = resolve_browser_binary(binary_path.as_deref())?;
+        info!("Using browser binary at {}", binary_path.display());
+
+        let browser = Self::create_browser(&stealth_config, &binary_path).await?;
         
-        Ok(Self {
+        Ok(Self::from_browser(max_instances, stealth_config, Some(Arc::new(browser)), Some(binary_path)))
+    }
+
+    /// A pool that never launched a browser, so every instance fails to start
+    #[cfg(test)]
+    pub(crate) fn without_browser(max_instances: usize, stealth_config: StealthConfig) -> Self {
+        Self::from_browser(max_instances, stealth_config, None, None)
+    }
+
+    fn from_browser(
+        max_instances: usize,
+        stealth_config: StealthConfig,
+        browser: Option<Arc<Browser>>,
+        binary_path: Option<PathBuf>,
+    ) -> Self {
+        Self {
             instances: Arc::new(RwLock::new(HashMap::new())),
-            browser: Arc::new(browser),
+            browser: RwLock::new(browser),
+            binary_path,
             stealth_config,
             user_agent_generator: UserAgentGenerator::new(),
             fingerprint_randomizer: FingerprintRandomizer::new(),
@@ -131,7 +205,15 @@ impl BrowserPool {
             proxy_list: vec![],
             proxy_index: Arc::new(Mutex::new(0)),
             bad_proxies: Arc::new(RwLock::new(HashMap::new())),
-        })
+            replacements: Arc::new(RwLock::new(HashMap::new())),
+            replaced_tx: broadcast::channel(64).0,
+        }
+    }
+
+    /// Proxies (`host:port`) handed out to new instances
+    pub fn with_proxy_list(mut self, proxy_list: Vec<String>) -> Self {
+        self.proxy_list = proxy_list;
+        self
     }
 
     pub async fn report_bad_proxy(&self, proxy: String) {
@@ -140,12 +222,31 @@ impl BrowserPool {
         warn!("Reported bad proxy: {}", proxy);
     }
 
-    async fn create_browser(stealth_config: &StealthConfig) -> Result<Browser> {
+    /// `preferred` if it's one of ours and not cooling down after being reported
+    /// bad, otherwise the next proxy in rotation
+    pub async fn choose_proxy(&self, preferred: Option<&str>) -> Option<String> {
+        if let Some(preferred) = preferred {
+            let known = self.proxy_list.iter().any(|proxy| proxy == preferred);
+            let cooling_down = self
+                .bad_proxies
+                .read()
+                .await
+                .get(preferred)
+                .is_some_and(|reported_time| reported_time.elapsed() < Duration::from_secs(300));
+            if known && !cooling_down {
+                return Some(preferred.to_string());
+            }
+            debug!("Proxy {} is no longer usable, picking another", preferred);
+        }
+        self.get_next_proxy().await
+    }
+
+    async fn create_browser(stealth_config: &StealthConfig, binary_path: &Path) -> Result<Browser> {
         info!("Creating browser with stealth config: {:?}", stealth_config);
         
         // kill any existing chrome processes that might be hanging
         let _ = std::process::Command::new("pkill")
-            .args(&["-f", "chrome"])
+            .args(["-f", "chrome"])
             .output();
         
         // create unique user data dir to avoid singleton lock issues
@@ -162,6 +263,7 @@ impl BrowserPool {
         tokio::time::sleep(Duration::from_millis(500)).await;
         
         let mut config = BrowserConfig::builder()
+            .chrome_executable(binary_path)
             .no_sandbox()
             .args(vec![
                 &format!("--user-data-dir={}", user_data_dir),
@@ -252,6 +354,12 @@ impl BrowserPool {
     }
 
     pub async fn create_instance(&self) -> Result<BrowserInstanceId> {
+        self.create_instance_with_proxy(None).await
+    }
+
+    /// Create an instance whose page stays on one proxy for its whole life,
+    /// `preferred` when it's still healthy
+    pub async fn create_instance_with_proxy(&self, preferred: Option<&str>) -> Result<BrowserInstanceId> {
         let instances = self.instances.read().await;
         let current_count = instances.len();
         if current_count >= self.max_instances {
@@ -264,16 +372,35 @@ impl BrowserPool {
         info!("Creating browser instance ({}/{})", current_count + 1, self.max_instances);
         drop(instances);
 
+        let Some(browser) = self.browser.read().await.clone() else {
+            return Err(ScrapingError::BrowserNotFound("no browser was launched".to_string()).into());
+        };
+
         let instance_id = Uuid::new_v4();
         let fingerprint = self.fingerprint_randomizer.generate_fingerprint();
         let user_agent = self.user_agent_generator.random_user_agent().to_string();
-        let proxy = self.get_next_proxy().await;
+        let proxy = self.choose_proxy(preferred).await;
+
+        // a proxied page gets a browser context of its own, the proxy is set per context
+        let browser_context_id = match proxy {
+            Some(ref proxy) => {
+                let params = CreateBrowserContextParams::builder().proxy_server(proxy.clone()).build();
+                let context_id = browser
+                    .create_browser_context(params)
+                    .await
+                    .map_err(|e| ScrapingError::BrowserError(format!("Failed to create browser context for proxy {}: {}", proxy, e)))?;
+                Some(context_id)
+            }
+            None => None,
+        };
+        let mut target = CreateTargetParams::new("about:blank");
+        target.browser_context_id = browser_context_id.clone();
 
         // Create new page with retry logic
         info!("Creating new browser page for instance {}", instance_id);
         let page = match tokio::time::timeout(
             Duration::from_secs(10),
-            self.browser.new_page("about:blank")
+            browser.new_page(target)
         ).await {
             Ok(Ok(page)) => {
                 info!("Successfully created browser page for instance {}", instance_id);
@@ -281,29 +408,54 @@ impl BrowserPool {
             }
             Ok(Err(e)) => {
                 error!("Failed to create new page for instance {}: {}", instance_id, e);
+                self.dispose_context(browser_context_id).await;
                 return Err(ScrapingError::BrowserError(format!("Failed to create new page: {}", e)).into());
             }
             Err(_) => {
                 error!("Timeout creating new page for instance {}", instance_id);
+                self.dispose_context(browser_context_id).await;
                 return Err(ScrapingError::BrowserError("Timeout creating new page".to_string()).into());
             }
         };
 
-        // set viewport if randomization enabled
-        if self.stealth_config.randomize_user_agents {
-            use chromiumoxide::cdp::browser_protocol::emulation::SetDeviceMetricsOverrideParams;
-            
-            let device_metrics = SetDeviceMetricsOverrideParams::builder()
-                .width(fingerprint.viewport.width as i64)
-                .height(fingerprint.viewport.height as i64)
-                .device_scale_factor(1.0)
-                .mobile(false)
-                .build()
-                .map_err(|e| ScrapingError::BrowserError(format!("Failed to build device metrics: {}", e)))?;
+        // viewport, timezone and locale come from the fingerprint and stay put for the instance's life
+        if self.stealth_config.fingerprint_randomization {
+            use chromiumoxide::cdp::browser_protocol::emulation::{
+                SetDeviceMetricsOverrideParams, SetLocaleOverrideParams, SetTimezoneOverrideParams,
+            };
+
+            if self.stealth_config.viewport_randomization {
+                let device_metrics = SetDeviceMetricsOverrideParams::builder()
+                    .width(fingerprint.viewport.width as i64)
+                    .height(fingerprint.viewport.height as i64)
+                    .device_scale_factor(1.0)
+                    .mobile(false)
+                    .build()
+                    .map_err(|e| ScrapingError::BrowserError(format!("Failed to build device metrics: {}", e)))?;
+
+                page.execute(device_metrics)
+                    .await
+                    .map_err(|e| ScrapingError::BrowserError(format!("Failed to set viewport: {}", e)))?;
+            }
+
+            page.execute(SetTimezoneOverrideParams::new(fingerprint.timezone.clone()))
+                .await
+                .map_err(|e| ScrapingError::BrowserError(format!("Failed to set timezone: {}", e)))?;
 
-            page.execute(device_metrics)
+            // ICU wants en_US rather than en-US
+            let locale = SetLocaleOverrideParams::builder()
+                .locale(fingerprint.locale().replace('-', "_"))
+                .build();
+            page.execute(locale)
                 .await
-                .map_err(|e| ScrapingError::BrowserError(format!("Failed to set viewport: {}", e)))?;
+                .map_err(|e| ScrapingError::BrowserError(format!("Failed to set locale: {}", e)))?;
+        }
+
+        if self.stealth_config.block_resources {
+            if let Err(e) = install_resource_blocking(&page).await {
+                // the page still works, it just downloads more
+                warn!("Resource blocking unavailable for instance {}: {}", instance_id, e);
+            }
         }
 
         // set user agent if randomization enabled
@@ -326,6 +478,7 @@ impl BrowserPool {
             fingerprint,
             user_agent,
             proxy,
+            browser_context_id,
             created_at: chrono::Utc::now(),
         };
 
@@ -342,18 +495,132 @@ impl BrowserPool {
         instances.get(&instance_id).cloned()
     }
 
+    /// `(dead, replacement)` every time a crashed instance is swapped out
+    pub fn subscribe_replacements(&self) -> broadcast::Receiver<(BrowserInstanceId, BrowserInstanceId)> {
+        self.replaced_tx.subscribe()
+    }
+
+    /// The instance currently standing in for `instance_id`, itself if it never crashed
+    pub async fn resolve_instance_id(&self, instance_id: BrowserInstanceId) -> BrowserInstanceId {
+        let replacements = self.replacements.read().await;
+        let mut current = instance_id;
+        // bounded in case a chain ever loops
+        for _ in 0..=replacements.len() {
+            match replacements.get(&current) {
+                Some(next) => current = *next,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// Ping every instance and swap the dead ones for fresh pages on the same
+    /// proxy. Dead instances leave the pool before anything else, so they're
+    /// never handed out again. Returns how many were replaced.
+    pub async fn replace_dead_instances(&self) -> usize {
+        let instances: Vec<BrowserInstance> = self.instances.read().await.values().cloned().collect();
+        let mut dead = Vec::new();
+        for instance in instances {
+            if !instance.is_alive().await && self.instances.write().await.remove(&instance.id).is_some() {
+                dead.push(instance);
+            }
+        }
+        if dead.is_empty() {
+            return 0;
+        }
+
+        // every page dying at once usually means the browser process went with them
+        self.relaunch_if_crashed().await;
+
+        let mut replaced = 0;
+        for instance in dead {
+            let dead_id = instance.id;
+            warn!("Browser instance {} stopped responding, replacing it", dead_id);
+            let proxy = instance.proxy.clone();
+            if let Err(e) = self.close_instance(instance).await {
+                debug!("Closing dead browser instance {} failed: {}", dead_id, e);
+            }
+
+            match self.create_instance_with_proxy(proxy.as_deref()).await {
+                Ok(replacement_id) => {
+                    self.replacements.write().await.insert(dead_id, replacement_id);
+                    let _ = self.replaced_tx.send((dead_id, replacement_id));
+                    info!("Replaced dead browser instance {} with {}", dead_id, replacement_id);
+                    replaced += 1;
+                }
+                Err(e) => {
+                    // the owner finds its instance gone and fails over the usual way
+                    error!("Failed to replace dead browser instance {}: {}", dead_id, e);
+                }
+            }
+        }
+
+        replaced
+    }
+
+    /// Launch a new browser if the current one no longer answers
+    async fn relaunch_if_crashed(&self) {
+        let Some(browser) = self.browser.read().await.clone() else {
+            return;
+        };
+        if matches!(tokio::time::timeout(Duration::from_secs(5), browser.version()).await, Ok(Ok(_))) {
+            return;
+        }
+        let Some(ref binary_path) = self.binary_path else {
+            return;
+        };
+
+        warn!("Browser process is not responding, relaunching it");
+        match Self::create_browser(&self.stealth_config, binary_path).await {
+            Ok(relaunched) => *self.browser.write().await = Some(Arc::new(relaunched)),
+            Err(e) => error!("Failed to relaunch browser: {}", e),
+        }
+    }
+
     pub async fn remove_instance(&self, instance_id: BrowserInstanceId) -> Result<()> {
+        let instance_id = self.resolve_instance_id(instance_id).await;
+        // forget the whole chain of dead instances that led here
+        let mut replacements = self.replacements.write().await;
+        let mut forgotten = vec![instance_id];
+        while let Some(forget) = forgotten.pop() {
+            replacements.retain(|dead, replacement| {
+                if *replacement == forget {
+                    forgotten.push(*dead);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        drop(replacements);
+
         let mut instances = self.instances.write().await;
         
         if let Some(instance) = instances.remove(&instance_id) {
             drop(instances);
-            instance.close().await?;
+            self.close_instance(instance).await?;
             info!("Removed browser instance {}", instance_id);
         }
         
         Ok(())
     }
 
+    /// Close the page, then drop the proxy context it had
+    async fn close_instance(&self, instance: BrowserInstance) -> Result<()> {
+        let browser_context_id = instance.browser_context_id.clone();
+        let closed = instance.close().await;
+        self.dispose_context(browser_context_id).await;
+        closed
+    }
+
+    async fn dispose_context(&self, browser_context_id: Option<BrowserContextId>) {
+        if let (Some(browser), Some(context_id)) = (self.browser.read().await.clone(), browser_context_id) {
+            if let Err(e) = browser.dispose_browser_context(context_id).await {
+                warn!("Failed to dispose browser context: {}", e);
+            }
+        }
+    }
+
     pub async fn get_instance_count(&self) -> usize {
         let instances = self.instances.read().await;
         instances.len()
@@ -365,7 +632,7 @@ impl BrowserPool {
         
         for instance_id in instance_ids {
             if let Some(instance) = instances.remove(&instance_id) {
-                if let Err(e) = instance.close().await {
+                if let Err(e) = self.close_instance(instance).await {
                     error!("Failed to close browser instance {}: {}", instance_id, e);
                 }
             }
@@ -421,7 +688,7 @@ impl BrowserPool {
         let removed_count = to_remove.len();
         for id in to_remove {
             if let Some(instance) = instances.remove(&id) {
-                if let Err(e) = instance.close().await {
+                if let Err(e) = self.close_instance(instance).await {
                     error!("Failed to close old browser instance {}: {}", id, e);
                 }
             }
@@ -441,15 +708,64 @@ pub struct BrowserManager {
 
 impl BrowserManager {
     pub async fn new(max_concurrent_sessions: usize, stealth_config: StealthConfig) -> Result<Self> {
-        let pool = BrowserPool::new(max_concurrent_sessions, stealth_config).await?;
-        
+        Self::with_binary_path(max_concurrent_sessions, stealth_config, None).await
+    }
+
+    /// Like `new`, but launching the browser at `binary_path` when given
+    pub async fn with_binary_path(
+        max_concurrent_sessions: usize,
+        stealth_config: StealthConfig,
+        binary_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        let pool = BrowserPool::with_binary_path(max_concurrent_sessions, stealth_config, binary_path).await?;
+
         Ok(Self { pool })
     }
 
+    /// A manager with no browser behind it, for exercising the orchestrator in tests
+    #[cfg(test)]
+    pub(crate) fn without_browser(max_concurrent_sessions: usize) -> Self {
+        Self {
+            pool: BrowserPool::without_browser(max_concurrent_sessions, StealthConfig::default()),
+        }
+    }
+
+    /// Proxies (`host:port`) for new browser instances, e.g. `agents.proxy_list`
+    pub fn with_proxy_list(mut self, proxy_list: Vec<String>) -> Self {
+        self.pool.proxy_list = proxy_list;
+        self
+    }
+
     pub async fn create_browser_instance(&self) -> Result<BrowserInstanceId> {
         self.pool.create_instance().await
     }
 
+    /// Like `create_browser_instance`, but reusing `preferred` as the proxy when it's still healthy
+    pub async fn create_browser_instance_with_proxy(&self, preferred: Option<&str>) -> Result<BrowserInstanceId> {
+        self.pool.create_instance_with_proxy(preferred).await
+    }
+
+    pub async fn report_bad_proxy(&self, proxy: String) {
+        self.pool.report_bad_proxy(proxy).await
+    }
+
+    pub async fn choose_proxy(&self, preferred: Option<&str>) -> Option<String> {
+        self.pool.choose_proxy(preferred).await
+    }
+
+    pub fn subscribe_replacements(&self) -> broadcast::Receiver<(BrowserInstanceId, BrowserInstanceId)> {
+        self.pool.subscribe_replacements()
+    }
+
+    pub async fn resolve_instance_id(&self, instance_id: BrowserInstanceId) -> BrowserInstanceId {
+        self.pool.resolve_instance_id(instance_id).await
+    }
+
+    /// Health check the pool, recreating crashed instances
+    pub async fn replace_dead_instances(&self) -> usize {
+        self.pool.replace_dead_instances().await
+    }
+
     pub async fn get_browser_instance(&self, instance_id: BrowserInstanceId) -> Option<BrowserInstance> {
         self.pool.get_instance(instance_id).await
     }
@@ -1,8 +1,9 @@
+pub mod intercept;
 pub mod manager;
 pub mod stealth;
 
 #[cfg(test)]
 mod tests;
 
-pub use manager::{BrowserManager, BrowserPool, BrowserInstance, BrowserInstanceId};
+pub use manager::{BrowserManager, BrowserPool, BrowserInstance, BrowserInstanceId, resolve_browser_binary};
 pub use stealth::{StealthConfig, UserAgentGenerator, FingerprintRandomizer};
\ No newline at end of file
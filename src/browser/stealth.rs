@@ -1,6 +1,7 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StealthConfig {
@@ -9,6 +10,7 @@ pub struct StealthConfig {
     pub proxy_rotation: bool,
     pub fingerprint_randomization: bool,
     pub viewport_randomization: bool,
+    pub block_resources: bool, // abort image/font/media/stylesheet requests
     pub delay_range: (u64, u64), // milliseconds
 }
 
@@ -20,15 +22,35 @@ impl Default for StealthConfig {
             proxy_rotation: false,
             fingerprint_randomization: true,
             viewport_randomization: true,
+            block_resources: true,
             delay_range: (1000, 5000),
         }
     }
 }
 
+impl From<&crate::config::StealthConfig> for StealthConfig {
+    fn from(config: &crate::config::StealthConfig) -> Self {
+        Self {
+            randomize_user_agents: config.randomize_user_agents,
+            simulate_human_behavior: config.simulate_human_behavior,
+            proxy_rotation: config.proxy_rotation,
+            fingerprint_randomization: config.fingerprint_randomization,
+            block_resources: config.block_resources,
+            ..Self::default()
+        }
+    }
+}
+
 pub struct UserAgentGenerator {
     user_agents: Vec<String>,
 }
 
+impl Default for UserAgentGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UserAgentGenerator {
     pub fn new() -> Self {
         let user_agents = vec![
@@ -52,7 +74,7 @@ impl UserAgentGenerator {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ViewportSize {
     pub width: u32,
     pub height: u32,
@@ -60,8 +82,14 @@ pub struct ViewportSize {
 
 pub struct FingerprintRandomizer {
     viewports: Vec<ViewportSize>,
-    languages: Vec<String>,
-    timezones: Vec<String>,
+    locales: Vec<(String, String)>, // accept-language and a timezone that goes with it
+    last_pick: Mutex<Option<(usize, usize)>>, // viewport and locale of the previous fingerprint
+}
+
+impl Default for FingerprintRandomizer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FingerprintRandomizer {
@@ -72,57 +100,65 @@ impl FingerprintRandomizer {
             ViewportSize { width: 1536, height: 864 },
             ViewportSize { width: 1440, height: 900 },
             ViewportSize { width: 1280, height: 720 },
+            ViewportSize { width: 1280, height: 800 },
             ViewportSize { width: 1600, height: 900 },
+            ViewportSize { width: 1680, height: 1050 },
+            ViewportSize { width: 1920, height: 1200 },
             ViewportSize { width: 2560, height: 1440 },
         ];
 
-        let languages = vec![
-            "en-US,en;q=0.9".to_string(),
-            "en-GB,en;q=0.9".to_string(),
-            "en-CA,en;q=0.9".to_string(),
-            "en-AU,en;q=0.9".to_string(),
-        ];
-
-        let timezones = vec![
-            "America/New_York".to_string(),
-            "America/Los_Angeles".to_string(),
-            "America/Chicago".to_string(),
-            "America/Denver".to_string(),
-            "Europe/London".to_string(),
-            "Europe/Berlin".to_string(),
-            "Australia/Sydney".to_string(),
-        ];
+        // a language that doesn't match the clock is its own red flag, so they're picked together
+        let locales = [
+            ("en-US,en;q=0.9", "America/New_York"),
+            ("en-US,en;q=0.9", "America/Chicago"),
+            ("en-US,en;q=0.9", "America/Denver"),
+            ("en-US,en;q=0.9", "America/Los_Angeles"),
+            ("en-CA,en;q=0.9", "America/Toronto"),
+            ("en-GB,en;q=0.9", "Europe/London"),
+            ("de-DE,de;q=0.9,en;q=0.8", "Europe/Berlin"),
+            ("fr-FR,fr;q=0.9,en;q=0.8", "Europe/Paris"),
+            ("en-AU,en;q=0.9", "Australia/Sydney"),
+        ]
+        .into_iter()
+        .map(|(language, timezone)| (language.to_string(), timezone.to_string()))
+        .collect();
 
         Self {
             viewports,
-            languages,
-            timezones,
+            locales,
+            last_pick: Mutex::new(None),
         }
     }
 
-    pub fn random_viewport(&self) -> &ViewportSize {
-        let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..self.viewports.len());
-        &self.viewports[index]
+    pub fn viewports(&self) -> &[ViewportSize] {
+        &self.viewports
     }
 
-    pub fn random_language(&self) -> &str {
-        let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..self.languages.len());
-        &self.languages[index]
+    /// Accept-language and timezone pairs fingerprints are drawn from
+    pub fn locales(&self) -> &[(String, String)] {
+        &self.locales
     }
 
-    pub fn random_timezone(&self) -> &str {
+    /// A fresh fingerprint, never the same viewport and locale as the one before
+    /// so instances opened back to back don't look cloned. The instance keeps it
+    /// for its whole life.
+    pub fn generate_fingerprint(&self) -> BrowserFingerprint {
         let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..self.timezones.len());
-        &self.timezones[index]
-    }
+        let mut last_pick = self.last_pick.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let pick = loop {
+            let pick = (rng.gen_range(0..self.viewports.len()), rng.gen_range(0..self.locales.len()));
+            if Some(pick) != *last_pick {
+                break pick;
+            }
+        };
+        *last_pick = Some(pick);
+        drop(last_pick);
 
-    pub fn generate_fingerprint(&self) -> BrowserFingerprint {
+        let (language, timezone) = self.locales[pick.1].clone();
         BrowserFingerprint {
-            viewport: self.random_viewport().clone(),
-            language: self.random_language().to_string(),
-            timezone: self.random_timezone().to_string(),
+            viewport: self.viewports[pick.0].clone(),
+            language,
+            timezone,
             platform: self.random_platform().to_string(),
             hardware_concurrency: self.random_hardware_concurrency(),
             device_memory: self.random_device_memory(),
@@ -165,12 +201,12 @@ impl BrowserFingerprint {
         
         overrides.insert(
             "navigator.language".to_string(),
-            format!("'{}'", self.language.split(',').next().unwrap_or("en-US")),
+            format!("'{}'", self.locale()),
         );
         
         overrides.insert(
             "navigator.languages".to_string(),
-            format!("['{}']", self.language.replace(";q=0.9", "")),
+            format!("[{}]", self.languages().iter().map(|language| format!("'{}'", language)).collect::<Vec<_>>().join(", ")),
         );
         
         overrides.insert(
@@ -188,13 +224,21 @@ impl BrowserFingerprint {
             self.device_memory.to_string(),
         );
         
-        overrides.insert(
-            "Intl.DateTimeFormat().resolvedOptions().timeZone".to_string(),
-            format!("'{}'", self.timezone),
-        );
-
         overrides
     }
+
+    /// Primary locale, e.g. `de-DE` out of `de-DE,de;q=0.9,en;q=0.8`
+    pub fn locale(&self) -> &str {
+        self.language.split(',').next().unwrap_or("en-US")
+    }
+
+    /// Every language in the accept-language list, without weights
+    pub fn languages(&self) -> Vec<&str> {
+        self.language
+            .split(',')
+            .map(|language| language.split(';').next().unwrap_or(language).trim())
+            .collect()
+    }
 }
 
 pub fn generate_video_disable_script() -> &'static str {
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::browser::{BrowserManager, StealthConfig, UserAgentGenerator, FingerprintRandomizer};
     use crate::browser::stealth::{generate_video_disable_script, generate_stealth_script};
@@ -6,17 +7,15 @@ mod tests {
     #[tokio::test]
     async fn test_browser_manager_creation() {
         let stealth_config = StealthConfig::default();
-        let proxy_list = Vec::new();
         
         // note: this test might fail in ci/cd due to chrome dependencies
         // In a real implementation, we would mock the browser for testing
-        let result = BrowserManager::new(2, stealth_config, proxy_list).await;
+        let result = BrowserManager::new(2, stealth_config).await;
         
         // We expect either success or a browser-related error (which is acceptable in test environments)
         match result {
             Ok(_) => {
                 // Browser manager created successfully
-                assert!(true);
             }
             Err(e) => {
                 // Check if it's a browser-related error (acceptable in test environments)
@@ -79,6 +78,80 @@ mod tests {
         assert!(fingerprint.device_memory > 0, "Device memory should be positive");
     }
 
+    #[test]
+    fn test_consecutive_fingerprints_differ() {
+        let randomizer = FingerprintRandomizer::new();
+
+        let mut previous = randomizer.generate_fingerprint();
+        for _ in 0..20 {
+            let fingerprint = randomizer.generate_fingerprint();
+            assert!(
+                fingerprint.viewport != previous.viewport || fingerprint.language != previous.language
+                    || fingerprint.timezone != previous.timezone,
+                "Back to back instances should not share viewport and locale"
+            );
+
+            // plausible: a known desktop size and a language that goes with the timezone
+            assert!(randomizer.viewports().contains(&fingerprint.viewport));
+            assert!(randomizer
+                .locales()
+                .iter()
+                .any(|(language, timezone)| *language == fingerprint.language && *timezone == fingerprint.timezone));
+            assert_eq!(fingerprint.languages()[0], fingerprint.locale());
+
+            let script = generate_stealth_script(&fingerprint);
+            assert!(script.contains(&format!("'{}'", fingerprint.locale())));
+            assert!(!script.contains("q=0."), "Language weights should not leak into navigator.languages");
+            previous = fingerprint;
+        }
+    }
+
+    #[test]
+    fn test_resource_blocking_aborts_images() {
+        use crate::browser::intercept::{blocked_request, resource_blocking_params};
+        use chromiumoxide::cdp::browser_protocol::fetch::EventRequestPaused;
+        use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+
+        // the interceptor only pauses the heavy resource types
+        let patterns = resource_blocking_params().patterns.expect("patterns should be registered");
+        let types: Vec<_> = patterns.iter().filter_map(|pattern| pattern.resource_type.clone()).collect();
+        for blocked in [ResourceType::Image, ResourceType::Font, ResourceType::Media, ResourceType::Stylesheet] {
+            assert!(types.contains(&blocked), "{:?} should be intercepted", blocked);
+        }
+        for allowed in [ResourceType::Document, ResourceType::Script, ResourceType::Xhr, ResourceType::WebSocket] {
+            assert!(!types.contains(&allowed), "{:?} should load normally", allowed);
+        }
+
+        let paused = |resource_type: &str, url: &str| -> EventRequestPaused {
+            serde_json::from_value(serde_json::json!({
+                "requestId": "interception-1",
+                "request": {
+                    "url": url,
+                    "method": "GET",
+                    "headers": {},
+                    "initialPriority": "Low",
+                    "referrerPolicy": "strict-origin-when-cross-origin"
+                },
+                "frameId": "frame-1",
+                "resourceType": resource_type
+            }))
+            .unwrap()
+        };
+
+        let emote = paused("Image", "https://static-cdn.jtvnw.net/emoticons/v2/25/default/dark/1.0");
+        let fail = blocked_request(&emote).expect("image requests should be aborted");
+        assert_eq!(fail.request_id, emote.request_id);
+        assert_eq!(fail.error_reason, ErrorReason::BlockedByClient);
+
+        let gql = paused("Fetch", "https://gql.twitch.tv/gql");
+        assert!(blocked_request(&gql).is_none(), "API calls should go through");
+
+        // on by default and carried over from the config
+        let config = crate::config::Config::default();
+        assert!(config.stealth.block_resources);
+        assert!(StealthConfig::from(&config.stealth).block_resources);
+    }
+
     #[test]
     fn test_video_disable_script_generation() {
         let script = generate_video_disable_script();
@@ -99,4 +172,70 @@ mod tests {
         assert!(script.contains("navigator"), "Script should modify navigator properties");
         assert!(script.contains("webdriver"), "Script should hide webdriver property");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_missing_browser_binary_fails_fast() {
+        use crate::browser::resolve_browser_binary;
+        use crate::error::ScrapingError;
+        use std::path::PathBuf;
+
+        let missing = PathBuf::from("/nonexistent/chrome-for-tests");
+        let err = resolve_browser_binary(Some(&missing)).unwrap_err();
+        match err.downcast_ref::<ScrapingError>() {
+            Some(ScrapingError::BrowserNotFound(message)) => {
+                assert!(message.contains("/nonexistent/chrome-for-tests"));
+                assert!(message.contains("BROWSER_PATH"));
+            }
+            other => panic!("Expected BrowserNotFound, got: {:?}", other),
+        }
+
+        let result = BrowserManager::with_binary_path(1, StealthConfig::default(), Some(missing)).await;
+        let err = result.err().expect("manager should not start without a browser");
+        assert!(matches!(err.downcast_ref::<ScrapingError>(), Some(ScrapingError::BrowserNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_without_instances() {
+        use uuid::Uuid;
+
+        // nothing to ping in an empty pool, and an id that never crashed resolves to itself
+        let browserless = BrowserManager::without_browser(1);
+        assert_eq!(browserless.replace_dead_instances().await, 0);
+        let untouched = Uuid::new_v4();
+        assert_eq!(browserless.resolve_instance_id(untouched).await, untouched);
+    }
+
+    #[tokio::test]
+    #[ignore = "launches Chrome, run with --ignored where it's installed"]
+    async fn test_crashed_instance_is_replaced() {
+        let browser_manager = BrowserManager::new(2, StealthConfig::default())
+            .await
+            .expect("Chrome or Chromium is needed for this test");
+        let healthy_id = browser_manager.create_browser_instance().await.unwrap();
+        let dead_id = browser_manager.create_browser_instance().await.unwrap();
+        let mut replaced_rx = browser_manager.subscribe_replacements();
+
+        // simulate a crashed tab by closing the page behind the pool's back
+        let dead = browser_manager.get_browser_instance(dead_id).await.unwrap();
+        dead.page.clone().close().await.unwrap();
+        assert!(!dead.is_alive().await);
+
+        assert_eq!(browser_manager.replace_dead_instances().await, 1);
+        let (crashed, replacement_id) = replaced_rx.recv().await.unwrap();
+        assert_eq!(crashed, dead_id);
+        assert_ne!(replacement_id, dead_id);
+
+        // the dead instance is never handed out again, its owner is pointed at the replacement
+        assert!(browser_manager.get_browser_instance(dead_id).await.is_none());
+        assert_eq!(browser_manager.resolve_instance_id(dead_id).await, replacement_id);
+        assert!(browser_manager.get_browser_instance(replacement_id).await.unwrap().is_alive().await);
+        assert!(browser_manager.get_browser_instance(healthy_id).await.is_some());
+        assert_eq!(browser_manager.get_active_instance_count().await, 2);
+
+        // cleaning up through the old id removes the replacement
+        browser_manager.remove_browser_instance(dead_id).await.unwrap();
+        assert!(browser_manager.get_browser_instance(replacement_id).await.is_none());
+        assert_eq!(browser_manager.resolve_instance_id(dead_id).await, dead_id);
+        browser_manager.shutdown().await.unwrap();
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::OnceLock;
 use std::time::Duration;
 use notify::{Watcher, RecursiveMode, Event, EventKind};
 use tokio::sync::mpsc;
@@ -10,11 +14,22 @@ use crate::error::{Result, ScrapingError};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
+    #[serde(default)]
     pub streamers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streamers_file: Option<PathBuf>, // newline-delimited channel list used instead of `streamers`
     pub agents: AgentConfig,
     pub output: OutputConfig,
     pub monitoring: MonitorConfig,
     pub stealth: StealthConfig,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    #[serde(default)]
+    pub browser: BrowserConfig,
+    #[serde(default)]
+    pub scraper: ScraperConfig,
+    #[serde(default)]
+    pub parser: ParserConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -23,14 +38,143 @@ pub struct AgentConfig {
     pub retry_attempts: u32,
     pub delay_range: (u64, u64), // milliseconds
     pub proxy_list: Option<Vec<String>>,
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: String, // "10m", "0s" disables
+    #[serde(default = "default_idle_probe_interval")]
+    pub idle_probe_interval: String, // "2m"
+    #[serde(default = "default_stale_after")]
+    pub stale_after: String, // restart a running agent on a live channel after this long without messages, "0s" disables
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize, // 1 = broadcast every message on its own
+    #[serde(default = "default_batch_window_ms")]
+    pub batch_window_ms: u64, // max time a partial batch waits before sending
+    #[serde(default = "default_error_alert_threshold")]
+    pub error_alert_threshold: u32, // alert once an agent has this many errors, 0 disables
+    #[serde(default)]
+    pub error_rate_alert_threshold: f64, // alert above this many errors per minute, 0 disables
+    #[serde(default = "default_error_alert_cooldown")]
+    pub error_alert_cooldown: String, // quiet period per streamer after an alert
+    #[serde(default)]
+    pub stable_ids: bool, // derive agent ids from the streamer name so they survive restarts
+    #[serde(default = "default_hot_message_rate")]
+    pub hot_message_rate: f64, // messages/minute at which a channel is never shed to save resources, 0 disables
+    #[serde(default = "default_cold_message_rate")]
+    pub cold_message_rate: f64, // messages/minute below which a channel gives its slot up to waiting streamers
+}
+
+fn default_idle_timeout() -> String {
+    "10m".to_string()
+}
+
+fn default_idle_probe_interval() -> String {
+    "2m".to_string()
+}
+
+fn default_stale_after() -> String {
+    "5m".to_string()
+}
+
+fn default_batch_size() -> usize {
+    1
+}
+
+fn default_batch_window_ms() -> u64 {
+    50
+}
+
+fn default_error_alert_threshold() -> u32 {
+    25
+}
+
+fn default_error_alert_cooldown() -> String {
+    "15m".to_string()
+}
+
+fn default_hot_message_rate() -> f64 {
+    60.0
+}
+
+fn default_cold_message_rate() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OutputConfig {
-    pub format: String, // "json", "csv", "custom"
+    pub format: String, // "json", "csv", "tsv", "avro", "sqlite", "custom"
     pub directory: PathBuf,
     pub rotation_size: String, // "100MB"
     pub rotation_time: String, // "1h"
+    #[serde(default = "default_timezone")]
+    pub timezone: String, // IANA name used for file dates, e.g. "Europe/Berlin"
+    #[serde(default)]
+    pub csv_local_timestamps: bool, // render the CSV/TSV timestamp column in `timezone`
+    #[serde(default)]
+    pub csv_excel_compat: bool, // UTF-8 BOM on the header and CRLF line endings for Excel
+    #[serde(default)]
+    pub summaries: bool, // write hourly summary_<date>_<hour>.json rollups per streamer
+    #[serde(default = "default_ingest_capacity")]
+    pub ingest_capacity: usize, // messages queued for storage before agents wait
+    #[serde(default)]
+    pub durability: Durability,
+    #[serde(default = "default_max_write_concurrency")]
+    pub max_write_concurrency: usize, // streamers written in parallel, 1 = one at a time
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: f64, // share of messages stored, 0.0-1.0
+    #[serde(default)]
+    pub sample_seed: Option<u64>, // fixed seed makes the sample reproducible
+    #[serde(default)]
+    pub compression: Option<String>, // "gzip" or "zstd", applied to files once they rotate
+    #[serde(default = "default_flush_interval")]
+    pub flush_interval: String, // how long writes are buffered, "0s" flushes every write
+    #[serde(default)]
+    pub filename_template: Option<String>, // e.g. "{streamer}-{timestamp}.{ext}", default "{streamer}/{date}/chat_{date}_{time}.{ext}"
+    #[serde(default)]
+    pub retention: Option<String>, // e.g. "7d", files older than this are deleted; None keeps everything
+    #[serde(default)]
+    pub rotation_overrides: BTreeMap<String, RotationOverride>, // per streamer, e.g. [output.rotation_overrides.xqc]
+}
+
+/// Rotation limits for one streamer, each falling back to the global value when unset
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RotationOverride {
+    #[serde(default)]
+    pub rotation_size: Option<String>,
+    #[serde(default)]
+    pub rotation_time: Option<String>,
+}
+
+fn default_flush_interval() -> String {
+    "0s".to_string()
+}
+
+fn default_sample_rate() -> f64 {
+    1.0
+}
+
+fn default_max_write_concurrency() -> usize {
+    1
+}
+
+/// When chat files are fsynced to disk. Every fsync waits on the device, so
+/// `always` can cut write throughput by an order of magnitude on spinning disks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Durability {
+    /// flush to the OS only and let it write back when it likes
+    #[default]
+    None,
+    /// fsync a file once it's rotated out
+    OnRotate,
+    /// fsync after every write
+    Always,
+}
+
+fn default_ingest_capacity() -> usize {
+    1000
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -39,9 +183,131 @@ pub struct MonitorConfig {
     pub api_port: u16,
     pub dashboard_port: Option<u16>,
     pub api_token: Option<String>,
+    #[serde(default = "default_api_rate_limit")]
+    pub api_rate_limit: u32, // API requests per second per client IP, 0 disables
     pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub webhook_template: Option<String>, // JSON body posted to webhook_url, with {username} style placeholders
     pub discord_webhook_url: Option<String>,
-    pub custom_css: Option<PathBuf>,
+    #[serde(default)]
+    pub slack_webhook_url: Option<String>, // slack incoming webhook, posted to alongside discord
+    #[serde(default)]
+    pub webhook_batch_interval: Option<String>, // combine forwarded messages into one post per interval, unset sends each
+    #[serde(default = "default_webhook_max_per_minute")]
+    pub webhook_max_per_minute: u32, // batched posts and keyword alerts per minute, 0 disables the limit
+    #[serde(default)]
+    pub webhook_dead_letter_file: Option<PathBuf>, // at-least-once delivery: messages no webhook took are kept here and replayed
+    #[serde(default)]
+    pub webhook_delivery_retries: u32, // retries per provider before a message is dead-lettered
+    #[serde(default)]
+    pub alert_keywords: Vec<String>, // when set (or alert_pattern is), only messages with these words are forwarded, as alerts
+    #[serde(default)]
+    pub alert_pattern: Option<String>, // regex alternative to alert_keywords, both ignore case
+    pub custom_css: Option<PathBuf>, // TUI theme file of `key = color` lines
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>, // empty (default) = disabled, ["*"] = any origin
+    #[serde(default)]
+    pub per_agent_logs: bool, // write output_dir/<streamer>/agent.log per agent
+    #[serde(default)]
+    pub lifecycle_notifications: bool, // post startup, shutdown and agent error alerts to the webhooks
+    #[serde(default = "default_tui_fps")]
+    pub tui_fps: u32, // max dashboard redraws per second, input is polled faster
+    #[serde(default = "default_tui_rate_smoothing")]
+    pub tui_rate_smoothing: f64, // weight of the newest sample in the dashboard's message rate, lower is steadier
+    #[serde(default = "default_ws_ping_interval")]
+    pub ws_ping_interval: String, // how often dashboard websocket clients are pinged
+    #[serde(default = "default_ws_pong_timeout")]
+    pub ws_pong_timeout: String, // clients that don't answer a ping (or read) within this are dropped
+    #[serde(default = "default_ws_max_clients")]
+    pub ws_max_clients: usize,
+    #[serde(default = "default_monitor_interval")]
+    pub monitor_interval: String, // how often system metrics are sampled
+    #[serde(default = "default_scale_interval")]
+    pub scale_interval: String, // how often agents are scaled up or down
+}
+
+impl MonitorConfig {
+    /// Replace `${NAME}` in `api_token` and the webhook URLs with environment
+    /// variable `NAME`, or `${file:/path}` with that file's trimmed contents,
+    /// so secrets stay out of the committed config
+    pub fn resolve_secrets(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        for (field, value) in self.secrets_mut() {
+            if let Some(ref mut value) = value {
+                *value = resolve_secret_refs(field, value, &lookup)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The fields that may hold secrets, by name
+    pub fn secrets_mut(&mut self) -> [(&'static str, &mut Option<String>); 4] {
+        [
+            ("api_token", &mut self.api_token),
+            ("webhook_url", &mut self.webhook_url),
+            ("discord_webhook_url", &mut self.discord_webhook_url),
+            ("slack_webhook_url", &mut self.slack_webhook_url),
+        ]
+    }
+}
+
+fn resolve_secret_refs(field: &str, value: &str, lookup: &impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut resolved = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        resolved.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(ScrapingError::ConfigError(format!("monitoring.{} has an unclosed '${{'", field)).into());
+        };
+        let reference = &rest[start + 2..start + end];
+        let secret = match reference.strip_prefix("file:") {
+            Some(path) => fs::read_to_string(path)
+                .map(|content| content.trim().to_string())
+                .map_err(|e| ScrapingError::ConfigError(format!("monitoring.{} reads secret file {}: {}", field, path, e)))?,
+            None => lookup(reference).ok_or_else(|| {
+                ScrapingError::ConfigError(format!("monitoring.{} refers to environment variable {}, which isn't set", field, reference))
+            })?,
+        };
+        resolved.push_str(&secret);
+        rest = &rest[start + end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+fn default_api_rate_limit() -> u32 {
+    20
+}
+
+fn default_webhook_max_per_minute() -> u32 {
+    30 // discord's per-webhook limit
+}
+
+fn default_tui_fps() -> u32 {
+    10
+}
+
+fn default_tui_rate_smoothing() -> f64 {
+    0.3
+}
+
+fn default_ws_ping_interval() -> String {
+    "30s".to_string()
+}
+
+fn default_ws_pong_timeout() -> String {
+    "10s".to_string()
+}
+
+fn default_ws_max_clients() -> usize {
+    32
+}
+
+fn default_monitor_interval() -> String {
+    "5s".to_string()
+}
+
+fn default_scale_interval() -> String {
+    "30s".to_string()
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -50,43 +316,412 @@ pub struct StealthConfig {
     pub simulate_human_behavior: bool,
     pub proxy_rotation: bool,
     pub fingerprint_randomization: bool,
+    #[serde(default = "default_block_resources")]
+    pub block_resources: bool, // skip images, fonts, video and css, chat is all we read
+}
+
+fn default_block_resources() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct BrowserConfig {
+    pub binary_path: Option<PathBuf>, // overrides BROWSER_PATH and auto-detection
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ScraperConfig {
+    #[serde(default)]
+    pub extra_headers: BTreeMap<String, String>, // sent with every HTTP scraper request, e.g. Accept-Language
+}
+
+impl ScraperConfig {
+    /// `extra_headers` as a header map, failing on names or values HTTP won't accept
+    pub fn header_map(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.extra_headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| ScrapingError::ConfigError(format!("Invalid header name '{}': {}", name, e)))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|e| ScrapingError::ConfigError(format!("Invalid value for header '{}': {}", name, e)))?;
+            headers.insert(header_name, header_value);
+        }
+        Ok(headers)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ParserConfig {
+    #[serde(default)]
+    pub third_party_emotes: bool, // tag BTTV/FFZ/7TV emote codes, fetched once per agent start
+    #[serde(default = "default_emote_cache_ttl")]
+    pub emote_cache_ttl: String, // how long a channel's emote list is reused, "1h"
+    #[serde(default)]
+    pub selectors: SelectorConfig,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            third_party_emotes: false,
+            emote_cache_ttl: default_emote_cache_ttl(),
+            selectors: SelectorConfig::default(),
+        }
+    }
+}
+
+/// CSS selectors the chat parser finds things with, patchable when Twitch
+/// changes its markup. Unset ones keep the built-in value.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SelectorConfig {
+    pub chat_line: String,
+    pub username: String,
+    pub display_name: String,
+    pub message_body: String,
+    pub fragment: String, // text and emote pieces inside the message body
+    pub badge: String,
+    pub timestamp: String,
+    pub notice_user: String, // who a sub/raid notice is about
+    pub reply: String,       // the "Replying to @user" header on threaded replies
+    pub user_notice: String, // the container of sub, gift, raid and announcement notices
+    pub emote: String,       // fragments that are emotes, their alt text is the emote name
+}
+
+impl Default for SelectorConfig {
+    fn default() -> Self {
+        Self {
+            chat_line: ".chat-line__no-background, .chat-line__message, .user-notice-line".to_string(),
+            username: "[data-a-target='chat-message-username']".to_string(),
+            display_name: ".chat-author__display-name".to_string(),
+            message_body: "[data-a-target='chat-line-message-body']".to_string(),
+            fragment: "span.text-fragment, span[data-a-target='chat-message-text'], img".to_string(),
+            badge: ".chat-badge".to_string(),
+            timestamp: ".chat-line__timestamp".to_string(),
+            notice_user: ".chatter-name, [data-a-user]".to_string(),
+            reply: "[data-test-selector='chat-reply-header'], .chat-line__reply, p[title^='Replying to']".to_string(),
+            user_notice: ".user-notice-line".to_string(),
+            emote: "img.chat-line__message--emote, img.chat-image".to_string(),
+        }
+    }
+}
+
+impl SelectorConfig {
+    /// Each selector by name, in declaration order
+    pub fn entries(&self) -> [(&'static str, &str); 11] {
+        [
+            ("chat_line", &self.chat_line),
+            ("username", &self.username),
+            ("display_name", &self.display_name),
+            ("message_body", &self.message_body),
+            ("fragment", &self.fragment),
+            ("badge", &self.badge),
+            ("timestamp", &self.timestamp),
+            ("notice_user", &self.notice_user),
+            ("reply", &self.reply),
+            ("user_notice", &self.user_notice),
+            ("emote", &self.emote),
+        ]
+    }
+}
+
+fn default_emote_cache_ttl() -> String {
+    "1h".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    pub rules: Vec<String>, // regex patterns, matches are replaced before storage
+    pub placeholder: String,
+    pub keep_length: bool, // append the original match length to the placeholder
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: vec![
+                r"(?i)\b(?:https?://|www\.)\S+".to_string(),                       // urls
+                r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b".to_string(),     // emails
+                r"\+?\d[\d\s().-]{7,}\d".to_string(),                            // phone numbers
+            ],
+            placeholder: "[REDACTED]".to_string(),
+            keep_length: false,
+        }
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             streamers: vec!["shroud".to_string(), "ninja".to_string()],
+            streamers_file: None,
             agents: AgentConfig {
                 max_concurrent: 5,
                 retry_attempts: 3,
                 delay_range: (1000, 5000),
                 proxy_list: None,
+                idle_timeout: default_idle_timeout(),
+                idle_probe_interval: default_idle_probe_interval(),
+                stale_after: default_stale_after(),
+                batch_size: default_batch_size(),
+                batch_window_ms: default_batch_window_ms(),
+                error_alert_threshold: default_error_alert_threshold(),
+                error_rate_alert_threshold: 0.0,
+                error_alert_cooldown: default_error_alert_cooldown(),
+                stable_ids: false,
+                hot_message_rate: default_hot_message_rate(),
+                cold_message_rate: default_cold_message_rate(),
             },
             output: OutputConfig {
                 format: "json".to_string(),
                 directory: PathBuf::from("./scraped_data"),
                 rotation_size: "100MB".to_string(),
                 rotation_time: "1h".to_string(),
+                timezone: default_timezone(),
+                csv_local_timestamps: false,
+                csv_excel_compat: false,
+                summaries: false,
+                ingest_capacity: default_ingest_capacity(),
+                durability: Durability::default(),
+                max_write_concurrency: default_max_write_concurrency(),
+                sample_rate: default_sample_rate(),
+                sample_seed: None,
+                compression: None,
+                flush_interval: default_flush_interval(),
+                filename_template: None,
+                retention: None,
+                rotation_overrides: BTreeMap::new(),
             },
             monitoring: MonitorConfig {
                 tui_enabled: true,
                 api_port: 8080,
                 dashboard_port: Some(8888),
                 api_token: None,
+                api_rate_limit: default_api_rate_limit(),
                 webhook_url: None,
+                webhook_template: None,
                 discord_webhook_url: None,
+                slack_webhook_url: None,
+                webhook_batch_interval: None,
+                webhook_max_per_minute: default_webhook_max_per_minute(),
+                webhook_dead_letter_file: None,
+                webhook_delivery_retries: 0,
+                alert_keywords: Vec::new(),
+                alert_pattern: None,
                 custom_css: None,
+                cors_allowed_origins: Vec::new(),
+                per_agent_logs: false,
+                lifecycle_notifications: false,
+                tui_fps: default_tui_fps(),
+                tui_rate_smoothing: default_tui_rate_smoothing(),
+                ws_ping_interval: default_ws_ping_interval(),
+                ws_pong_timeout: default_ws_pong_timeout(),
+                ws_max_clients: default_ws_max_clients(),
+                monitor_interval: default_monitor_interval(),
+                scale_interval: default_scale_interval(),
             },
             stealth: StealthConfig {
                 randomize_user_agents: true,
                 simulate_human_behavior: true,
                 proxy_rotation: false,
                 fingerprint_randomization: true,
+                block_resources: default_block_resources(),
             },
+            redaction: RedactionConfig::default(),
+            browser: BrowserConfig::default(),
+            scraper: ScraperConfig::default(),
+            parser: ParserConfig::default(),
+        }
+    }
+}
+
+/// Overrides layered onto a `Config` with `Config::apply`. Every field is
+/// optional and only the ones that are set replace the existing values.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streamers: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streamers_file: Option<PathBuf>,
+    #[serde(default)]
+    pub agents: PartialAgentConfig,
+    #[serde(default)]
+    pub output: PartialOutputConfig,
+    #[serde(default)]
+    pub monitoring: PartialMonitorConfig,
+    #[serde(default)]
+    pub stealth: PartialStealthConfig,
+    #[serde(default)]
+    pub redaction: PartialRedactionConfig,
+    #[serde(default)]
+    pub browser: PartialBrowserConfig,
+    #[serde(default)]
+    pub scraper: PartialScraperConfig,
+    #[serde(default)]
+    pub parser: PartialParserConfig,
+}
+
+impl Config {
+    /// Overlay every field that is set in `partial`
+    pub fn apply(&mut self, partial: PartialConfig) {
+        if let Some(streamers) = partial.streamers {
+            self.streamers = streamers;
+        }
+        if let Some(streamers_file) = partial.streamers_file {
+            self.streamers_file = Some(streamers_file);
+        }
+        self.agents.apply(partial.agents);
+        self.output.apply(partial.output);
+        self.monitoring.apply(partial.monitoring);
+        self.stealth.apply(partial.stealth);
+        self.redaction.apply(partial.redaction);
+        self.browser.apply(partial.browser);
+        self.scraper.apply(partial.scraper);
+        self.parser.apply(partial.parser);
+    }
+
+    /// `apply` for building a config in one expression
+    pub fn merge(mut self, partial: PartialConfig) -> Self {
+        self.apply(partial);
+        self
+    }
+
+    /// Lowercase and trim streamer names. Twitch logins are case-insensitive,
+    /// so this keeps agents and output directories to one spelling per channel.
+    pub fn normalize_streamers(&mut self) {
+        for streamer in &mut self.streamers {
+            *streamer = streamer.trim().to_lowercase();
         }
     }
 }
 
+/// All-optional mirror of a config section plus its `apply`
+macro_rules! partial_section {
+    ($partial:ident for $section:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        #[doc = concat!("Optional overrides for `", stringify!($section), "`")]
+        #[derive(Debug, Clone, Default, Deserialize, Serialize)]
+        #[serde(deny_unknown_fields)]
+        pub struct $partial {
+            $(
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                pub $field: Option<$ty>,
+            )*
+        }
+
+        impl $section {
+            /// Overlay every field that is set in `partial`
+            pub fn apply(&mut self, partial: $partial) {
+                $(
+                    if let Some(value) = partial.$field {
+                        self.$field = value;
+                    }
+                )*
+            }
+        }
+    };
+}
+
+partial_section!(PartialAgentConfig for AgentConfig {
+    max_concurrent: usize,
+    retry_attempts: u32,
+    delay_range: (u64, u64),
+    proxy_list: Option<Vec<String>>,
+    idle_timeout: String,
+    idle_probe_interval: String,
+    stale_after: String,
+    batch_size: usize,
+    batch_window_ms: u64,
+    error_alert_threshold: u32,
+    error_rate_alert_threshold: f64,
+    error_alert_cooldown: String,
+    stable_ids: bool,
+    hot_message_rate: f64,
+    cold_message_rate: f64,
+});
+
+partial_section!(PartialOutputConfig for OutputConfig {
+    format: String,
+    directory: PathBuf,
+    rotation_size: String,
+    rotation_time: String,
+    timezone: String,
+    csv_local_timestamps: bool,
+    csv_excel_compat: bool,
+    summaries: bool,
+    ingest_capacity: usize,
+    durability: Durability,
+    max_write_concurrency: usize,
+    sample_rate: f64,
+    sample_seed: Option<u64>,
+    compression: Option<String>,
+    flush_interval: String,
+    filename_template: Option<String>,
+    retention: Option<String>,
+    rotation_overrides: BTreeMap<String, RotationOverride>,
+});
+
+partial_section!(PartialMonitorConfig for MonitorConfig {
+    tui_enabled: bool,
+    api_port: u16,
+    dashboard_port: Option<u16>,
+    api_token: Option<String>,
+    api_rate_limit: u32,
+    webhook_url: Option<String>,
+    webhook_template: Option<String>,
+    discord_webhook_url: Option<String>,
+    slack_webhook_url: Option<String>,
+    webhook_batch_interval: Option<String>,
+    webhook_max_per_minute: u32,
+    webhook_dead_letter_file: Option<PathBuf>,
+    webhook_delivery_retries: u32,
+    alert_keywords: Vec<String>,
+    alert_pattern: Option<String>,
+    custom_css: Option<PathBuf>,
+    cors_allowed_origins: Vec<String>,
+    per_agent_logs: bool,
+    lifecycle_notifications: bool,
+    tui_fps: u32,
+    tui_rate_smoothing: f64,
+    ws_ping_interval: String,
+    ws_pong_timeout: String,
+    ws_max_clients: usize,
+    monitor_interval: String,
+    scale_interval: String,
+});
+
+partial_section!(PartialStealthConfig for StealthConfig {
+    randomize_user_agents: bool,
+    simulate_human_behavior: bool,
+    proxy_rotation: bool,
+    fingerprint_randomization: bool,
+    block_resources: bool,
+});
+
+partial_section!(PartialRedactionConfig for RedactionConfig {
+    enabled: bool,
+    rules: Vec<String>,
+    placeholder: String,
+    keep_length: bool,
+});
+
+partial_section!(PartialBrowserConfig for BrowserConfig {
+    binary_path: Option<PathBuf>,
+});
+
+partial_section!(PartialScraperConfig for ScraperConfig {
+    extra_headers: BTreeMap<String, String>,
+});
+
+partial_section!(PartialParserConfig for ParserConfig {
+    third_party_emotes: bool,
+    emote_cache_ttl: String,
+    selectors: SelectorConfig,
+});
+
 #[async_trait::async_trait]
 pub trait ConfigManager {
     async fn load_config(&self) -> Result<Config>;
@@ -99,6 +734,54 @@ pub struct FileConfigManager {
     config_path: PathBuf,
 }
 
+/// Quiet time after a config file event before it's reloaded
+const CONFIG_RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Config file syntax, picked from the file extension. Anything that isn't
+/// `.yaml`, `.yml` or `.json` is read as TOML, as it always was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("yaml") | Some("yml") => Self::Yaml,
+            Some("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+            Self::Json => "JSON",
+        }
+    }
+
+    pub fn parse<T: serde::de::DeserializeOwned>(&self, content: &str) -> Result<T> {
+        let parsed = match self {
+            Self::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            Self::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|e| ScrapingError::ConfigError(format!("Failed to parse {} config: {}", self.name(), e)).into())
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String> {
+        let serialized = match self {
+            Self::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+            Self::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        };
+        serialized.map_err(|e| ScrapingError::ConfigError(format!("Failed to serialize config: {}", e)).into())
+    }
+}
+
 impl FileConfigManager {
     pub fn new(config_path: PathBuf) -> Self {
         Self { config_path }
@@ -120,11 +803,23 @@ impl ConfigManager for FileConfigManager {
         let config_content = fs::read_to_string(&self.config_path)
             .map_err(|e| ScrapingError::ConfigError(format!("Failed to read config file: {}", e)))?;
 
-        let config: Config = toml::from_str(&config_content)
-            .map_err(|e| ScrapingError::ConfigError(format!("Failed to parse TOML config: {}", e)))?;
+        let mut config: Config = ConfigFormat::from_path(&self.config_path).parse(&config_content)?;
+
+        // an external channel list replaces the inline one
+        if let Some(streamers_file) = self.streamers_file_path(&config) {
+            config.streamers = Self::load_streamers_file(&streamers_file)?;
+            info!("Loaded {} streamers from {:?}", config.streamers.len(), streamers_file);
+        }
+
+        // env vars win over the file, then everything is validated together
+        let overrides = Self::env_overrides(std::env::vars())?;
+        config.apply(overrides);
+        config.monitoring.resolve_secrets(|name| std::env::var(name).ok())?;
 
-        // validate the loaded config
+        // validate the loaded config, and probe the output directory once here rather than on every validation
         self.validate_config(&config)?;
+        Self::check_writable(&config.output.directory)?;
+        config.normalize_streamers();
 
         info!("Configuration loaded successfully");
         Ok(config)
@@ -135,8 +830,12 @@ impl ConfigManager for FileConfigManager {
         let config_path = self.config_path.clone();
         let config_manager = FileConfigManager::new(config_path.clone());
 
+        // the streamers file is found through the config, so read it once up front
+        let streamers_file = self.load_config().await.ok()
+            .and_then(|config| self.streamers_file_path(&config));
+
         tokio::spawn(async move {
-            if let Err(e) = Self::watch_config_file(config_path, tx, config_manager).await {
+            if let Err(e) = Self::watch_config_file(config_path, streamers_file, tx, config_manager).await {
                 error!("Configuration file watcher error: {}", e);
             }
         });
@@ -156,14 +855,25 @@ impl ConfigManager for FileConfigManager {
             if streamer.trim().is_empty() {
                 return Err(ScrapingError::ConfigError("Streamer name cannot be empty".to_string()).into());
             }
-            if streamer.contains(' ') {
-                return Err(ScrapingError::ConfigError(format!("Streamer name '{}' cannot contain spaces", streamer)).into());
-            }
-            if streamer.len() > 25 {
-                return Err(ScrapingError::ConfigError(format!("Streamer name '{}' is too long (max 25 characters)", streamer)).into());
+            if !Self::is_valid_streamer_name(streamer) {
+                return Err(ScrapingError::ConfigError(format!("Invalid streamer name '{}': Twitch names are 4-25 characters of letters, numbers and underscores", streamer)).into());
             }
         }
 
+        // checking no channel is listed twice, names are case-insensitive
+        let mut by_login: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for streamer in &config.streamers {
+            by_login.entry(streamer.trim().to_lowercase()).or_default().push(streamer);
+        }
+        let duplicates: Vec<String> = by_login
+            .values()
+            .filter(|names| names.len() > 1)
+            .map(|names| names.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(" and "))
+            .collect();
+        if !duplicates.is_empty() {
+            return Err(ScrapingError::ConfigError(format!("Streamers listed more than once: {}", duplicates.join(", "))).into());
+        }
+
         // checking agent config
         if config.agents.max_concurrent == 0 {
             return Err(ScrapingError::ConfigError("max_concurrent must be greater than 0".to_string()).into());
@@ -181,6 +891,51 @@ impl ConfigManager for FileConfigManager {
             return Err(ScrapingError::ConfigError("delay_range maximum cannot exceed 60 seconds".to_string()).into());
         }
 
+        // checking idle timeout settings
+        if !Self::is_valid_time_format(&config.agents.idle_timeout) {
+            return Err(ScrapingError::ConfigError(format!("Invalid idle_timeout format '{}', expected format like '10m', '1h' or '0s' to disable", config.agents.idle_timeout)).into());
+        }
+        if !Self::is_valid_time_format(&config.agents.idle_probe_interval) {
+            return Err(ScrapingError::ConfigError(format!("Invalid idle_probe_interval format '{}', expected format like '2m', '30s'", config.agents.idle_probe_interval)).into());
+        }
+        if Self::parse_time_to_duration(&config.agents.idle_probe_interval)?.is_zero() {
+            return Err(ScrapingError::ConfigError("idle_probe_interval must be greater than 0".to_string()).into());
+        }
+
+        // checking stalled agent detection
+        if !Self::is_valid_time_format(&config.agents.stale_after) {
+            return Err(ScrapingError::ConfigError(format!("Invalid stale_after format '{}', expected format like '5m' or '0s' to disable", config.agents.stale_after)).into());
+        }
+
+        // checking batching settings
+        if config.agents.batch_size == 0 {
+            return Err(ScrapingError::ConfigError("batch_size must be at least 1".to_string()).into());
+        }
+        if config.agents.batch_size > 10000 {
+            return Err(ScrapingError::ConfigError("batch_size cannot exceed 10000".to_string()).into());
+        }
+        if config.agents.batch_window_ms > 10000 {
+            return Err(ScrapingError::ConfigError("batch_window_ms cannot exceed 10 seconds".to_string()).into());
+        }
+
+        // checking error alert settings
+        if !config.agents.error_rate_alert_threshold.is_finite() || config.agents.error_rate_alert_threshold < 0.0 {
+            return Err(ScrapingError::ConfigError("error_rate_alert_threshold must be 0 or a positive number".to_string()).into());
+        }
+        if !Self::is_valid_time_format(&config.agents.error_alert_cooldown) {
+            return Err(ScrapingError::ConfigError(format!("Invalid error_alert_cooldown format '{}', expected format like '15m', '1h'", config.agents.error_alert_cooldown)).into());
+        }
+
+        // checking throughput scaling thresholds
+        for (name, rate) in [("hot_message_rate", config.agents.hot_message_rate), ("cold_message_rate", config.agents.cold_message_rate)] {
+            if !rate.is_finite() || rate < 0.0 {
+                return Err(ScrapingError::ConfigError(format!("{} must be 0 or a positive number", name)).into());
+            }
+        }
+        if config.agents.hot_message_rate > 0.0 && config.agents.cold_message_rate >= config.agents.hot_message_rate {
+            return Err(ScrapingError::ConfigError("cold_message_rate must be less than hot_message_rate".to_string()).into());
+        }
+
         // checking proxy list if provided
         if let Some(ref proxies) = config.agents.proxy_list {
             for proxy in proxies {
@@ -191,7 +946,7 @@ impl ConfigManager for FileConfigManager {
         }
 
         // checking output config
-        let valid_formats = ["json", "csv", "custom"];
+        let valid_formats = ["json", "csv", "tsv", "avro", "sqlite", "custom"];
         if !valid_formats.contains(&config.output.format.as_str()) {
             return Err(ScrapingError::ConfigError(format!("Invalid output format '{}', must be one of: {:?}", config.output.format, valid_formats)).into());
         }
@@ -202,10 +957,74 @@ impl ConfigManager for FileConfigManager {
         }
 
         // Validate rotation time format
-        if !Self::is_valid_time_format(&config.output.rotation_time) {
+        if Self::parse_rotation_time(&config.output.rotation_time).is_err() {
             return Err(ScrapingError::ConfigError(format!("Invalid rotation_time format '{}', expected format like '1h', '30m', '1d'", config.output.rotation_time)).into());
         }
 
+        // checking per-streamer rotation overrides the same way
+        for (streamer, rotation) in &config.output.rotation_overrides {
+            if let Some(ref size) = rotation.rotation_size {
+                if !Self::is_valid_size_format(size) {
+                    return Err(ScrapingError::ConfigError(format!("Invalid rotation_size format '{}' for streamer '{}', expected format like '100MB', '1GB'", size, streamer)).into());
+                }
+            }
+            if let Some(ref time) = rotation.rotation_time {
+                if Self::parse_rotation_time(time).is_err() {
+                    return Err(ScrapingError::ConfigError(format!("Invalid rotation_time format '{}' for streamer '{}', expected format like '1h', '30m', '1d'", time, streamer)).into());
+                }
+            }
+        }
+
+        // checking storage ingest queue size
+        if config.output.ingest_capacity == 0 {
+            return Err(ScrapingError::ConfigError("ingest_capacity must be at least 1".to_string()).into());
+        }
+        if config.output.ingest_capacity > 1_000_000 {
+            return Err(ScrapingError::ConfigError("ingest_capacity cannot exceed 1000000".to_string()).into());
+        }
+
+        // checking storage write concurrency
+        if config.output.max_write_concurrency == 0 {
+            return Err(ScrapingError::ConfigError("max_write_concurrency must be at least 1".to_string()).into());
+        }
+        if config.output.max_write_concurrency > 64 {
+            return Err(ScrapingError::ConfigError("max_write_concurrency cannot exceed 64".to_string()).into());
+        }
+
+        // checking sample rate is a probability
+        if !(0.0..=1.0).contains(&config.output.sample_rate) {
+            return Err(ScrapingError::ConfigError("sample_rate must be between 0.0 and 1.0".to_string()).into());
+        }
+
+        // checking filename template placeholders
+        if let Some(ref template) = config.output.filename_template {
+            crate::storage::FilenameTemplate::parse(template)?;
+        }
+
+        // checking write buffering interval
+        if !Self::is_valid_time_format(&config.output.flush_interval) {
+            return Err(ScrapingError::ConfigError(format!("Invalid flush_interval format: {}", config.output.flush_interval)).into());
+        }
+
+        // checking retention period
+        if let Some(ref retention) = config.output.retention {
+            if !Self::parse_time_to_duration(retention).is_ok_and(|period| !period.is_zero()) {
+                return Err(ScrapingError::ConfigError(format!("Invalid retention: {} (expected a period like \"7d\")", retention)).into());
+            }
+        }
+
+        // checking rotated file compression
+        if let Some(ref compression) = config.output.compression {
+            if !matches!(compression.as_str(), "gzip" | "zstd") {
+                return Err(ScrapingError::ConfigError(format!("Unsupported compression: {} (expected gzip or zstd)", compression)).into());
+            }
+        }
+
+        // checking timezone is a known IANA name
+        if config.output.timezone.parse::<chrono_tz::Tz>().is_err() {
+            return Err(ScrapingError::ConfigError(format!("Unknown timezone '{}', expected an IANA name like 'UTC' or 'Europe/Berlin'", config.output.timezone)).into());
+        }
+
         // checking monitoring config
         if config.monitoring.api_port < 1024 {
             return Err(ScrapingError::ConfigError("api_port must be between 1024 and 65535".to_string()).into());
@@ -217,14 +1036,111 @@ impl ConfigManager for FileConfigManager {
                 return Err(ScrapingError::ConfigError("webhook_url must start with http:// or https://".to_string()).into());
             }
         }
+        if let Some(ref template) = config.monitoring.webhook_template {
+            if config.monitoring.webhook_url.is_none() {
+                return Err(ScrapingError::ConfigError("webhook_template requires webhook_url".to_string()).into());
+            }
+            crate::webhooks::generic::parse_template(template)?;
+        }
+        // checking webhook batches go out on an interval
+        if let Some(ref interval) = config.monitoring.webhook_batch_interval {
+            if Self::parse_time_to_duration(interval).map_or(true, |duration| duration.is_zero()) {
+                return Err(ScrapingError::ConfigError(format!("Invalid webhook_batch_interval format: {}", interval)).into());
+            }
+        }
+        // checking webhook retries stay within a sane backoff
+        if config.monitoring.webhook_delivery_retries > 0 && config.monitoring.webhook_dead_letter_file.is_none() {
+            return Err(ScrapingError::ConfigError("webhook_delivery_retries requires webhook_dead_letter_file".to_string()).into());
+        }
+        if config.monitoring.webhook_delivery_retries > 10 {
+            return Err(ScrapingError::ConfigError("webhook_delivery_retries must be at most 10".to_string()).into());
+        }
+        // checking the keyword alert pattern compiles
+        crate::webhooks::keywords::KeywordFilter::new(&config.monitoring.alert_keywords, config.monitoring.alert_pattern.as_deref())?;
+        if let Some(ref slack_webhook_url) = config.monitoring.slack_webhook_url {
+            if !slack_webhook_url.starts_with("http://") && !slack_webhook_url.starts_with("https://") {
+                return Err(ScrapingError::ConfigError("slack_webhook_url must start with http:// or https://".to_string()).into());
+            }
+        }
 
-        // Validate custom CSS file if provided
-        if let Some(ref css_path) = config.monitoring.custom_css {
-            if !css_path.exists() {
-                return Err(ScrapingError::ConfigError(format!("Custom CSS file not found: {:?}", css_path)).into());
+        // checking tui frame rate
+        if config.monitoring.tui_fps == 0 || config.monitoring.tui_fps > 60 {
+            return Err(ScrapingError::ConfigError("tui_fps must be between 1 and 60".to_string()).into());
+        }
+        if !(config.monitoring.tui_rate_smoothing > 0.0 && config.monitoring.tui_rate_smoothing <= 1.0) {
+            return Err(ScrapingError::ConfigError("tui_rate_smoothing must be above 0 and at most 1".to_string()).into());
+        }
+
+        // checking websocket heartbeat and client cap
+        for (name, value) in [
+            ("ws_ping_interval", &config.monitoring.ws_ping_interval),
+            ("ws_pong_timeout", &config.monitoring.ws_pong_timeout),
+        ] {
+            if Self::parse_time_to_duration(value).map_or(true, |duration| duration.is_zero()) {
+                return Err(ScrapingError::ConfigError(format!("Invalid {} format: {}", name, value)).into());
+            }
+        }
+        if config.monitoring.ws_max_clients == 0 {
+            return Err(ScrapingError::ConfigError("ws_max_clients must be at least 1".to_string()).into());
+        }
+
+        // checking the monitoring and scaling loops don't spin
+        for (name, value) in [
+            ("monitor_interval", &config.monitoring.monitor_interval),
+            ("scale_interval", &config.monitoring.scale_interval),
+        ] {
+            match Self::parse_time_to_duration(value) {
+                Ok(duration) if duration >= Duration::from_secs(1) => {}
+                Ok(_) => return Err(ScrapingError::ConfigError(format!("{} must be at least 1s", name)).into()),
+                Err(_) => return Err(ScrapingError::ConfigError(format!("Invalid {} format: {}", name, value)).into()),
+            }
+        }
+
+        // checking lifecycle notifications have somewhere to go
+        if config.monitoring.lifecycle_notifications
+            && config.monitoring.webhook_url.is_none()
+            && config.monitoring.discord_webhook_url.is_none()
+            && config.monitoring.slack_webhook_url.is_none()
+        {
+            return Err(ScrapingError::ConfigError("lifecycle_notifications requires webhook_url, discord_webhook_url or slack_webhook_url".to_string()).into());
+        }
+
+        // Validate CORS origins
+        for origin in &config.monitoring.cors_allowed_origins {
+            if origin != "*" && !origin.starts_with("http://") && !origin.starts_with("https://") {
+                return Err(ScrapingError::ConfigError(format!("Invalid CORS origin '{}', expected '*' or an http(s):// origin", origin)).into());
+            }
+        }
+
+        // checking redaction rules compile
+        if config.redaction.enabled {
+            for rule in &config.redaction.rules {
+                if let Err(e) = regex::Regex::new(rule) {
+                    return Err(ScrapingError::ConfigError(format!("Invalid redaction rule '{}': {}", rule, e)).into());
+                }
+            }
+        }
+
+        // checking emote cache ttl
+        if !Self::is_valid_time_format(&config.parser.emote_cache_ttl) {
+            return Err(ScrapingError::ConfigError(format!("Invalid emote_cache_ttl format: {}", config.parser.emote_cache_ttl)).into());
+        }
+
+        // checking parser selectors are valid CSS
+        for (name, selector) in config.parser.selectors.entries() {
+            if let Err(e) = scraper::Selector::parse(selector) {
+                return Err(ScrapingError::ConfigError(format!("Invalid {} selector '{}': {}", name, selector, e)).into());
             }
         }
 
+        // checking scraper headers are valid HTTP
+        config.scraper.header_map()?;
+
+        // Validate the TUI theme file if provided, a malformed one would otherwise only warn once the TUI starts
+        if let Some(ref css_path) = config.monitoring.custom_css {
+            crate::tui::load_custom_theme(css_path).map_err(|e| ScrapingError::ConfigError(e.to_string()))?;
+        }
+
         debug!("Configuration validation passed");
         Ok(())
     }
@@ -232,21 +1148,67 @@ impl ConfigManager for FileConfigManager {
     async fn save_config(&self, config: &Config) -> Result<()> {
         info!("Saving configuration to {:?}", self.config_path);
         
-        let toml_content = toml::to_string_pretty(config)
-            .map_err(|e| ScrapingError::ConfigError(format!("Failed to serialize config: {}", e)))?;
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let content = ConfigFormat::from_path(&self.config_path).serialize(&self.file_backed(config, &env))?;
         
-        fs::write(&self.config_path, toml_content)
+        fs::write(&self.config_path, content)
             .map_err(|e| ScrapingError::ConfigError(format!("Failed to write config file: {}", e)))?;
         
         info!("Configuration saved successfully");
         Ok(())
     }}
 impl FileConfigManager {
+    /// `config` as it goes back into the file, given the environment `env` it
+    /// was loaded with. Secrets that still resolve to what the file's `${...}`
+    /// references give are written as those references again, so saving never
+    /// puts the plaintext on disk, and values still equal to their `SCRAPER_*`
+    /// override keep what the file had.
+    fn file_backed(&self, config: &Config, env: &HashMap<String, String>) -> Config {
+        let mut saved = config.clone();
+        let on_disk = fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|content| ConfigFormat::from_path(&self.config_path).parse::<Config>(&content).ok());
+        let Some(mut on_disk) = on_disk else {
+            return saved;
+        };
+
+        // the env only ever applied to the running copy
+        if let Ok(overrides) = Self::env_overrides(env.clone()) {
+            let mut overridden = on_disk.clone().merge(overrides.clone());
+            overridden.normalize_streamers();
+            if overrides.streamers.is_some() && saved.streamers == overridden.streamers {
+                saved.streamers = on_disk.streamers.clone();
+            }
+            if overrides.output.directory.is_some() && saved.output.directory == overridden.output.directory {
+                saved.output.directory = on_disk.output.directory.clone();
+            }
+            if overrides.monitoring.api_port.is_some() && saved.monitoring.api_port == overridden.monitoring.api_port {
+                saved.monitoring.api_port = on_disk.monitoring.api_port;
+            }
+            if overrides.agents.max_concurrent.is_some() && saved.agents.max_concurrent == overridden.agents.max_concurrent {
+                saved.agents.max_concurrent = on_disk.agents.max_concurrent;
+            }
+        }
+
+        let mut resolved = on_disk.monitoring.clone();
+        if resolved.resolve_secrets(|name| env.get(name).cloned()).is_err() {
+            return saved;
+        }
+        for (((_, secret), (_, reference)), (_, resolved_secret)) in saved.monitoring.secrets_mut().into_iter()
+            .zip(on_disk.monitoring.secrets_mut())
+            .zip(resolved.secrets_mut())
+        {
+            if *secret == *resolved_secret {
+                *secret = reference.take();
+            }
+        }
+        saved
+    }
+
     /// Create a default configuration file
     async fn create_default_config(&self) -> Result<()> {
         let default_config = Config::default();
-        let toml_content = toml::to_string_pretty(&default_config)
-            .map_err(|e| ScrapingError::ConfigError(format!("Failed to serialize default config: {}", e)))?;
+        let content = ConfigFormat::from_path(&self.config_path).serialize(&default_config)?;
 
         // Create parent directory if it doesn't exist
         if let Some(parent) = self.config_path.parent() {
@@ -254,7 +1216,7 @@ impl FileConfigManager {
                 .map_err(|e| ScrapingError::ConfigError(format!("Failed to create config directory: {}", e)))?;
         }
 
-        fs::write(&self.config_path, toml_content)
+        fs::write(&self.config_path, content)
             .map_err(|e| ScrapingError::ConfigError(format!("Failed to write default config: {}", e)))?;
 
         info!("Default configuration file created at {:?}", self.config_path);
@@ -264,10 +1226,11 @@ impl FileConfigManager {
     /// Watch configuration file for changes and send updates through the channel
     async fn watch_config_file(
         config_path: PathBuf,
+        streamers_file: Option<PathBuf>,
         tx: mpsc::Sender<Config>,
         config_manager: FileConfigManager,
     ) -> Result<()> {
-        let (file_tx, mut file_rx) = mpsc::channel(100);
+        let (file_tx, file_rx) = mpsc::channel(100);
 
         // Set up file system watcher
         let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
@@ -288,74 +1251,237 @@ impl FileConfigManager {
 
         info!("Started watching configuration file: {:?}", config_path);
 
-        // Process file system events
-        while let Some(event) = file_rx.recv().await {
-            match event.kind {
-                EventKind::Modify(_) | EventKind::Create(_) => {
-                    // Check if the event is for our config file
-                    if event.paths.iter().any(|p| p == &config_path) {
-                        debug!("Configuration file changed, reloading...");
-                        
-                        // Add a small delay to ensure file write is complete
-                        tokio::time::sleep(Duration::from_millis(100)).await;
-
-                        match config_manager.load_config().await {
-                            Ok(new_config) => {
-                                info!("Configuration reloaded successfully");
-                                if let Err(e) = tx.send(new_config).await {
-                                    error!("Failed to send updated config: {}", e);
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to reload configuration: {}", e);
-                                // Continue watching even if reload fails
-                            }
-                        }
-                    }
-                }
-                _ => {} // Ignore other event types
-            }
+        // Also watch the directory holding the streamers file, if it lives elsewhere
+        let mut watched_dirs = vec![watch_path.to_path_buf()];
+        if let Some(ref streamers_file) = streamers_file {
+            Self::watch_parent_dir(&mut watcher, &mut watched_dirs, streamers_file);
         }
 
+        Self::reload_on_changes(file_rx, config_path, streamers_file, tx, config_manager, CONFIG_RELOAD_DEBOUNCE, |file| {
+            Self::watch_parent_dir(&mut watcher, &mut watched_dirs, file);
+        })
+        .await;
+
         Ok(())
     }
 
-    /// Validate size format (e.g., "100MB", "1GB")
-    fn is_valid_size_format(size_str: &str) -> bool {
-        let size_str = size_str.to_uppercase();
-        let valid_suffixes = ["B", "KB", "MB", "GB", "TB"];
-        
-        for suffix in &valid_suffixes {
-            if size_str.ends_with(suffix) {
-                let number_part = &size_str[..size_str.len() - suffix.len()];
-                if let Ok(_) = number_part.parse::<u64>() {
-                    return true;
-                }
-            }
-        }
-        false
-    }
+    /// Reload and send the config once per burst of changes to the config or
+    /// streamers file. Editors that write then rename fire several events for
+    /// one save, so a reload waits until `debounce` has passed without another.
+    async fn reload_on_changes(
+        mut file_rx: mpsc::Receiver<Event>,
+        config_path: PathBuf,
+        mut streamers_file: Option<PathBuf>,
+        tx: mpsc::Sender<Config>,
+        config_manager: FileConfigManager,
+        debounce: Duration,
+        mut watch_streamers_file: impl FnMut(&Path),
+    ) {
+        loop {
+            let is_change = |event: &Event| {
+                matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
+                    && event.paths.iter().any(|p| p == &config_path || Some(p) == streamers_file.as_ref())
+            };
 
-    /// Validate time format (e.g., "1h", "30m", "1d")
-    fn is_valid_time_format(time_str: &str) -> bool {
-        let time_str = time_str.to_lowercase();
-        let valid_suffixes = ["s", "m", "h", "d"];
-        
-        for suffix in &valid_suffixes {
-            if time_str.ends_with(suffix) {
-                let number_part = &time_str[..time_str.len() - suffix.len()];
-                if let Ok(_) = number_part.parse::<u64>() {
-                    return true;
+            // wait for a change, then for the burst it starts to die down
+            loop {
+                match file_rx.recv().await {
+                    Some(event) if is_change(&event) => break,
+                    Some(_) => {}
+                    None => return,
                 }
             }
+            let mut closed = false;
+            loop {
+                match tokio::time::timeout(debounce, file_rx.recv()).await {
+                    Ok(Some(event)) if is_change(&event) => debug!("Configuration changed again, waiting for writes to settle"),
+                    Ok(Some(_)) => {}
+                    Ok(None) => {
+                        closed = true;
+                        break;
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            debug!("Configuration file changed, reloading...");
+            match config_manager.load_config().await {
+                Ok(new_config) => {
+                    info!("Configuration reloaded successfully");
+
+                    // follow the streamers file if the config now points somewhere else
+                    streamers_file = config_manager.streamers_file_path(&new_config);
+                    if let Some(ref streamers_file) = streamers_file {
+                        watch_streamers_file(streamers_file);
+                    }
+
+                    if let Err(e) = tx.send(new_config).await {
+                        error!("Failed to send updated config: {}", e);
+                        return;
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to reload configuration: {}", e);
+                    // Continue watching even if reload fails
+                }
+            }
+            if closed {
+                return;
+            }
+        }
+    }
+
+    fn watch_parent_dir(watcher: &mut impl Watcher, watched_dirs: &mut Vec<PathBuf>, file: &Path) {
+        let Some(dir) = file.parent() else {
+            return;
+        };
+        if watched_dirs.iter().any(|watched| watched == dir) {
+            return;
+        }
+        match watcher.watch(dir, RecursiveMode::NonRecursive) {
+            Ok(_) => {
+                info!("Started watching streamers file: {:?}", file);
+                watched_dirs.push(dir.to_path_buf());
+            }
+            Err(e) => warn!("Failed to watch streamers file directory {:?}: {}", dir, e),
+        }
+    }
+
+    /// Write and remove a probe file in `dir`, or in the closest existing
+    /// parent it would be created under, so nothing is left behind either way
+    fn check_writable(dir: &Path) -> Result<()> {
+        static PROBES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let not_writable = |reason: String| {
+            ScrapingError::ConfigError(format!("output directory {} is not writable: {}", dir.display(), reason))
+        };
+
+        let mut existing = dir;
+        while !existing.exists() {
+            match existing.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => existing = parent,
+                _ => {
+                    existing = Path::new(".");
+                    break;
+                }
+            }
+        }
+        if !existing.is_dir() {
+            return Err(not_writable(format!("{} is not a directory", existing.display())).into());
+        }
+
+        let probe_id = PROBES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let probe = existing.join(format!(".write_probe_{}_{}", std::process::id(), probe_id));
+        match fs::OpenOptions::new().write(true).create_new(true).open(&probe) {
+            Ok(_) => {
+                let _ = fs::remove_file(&probe);
+                Ok(())
+            }
+            Err(e) => Err(not_writable(e.to_string()).into()),
+        }
+    }
+
+    /// Config overrides read from environment variables, for containers that
+    /// don't want to template a whole config file. Empty values are ignored.
+    ///
+    /// - `SCRAPER_STREAMERS`: comma-separated channels, replaces `streamers` even when read from `streamers_file`
+    /// - `SCRAPER_OUTPUT_DIRECTORY`: `output.directory`
+    /// - `SCRAPER_API_PORT`: `monitoring.api_port`
+    /// - `SCRAPER_MAX_CONCURRENT`: `agents.max_concurrent`
+    pub fn env_overrides(vars: impl IntoIterator<Item = (String, String)>) -> Result<PartialConfig> {
+        let mut partial = PartialConfig::default();
+        for (name, value) in vars {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let invalid = |expected: &str| ScrapingError::ConfigError(format!("{} must be {}, got '{}'", name, expected, value));
+            match name.as_str() {
+                "SCRAPER_STREAMERS" => {
+                    partial.streamers = Some(
+                        value.split(',').map(str::trim).filter(|streamer| !streamer.is_empty()).map(str::to_string).collect(),
+                    );
+                }
+                "SCRAPER_OUTPUT_DIRECTORY" => partial.output.directory = Some(PathBuf::from(value)),
+                "SCRAPER_API_PORT" => {
+                    partial.monitoring.api_port = Some(value.parse().map_err(|_| invalid("a port number"))?);
+                }
+                "SCRAPER_MAX_CONCURRENT" => {
+                    partial.agents.max_concurrent = Some(value.parse().map_err(|_| invalid("a whole number"))?);
+                }
+                _ => continue,
+            }
+            info!("Config overridden by {}", name);
+        }
+        Ok(partial)
+    }
+
+    /// `streamers_file` from the config, relative paths resolved against the config file's directory
+    pub fn streamers_file_path(&self, config: &Config) -> Option<PathBuf> {
+        let streamers_file = config.streamers_file.as_ref()?;
+        if streamers_file.is_absolute() {
+            return Some(streamers_file.clone());
         }
-        false
+        let base = self.config_path.parent().unwrap_or(Path::new(""));
+        Some(base.join(streamers_file))
     }
 
-    /// Parse size string to bytes
+    /// Read a newline-delimited streamer list; blank lines and `#` comments are skipped
+    pub fn load_streamers_file(path: &Path) -> Result<Vec<String>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| ScrapingError::ConfigError(format!("Failed to read streamers file {:?}: {}", path, e)))?;
+
+        let mut streamers = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let name = line.split('#').next().unwrap_or_default().trim();
+            if name.is_empty() {
+                continue;
+            }
+            if !Self::is_valid_streamer_name(name) {
+                return Err(ScrapingError::ConfigError(format!(
+                    "Invalid streamer name '{}' on line {} of {:?}: Twitch names are 4-25 characters of letters, numbers and underscores",
+                    name, index + 1, path
+                )).into());
+            }
+            streamers.push(name.to_string());
+        }
+        Ok(streamers)
+    }
+
+    /// Validate a Twitch login name (e.g., "shroud", "summit1g", "some_user")
+    pub fn is_valid_streamer_name(name: &str) -> bool {
+        static STREAMER_NAME: OnceLock<Regex> = OnceLock::new();
+        let pattern = STREAMER_NAME.get_or_init(|| Regex::new("^[a-z0-9_]{4,25}$").expect("streamer name pattern is valid"));
+        // logins are case-insensitive, so compare the lowercased form
+        pattern.is_match(&name.to_lowercase())
+    }
+
+    /// Validate size format (e.g., "100MB", "1.5GB", "100 MB", "unlimited")
+    fn is_valid_size_format(size_str: &str) -> bool {
+        Self::parse_size_to_bytes(size_str).is_ok()
+    }
+
+    /// Validate time format (e.g., "1h", "30m", "0.5h")
+    fn is_valid_time_format(time_str: &str) -> bool {
+        Self::parse_time_to_duration(time_str).is_ok()
+    }
+
+    /// Parse the number in front of a unit, allowing decimals and surrounding spaces
+    fn parse_unit_number(number_part: &str) -> Option<f64> {
+        let number: f64 = number_part.trim().parse().ok()?;
+        if number.is_finite() && number >= 0.0 {
+            Some(number)
+        } else {
+            None
+        }
+    }
+
+    /// Parse size string to bytes. "unlimited" and a bare "0" give 0, which
+    /// turns size-based rotation off.
     pub fn parse_size_to_bytes(size_str: &str) -> Result<u64> {
-        let size_str = size_str.to_uppercase();
+        let size_str = size_str.trim().to_uppercase();
+        if size_str == "UNLIMITED" || size_str == "0" {
+            return Ok(0);
+        }
         // Order matters - check longer suffixes first to avoid partial matches
         let multipliers = [
             ("TB", 1024_u64.pow(4)),
@@ -366,20 +1492,27 @@ impl FileConfigManager {
         ];
 
         for (suffix, multiplier) in &multipliers {
-            if size_str.ends_with(suffix) {
-                let number_part = &size_str[..size_str.len() - suffix.len()];
-                let number: u64 = number_part.parse()
-                    .map_err(|_| ScrapingError::ConfigError(format!("Invalid number in size format: {}", size_str)))?;
-                return Ok(number * multiplier);
+            if let Some(number_part) = size_str.strip_suffix(suffix) {
+                let number = Self::parse_unit_number(number_part)
+                    .ok_or_else(|| ScrapingError::ConfigError(format!("Invalid number in size format: {}", size_str)))?;
+                return Ok((number * *multiplier as f64).round() as u64);
             }
         }
 
         Err(ScrapingError::ConfigError(format!("Invalid size format: {}", size_str)).into())
     }
 
+    /// Parse a rotation time, where "unlimited" means files never rotate by age
+    pub fn parse_rotation_time(time_str: &str) -> Result<Option<Duration>> {
+        if time_str.trim().eq_ignore_ascii_case("unlimited") {
+            return Ok(None);
+        }
+        Self::parse_time_to_duration(time_str).map(Some)
+    }
+
     /// Parse time string to duration
     pub fn parse_time_to_duration(time_str: &str) -> Result<Duration> {
-        let time_str = time_str.to_lowercase();
+        let time_str = time_str.trim().to_lowercase();
         let multipliers = [
             ("s", 1),
             ("m", 60),
@@ -388,11 +1521,10 @@ impl FileConfigManager {
         ];
 
         for (suffix, multiplier) in &multipliers {
-            if time_str.ends_with(suffix) {
-                let number_part = &time_str[..time_str.len() - suffix.len()];
-                let number: u64 = number_part.parse()
-                    .map_err(|_| ScrapingError::ConfigError(format!("Invalid time format: {}", time_str)))?;
-                return Ok(Duration::from_secs(number * multiplier));
+            if let Some(number_part) = time_str.strip_suffix(suffix) {
+                let number = Self::parse_unit_number(number_part)
+                    .ok_or_else(|| ScrapingError::ConfigError(format!("Invalid time format: {}", time_str)))?;
+                return Ok(Duration::from_secs((number * *multiplier as f64).round() as u64));
             }
         }
 
@@ -403,7 +1535,7 @@ impl FileConfigManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+    
     use tempfile::tempdir;
 
     #[tokio::test]
@@ -420,6 +1552,284 @@ mod tests {
         assert!(config_path.exists());
     }
 
+    #[tokio::test]
+    async fn test_config_formats_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let mut config = Config {
+            streamers: vec!["xqcow".to_string(), "summit1g".to_string()],
+            ..Config::default()
+        };
+        config.agents.delay_range = (500, 1500);
+        config.output.format = "csv".to_string();
+        config.output.retention = Some("7d".to_string());
+        config.output.rotation_overrides.insert("xqcow".to_string(), RotationOverride {
+            rotation_size: None,
+            rotation_time: Some("10m".to_string()),
+        });
+        config.monitoring.dashboard_port = None;
+
+        for (file_name, format) in [
+            ("config.toml", ConfigFormat::Toml),
+            ("config.yaml", ConfigFormat::Yaml),
+            ("config.yml", ConfigFormat::Yaml),
+            ("config.json", ConfigFormat::Json),
+        ] {
+            let config_path = temp_dir.path().join(file_name);
+            assert_eq!(ConfigFormat::from_path(&config_path), format);
+            let manager = FileConfigManager::new(config_path.clone());
+            manager.save_config(&config).await.unwrap();
+
+            // written in the file's own syntax
+            let content = fs::read_to_string(&config_path).unwrap();
+            assert!(format.parse::<serde_json::Value>(&content).is_ok(), "{}", file_name);
+
+            let loaded = manager.load_config().await.unwrap();
+            assert_eq!(
+                serde_json::to_value(&loaded).unwrap(),
+                serde_json::to_value(&config).unwrap(),
+                "{}", file_name
+            );
+        }
+
+        // a missing JSON config gets a JSON default
+        let manager = FileConfigManager::new(temp_dir.path().join("fresh.json"));
+        assert_eq!(manager.load_config().await.unwrap().streamers, vec!["shroud", "ninja"]);
+        assert!(serde_json::from_str::<serde_json::Value>(&fs::read_to_string(temp_dir.path().join("fresh.json")).unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_output_directory_must_be_writable() {
+        let temp_dir = tempdir().unwrap();
+
+        // a directory that doesn't exist yet is probed where it would be created
+        let new_dir = temp_dir.path().join("new").join("output");
+        assert!(FileConfigManager::check_writable(&new_dir).is_ok());
+        assert!(!new_dir.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        // nothing can be created below a regular file
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+        let error = FileConfigManager::check_writable(&file.join("output")).unwrap_err().to_string();
+        assert!(error.contains("is not writable"), "{}", error);
+
+        // validation alone doesn't touch the disk, loading the config does
+        let config_path = temp_dir.path().join("config.toml");
+        let manager = FileConfigManager::new(config_path.clone());
+        let mut config = Config::default();
+        config.output.directory = file.join("output");
+        assert!(manager.validate_config(&config).is_ok());
+        fs::write(&config_path, ConfigFormat::Toml.serialize(&config).unwrap()).unwrap();
+        let error = manager.load_config().await.unwrap_err().to_string();
+        assert!(error.contains("is not writable"), "{}", error);
+
+        // a read-only directory, unless we're root and permissions don't apply
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let read_only = temp_dir.path().join("read_only");
+            fs::create_dir(&read_only).unwrap();
+            fs::set_permissions(&read_only, fs::Permissions::from_mode(0o555)).unwrap();
+            let enforced = fs::write(read_only.join("check"), "").is_err();
+            let result = FileConfigManager::check_writable(&read_only);
+            fs::set_permissions(&read_only, fs::Permissions::from_mode(0o755)).unwrap();
+            if enforced {
+                assert!(result.unwrap_err().to_string().contains("is not writable"));
+            }
+            assert!(fs::read_dir(&read_only).unwrap().all(|entry| entry.unwrap().file_name() == "check"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_config_reload_debounced() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let manager = FileConfigManager::new(config_path.clone());
+        manager.save_config(&Config::default()).await.unwrap();
+
+        let (file_tx, file_rx) = mpsc::channel(10);
+        let (tx, mut rx) = mpsc::channel(10);
+        let reloads = tokio::spawn(FileConfigManager::reload_on_changes(
+            file_rx,
+            config_path.clone(),
+            None,
+            tx,
+            FileConfigManager::new(config_path.clone()),
+            Duration::from_millis(200),
+            |_| {},
+        ));
+
+        // one editor save: write, create the swap file, rename over
+        let modify = || Event::new(EventKind::Modify(notify::event::ModifyKind::Any)).add_path(config_path.clone());
+        file_tx.send(modify()).await.unwrap();
+        file_tx.send(Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(config_path.clone())).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        file_tx.send(modify()).await.unwrap();
+        // another file in the directory doesn't count
+        file_tx.send(Event::new(EventKind::Modify(notify::event::ModifyKind::Any)).add_path(temp_dir.path().join("other.txt"))).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        drop(file_tx);
+        reloads.await.unwrap();
+
+        assert!(rx.recv().await.is_some());
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[test]
+    fn test_resolve_secrets() {
+        let temp_dir = tempdir().unwrap();
+        let secret_file = temp_dir.path().join("discord_webhook");
+        fs::write(&secret_file, "https://discord.com/api/webhooks/1/abc\n").unwrap();
+        let lookup = |name: &str| (name == "SCRAPER_API_TOKEN").then(|| "s3cret".to_string());
+
+        let mut monitoring = Config::default().monitoring;
+        monitoring.api_token = Some("${SCRAPER_API_TOKEN}".to_string());
+        monitoring.webhook_url = Some("https://hooks.example.com/${SCRAPER_API_TOKEN}/chat".to_string());
+        monitoring.discord_webhook_url = Some(format!("${{file:{}}}", secret_file.display()));
+        monitoring.resolve_secrets(lookup).unwrap();
+        assert_eq!(monitoring.api_token.as_deref(), Some("s3cret"));
+        assert_eq!(monitoring.webhook_url.as_deref(), Some("https://hooks.example.com/s3cret/chat"));
+        assert_eq!(monitoring.discord_webhook_url.as_deref(), Some("https://discord.com/api/webhooks/1/abc"));
+
+        // a missing variable is an error, never the literal reference
+        let mut monitoring = Config::default().monitoring;
+        monitoring.api_token = Some("${MISSING_TOKEN}".to_string());
+        let error = monitoring.resolve_secrets(lookup).unwrap_err().to_string();
+        assert!(error.contains("MISSING_TOKEN") && error.contains("api_token"), "{}", error);
+
+        let mut monitoring = Config::default().monitoring;
+        monitoring.discord_webhook_url = Some("${file:/nonexistent/secret}".to_string());
+        assert!(monitoring.resolve_secrets(lookup).is_err());
+        monitoring.discord_webhook_url = Some("${SCRAPER_API_TOKEN".to_string());
+        assert!(monitoring.resolve_secrets(lookup).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_keeps_secret_references() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let manager = FileConfigManager::new(config_path.clone());
+        let secret_file = temp_dir.path().join("discord_webhook");
+        fs::write(&secret_file, "https://discord.com/api/webhooks/1/abc\n").unwrap();
+
+        let mut config = Config::default();
+        config.monitoring.discord_webhook_url = Some(format!("${{file:{}}}", secret_file.display()));
+        config.monitoring.api_token = Some("plain-token".to_string());
+        manager.save_config(&config).await.unwrap();
+
+        // what the TUI and API hold is the resolved config, saving it keeps the reference
+        let mut loaded = manager.load_config().await.unwrap();
+        assert_eq!(loaded.monitoring.discord_webhook_url.as_deref(), Some("https://discord.com/api/webhooks/1/abc"));
+        loaded.monitoring.api_port = 9191;
+        manager.save_config(&loaded).await.unwrap();
+        let content = fs::read_to_string(&config_path).unwrap();
+        assert!(!content.contains("api/webhooks"), "{}", content);
+        assert!(content.contains(&format!("${{file:{}}}", secret_file.display())));
+        assert!(content.contains("9191"));
+
+        // a secret changed at runtime is saved as given
+        loaded.monitoring.discord_webhook_url = Some("https://discord.com/api/webhooks/2/def".to_string());
+        manager.save_config(&loaded).await.unwrap();
+        let reloaded = manager.load_config().await.unwrap();
+        assert_eq!(reloaded.monitoring.discord_webhook_url.as_deref(), Some("https://discord.com/api/webhooks/2/def"));
+        assert_eq!(reloaded.monitoring.api_token.as_deref(), Some("plain-token"));
+    }
+
+    #[tokio::test]
+    async fn test_save_leaves_env_overrides_out_of_the_file() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileConfigManager::new(temp_dir.path().join("config.toml"));
+        manager.save_config(&Config::default()).await.unwrap();
+
+        let env = HashMap::from([
+            ("SCRAPER_STREAMERS".to_string(), "XQCow,summit1g".to_string()),
+            ("SCRAPER_API_PORT".to_string(), "9090".to_string()),
+            ("SCRAPER_MAX_CONCURRENT".to_string(), "12".to_string()),
+        ]);
+        let mut runtime = Config::default().merge(FileConfigManager::env_overrides(env.clone()).unwrap());
+        runtime.normalize_streamers();
+        runtime.agents.max_concurrent = 3; // changed in the TUI after startup
+        runtime.output.rotation_size = "50MB".to_string();
+
+        let saved = manager.file_backed(&runtime, &env);
+        assert_eq!(saved.streamers, Config::default().streamers);
+        assert_eq!(saved.monitoring.api_port, Config::default().monitoring.api_port);
+        assert_eq!(saved.agents.max_concurrent, 3);
+        assert_eq!(saved.output.rotation_size, "50MB");
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+        };
+        let manager = FileConfigManager::new(PathBuf::from("test.toml"));
+
+        let overrides = FileConfigManager::env_overrides(vars(&[
+            ("SCRAPER_STREAMERS", "xqcow, summit1g,,"),
+            ("SCRAPER_OUTPUT_DIRECTORY", "/data/chat"),
+            ("SCRAPER_API_PORT", "9090"),
+            ("SCRAPER_MAX_CONCURRENT", "12"),
+            ("SCRAPER_UNKNOWN", "ignored"),
+            ("HOME", "/root"),
+        ])).unwrap();
+        let config = Config::default().merge(overrides);
+        assert_eq!(config.streamers, vec!["xqcow", "summit1g"]);
+        assert_eq!(config.output.directory, PathBuf::from("/data/chat"));
+        assert_eq!(config.monitoring.api_port, 9090);
+        assert_eq!(config.agents.max_concurrent, 12);
+        assert!(manager.validate_config(&config).is_ok());
+
+        // unset and empty variables leave the file's values alone
+        let config = Config::default().merge(FileConfigManager::env_overrides(vars(&[("SCRAPER_API_PORT", " ")])).unwrap());
+        assert_eq!(config.monitoring.api_port, Config::default().monitoring.api_port);
+
+        // values that don't parse, or that validation rejects, are errors
+        assert!(FileConfigManager::env_overrides(vars(&[("SCRAPER_API_PORT", "http")])).is_err());
+        let config = Config::default().merge(FileConfigManager::env_overrides(vars(&[("SCRAPER_API_PORT", "80")])).unwrap());
+        assert!(manager.validate_config(&config).is_err());
+        let config = Config::default().merge(FileConfigManager::env_overrides(vars(&[("SCRAPER_STREAMERS", "not a name")])).unwrap());
+        assert!(manager.validate_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_streamers_loaded_lowercase() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileConfigManager::new(temp_dir.path().join("config.toml"));
+        let config = Config {
+            streamers: vec!["Shroud".to_string(), "summit1g".to_string()],
+            ..Config::default()
+        };
+        manager.save_config(&config).await.unwrap();
+
+        assert_eq!(manager.load_config().await.unwrap().streamers, vec!["shroud", "summit1g"]);
+    }
+
+    #[tokio::test]
+    async fn test_streamers_file() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let manager = FileConfigManager::new(config_path.clone());
+
+        let mut config = Config::default();
+        config.streamers.clear();
+        config.streamers_file = Some(PathBuf::from("channels.txt"));
+        manager.save_config(&config).await.unwrap();
+
+        fs::write(temp_dir.path().join("channels.txt"), "# managed elsewhere\nshroud\n\n  summit1g  # comment\n").unwrap();
+        let config = manager.load_config().await.unwrap();
+        assert_eq!(config.streamers, vec!["shroud", "summit1g"]);
+
+        // entries follow the same rules as inline streamers
+        fs::write(temp_dir.path().join("channels.txt"), "shroud\nnot a name\n").unwrap();
+        let error = manager.load_config().await.unwrap_err().to_string();
+        assert!(error.contains("line 2"), "{}", error);
+
+        fs::remove_file(temp_dir.path().join("channels.txt")).unwrap();
+        assert!(manager.load_config().await.is_err());
+    }
+
     #[tokio::test]
     async fn test_config_validation() {
         let manager = FileConfigManager::new(PathBuf::from("test.toml"));
@@ -433,6 +1843,21 @@ mod tests {
         invalid_config.streamers.clear();
         assert!(manager.validate_config(&invalid_config).is_err());
 
+        // Test invalid config - streamer name with a hyphen, error names the streamer
+        let mut invalid_config = Config::default();
+        invalid_config.streamers.push("bad-name".to_string());
+        let err = manager.validate_config(&invalid_config).unwrap_err();
+        assert!(err.to_string().contains("bad-name"));
+
+        // Test invalid config - the same channel twice in different case, error names both
+        let invalid_config = Config {
+            streamers: vec!["Ninja".to_string(), "shroud".to_string(), "ninja".to_string(), "NINJA".to_string()],
+            ..Config::default()
+        };
+        let err = manager.validate_config(&invalid_config).unwrap_err().to_string();
+        assert!(err.contains("'Ninja' and 'ninja' and 'NINJA'"), "{}", err);
+        assert!(!err.contains("shroud"));
+
         // Test invalid config - max_concurrent = 0
         let mut invalid_config = Config::default();
         invalid_config.agents.max_concurrent = 0;
@@ -442,6 +1867,368 @@ mod tests {
         let mut invalid_config = Config::default();
         invalid_config.agents.delay_range = (5000, 1000);
         assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - bad idle timeout
+        let mut invalid_config = Config::default();
+        invalid_config.agents.idle_timeout = "forever".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - zero probe interval
+        let mut invalid_config = Config::default();
+        invalid_config.agents.idle_probe_interval = "0s".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - zero batch size
+        let mut invalid_config = Config::default();
+        invalid_config.agents.batch_size = 0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - CORS origin without scheme
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.cors_allowed_origins = vec!["dashboard.local".to_string()];
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test valid config - CORS origins
+        let mut valid_config = Config::default();
+        valid_config.monitoring.cors_allowed_origins = vec!["*".to_string(), "https://dashboard.local".to_string()];
+        assert!(manager.validate_config(&valid_config).is_ok());
+
+        // Test invalid config - bad emote cache ttl
+        let mut invalid_config = Config::default();
+        invalid_config.parser.emote_cache_ttl = "soon".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - selector that isn't CSS
+        let mut invalid_config = Config::default();
+        invalid_config.parser.selectors.badge = "[[badge".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - tui frame rate out of range
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.tui_fps = 0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.tui_fps = 120;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - rate smoothing out of range
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.tui_rate_smoothing = 0.0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.tui_rate_smoothing = 1.5;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - websocket heartbeat and client cap
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.ws_ping_interval = "0s".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.ws_pong_timeout = "later".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.ws_max_clients = 0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - monitoring and scaling intervals under a second
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.monitor_interval = "0s".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.scale_interval = "often".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - lifecycle notifications without any webhook
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.lifecycle_notifications = true;
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.discord_webhook_url = Some("https://discord.com/api/webhooks/1/abc".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+        invalid_config.monitoring.discord_webhook_url = None;
+        invalid_config.monitoring.slack_webhook_url = Some("https://hooks.slack.com/services/T000/B000/XXXX".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+        invalid_config.monitoring.slack_webhook_url = None;
+        invalid_config.monitoring.webhook_url = Some("https://hooks.example.com/chat".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test invalid config - webhook template that isn't JSON, or has no url to post to
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.webhook_url = Some("https://hooks.example.com/chat".to_string());
+        invalid_config.monitoring.webhook_template = Some(r#"{"text": "{message}""#.to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.webhook_template = Some(r#"{"text": "{user}"}"#.to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.webhook_template = Some(r#"{"text": "{username}: {message}"}"#.to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+        invalid_config.monitoring.webhook_url = None;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - webhook batches that never go out
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.webhook_batch_interval = Some("0s".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.webhook_batch_interval = Some("2s".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test invalid config - webhook retries without a dead-letter file, or too many of them
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.webhook_delivery_retries = 3;
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.webhook_dead_letter_file = Some(PathBuf::from("output/dead_letters.jsonl"));
+        assert!(manager.validate_config(&invalid_config).is_ok());
+        invalid_config.monitoring.webhook_delivery_retries = 11;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - keyword alert pattern that doesn't compile
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.alert_keywords = vec!["raid".to_string()];
+        invalid_config.monitoring.alert_pattern = Some("give(away".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.monitoring.alert_pattern = Some("give ?away".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test invalid config - slack webhook that isn't a url
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.slack_webhook_url = Some("hooks.slack.com/services/T000".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - redaction rule that doesn't compile
+        let mut invalid_config = Config::default();
+        invalid_config.redaction.enabled = true;
+        invalid_config.redaction.rules.push("(unclosed".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - negative error rate threshold
+        let mut invalid_config = Config::default();
+        invalid_config.agents.error_rate_alert_threshold = -1.0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - bad stale_after
+        let mut invalid_config = Config::default();
+        invalid_config.agents.stale_after = "a while".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.agents.stale_after = "0s".to_string();
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test invalid config - throughput thresholds out of order
+        let mut invalid_config = Config::default();
+        invalid_config.agents.cold_message_rate = 100.0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.agents.hot_message_rate = 0.0;
+        assert!(manager.validate_config(&invalid_config).is_ok());
+        invalid_config.agents.cold_message_rate = f64::NAN;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - bad error alert cooldown
+        let mut invalid_config = Config::default();
+        invalid_config.agents.error_alert_cooldown = "soon".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - zero ingest capacity
+        let mut invalid_config = Config::default();
+        invalid_config.output.ingest_capacity = 0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - zero write concurrency
+        let mut invalid_config = Config::default();
+        invalid_config.output.max_write_concurrency = 0;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - unknown filename template placeholder
+        let mut invalid_config = Config::default();
+        invalid_config.output.filename_template = Some("{streamer}-{hour}.{ext}".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.output.filename_template = Some("{streamer}-{timestamp}.{ext}".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test invalid config - bad flush interval
+        let mut invalid_config = Config::default();
+        invalid_config.output.flush_interval = "often".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - bad per-streamer rotation override
+        let mut invalid_config = Config::default();
+        invalid_config.output.rotation_overrides.insert("xqc".to_string(), RotationOverride {
+            rotation_size: None,
+            rotation_time: Some("soon".to_string()),
+        });
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.output.rotation_overrides.insert("xqc".to_string(), RotationOverride {
+            rotation_size: Some("huge".to_string()),
+            rotation_time: Some("10m".to_string()),
+        });
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.output.rotation_overrides.get_mut("xqc").unwrap().rotation_size = Some("50MB".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+        invalid_config.output.rotation_overrides.get_mut("xqc").unwrap().rotation_time = Some("unlimited".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test valid config - rotation turned off entirely
+        let mut unlimited_config = Config::default();
+        unlimited_config.output.rotation_size = "unlimited".to_string();
+        unlimited_config.output.rotation_time = "unlimited".to_string();
+        assert!(manager.validate_config(&unlimited_config).is_ok());
+        unlimited_config.output.rotation_size = "0".to_string();
+        assert!(manager.validate_config(&unlimited_config).is_ok());
+
+        // Test invalid config - bad retention period
+        let mut invalid_config = Config::default();
+        invalid_config.output.retention = Some("forever".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.output.retention = Some("0d".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.output.retention = Some("7d".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test invalid config - unknown compression
+        let mut invalid_config = Config::default();
+        invalid_config.output.compression = Some("rar".to_string());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.output.compression = Some("zstd".to_string());
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test invalid config - sample rate outside 0.0-1.0
+        let mut invalid_config = Config::default();
+        invalid_config.output.sample_rate = 1.5;
+        assert!(manager.validate_config(&invalid_config).is_err());
+        invalid_config.output.sample_rate = f64::NAN;
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - unknown timezone
+        let mut invalid_config = Config::default();
+        invalid_config.output.timezone = "Mars/Olympus_Mons".to_string();
+        assert!(manager.validate_config(&invalid_config).is_err());
+
+        // Test invalid config - malformed theme file
+        let theme_dir = tempdir().unwrap();
+        let theme_path = theme_dir.path().join("theme.conf");
+        fs::write(&theme_path, "accent_color = chartreuse\n").unwrap();
+        let mut invalid_config = Config::default();
+        invalid_config.monitoring.custom_css = Some(theme_path.clone());
+        assert!(manager.validate_config(&invalid_config).is_err());
+        fs::write(&theme_path, "accent_color = \"#9146FF\"\n").unwrap();
+        assert!(manager.validate_config(&invalid_config).is_ok());
+
+        // Test valid config - non-UTC timezone
+        let mut valid_config = Config::default();
+        valid_config.output.timezone = "America/New_York".to_string();
+        assert!(manager.validate_config(&valid_config).is_ok());
+
+        // Test valid config - idle timeout disabled
+        let mut valid_config = Config::default();
+        valid_config.agents.idle_timeout = "0s".to_string();
+        assert!(manager.validate_config(&valid_config).is_ok());
+    }
+
+    #[test]
+    fn test_idle_settings_default_when_missing() {
+        let toml_content = r#"
+            streamers = ["shroud"]
+
+            [agents]
+            max_concurrent = 2
+            retry_attempts = 3
+            delay_range = [1000, 5000]
+
+            [output]
+            format = "json"
+            directory = "./scraped_data"
+            rotation_size = "100MB"
+            rotation_time = "1h"
+
+            [monitoring]
+            tui_enabled = false
+            api_port = 8080
+
+            [stealth]
+            randomize_user_agents = true
+            simulate_human_behavior = true
+            proxy_rotation = false
+            fingerprint_randomization = true
+        "#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.agents.idle_timeout, "10m");
+        assert_eq!(config.agents.idle_probe_interval, "2m");
+        assert_eq!(config.agents.batch_size, 1);
+        assert_eq!(config.agents.batch_window_ms, 50);
+    }
+
+    #[test]
+    fn test_partial_redaction_section() {
+        let toml_content = r#"
+            streamers = ["shroud"]
+
+            [agents]
+            max_concurrent = 2
+            retry_attempts = 3
+            delay_range = [1000, 5000]
+
+            [output]
+            format = "json"
+            directory = "./scraped_data"
+            rotation_size = "100MB"
+            rotation_time = "1h"
+
+            [monitoring]
+            tui_enabled = false
+            api_port = 8080
+
+            [stealth]
+            randomize_user_agents = true
+            simulate_human_behavior = true
+            proxy_rotation = false
+            fingerprint_randomization = true
+
+            [redaction]
+            enabled = true
+        "#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let defaults = RedactionConfig::default();
+        assert!(config.redaction.enabled);
+        assert_eq!(config.redaction.rules, defaults.rules);
+        assert_eq!(config.redaction.placeholder, "[REDACTED]");
+        assert!(!config.redaction.keep_length);
+    }
+
+    #[test]
+    fn test_scraper_extra_headers() {
+        let scraper: ScraperConfig = toml::from_str(r#"
+            [extra_headers]
+            "Accept-Language" = "en-US,en;q=0.9"
+            DNT = "1"
+        "#).unwrap();
+        let headers = scraper.header_map().unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["accept-language"], "en-US,en;q=0.9");
+        assert_eq!(headers["dnt"], "1");
+
+        let mut invalid = ScraperConfig::default();
+        invalid.extra_headers.insert("Bad Header".to_string(), "x".to_string());
+        assert!(invalid.header_map().is_err());
+
+        let mut invalid = ScraperConfig::default();
+        invalid.extra_headers.insert("X-Test".to_string(), "line\nbreak".to_string());
+        assert!(invalid.header_map().is_err());
+    }
+
+    #[test]
+    fn test_durability_parsing() {
+        let output: OutputConfig = toml::from_str(r#"
+            format = "json"
+            directory = "./scraped_data"
+            rotation_size = "100MB"
+            rotation_time = "1h"
+        "#).unwrap();
+        assert_eq!(output.durability, Durability::None);
+
+        let output: OutputConfig = toml::from_str(r#"
+            format = "json"
+            directory = "./scraped_data"
+            rotation_size = "100MB"
+            rotation_time = "1h"
+            durability = "on_rotate"
+        "#).unwrap();
+        assert_eq!(output.durability, Durability::OnRotate);
     }
 
     #[test]
@@ -451,6 +2238,26 @@ mod tests {
         assert!(FileConfigManager::is_valid_size_format("500kb"));
         assert!(!FileConfigManager::is_valid_size_format("invalid"));
         assert!(!FileConfigManager::is_valid_size_format("100"));
+        assert!(FileConfigManager::is_valid_size_format("1.5GB"));
+        assert!(FileConfigManager::is_valid_size_format("100 MB"));
+        assert!(!FileConfigManager::is_valid_size_format("-1MB"));
+        assert!(FileConfigManager::is_valid_size_format("unlimited"));
+        assert!(FileConfigManager::is_valid_size_format("0"));
+    }
+
+    #[test]
+    fn test_streamer_name_validation() {
+        assert!(FileConfigManager::is_valid_streamer_name("shroud"));
+        assert!(FileConfigManager::is_valid_streamer_name("Summit1G"));
+        assert!(FileConfigManager::is_valid_streamer_name("some_user_123"));
+        assert!(FileConfigManager::is_valid_streamer_name("abcd"));
+        assert!(FileConfigManager::is_valid_streamer_name(&"a".repeat(25)));
+        assert!(!FileConfigManager::is_valid_streamer_name("abc"));
+        assert!(!FileConfigManager::is_valid_streamer_name(&"a".repeat(26)));
+        assert!(!FileConfigManager::is_valid_streamer_name("some-user"));
+        assert!(!FileConfigManager::is_valid_streamer_name("some.user"));
+        assert!(!FileConfigManager::is_valid_streamer_name("some user"));
+        assert!(!FileConfigManager::is_valid_streamer_name("#shroud"));
     }
 
     #[test]
@@ -460,6 +2267,8 @@ mod tests {
         assert!(FileConfigManager::is_valid_time_format("1d"));
         assert!(!FileConfigManager::is_valid_time_format("invalid"));
         assert!(!FileConfigManager::is_valid_time_format("100"));
+        assert!(FileConfigManager::is_valid_time_format("0.5h"));
+        assert!(FileConfigManager::is_valid_time_format("30 m"));
     }
 
     #[test]
@@ -468,6 +2277,11 @@ mod tests {
         assert_eq!(FileConfigManager::parse_size_to_bytes("1GB").unwrap(), 1024 * 1024 * 1024);
         assert_eq!(FileConfigManager::parse_size_to_bytes("500KB").unwrap(), 500 * 1024);
         assert!(FileConfigManager::parse_size_to_bytes("invalid").is_err());
+        assert_eq!(FileConfigManager::parse_size_to_bytes("1.5GB").unwrap(), 1536 * 1024 * 1024);
+        assert_eq!(FileConfigManager::parse_size_to_bytes("100 MB").unwrap(), 100 * 1024 * 1024);
+        assert!(FileConfigManager::parse_size_to_bytes("1.5.5GB").is_err());
+        assert_eq!(FileConfigManager::parse_size_to_bytes("Unlimited").unwrap(), 0);
+        assert_eq!(FileConfigManager::parse_size_to_bytes("0").unwrap(), 0);
     }
 
     #[test]
@@ -476,5 +2290,56 @@ mod tests {
         assert_eq!(FileConfigManager::parse_time_to_duration("30m").unwrap(), Duration::from_secs(1800));
         assert_eq!(FileConfigManager::parse_time_to_duration("1d").unwrap(), Duration::from_secs(86400));
         assert!(FileConfigManager::parse_time_to_duration("invalid").is_err());
+        assert_eq!(FileConfigManager::parse_time_to_duration("0.5h").unwrap(), Duration::from_secs(1800));
+        assert_eq!(FileConfigManager::parse_time_to_duration("2 d").unwrap(), Duration::from_secs(172800));
+        assert!(FileConfigManager::parse_time_to_duration("unlimited").is_err());
+        assert_eq!(FileConfigManager::parse_rotation_time("unlimited").unwrap(), None);
+        assert_eq!(FileConfigManager::parse_rotation_time("1h").unwrap(), Some(Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn test_apply_partial_config() {
+        let partial: PartialConfig = toml::from_str(r#"
+            streamers = ["xqc"]
+
+            [agents]
+            max_concurrent = 2
+
+            [output]
+            format = "csv"
+            sample_seed = 7
+
+            [parser]
+            third_party_emotes = true
+        "#).unwrap();
+
+        let config = Config::default().merge(partial);
+        assert_eq!(config.streamers, vec!["xqc"]);
+        assert_eq!(config.agents.max_concurrent, 2);
+        assert_eq!(config.output.format, "csv");
+        assert_eq!(config.output.sample_seed, Some(7));
+        assert!(config.parser.third_party_emotes);
+
+        // everything that wasn't set keeps its value
+        let defaults = Config::default();
+        assert_eq!(config.agents.retry_attempts, defaults.agents.retry_attempts);
+        assert_eq!(config.output.directory, defaults.output.directory);
+        assert_eq!(config.monitoring.api_port, defaults.monitoring.api_port);
+
+        // an empty partial changes nothing
+        let mut unchanged = Config::default();
+        unchanged.apply(PartialConfig::default());
+        assert_eq!(toml::to_string(&unchanged).unwrap(), toml::to_string(&defaults).unwrap());
+
+        // a full config is a valid partial too, so the mirror can't fall behind `Config`
+        let full: PartialConfig = toml::from_str(&toml::to_string(&defaults).unwrap()).unwrap();
+        let mut overwritten = Config::default().merge(PartialConfig {
+            streamers: Some(vec!["someone".to_string()]),
+            ..Default::default()
+        });
+        overwritten.apply(full);
+        assert_eq!(toml::to_string(&overwritten).unwrap(), toml::to_string(&defaults).unwrap());
+        assert!(toml::from_str::<PartialConfig>("[agents]\nmax_concurent = 2").is_err());
     }
-}
\ No newline at end of file
+}
+
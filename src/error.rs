@@ -1,7 +1,11 @@
+use std::time::Duration;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+/// Pause after a rate limit that came without a usable `Retry-After`
+pub const DEFAULT_RATE_LIMIT_PAUSE: Duration = Duration::from_secs(60);
+
 #[derive(Error, Debug)]
 pub enum ScrapingError {
     #[error("Network error: {0}")]
@@ -10,6 +14,9 @@ pub enum ScrapingError {
     #[error("Browser error: {0}")]
     BrowserError(String),
 
+    #[error("Browser binary not found: {0}")]
+    BrowserNotFound(String),
+
     #[error("Parse error: {0}")]
     ParseError(String),
 
@@ -27,6 +34,9 @@ pub enum ScrapingError {
 
     #[error("TUI error: {0}")]
     TUIError(String),
+
+    #[error("Rate limited by Twitch{}", .retry_after.map(|d| format!(", retry after {}s", d.as_secs())).unwrap_or_default())]
+    RateLimited { retry_after: Option<Duration> },
 }
 
 #[derive(Debug)]
@@ -37,6 +47,7 @@ pub enum RecoveryStrategy {
     SwitchStorage,
     ReloadConfig,
     StopAgent,
+    PauseStreamer, // back off for the rate limit's retry-after before trying again
 }
 
 impl ScrapingError {
@@ -44,16 +55,50 @@ impl ScrapingError {
         match self {
             ScrapingError::NetworkError(_) => RecoveryStrategy::RetryWithBackoff,
             ScrapingError::BrowserError(_) => RecoveryStrategy::RestartBrowser,
+            ScrapingError::BrowserNotFound(_) => RecoveryStrategy::StopAgent, // restarting won't install chrome
             ScrapingError::ParseError(_) => RecoveryStrategy::LogAndContinue,
             ScrapingError::StorageError(_) => RecoveryStrategy::SwitchStorage,
             ScrapingError::ConfigError(_) => RecoveryStrategy::ReloadConfig,
             ScrapingError::ResourceLimit(_) => RecoveryStrategy::StopAgent,
             ScrapingError::AgentError(_) => RecoveryStrategy::RestartBrowser,
             ScrapingError::TUIError(_) => RecoveryStrategy::LogAndContinue,
+            ScrapingError::RateLimited { .. } => RecoveryStrategy::PauseStreamer,
+        }
+    }
+
+    /// `RateLimited` for a 429 response, honouring its `Retry-After` header
+    pub fn from_rate_limited_response(response: &reqwest::Response) -> Option<Self> {
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        Some(ScrapingError::RateLimited { retry_after })
+    }
+
+    /// How long to wait if this is a rate limit
+    pub fn rate_limit_pause(error: &(dyn std::error::Error + Send + Sync + 'static)) -> Option<Duration> {
+        match error.downcast_ref::<ScrapingError>() {
+            Some(ScrapingError::RateLimited { retry_after }) => Some(retry_after.unwrap_or(DEFAULT_RATE_LIMIT_PAUSE)),
+            _ => None,
         }
     }
 }
 
+/// Parse a `Retry-After` value, either delay seconds or an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let retry_at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = retry_at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
 // Conversion implementations for common error types
 impl From<std::io::Error> for ScrapingError {
     fn from(err: std::io::Error) -> Self {
@@ -83,4 +128,31 @@ impl From<chromiumoxide::error::CdpError> for ScrapingError {
     fn from(err: chromiumoxide::error::CdpError) -> Self {
         ScrapingError::BrowserError(err.to_string())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+
+        // dates in the past mean retry now
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let wait = parse_retry_after(&later).unwrap();
+        assert!(wait > Duration::from_secs(80) && wait <= Duration::from_secs(90));
+
+        let error: Box<dyn std::error::Error + Send + Sync> =
+            ScrapingError::RateLimited { retry_after: None }.into();
+        assert_eq!(ScrapingError::rate_limit_pause(error.as_ref()), Some(DEFAULT_RATE_LIMIT_PAUSE));
+        assert_eq!(error.to_string(), "Rate limited by Twitch");
+        assert_eq!(
+            ScrapingError::RateLimited { retry_after: Some(Duration::from_secs(30)) }.to_string(),
+            "Rate limited by Twitch, retry after 30s"
+        );
+    }
+}
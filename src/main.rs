@@ -1,15 +1,31 @@
 
 use std::sync::Arc;
-use twitch_chat_scraper::config::{ConfigManager, FileConfigManager};
-use twitch_chat_scraper::tui::{Dashboard, TUIMonitor};
+use tokio::sync::RwLock;
+use twitch_chat_scraper::agents::AgentOrchestrator;
+use twitch_chat_scraper::browser::{BrowserManager, StealthConfig};
+use twitch_chat_scraper::config::{Config, ConfigManager, FileConfigManager};
+use twitch_chat_scraper::storage::storage_from_config;
+use twitch_chat_scraper::tui::{run_tui, Dashboard, LogEntry, LogLevel, RenderTicker, TUIMonitor};
 use twitch_chat_scraper::scraper::SimpleTwitchScraper;
+use twitch_chat_scraper::webhooks::WebhookManager;
 use std::path::PathBuf;
 
 #[tokio::main]
 async fn main() -> twitch_chat_scraper::error::Result<()> {
     tracing_subscriber::fmt::init();
 
-    let config_manager = Arc::new(FileConfigManager::new(PathBuf::from("config.toml")));
+    // offline conversion mode, no scraping
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--export") {
+        return run_export(&args[2..]);
+    }
+
+    // `--config path.{toml,yaml,json}`, syntax follows the extension
+    let config_path = args.iter()
+        .position(|arg| arg == "--config")
+        .and_then(|index| args.get(index + 1))
+        .map_or_else(|| PathBuf::from("config.toml"), PathBuf::from);
+    let config_manager = Arc::new(FileConfigManager::new(config_path));
     let config = config_manager.load_config().await?;
     let config_arc = Arc::new(config);
 
@@ -22,35 +38,144 @@ async fn main() -> twitch_chat_scraper::error::Result<()> {
         tracing::info!("Created output directory: {}", config_arc.output.directory.display());
     }
     
+    // browser agents feed the dashboard live, without a browser the HTTP scraper runs instead
+    let stealth_config = StealthConfig::from(&config_arc.stealth);
+    match BrowserManager::with_binary_path(
+        config_arc.agents.max_concurrent,
+        stealth_config,
+        config_arc.browser.binary_path.clone(),
+    ).await {
+        Ok(browser_manager) => {
+            let browser_manager = browser_manager.with_proxy_list(config_arc.agents.proxy_list.clone().unwrap_or_default());
+            run_with_orchestrator(config_arc, config_manager, Arc::new(browser_manager)).await?
+        }
+        Err(e) => {
+            tracing::warn!("No browser available ({}), falling back to the HTTP scraper", e);
+            run_simple_scraper(config_arc).await;
+        }
+    }
+
+    tracing::info!("Twitch Chat Scraper stopped.");
+    Ok(())
+}
+
+/// Agents, storage and webhooks under the orchestrator, with the TUI showing its live state
+async fn run_with_orchestrator(
+    config: Arc<Config>,
+    config_manager: Arc<FileConfigManager>,
+    browser_manager: Arc<BrowserManager>,
+) -> twitch_chat_scraper::error::Result<()> {
+    let mut orchestrator = AgentOrchestrator::new((*config).clone(), browser_manager)
+        .with_storage_manager(storage_from_config(&config.output)?);
+
+    // forwarding chat, and lifecycle pings when enabled, are handled by the orchestrator
+    match WebhookManager::from_config(&config.monitoring) {
+        Ok(webhooks) if webhooks.has_providers() => {
+            orchestrator = orchestrator.with_webhook_manager(Arc::new(webhooks));
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Webhooks disabled: {}", e),
+    }
+
+    orchestrator.start(config_manager.clone()).await?;
+
+    // the tui stops the orchestrator when it exits
+    let orchestrator = Arc::new(RwLock::new(orchestrator));
+    let recovery_task = AgentOrchestrator::drive_recovery(&orchestrator);
+    let scaling_task = AgentOrchestrator::drive_scaling(&orchestrator);
+    let config_reload_task = AgentOrchestrator::drive_config_reloads(&orchestrator);
+    if let Err(e) = run_tui(orchestrator, config, config_manager).await {
+        eprintln!("TUI error: {}", e);
+    }
+    recovery_task.abort();
+    scaling_task.abort();
+    config_reload_task.abort();
+    Ok(())
+}
+
+/// The HTTP scraper in the background behind a TUI without agent metrics
+async fn run_simple_scraper(config_arc: Arc<Config>) {
+    // lifecycle pings go to the webhooks only when asked for, dev restarts stay quiet
+    let lifecycle_webhooks = if config_arc.monitoring.lifecycle_notifications {
+        match WebhookManager::from_config(&config_arc.monitoring) {
+            Ok(manager) => Some(Arc::new(manager)),
+            Err(e) => {
+                tracing::warn!("Lifecycle notifications disabled: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if let Some(ref webhooks) = lifecycle_webhooks {
+        let message = format!("Scraper started monitoring {} channels", config_arc.streamers.len());
+        let _ = webhooks.send_alert("info", "Scraper started", &message).await;
+    }
+
     // starting scraper in background
     let scraper_config = config_arc.clone();
+    let scraper_webhooks = lifecycle_webhooks.clone();
     tokio::spawn(async move {
-        let scraper = SimpleTwitchScraper::new(
-            scraper_config.output.directory.clone(),
-            scraper_config.streamers.clone()
-        );
+        let scraper = match SimpleTwitchScraper::from_config(&scraper_config) {
+            Ok(scraper) => scraper,
+            Err(e) => {
+                tracing::error!("Failed to set up scraper: {}", e);
+                return;
+            }
+        };
         
         if let Err(e) = scraper.start_scraping().await {
             tracing::error!("Scraper error: {}", e);
+            if let Some(webhooks) = scraper_webhooks {
+                let _ = webhooks.send_alert("critical", "Scraper crashed", &e.to_string()).await;
+            }
         }
     });
     
     // running the tui
     let config_for_tui = config_arc.clone();
-    let config_manager_for_tui = config_manager.clone();
     if let Err(e) = run_tui_without_orchestrator(config_for_tui).await {
         eprintln!("TUI error: {}", e);
     }
 
-    tracing::info!("Twitch Chat Scraper stopped.");
+    if let Some(ref webhooks) = lifecycle_webhooks {
+        let _ = webhooks.send_alert("info", "Shutting down", "Twitch Chat Scraper is shutting down").await;
+    }
+}
+
+/// `--export <input_dir> <output_dir> <json|csv|tsv|avro> [--columns a,b,c]`
+fn run_export(args: &[String]) -> twitch_chat_scraper::error::Result<()> {
+    use twitch_chat_scraper::error::ScrapingError;
+
+    let usage = || ScrapingError::ConfigError(
+        "usage: twitch-chat-scraper --export <input_dir> <output_dir> <json|csv|tsv|avro> [--columns a,b,c]".to_string()
+    );
+    let [input_dir, output_dir, format, rest @ ..] = args else {
+        return Err(usage().into());
+    };
+    let columns = match rest {
+        [] => None,
+        [flag, columns] if flag == "--columns" => Some(columns.split(',').map(|c| c.trim().to_string()).collect()),
+        _ => return Err(usage().into()),
+    };
+
+    let stats = twitch_chat_scraper::storage::convert(
+        &PathBuf::from(input_dir),
+        &PathBuf::from(output_dir),
+        format,
+        columns,
+    )?;
+    println!(
+        "Converted {} files ({} messages), skipped {}",
+        stats.files_converted, stats.messages, stats.files_skipped
+    );
     Ok(())
 }
 
-async fn run_tui_without_orchestrator(config: Arc<twitch_chat_scraper::config::Config>) -> Result<(), Box<dyn std::error::Error>> {
+async fn run_tui_without_orchestrator(config: Arc<Config>) -> Result<(), Box<dyn std::error::Error>> {
     use crossterm::{event, terminal, execute};
     use ratatui::prelude::{CrosstermBackend, Terminal};
     use std::io;
-    use std::time::Duration;
 
     tracing::info!("Initializing TUI...");
 
@@ -60,57 +185,34 @@ async fn run_tui_without_orchestrator(config: Arc<twitch_chat_scraper::config::C
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut dashboard = Dashboard::new();
+    let mut dashboard = Dashboard::new().with_custom_theme(config.monitoring.custom_css.clone());
     dashboard.set_config((*config).clone());
     
     // adding initial logs
-    dashboard.add_log(twitch_chat_scraper::tui::LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: twitch_chat_scraper::tui::LogLevel::Info,
-        message: "TUI started successfully! Press 'q' to quit".to_string(),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, "TUI started successfully! Press 'q' to quit", None));
     
-    dashboard.add_log(twitch_chat_scraper::tui::LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: twitch_chat_scraper::tui::LogLevel::Info,
-        message: "Simple HTTP scraper started for all streamers".to_string(),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, "Simple HTTP scraper started for all streamers", None));
 
-    dashboard.add_log(twitch_chat_scraper::tui::LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: twitch_chat_scraper::tui::LogLevel::Info,
-        message: format!("Scraping {} streamers: {}", config.streamers.len(), config.streamers.join(", ")),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, format!("Scraping {} streamers: {}", config.streamers.len(), config.streamers.join(", ")), None));
 
-    dashboard.add_log(twitch_chat_scraper::tui::LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: twitch_chat_scraper::tui::LogLevel::Info,
-        message: format!("Output directory: {}", config.output.directory.display()),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, format!("Output directory: {}", config.output.directory.display()), None));
 
     tracing::info!("TUI initialized, entering main loop");
 
     let mut should_quit = false;
     let start_time = std::time::Instant::now();
+    let mut ticker = RenderTicker::new(config.monitoring.tui_fps);
 
     while !should_quit {
-        // handling input with timeout
-        if event::poll(Duration::from_millis(100))? {
+        // handling input, polled several times per frame so keys feel immediate
+        if event::poll(ticker.poll_timeout())? {
             let input_event = event::read()?;
+            ticker.mark_dirty();
             
             // handling ctrl+c manually
             if let event::Event::Key(key) = input_event {
                 if key.code == event::KeyCode::Char('c') && key.modifiers.contains(event::KeyModifiers::CONTROL) {
-                    dashboard.add_log(twitch_chat_scraper::tui::LogEntry {
-                        timestamp: chrono::Utc::now(),
-                        level: twitch_chat_scraper::tui::LogLevel::Info,
-                        message: "Received Ctrl+C, shutting down...".to_string(),
-                        agent_id: None,
-                    });
+                    dashboard.add_log(LogEntry::new(LogLevel::Info, "Received Ctrl+C, shutting down...", None));
                     should_quit = true;
                     continue;
                 }
@@ -118,18 +220,20 @@ async fn run_tui_without_orchestrator(config: Arc<twitch_chat_scraper::config::C
             
             match dashboard.handle_input(input_event)? {
                 twitch_chat_scraper::tui::Action::Quit => {
-                    dashboard.add_log(twitch_chat_scraper::tui::LogEntry {
-                        timestamp: chrono::Utc::now(),
-                        level: twitch_chat_scraper::tui::LogLevel::Info,
-                        message: "Quit requested, shutting down...".to_string(),
-                        agent_id: None,
-                    });
+                    dashboard.add_log(LogEntry::new(LogLevel::Info, "Quit requested, shutting down...", None));
                     should_quit = true;
                 }
+                twitch_chat_scraper::tui::Action::Redraw => ticker.force_draw(),
+                // there are no agents to control without the orchestrator
                 _ => {}
             }
         }
 
+        let now = std::time::Instant::now();
+        if !ticker.should_draw(now) {
+            continue;
+        }
+
         // updating dashboard data
         let system_metrics = twitch_chat_scraper::tui::SystemMetrics {
             active_agents: 0,
@@ -149,9 +253,7 @@ async fn run_tui_without_orchestrator(config: Arc<twitch_chat_scraper::config::C
                 tracing::error!("Render error: {}", e);
             }
         })?;
-
-        // small delay to save cpu
-        tokio::time::sleep(Duration::from_millis(50)).await;
+        ticker.drawn(now);
     }
 
     tracing::info!("Cleaning up TUI...");
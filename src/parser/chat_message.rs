@@ -9,6 +9,19 @@ pub struct MessageFragment {
     #[serde(rename = "type")]
     pub fragment_type: String,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>, // "bttv", "ffz" or "7tv" for third-party emotes
+}
+
+impl MessageFragment {
+    /// A `"text"`, `"emote"` or `"cheermote"` fragment from Twitch itself, no third-party provider
+    pub fn new(fragment_type: &str, content: impl Into<String>) -> Self {
+        Self {
+            fragment_type: fragment_type.to_string(),
+            content: content.into(),
+            provider: None,
+        }
+    }
 }
 
 /// User info pulled from the chat message
@@ -30,12 +43,55 @@ pub struct MessageContent {
 
 /// Context about the stream
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Default)]
 pub struct StreamContext {
     pub viewer_count: Option<u32>,
     pub game_category: Option<String>,
     pub stream_title: Option<String>,
 }
 
+/// What kind of chat line a message came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    #[default]
+    Chat,
+    Sub,
+    Resub,
+    SubGift,
+    Raid,
+    FirstMessage,
+    Announcement,
+}
+
+impl MessageType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageType::Chat => "chat",
+            MessageType::Sub => "sub",
+            MessageType::Resub => "resub",
+            MessageType::SubGift => "sub_gift",
+            MessageType::Raid => "raid",
+            MessageType::FirstMessage => "first_message",
+            MessageType::Announcement => "announcement",
+        }
+    }
+
+    /// Inverse of `as_str`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "chat" => Some(MessageType::Chat),
+            "sub" => Some(MessageType::Sub),
+            "resub" => Some(MessageType::Resub),
+            "sub_gift" => Some(MessageType::SubGift),
+            "raid" => Some(MessageType::Raid),
+            "first_message" => Some(MessageType::FirstMessage),
+            "announcement" => Some(MessageType::Announcement),
+            _ => None,
+        }
+    }
+}
+
 /// Full chat message setup for LLM training
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ChatMessage {
@@ -45,6 +101,54 @@ pub struct ChatMessage {
     pub user: ChatUser,
     pub message: MessageContent,
     pub context: StreamContext,
+    #[serde(default)]
+    pub message_type: MessageType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bits: Option<u64>, // total cheered, None for messages without a cheermote
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mentions: Vec<String>, // normalized usernames @-mentioned in the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>, // normalized username of the parent message's author
+}
+
+/// Cheermote names Twitch chat accepts, matched case-insensitively before the amount
+const CHEERMOTE_PREFIXES: &[&str] = &[
+    "cheer", "doodlecheer", "biblethump", "cheerwhal", "corgo", "uni", "showlove", "party",
+    "seemsgood", "pride", "kappa", "frankerz", "heyguys", "dansgame", "elegiggle", "trihard",
+    "kreygasm", "4head", "swiftrage", "notlikethis", "failfish", "vohiyo", "pjsalt",
+    "mrdestructoid", "bday", "ripcheer", "shamrock", "bitboss", "streamlabs", "muxy",
+    "holidaycheer", "goal", "anon", "charity",
+];
+
+/// Bits in a single word like `Cheer100` or `uni500`, None if it isn't a cheermote
+pub fn cheer_amount(word: &str) -> Option<u64> {
+    let digits_at = word.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, amount) = word.split_at(digits_at);
+    if !amount.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if !CHEERMOTE_PREFIXES.iter().any(|cheermote| cheermote.eq_ignore_ascii_case(prefix)) {
+        return None;
+    }
+    amount.parse().ok().filter(|bits| *bits > 0)
+}
+
+/// Username in the form logins are compared in: no `@`, no surrounding whitespace, lowercase
+pub fn normalize_username(name: &str) -> String {
+    name.trim().trim_start_matches('@').to_lowercase()
+}
+
+/// The user an `@name` word points at, None for words that aren't mentions.
+/// Punctuation after the name (`@someone,`) isn't part of it.
+pub fn mentioned_username(word: &str) -> Option<String> {
+    let name = word.strip_prefix('@')?;
+    let end = name.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(name.len());
+    let name = &name[..end];
+    if name.is_empty() {
+        None
+    } else {
+        Some(normalize_username(name))
+    }
 }
 
 impl ChatMessage {
@@ -65,6 +169,54 @@ impl ChatMessage {
             user,
             message,
             context,
+            message_type: MessageType::Chat,
+            bits: None,
+            mentions: Vec::new(),
+            reply_to: None,
+        }
+    }
+
+    // collect the users @-mentioned in the typed text, once each in order of appearance
+    pub fn tag_mentions(&mut self) {
+        let typed: Vec<&str> = if self.message.fragments.is_empty() {
+            vec![self.message.text.as_str()]
+        } else {
+            self.message
+                .fragments
+                .iter()
+                .filter(|f| f.fragment_type == "text")
+                .map(|f| f.content.as_str())
+                .collect()
+        };
+
+        let mut mentions: Vec<String> = Vec::new();
+        for username in typed.iter().flat_map(|text| text.split_whitespace()).filter_map(mentioned_username) {
+            if !mentions.contains(&username) {
+                mentions.push(username);
+            }
+        }
+        self.mentions = mentions;
+    }
+
+    // tag the cheermotes chat rendered (they arrive as emote images) and total them up in `bits`,
+    // a `Cheer100` typed as plain text sends no bits so text fragments are left alone
+    pub fn tag_cheers(&mut self) {
+        let mut bits: Option<u64> = None;
+        for fragment in &mut self.message.fragments {
+            if fragment.fragment_type != "emote" && fragment.fragment_type != "cheermote" {
+                continue;
+            }
+            let Some(amount) = cheer_amount(&fragment.content) else {
+                continue;
+            };
+            if fragment.fragment_type == "emote" {
+                self.message.emotes.retain(|emote| *emote != fragment.content);
+                fragment.fragment_type = "cheermote".to_string();
+            }
+            bits = Some(bits.unwrap_or(0).saturating_add(amount));
+        }
+        if bits.is_some() {
+            self.bits = bits;
         }
     }
 
@@ -117,18 +269,40 @@ impl ChatMessage {
         
         false
     }
-}
 
-impl Default for StreamContext {
-    fn default() -> Self {
-        Self {
-            viewer_count: None,
-            game_category: None,
-            stream_title: None,
+    /// Message made up of nothing but emotes
+    pub fn is_emote_only(&self) -> bool {
+        if self.message.fragments.is_empty() {
+            return !self.message.emotes.is_empty()
+                && self.message.text.split_whitespace().all(|word| self.message.emotes.iter().any(|e| e == word));
         }
+        self.message.fragments.iter().all(|f| f.fragment_type == "emote")
+    }
+
+    /// Share of uppercase letters in the typed text, ignoring emote names.
+    /// None when there are no letters to judge.
+    pub fn caps_ratio(&self) -> Option<f64> {
+        let letters: Vec<char> = if self.message.fragments.is_empty() {
+            self.message.text.chars().filter(|c| c.is_alphabetic()).collect()
+        } else {
+            self.message
+                .fragments
+                .iter()
+                .filter(|f| f.fragment_type == "text")
+                .flat_map(|f| f.content.chars())
+                .filter(|c| c.is_alphabetic())
+                .collect()
+        };
+
+        if letters.is_empty() {
+            return None;
+        }
+        let caps = letters.iter().filter(|c| c.is_uppercase()).count();
+        Some(caps as f64 / letters.len() as f64)
     }
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,10 +320,7 @@ mod tests {
             MessageContent {
                 text: "Hello world!".to_string(),
                 emotes: vec![],
-                fragments: vec![MessageFragment {
-                    fragment_type: "text".to_string(),
-                    content: "Hello world!".to_string(),
-                }],
+                fragments: vec![MessageFragment::new("text", "Hello world!")],
             },
             StreamContext::default(),
         )
@@ -204,4 +375,131 @@ mod tests {
         let deserialized: ChatMessage = serde_json::from_str(&json).unwrap();
         assert_eq!(message, deserialized);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_emote_only_detection() {
+        let text_message = create_test_message();
+        assert!(!text_message.is_emote_only());
+
+        let mut emote_message = create_test_message();
+        emote_message.message.text = "Kappa PogChamp".to_string();
+        emote_message.message.emotes = vec!["Kappa".to_string(), "PogChamp".to_string()];
+        emote_message.message.fragments = vec![
+            MessageFragment::new("emote", "Kappa"),
+            MessageFragment::new("emote", "PogChamp"),
+        ];
+        assert!(emote_message.is_emote_only());
+    }
+
+    #[test]
+    fn test_single_cheer() {
+        let mut message = create_test_message();
+        message.message.text = "Cheer100 great stream".to_string();
+        message.message.emotes = vec!["Cheer100".to_string()];
+        message.message.fragments = vec![
+            MessageFragment::new("emote", "Cheer100"),
+            MessageFragment::new("text", "great stream"),
+        ];
+        message.tag_cheers();
+
+        assert_eq!(message.bits, Some(100));
+        assert_eq!(message.message.fragments, vec![
+            MessageFragment::new("cheermote", "Cheer100"),
+            MessageFragment::new("text", "great stream"),
+        ]);
+        assert!(message.message.emotes.is_empty());
+        assert_eq!(message.message.text, "Cheer100 great stream");
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["bits"], 100);
+    }
+
+    #[test]
+    fn test_multiple_cheers() {
+        let mut message = create_test_message();
+        message.message.text = "uni500 hype cheer1 Kappa10".to_string();
+        message.message.emotes = vec!["uni500".to_string(), "cheer1".to_string(), "Kappa10".to_string()];
+        message.message.fragments = vec![
+            MessageFragment::new("emote", "uni500"),
+            MessageFragment::new("text", "hype"),
+            MessageFragment::new("emote", "cheer1"),
+            MessageFragment::new("emote", "Kappa10"),
+        ];
+        message.tag_cheers();
+
+        assert_eq!(message.bits, Some(511));
+        let cheermotes: Vec<&str> = message.message.fragments
+            .iter()
+            .filter(|f| f.fragment_type == "cheermote")
+            .map(|f| f.content.as_str())
+            .collect();
+        assert_eq!(cheermotes, vec!["uni500", "cheer1", "Kappa10"]);
+        assert!(message.message.emotes.is_empty());
+
+        // tagging twice doesn't count anything again
+        message.tag_cheers();
+        assert_eq!(message.bits, Some(511));
+    }
+
+    #[test]
+    fn test_cheer_word_is_not_bits() {
+        // typed out as text a cheermote name is just a word, only the rendered image carries bits
+        for text in ["cheer up everyone", "Cheer", "cheer100abc", "Cheer0", "Kappa", "notacheer100", "Cheer100 great stream"] {
+            let mut message = create_test_message();
+            message.message.text = text.to_string();
+            message.message.fragments = vec![
+                MessageFragment::new("text", text),
+            ];
+            let before = message.clone();
+            message.tag_cheers();
+            assert_eq!(message.bits, None, "{:?} is not a cheer", text);
+            assert_eq!(message, before);
+        }
+
+        let json = serde_json::to_value(create_test_message()).unwrap();
+        assert!(json.get("bits").is_none());
+    }
+
+    #[test]
+    fn test_caps_ratio() {
+        let mut message = create_test_message();
+        message.message.fragments = vec![
+            MessageFragment::new("text", "HEllo"),
+            MessageFragment::new("emote", "KAPPA"),
+        ];
+        assert_eq!(message.caps_ratio(), Some(0.4));
+
+        message.message.fragments = vec![
+            MessageFragment::new("text", "123 !!"),
+        ];
+        assert_eq!(message.caps_ratio(), None);
+    }
+
+    #[test]
+    fn test_multiple_mentions() {
+        let mut message = create_test_message();
+        message.message.fragments = vec![
+            MessageFragment::new("text", "@Alice and @bob_99, look"),
+            MessageFragment::new("emote", "Kappa"),
+            MessageFragment::new("text", "@ALICE again, email me@example.com"),
+        ];
+        message.tag_mentions();
+        assert_eq!(message.mentions, vec!["alice", "bob_99"]);
+
+        assert_eq!(normalize_username("  @SomeUser "), "someuser");
+        assert_eq!(mentioned_username("@someone!"), Some("someone".to_string()));
+        assert_eq!(mentioned_username("@"), None);
+    }
+
+    #[test]
+    fn test_no_mentions() {
+        let mut message = create_test_message();
+        message.tag_mentions();
+        assert!(message.mentions.is_empty());
+
+        // neither field shows up in the JSON when empty
+        let json = serde_json::to_value(&message).unwrap();
+        assert!(json.get("mentions").is_none());
+        assert!(json.get("reply_to").is_none());
+    }
+}
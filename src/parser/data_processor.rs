@@ -1,9 +1,193 @@
+use crate::config::RedactionConfig;
 use crate::error::ScrapingError;
-use crate::parser::{ChatMessage, QualityAlert, QualityMetricsTracker};
+use crate::parser::{ChatMessage, MessageFragment, QualityAlert, QualityMetricsTracker};
 use crate::parser::html_parser::TwitchChatParser;
-use std::collections::HashSet;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use tracing::{debug, warn, info};
 
+/// scrubs urls, emails, phone numbers etc. out of message text before it's stored
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    rules: Vec<Regex>,
+    placeholder: String,
+    keep_length: bool,
+}
+
+impl Redactor {
+    // make a redactor from raw regex rules
+    pub fn new(rules: &[String], placeholder: String, keep_length: bool) -> Result<Self, ScrapingError> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                Regex::new(rule)
+                    .map_err(|e| ScrapingError::ConfigError(format!("Invalid redaction rule '{}': {}", rule, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            rules,
+            placeholder,
+            keep_length,
+        })
+    }
+
+    // build from config, None when redaction is turned off
+    pub fn from_config(config: &RedactionConfig) -> Result<Option<Self>, ScrapingError> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        Self::new(&config.rules, config.placeholder.clone(), config.keep_length).map(Some)
+    }
+
+    // replace every rule match in a piece of text
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut redacted = text.to_string();
+        for rule in &self.rules {
+            redacted = rule
+                .replace_all(&redacted, |caps: &regex::Captures| {
+                    if self.keep_length {
+                        let length = caps[0].chars().count();
+                        // "[REDACTED]" becomes "[REDACTED:23]"
+                        match self.placeholder.strip_suffix(']') {
+                            Some(open) => format!("{}:{}]", open, length),
+                            None => format!("{}:{}", self.placeholder, length),
+                        }
+                    } else {
+                        self.placeholder.clone()
+                    }
+                })
+                .into_owned();
+        }
+        redacted
+    }
+
+    // redact a message in place, rebuilding text from its fragments
+    pub fn redact_message(&self, message: &mut ChatMessage) {
+        if message.message.fragments.is_empty() {
+            message.message.text = self.redact_text(&message.message.text);
+            return;
+        }
+
+        for fragment in message.message.fragments.iter_mut() {
+            if fragment.fragment_type == "text" {
+                fragment.content = self.redact_text(&fragment.content);
+            }
+        }
+
+        // same joining as the html parser so text and fragments stay in sync
+        message.message.text = message
+            .message
+            .fragments
+            .iter()
+            .map(|fragment| fragment.content.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ");
+    }
+}
+
+/// where a third-party emote comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EmoteProvider {
+    Bttv,
+    Ffz,
+    SevenTv,
+}
+
+impl EmoteProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmoteProvider::Bttv => "bttv",
+            EmoteProvider::Ffz => "ffz",
+            EmoteProvider::SevenTv => "7tv",
+        }
+    }
+}
+
+/// third-party emote codes usable in one channel, globals included
+#[derive(Debug, Clone, Default)]
+pub struct EmoteSet {
+    emotes: HashMap<String, EmoteProvider>,
+}
+
+impl EmoteSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // later inserts win, so add globals first and channel emotes after
+    pub fn insert(&mut self, code: String, provider: EmoteProvider) {
+        self.emotes.insert(code, provider);
+    }
+
+    pub fn provider(&self, code: &str) -> Option<EmoteProvider> {
+        self.emotes.get(code).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.emotes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.emotes.is_empty()
+    }
+
+    // split text fragments on whitespace and turn known emote codes into emote fragments,
+    // the message text itself is left alone
+    pub fn enrich_message(&self, message: &mut ChatMessage) {
+        let has_emote = |text: &str| text.split_whitespace().any(|word| self.emotes.contains_key(word));
+        let matched = if message.message.fragments.is_empty() {
+            has_emote(&message.message.text)
+        } else {
+            message.message.fragments.iter().any(|f| f.fragment_type == "text" && has_emote(&f.content))
+        };
+        if !matched {
+            return;
+        }
+
+        let fragments = if message.message.fragments.is_empty() {
+            vec![MessageFragment::new("text", message.message.text.clone())]
+        } else {
+            std::mem::take(&mut message.message.fragments)
+        };
+
+        let mut enriched = Vec::with_capacity(fragments.len());
+        for fragment in fragments {
+            if fragment.fragment_type != "text" {
+                enriched.push(fragment);
+                continue;
+            }
+
+            let mut words: Vec<&str> = Vec::new();
+            for word in fragment.content.split_whitespace() {
+                let Some(provider) = self.provider(word) else {
+                    words.push(word);
+                    continue;
+                };
+                if !words.is_empty() {
+                    enriched.push(MessageFragment::new("text", words.join(" ")));
+                    words.clear();
+                }
+                enriched.push(MessageFragment {
+                    fragment_type: "emote".to_string(),
+                    content: word.to_string(),
+                    provider: Some(provider.as_str().to_string()),
+                });
+                message.message.emotes.push(word.to_string());
+            }
+            if !words.is_empty() {
+                enriched.push(MessageFragment::new("text", words.join(" ")));
+            }
+        }
+
+        message.message.fragments = enriched;
+    }
+}
+
 /// processor for checking, filtering, and removing duplicate chat messages
 pub struct DataProcessor {
     parser: TwitchChatParser,
@@ -13,19 +197,21 @@ pub struct DataProcessor {
     filter_spam: bool,
     filter_bots: bool,
     quality_tracker: QualityMetricsTracker,
+    redactor: Option<Redactor>,
 }
 
 impl DataProcessor {
     /// Create a new data processor with default settings
     pub fn new() -> Result<Self, ScrapingError> {
         Ok(Self {
-            parser: TwitchChatParser::new()?,
+            parser: TwitchChatParser::new(None)?,
             seen_hashes: HashSet::new(),
             min_message_length: 1,
             max_message_length: 500,
             filter_spam: true,
             filter_bots: true,
             quality_tracker: QualityMetricsTracker::new(),
+            redactor: None,
         })
     }
 
@@ -37,16 +223,23 @@ impl DataProcessor {
         filter_bots: bool,
     ) -> Result<Self, ScrapingError> {
         Ok(Self {
-            parser: TwitchChatParser::new()?,
+            parser: TwitchChatParser::new(None)?,
             seen_hashes: HashSet::new(),
             min_message_length: min_length,
             max_message_length: max_length,
             filter_spam,
             filter_bots,
             quality_tracker: QualityMetricsTracker::new(),
+            redactor: None,
         })
     }
 
+    // scrub messages with this redactor before handing them back
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
     // pull chat messages from html
     pub fn parse_chat_html(&self, html: &str, streamer: &str) -> Result<Vec<ChatMessage>, ScrapingError> {
         self.parser.parse_chat_html(html, streamer)
@@ -158,9 +351,16 @@ impl DataProcessor {
 
         // Count duplicates before deduplication
         let pre_dedup_count = valid_messages.len();
-        let final_messages = self.deduplicate(valid_messages);
+        let mut final_messages = self.deduplicate(valid_messages);
         let duplicates_filtered = pre_dedup_count - final_messages.len();
 
+        // Redact after dedup so hashes are taken from the original text
+        if let Some(ref redactor) = self.redactor {
+            for message in final_messages.iter_mut() {
+                redactor.redact_message(message);
+            }
+        }
+
         // Record quality metrics
         self.quality_tracker.record_batch_processed(
             &streamer,
@@ -290,10 +490,7 @@ mod tests {
             MessageContent {
                 text: text.to_string(),
                 emotes: vec![],
-                fragments: vec![MessageFragment {
-                    fragment_type: "text".to_string(),
-                    content: text.to_string(),
-                }],
+                fragments: vec![MessageFragment::new("text", text)],
             },
             StreamContext::default(),
         )
@@ -455,4 +652,96 @@ mod tests {
         processor.reset_quality_metrics();
         assert_eq!(processor.get_quality_metrics().total_processed, 0);
     }
-}
\ No newline at end of file
+
+    fn test_redactor() -> Redactor {
+        Redactor::from_config(&RedactionConfig {
+            enabled: true,
+            ..RedactionConfig::default()
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_redactor_disabled_by_default() {
+        assert!(Redactor::from_config(&RedactionConfig::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_redact_urls_emails_phones() {
+        let redactor = test_redactor();
+
+        assert_eq!(redactor.redact_text("check https://example.com/clip now"), "check [REDACTED] now");
+        assert_eq!(redactor.redact_text("mail me at someone@example.com"), "mail me at [REDACTED]");
+        assert_eq!(redactor.redact_text("call +1 (555) 123-4567 pls"), "call [REDACTED] pls");
+        assert_eq!(redactor.redact_text("gg wp"), "gg wp");
+    }
+
+    #[test]
+    fn test_redact_keep_length() {
+        let redactor = Redactor::new(&[r"\S+@\S+".to_string()], "[REDACTED]".to_string(), true).unwrap();
+        assert_eq!(redactor.redact_text("hi a@b.io"), "hi [REDACTED:6]");
+    }
+
+    #[test]
+    fn test_redact_message_rebuilds_fragments() {
+        let redactor = test_redactor();
+        let mut message = create_test_message("testuser", "go to www.example.com");
+        message.message.fragments.push(MessageFragment::new("emote", "Kappa"));
+
+        redactor.redact_message(&mut message);
+
+        assert_eq!(message.message.fragments[0].content, "go to [REDACTED]");
+        assert_eq!(message.message.fragments[1].content, "Kappa");
+        assert_eq!(message.message.text, "go to [REDACTED] Kappa");
+    }
+
+    #[test]
+    fn test_processor_applies_redactor() {
+        let mut processor = DataProcessor::with_settings(1, 500, false, false)
+            .unwrap()
+            .with_redactor(test_redactor());
+
+        let messages = vec![create_test_message("viewer", "my email is viewer@example.com")];
+        let processed = processor.apply_filters(messages);
+
+        assert_eq!(processed.len(), 1);
+        assert_eq!(processed[0].message.text, "my email is [REDACTED]");
+    }
+
+    #[test]
+    fn test_third_party_emote_enrichment() {
+        let mut emotes = EmoteSet::new();
+        emotes.insert("catJAM".to_string(), EmoteProvider::Bttv);
+        emotes.insert("OMEGALUL".to_string(), EmoteProvider::Ffz);
+
+        let mut message = create_test_message("user1", "that was catJAM so good OMEGALUL");
+        emotes.enrich_message(&mut message);
+
+        let fragments: Vec<(&str, &str, Option<&str>)> = message.message.fragments
+            .iter()
+            .map(|f| (f.fragment_type.as_str(), f.content.as_str(), f.provider.as_deref()))
+            .collect();
+        assert_eq!(fragments, vec![
+            ("text", "that was", None),
+            ("emote", "catJAM", Some("bttv")),
+            ("text", "so good", None),
+            ("emote", "OMEGALUL", Some("ffz")),
+        ]);
+        assert_eq!(message.message.emotes, vec!["catJAM", "OMEGALUL"]);
+        assert_eq!(message.message.text, "that was catJAM so good OMEGALUL");
+
+        // native emote fragments are kept, and lookalike words don't match
+        let mut message = create_test_message("user1", "catjam");
+        message.message.fragments.push(MessageFragment::new("emote", "Kappa"));
+        let before = message.clone();
+        emotes.enrich_message(&mut message);
+        assert_eq!(message, before);
+
+        // messages without fragments get them built from the text
+        let mut message = create_test_message("user1", "catJAM");
+        message.message.fragments.clear();
+        emotes.enrich_message(&mut message);
+        assert!(message.is_emote_only());
+    }
+}
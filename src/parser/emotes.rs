@@ -0,0 +1,202 @@
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::error::{Result, ScrapingError};
+use crate::parser::data_processor::{EmoteProvider, EmoteSet};
+
+const FFZ_API: &str = "https://api.frankerfacez.com/v1";
+const BTTV_API: &str = "https://api.betterttv.net/3/cached";
+const SEVEN_TV_API: &str = "https://7tv.io/v3";
+
+/// Per-channel BTTV/FFZ/7TV emote lists, fetched on first use and reused until the ttl runs out
+pub struct EmoteCache {
+    client: Client,
+    ttl: Duration,
+    globals: RwLock<Option<(Instant, EmoteSet)>>,
+    channels: RwLock<HashMap<String, (Instant, Arc<EmoteSet>)>>,
+}
+
+impl EmoteCache {
+    pub fn new(ttl: Duration) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            ttl,
+            globals: RwLock::new(None),
+            channels: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Emotes usable in `streamer`'s chat. Providers that can't be reached are
+    /// skipped, so this never fails, it just knows fewer emotes.
+    pub async fn get(&self, streamer: &str) -> Arc<EmoteSet> {
+        let key = streamer.to_lowercase();
+        if let Some((fetched_at, emotes)) = self.channels.read().await.get(&key) {
+            if fetched_at.elapsed() < self.ttl {
+                return emotes.clone();
+            }
+        }
+
+        let mut emotes = self.global_emotes().await;
+        self.add_channel_emotes(&key, &mut emotes).await;
+        debug!("Loaded {} third-party emotes for {}", emotes.len(), key);
+
+        let emotes = Arc::new(emotes);
+        self.channels.write().await.insert(key, (Instant::now(), emotes.clone()));
+        emotes
+    }
+
+    async fn global_emotes(&self) -> EmoteSet {
+        if let Some((fetched_at, ref emotes)) = *self.globals.read().await {
+            if fetched_at.elapsed() < self.ttl {
+                return emotes.clone();
+            }
+        }
+
+        let mut emotes = EmoteSet::new();
+        let sources = [
+            (format!("{}/set/global", FFZ_API), EmoteProvider::Ffz),
+            (format!("{}/emotes/global", BTTV_API), EmoteProvider::Bttv),
+            (format!("{}/emote-sets/global", SEVEN_TV_API), EmoteProvider::SevenTv),
+        ];
+        for (url, provider) in sources {
+            match self.get_json(&url).await {
+                Ok(body) => {
+                    for code in parse_emote_codes(&body, provider) {
+                        emotes.insert(code, provider);
+                    }
+                }
+                Err(e) => warn!("Failed to fetch global {} emotes: {}", provider.as_str(), e),
+            }
+        }
+
+        *self.globals.write().await = Some((Instant::now(), emotes.clone()));
+        emotes
+    }
+
+    async fn add_channel_emotes(&self, streamer: &str, emotes: &mut EmoteSet) {
+        // ffz looks rooms up by login and hands back the twitch id the others need
+        let twitch_id = match self.get_json(&format!("{}/room/{}", FFZ_API, streamer)).await {
+            Ok(room) => {
+                for code in parse_emote_codes(&room, EmoteProvider::Ffz) {
+                    emotes.insert(code, EmoteProvider::Ffz);
+                }
+                room["room"]["twitch_id"].as_u64()
+            }
+            Err(e) => {
+                warn!("Failed to fetch ffz emotes for {}: {}", streamer, e);
+                None
+            }
+        };
+
+        let Some(twitch_id) = twitch_id else {
+            return;
+        };
+        let sources = [
+            (format!("{}/users/twitch/{}", BTTV_API, twitch_id), EmoteProvider::Bttv),
+            (format!("{}/users/twitch/{}", SEVEN_TV_API, twitch_id), EmoteProvider::SevenTv),
+        ];
+        for (url, provider) in sources {
+            match self.get_json(&url).await {
+                Ok(body) => {
+                    for code in parse_emote_codes(&body, provider) {
+                        emotes.insert(code, provider);
+                    }
+                }
+                Err(e) => debug!("No {} emotes for {}: {}", provider.as_str(), streamer, e),
+            }
+        }
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value> {
+        let response = self.client.get(url).send().await?;
+        if let Some(rate_limited) = ScrapingError::from_rate_limited_response(&response) {
+            return Err(rate_limited.into());
+        }
+        if !response.status().is_success() {
+            return Err(ScrapingError::NetworkError(format!("{} returned {}", url, response.status())).into());
+        }
+        Ok(response.json().await?)
+    }
+}
+
+/// Emote codes out of a provider's global or channel response
+pub fn parse_emote_codes(body: &Value, provider: EmoteProvider) -> Vec<String> {
+    let names = |emotes: &Value, field: &str| -> Vec<String> {
+        emotes
+            .as_array()
+            .map(|emotes| {
+                emotes
+                    .iter()
+                    .filter_map(|emote| emote[field].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    match provider {
+        // `{"sets": {"<id>": {"emoticons": [{"name": ..}]}}}`
+        EmoteProvider::Ffz => body["sets"]
+            .as_object()
+            .map(|sets| sets.values().flat_map(|set| names(&set["emoticons"], "name")).collect())
+            .unwrap_or_default(),
+        // globals are a bare array, channels split into channel and shared emotes
+        EmoteProvider::Bttv => {
+            if body.is_array() {
+                names(body, "code")
+            } else {
+                let mut codes = names(&body["channelEmotes"], "code");
+                codes.extend(names(&body["sharedEmotes"], "code"));
+                codes
+            }
+        }
+        // globals are an emote set, channels wrap theirs in `emote_set`
+        EmoteProvider::SevenTv => {
+            if body["emotes"].is_array() {
+                names(&body["emotes"], "name")
+            } else {
+                names(&body["emote_set"]["emotes"], "name")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_emote_codes() {
+        let ffz = json!({
+            "room": {"twitch_id": 123, "set": 7},
+            "sets": {"7": {"emoticons": [{"id": 1, "name": "monkaW"}, {"id": 2, "name": "OMEGALUL"}]}}
+        });
+        assert_eq!(parse_emote_codes(&ffz, EmoteProvider::Ffz), vec!["monkaW", "OMEGALUL"]);
+
+        let bttv_global = json!([{"id": "a", "code": "catJAM"}, {"id": "b", "code": "monkaS"}]);
+        assert_eq!(parse_emote_codes(&bttv_global, EmoteProvider::Bttv), vec!["catJAM", "monkaS"]);
+        let bttv_channel = json!({
+            "channelEmotes": [{"code": "shroudW"}],
+            "sharedEmotes": [{"code": "pepeD"}]
+        });
+        assert_eq!(parse_emote_codes(&bttv_channel, EmoteProvider::Bttv), vec!["shroudW", "pepeD"]);
+
+        let seven_global = json!({"id": "global", "emotes": [{"name": "EZ"}]});
+        assert_eq!(parse_emote_codes(&seven_global, EmoteProvider::SevenTv), vec!["EZ"]);
+        let seven_channel = json!({"emote_set": {"emotes": [{"name": "Clap"}, {"id": "no name"}]}});
+        assert_eq!(parse_emote_codes(&seven_channel, EmoteProvider::SevenTv), vec!["Clap"]);
+
+        assert!(parse_emote_codes(&json!({"error": "Not Found"}), EmoteProvider::Bttv).is_empty());
+    }
+}
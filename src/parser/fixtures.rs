@@ -0,0 +1,31 @@
+//! Chat messages for tests. Also pulled into tests/pipeline.rs with `#[path]`,
+//! so the parser types come from `super` rather than `crate`.
+
+use chrono::Utc;
+
+use super::{ChatMessage, ChatUser, MessageContent, MessageFragment, StreamContext};
+
+/// A message sent now by `user1` in `teststreamer`'s chat, with `text` as its
+/// one text fragment. Tests change whatever else they care about on the result.
+pub fn chat_message(text: &str) -> ChatMessage {
+    ChatMessage::new(
+        "teststreamer".to_string(),
+        Utc::now(),
+        ChatUser {
+            username: "user1".to_string(),
+            display_name: "User1".to_string(),
+            color: Some("#FF0000".to_string()),
+            badges: vec!["subscriber".to_string()],
+        },
+        MessageContent {
+            text: text.to_string(),
+            emotes: vec![],
+            fragments: vec![MessageFragment::new("text", text)],
+        },
+        StreamContext {
+            viewer_count: Some(1000),
+            game_category: Some("Just Chatting".to_string()),
+            stream_title: None,
+        },
+    )
+}
@@ -1,36 +1,50 @@
+use crate::config::SelectorConfig;
 use crate::error::ScrapingError;
-use crate::parser::{ChatMessage, ChatUser, MessageContent, MessageFragment, StreamContext};
+use crate::parser::chat_message::{mentioned_username, normalize_username};
+use crate::parser::{ChatMessage, ChatUser, MessageContent, MessageFragment, MessageType, StreamContext};
 use chrono::{DateTime, Utc};
 use scraper::{Html, Selector};
 use tracing::{debug, warn};
 
 /// html parser for pulling twitch chat messages
+#[derive(Clone)]
 pub struct TwitchChatParser {
     // CSS selectors for different parts of chat messages
     chat_line_selector: Selector,
     username_selector: Selector,
     display_name_selector: Selector,
     message_body_selector: Selector,
+    fragment_selector: Selector,
     badge_selector: Selector,
     timestamp_selector: Selector,
+    notice_user_selector: Selector,
+    reply_selector: Selector,
+    user_notice_selector: Selector,
+    emote_selector: Selector,
 }
 
 impl TwitchChatParser {
-    // set up a parser with css selectors ready
-    pub fn new() -> Result<Self, ScrapingError> {
+    // set up a parser with css selectors ready, the built-in ones unless overridden
+    pub fn new(selectors: Option<&SelectorConfig>) -> Result<Self, ScrapingError> {
+        let defaults = SelectorConfig::default();
+        let selectors = selectors.unwrap_or(&defaults);
+        let parse = |name: &str, selector: &str| {
+            Selector::parse(selector)
+                .map_err(|e| ScrapingError::ParseError(format!("Invalid {} selector: {}", name, e)))
+        };
+
         Ok(Self {
-            chat_line_selector: Selector::parse(".chat-line__no-background, .chat-line__message")
-                .map_err(|e| ScrapingError::ParseError(format!("Invalid chat line selector: {}", e)))?,
-            username_selector: Selector::parse("[data-a-target='chat-message-username']")
-                .map_err(|e| ScrapingError::ParseError(format!("Invalid username selector: {}", e)))?,
-            display_name_selector: Selector::parse(".chat-author__display-name")
-                .map_err(|e| ScrapingError::ParseError(format!("Invalid display name selector: {}", e)))?,
-            message_body_selector: Selector::parse("[data-a-target='chat-line-message-body']")
-                .map_err(|e| ScrapingError::ParseError(format!("Invalid message body selector: {}", e)))?,
-            badge_selector: Selector::parse(".chat-badge")
-                .map_err(|e| ScrapingError::ParseError(format!("Invalid badge selector: {}", e)))?,
-            timestamp_selector: Selector::parse(".chat-line__timestamp")
-                .map_err(|e| ScrapingError::ParseError(format!("Invalid timestamp selector: {}", e)))?,
+            chat_line_selector: parse("chat line", &selectors.chat_line)?,
+            username_selector: parse("username", &selectors.username)?,
+            display_name_selector: parse("display name", &selectors.display_name)?,
+            message_body_selector: parse("message body", &selectors.message_body)?,
+            fragment_selector: parse("fragment", &selectors.fragment)?,
+            badge_selector: parse("badge", &selectors.badge)?,
+            timestamp_selector: parse("timestamp", &selectors.timestamp)?,
+            notice_user_selector: parse("notice user", &selectors.notice_user)?,
+            reply_selector: parse("reply", &selectors.reply)?,
+            user_notice_selector: parse("user notice", &selectors.user_notice)?,
+            emote_selector: parse("emote", &selectors.emote)?,
         })
     }
 
@@ -40,7 +54,18 @@ impl TwitchChatParser {
         let mut messages = Vec::new();
 
         for chat_line in document.select(&self.chat_line_selector) {
-            match self.parse_single_message(&chat_line, streamer) {
+            // a notice carrying the user's own message is parsed through that nested chat line
+            let is_notice = self.user_notice_selector.matches(&chat_line);
+            if is_notice && chat_line.select(&self.chat_line_selector).next().is_some() {
+                continue;
+            }
+
+            let parsed = if is_notice {
+                self.parse_notice(&chat_line, streamer)
+            } else {
+                self.parse_single_message(&chat_line, streamer)
+            };
+            match parsed {
                 Ok(Some(message)) => {
                     if message.is_valid() {
                         messages.push(message);
@@ -89,17 +114,135 @@ impl TwitchChatParser {
         // Create stream context (basic for now)
         let context = StreamContext::default();
 
-        let message = ChatMessage::new(
+        let mut message = ChatMessage::new(
             streamer.to_string(),
             timestamp,
             user,
             message_content,
             context,
         );
+        message.tag_cheers();
+        message.tag_mentions();
+        message.reply_to = self.extract_reply_target(element);
+        message.message_type = self.classify_message(element);
 
         Ok(Some(message))
     }
 
+    // a sub, gift, raid or announcement notice the user didn't add a message to
+    fn parse_notice(
+        &self,
+        element: &scraper::ElementRef,
+        streamer: &str,
+    ) -> Result<Option<ChatMessage>, ScrapingError> {
+        let text = element.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+        let Some(user_element) = element.select(&self.notice_user_selector).next() else {
+            debug!("Notice without a user, skipping: {}", text);
+            return Ok(None);
+        };
+        let display_name = user_element.text().collect::<String>().trim().to_string();
+        let username = user_element
+            .value()
+            .attr("data-a-user")
+            .map(str::to_string)
+            .unwrap_or_else(|| display_name.to_lowercase());
+        if username.is_empty() || text.is_empty() {
+            return Ok(None);
+        }
+
+        let user = ChatUser {
+            username,
+            display_name,
+            color: None,
+            badges: self.extract_badges(element),
+        };
+        let content = MessageContent {
+            fragments: vec![MessageFragment::new("text", text.clone())],
+            emotes: vec![],
+            text,
+        };
+        let timestamp = self.extract_timestamp(element).unwrap_or_else(Utc::now);
+
+        let mut message = ChatMessage::new(streamer.to_string(), timestamp, user, content, StreamContext::default());
+        message.message_type = self.classify_message(element);
+        Ok(Some(message))
+    }
+
+    // the notice's own wording, without any message the user attached
+    fn notice_text(&self, notice: &scraper::ElementRef) -> String {
+        notice
+            .descendants()
+            .filter_map(|node| node.value().as_text().map(|text| (node, text)))
+            .filter(|(node, _)| {
+                !node
+                    .ancestors()
+                    .take_while(|ancestor| ancestor.id() != notice.id())
+                    .filter_map(scraper::ElementRef::wrap)
+                    .any(|ancestor| self.chat_line_selector.matches(&ancestor))
+            })
+            .map(|(_, text)| &**text)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // notices are told apart by their wording, first-timers by the highlight around their line
+    fn classify_message(&self, element: &scraper::ElementRef) -> MessageType {
+        let lineage = std::iter::once(*element).chain(element.ancestors().filter_map(scraper::ElementRef::wrap));
+
+        for node in lineage {
+            let classes: Vec<&str> = node.value().classes().collect();
+            if self.user_notice_selector.matches(&node) {
+                let text = self.notice_text(&node).to_lowercase();
+                return if text.contains("raiding") || text.contains("raiders") {
+                    MessageType::Raid
+                } else if text.contains("gifted") || text.contains("gifting") {
+                    MessageType::SubGift
+                } else if text.contains("subscribed") && (text.contains("months") || text.contains("streak")) {
+                    MessageType::Resub
+                } else if text.contains("subscribed") {
+                    MessageType::Sub
+                } else if text.contains("announcement") {
+                    MessageType::Announcement
+                } else {
+                    MessageType::Chat
+                };
+            }
+            if classes.iter().any(|c| c.contains("first-message") || c.contains("first-time-chatter"))
+                || node.value().attr("data-test-selector") == Some("chat-line-first-message")
+            {
+                return MessageType::FirstMessage;
+            }
+        }
+
+        // twitch also badges the first message itself
+        let first_time_badge = self
+            .extract_badges(element)
+            .iter()
+            .any(|badge| badge.to_lowercase().contains("first-time") || badge.to_lowercase().contains("first time"));
+        if first_time_badge {
+            MessageType::FirstMessage
+        } else {
+            MessageType::Chat
+        }
+    }
+
+    // who a threaded reply answers, from the "Replying to @user: ..." header above it
+    fn extract_reply_target(&self, element: &scraper::ElementRef) -> Option<String> {
+        let header = element.select(&self.reply_selector).next()?;
+        if let Some(user) = header.value().attr("data-a-user") {
+            return Some(normalize_username(user));
+        }
+
+        let text = header.text().collect::<String>();
+        header
+            .value()
+            .attr("title")
+            .into_iter()
+            .chain(std::iter::once(text.as_str()))
+            .flat_map(str::split_whitespace)
+            .find_map(mentioned_username)
+    }
+
     // grab user info from the chat message element
     fn extract_user_info(&self, element: &scraper::ElementRef) -> Result<Option<ChatUser>, ScrapingError> {
         // Try to find username element
@@ -169,19 +312,12 @@ impl TwitchChatParser {
         let mut emotes = Vec::new();
 
         // Collect all elements (text and emotes) in document order
-        let all_selector = Selector::parse("span.text-fragment, span[data-a-target='chat-message-text'], img").unwrap();
-        
-        for elem in message_body.select(&all_selector) {
-            if elem.value().name() == "img" && 
-               (elem.value().classes().any(|c| c == "chat-line__message--emote") ||
-                elem.value().classes().any(|c| c == "chat-image")) {
+        for elem in message_body.select(&self.fragment_selector) {
+            if self.emote_selector.matches(&elem) {
                 // It's an emote
                 if let Some(alt_text) = elem.value().attr("alt") {
                     emotes.push(alt_text.to_string());
-                    fragments.push(MessageFragment {
-                        fragment_type: "emote".to_string(),
-                        content: alt_text.to_string(),
-                    });
+                    fragments.push(MessageFragment::new("emote", alt_text));
                     text_parts.push(alt_text.to_string());
                 }
             } else if elem.value().name() == "span" {
@@ -189,10 +325,7 @@ impl TwitchChatParser {
                 let text = elem.text().collect::<String>().trim().to_string();
                 if !text.is_empty() {
                     text_parts.push(text.clone());
-                    fragments.push(MessageFragment {
-                        fragment_type: "text".to_string(),
-                        content: text,
-                    });
+                    fragments.push(MessageFragment::new("text", text));
                 }
             }
         }
@@ -202,10 +335,7 @@ impl TwitchChatParser {
             let text = message_body.text().collect::<String>().trim().to_string();
             if !text.is_empty() {
                 text_parts.push(text.clone());
-                fragments.push(MessageFragment {
-                    fragment_type: "text".to_string(),
-                    content: text,
-                });
+                fragments.push(MessageFragment::new("text", text));
             }
         }
 
@@ -290,7 +420,7 @@ impl TwitchChatParser {
 
 impl Default for TwitchChatParser {
     fn default() -> Self {
-        Self::new().expect("Failed to create default TwitchChatParser")
+        Self::new(None).expect("Failed to create default TwitchChatParser")
     }
 }
 
@@ -332,13 +462,13 @@ mod tests {
 
     #[test]
     fn test_parser_creation() {
-        let parser = TwitchChatParser::new();
+        let parser = TwitchChatParser::new(None);
         assert!(parser.is_ok());
     }
 
     #[test]
     fn test_parse_basic_message() {
-        let parser = TwitchChatParser::new().unwrap();
+        let parser = TwitchChatParser::new(None).unwrap();
         let messages = parser.parse_chat_html(MOCK_CHAT_HTML, "teststreamer").unwrap();
         
         assert_eq!(messages.len(), 1);
@@ -353,7 +483,7 @@ mod tests {
 
     #[test]
     fn test_parse_message_with_emote() {
-        let parser = TwitchChatParser::new().unwrap();
+        let parser = TwitchChatParser::new(None).unwrap();
         let messages = parser.parse_chat_html(MOCK_CHAT_WITH_EMOTE, "teststreamer").unwrap();
         
         assert_eq!(messages.len(), 1);
@@ -365,9 +495,183 @@ mod tests {
         assert_eq!(message.message.fragments.len(), 3);
     }
 
+    #[test]
+    fn test_parse_message_with_third_party_emote() {
+        use crate::parser::data_processor::{EmoteProvider, EmoteSet};
+
+        let parser = TwitchChatParser::new(None).unwrap();
+        let html = MOCK_CHAT_WITH_EMOTE.replace("Hello ", "Hello catJAM ");
+        let mut messages = parser.parse_chat_html(&html, "teststreamer").unwrap();
+        let message = &mut messages[0];
+
+        let mut emotes = EmoteSet::new();
+        emotes.insert("catJAM".to_string(), EmoteProvider::Bttv);
+        emotes.enrich_message(message);
+
+        let fragments: Vec<(&str, &str, Option<&str>)> = message.message.fragments
+            .iter()
+            .map(|f| (f.fragment_type.as_str(), f.content.as_str(), f.provider.as_deref()))
+            .collect();
+        assert_eq!(fragments, vec![
+            ("text", "Hello", None),
+            ("emote", "catJAM", Some("bttv")),
+            ("emote", "Kappa", None),
+            ("text", "world", None),
+        ]);
+        assert_eq!(message.message.emotes, vec!["Kappa", "catJAM"]);
+        assert_eq!(message.message.text, "Hello catJAM Kappa world");
+    }
+
+    #[test]
+    fn test_message_type_classification() {
+        let parser = TwitchChatParser::new(None).unwrap();
+        let notice = |body: &str| format!(
+            r#"<div class="user-notice-line">{}</div>"#,
+            body
+        );
+        let chat_line = |classes: &str, badges: &str, text: &str| format!(
+            r#"<div class="chat-line__message {}"><div>{}
+                <span data-a-target="chat-message-username" data-a-user="viewer">Viewer</span>
+                <span data-a-target="chat-line-message-body"><span class="text-fragment">{}</span></span>
+            </div></div>"#,
+            classes, badges, text
+        );
+
+        let cases = vec![
+            (chat_line("", "", "just chatting"), MessageType::Chat, "viewer"),
+            (
+                notice(r#"<span class="chatter-name"><span>SubGuy</span></span> subscribed at Tier 1."#),
+                MessageType::Sub,
+                "subguy",
+            ),
+            (
+                // the resub notice wraps the user's own message, which is what gets kept
+                notice(&format!(
+                    r#"<span class="chatter-name"><span>Viewer</span></span> subscribed with Prime. They've subscribed for 14 months!{}"#,
+                    chat_line("", "", "raiding the fridge brb")
+                )),
+                MessageType::Resub,
+                "viewer",
+            ),
+            (
+                notice(r#"<span class="chatter-name"><span>Santa</span></span> gifted a Tier 1 sub to Viewer!"#),
+                MessageType::SubGift,
+                "santa",
+            ),
+            (
+                notice(r#"<span class="chatter-name"><span>BigStreamer</span></span> is raiding with a party of 1,204."#),
+                MessageType::Raid,
+                "bigstreamer",
+            ),
+            (
+                notice(&format!("<p>Announcement</p>{}", chat_line("", "", "giveaway at 8pm"))),
+                MessageType::Announcement,
+                "viewer",
+            ),
+            (chat_line("chat-line__message--first-message", "", "hi first time here"), MessageType::FirstMessage, "viewer"),
+            (
+                chat_line("", r#"<img class="chat-badge" alt="First-Time Chatter">"#, "hello"),
+                MessageType::FirstMessage,
+                "viewer",
+            ),
+        ];
+
+        for (html, expected, username) in cases {
+            let messages = parser.parse_chat_html(&html, "teststreamer").unwrap();
+            assert_eq!(messages.len(), 1, "one message out of {}", html);
+            assert_eq!(messages[0].message_type, expected, "{}", html);
+            assert_eq!(messages[0].user.username, username);
+        }
+
+        let resub = parser.parse_chat_html(&notice(&format!(
+            r#"<span class="chatter-name"><span>Viewer</span></span> subscribed for 3 months.{}"#,
+            chat_line("", "", "raiding the fridge brb")
+        )), "teststreamer").unwrap();
+        assert_eq!(resub[0].message.text, "raiding the fridge brb");
+
+        let json = serde_json::to_value(&resub[0]).unwrap();
+        assert_eq!(json["message_type"], "resub");
+        let plain = parser.parse_chat_html(MOCK_CHAT_HTML, "teststreamer").unwrap();
+        assert_eq!(serde_json::to_value(&plain[0]).unwrap()["message_type"], "chat");
+    }
+
+    #[test]
+    fn test_parse_reply_line() {
+        let html = r#"
+        <div class="chat-line__message">
+            <div data-test-selector="chat-reply-header">
+                <p title="Replying to @OriginalPoster: who is winning?">Replying to @OriginalPoster: who is winning?</p>
+            </div>
+            <span data-a-target="chat-message-username" data-a-user="replier">Replier</span>
+            <span data-a-target="chat-line-message-body">
+                <span class="text-fragment">@OriginalPoster blue side, ask @mod_helper</span>
+            </span>
+        </div>
+        <div class="chat-line__message">
+            <span data-a-target="chat-message-username" data-a-user="plainuser">PlainUser</span>
+            <span data-a-target="chat-line-message-body">
+                <span class="text-fragment">no replies here</span>
+            </span>
+        </div>
+        "#;
+
+        let parser = TwitchChatParser::new(None).unwrap();
+        let messages = parser.parse_chat_html(html, "teststreamer").unwrap();
+        assert_eq!(messages.len(), 2);
+
+        assert_eq!(messages[0].reply_to.as_deref(), Some("originalposter"));
+        assert_eq!(messages[0].mentions, vec!["originalposter", "mod_helper"]);
+        assert_eq!(messages[0].message.text, "@OriginalPoster blue side, ask @mod_helper");
+
+        assert_eq!(messages[1].reply_to, None);
+        assert!(messages[1].mentions.is_empty());
+    }
+
+    #[test]
+    fn test_custom_selectors() {
+        // the same chat after a hypothetical redesign renamed every hook
+        let html = r#"
+        <li class="chat-row">
+            <time class="chat-row__time" datetime="2024-01-15T10:30:45Z"></time>
+            <img class="chat-row__badge" alt="Moderator">
+            <a class="chat-row__author" data-a-user="newlayout">NewLayout</a>
+            <p class="chat-row__body"><span class="chat-row__text">still works</span><img class="chat-row__emote" alt="Kappa"></p>
+        </li>
+        "#;
+        let selectors = SelectorConfig {
+            chat_line: ".chat-row".to_string(),
+            username: ".chat-row__author".to_string(),
+            display_name: ".chat-row__author".to_string(),
+            message_body: ".chat-row__body".to_string(),
+            fragment: "span.chat-row__text, img".to_string(),
+            badge: ".chat-row__badge".to_string(),
+            timestamp: ".chat-row__time".to_string(),
+            emote: "img.chat-row__emote".to_string(),
+            ..SelectorConfig::default()
+        };
+
+        // the built-in selectors find nothing
+        let default_parser = TwitchChatParser::new(None).unwrap();
+        assert!(default_parser.parse_chat_html(html, "teststreamer").unwrap().is_empty());
+
+        let parser = TwitchChatParser::new(Some(&selectors)).unwrap();
+        let messages = parser.parse_chat_html(html, "teststreamer").unwrap();
+        assert_eq!(messages.len(), 1);
+        let message = &messages[0];
+        assert_eq!(message.user.username, "newlayout");
+        assert_eq!(message.user.display_name, "NewLayout");
+        assert_eq!(message.user.badges, vec!["Moderator"]);
+        assert_eq!(message.message.text, "still works Kappa");
+        assert_eq!(message.message.emotes, vec!["Kappa"]);
+        assert_eq!(message.timestamp.to_rfc3339(), "2024-01-15T10:30:45+00:00");
+
+        let broken = SelectorConfig { chat_line: "div[".to_string(), ..SelectorConfig::default() };
+        assert!(TwitchChatParser::new(Some(&broken)).is_err());
+    }
+
     #[test]
     fn test_color_extraction() {
-        let parser = TwitchChatParser::new().unwrap();
+        let parser = TwitchChatParser::new(None).unwrap();
         
         // Test RGB to hex conversion
         assert_eq!(parser.rgb_to_hex("154, 205, 50").unwrap(), "#9ACD32");
@@ -386,17 +690,16 @@ mod tests {
 
     #[test]
     fn test_empty_html() {
-        let parser = TwitchChatParser::new().unwrap();
+        let parser = TwitchChatParser::new(None).unwrap();
         let messages = parser.parse_chat_html("", "teststreamer").unwrap();
         assert_eq!(messages.len(), 0);
     }
 
     #[test]
     fn test_malformed_html() {
-        let parser = TwitchChatParser::new().unwrap();
+        let parser = TwitchChatParser::new(None).unwrap();
         let malformed_html = "<div><span>incomplete";
-        let messages = parser.parse_chat_html(malformed_html, "teststreamer").unwrap();
         // Should not crash, may return empty or partial results
-        assert!(messages.len() >= 0);
+        assert!(parser.parse_chat_html(malformed_html, "teststreamer").is_ok());
     }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 pub mod chat_message;
 pub mod data_processor;
+pub mod emotes;
+#[cfg(test)]
+pub mod fixtures;
 pub mod html_parser;
 pub mod quality_metrics;
 
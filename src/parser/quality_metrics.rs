@@ -1,8 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use tracing::{debug, info};
 
+use crate::parser::chat_message::ChatMessage;
+
 /// Metrics for tracking how well data processing is going
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct QualityMetrics {
@@ -46,6 +48,279 @@ pub struct StreamerMetrics {
     pub last_message_time: Option<DateTime<Utc>>,
 }
 
+/// Emote and caps "vibe" signal for one streamer's chat over a rolling window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelQuality {
+    pub streamer_name: String,
+    /// Messages inside the current window
+    pub window_messages: u64,
+    /// Share of messages made up only of emotes (0.0 to 1.0)
+    pub emote_only_ratio: f64,
+    /// Average share of uppercase letters per message (0.0 to 1.0)
+    pub average_caps_ratio: f64,
+    /// Share of messages repeating another one in the window (0.0 to 1.0)
+    #[serde(default)]
+    pub duplicate_ratio: f64,
+    /// Oldest message still counted in the window
+    pub window_start: Option<DateTime<Utc>>,
+    pub last_updated: DateTime<Utc>,
+}
+
+/// One message's contribution to the channel quality window
+#[derive(Debug, Clone)]
+struct ChannelSample {
+    timestamp: DateTime<Utc>,
+    emote_only: bool,
+    caps_ratio: Option<f64>,
+    normalized_text: String,
+}
+
+/// Windows smaller than this are never called a flood
+pub const MIN_FLOOD_MESSAGES: usize = 5;
+/// Duplicate share at which a window counts as a spam burst
+pub const FLOOD_DUPLICATE_RATIO: f64 = 0.5;
+
+/// Copypasta signal for a window of messages
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateFlood {
+    /// Share of messages whose text shows up more than once (0.0 to 1.0)
+    pub duplicate_ratio: f64,
+    /// Repeated texts, normalized, most repeated first
+    pub repeated_texts: Vec<String>,
+    /// Enough of the window is repeats to look like a spam burst
+    pub is_flood: bool,
+}
+
+// lowercase, trim and squash inner whitespace so trivial edits still match
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// share of repeats and the repeated texts, most repeated first
+fn duplicate_stats<'a>(texts: impl Iterator<Item = &'a str>) -> (f64, Vec<String>) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut total = 0;
+    for text in texts {
+        total += 1;
+        if !text.is_empty() {
+            *counts.entry(text).or_default() += 1;
+        }
+    }
+    if total == 0 {
+        return (0.0, Vec::new());
+    }
+
+    let mut repeated: Vec<(&str, usize)> = counts.into_iter().filter(|(_, count)| *count > 1).collect();
+    repeated.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let duplicates: usize = repeated.iter().map(|(_, count)| count).sum();
+
+    (
+        duplicates as f64 / total as f64,
+        repeated.into_iter().map(|(text, _)| text.to_string()).collect(),
+    )
+}
+
+/// How much of `messages` is the same few lines pasted over and over
+pub fn duplicate_flood(messages: &[ChatMessage]) -> DuplicateFlood {
+    let normalized: Vec<String> = messages.iter().map(|message| normalize_text(&message.message.text)).collect();
+    let (duplicate_ratio, repeated_texts) = duplicate_stats(normalized.iter().map(String::as_str));
+
+    DuplicateFlood {
+        duplicate_ratio,
+        repeated_texts,
+        is_flood: messages.len() >= MIN_FLOOD_MESSAGES && duplicate_ratio >= FLOOD_DUPLICATE_RATIO,
+    }
+}
+
+/// Tracks emote-only and caps ratios per streamer over a rolling time window
+pub struct ChannelQualityTracker {
+    window: chrono::Duration,
+    samples: HashMap<String, VecDeque<ChannelSample>>,
+}
+
+impl ChannelQualityTracker {
+    // make a tracker that keeps messages for the given window
+    pub fn new(window: chrono::Duration) -> Self {
+        Self {
+            window,
+            samples: HashMap::new(),
+        }
+    }
+
+    // add a message to its streamer's window
+    pub fn record_message(&mut self, message: &ChatMessage) {
+        let samples = self.samples.entry(message.streamer.clone()).or_default();
+        samples.push_back(ChannelSample {
+            timestamp: message.timestamp,
+            emote_only: message.is_emote_only(),
+            caps_ratio: message.caps_ratio(),
+            normalized_text: normalize_text(&message.message.text),
+        });
+    }
+
+    // drop samples that fell out of the window
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - self.window;
+        for samples in self.samples.values_mut() {
+            while samples.front().map(|s| s.timestamp < cutoff).unwrap_or(false) {
+                samples.pop_front();
+            }
+        }
+        self.samples.retain(|_, samples| !samples.is_empty());
+    }
+
+    // current window stats for one streamer
+    pub fn channel_quality(&self, streamer: &str) -> Option<ChannelQuality> {
+        let samples = self.samples.get(streamer)?;
+        if samples.is_empty() {
+            return None;
+        }
+
+        let emote_only = samples.iter().filter(|s| s.emote_only).count();
+        let caps: Vec<f64> = samples.iter().filter_map(|s| s.caps_ratio).collect();
+        let average_caps_ratio = if caps.is_empty() {
+            0.0
+        } else {
+            caps.iter().sum::<f64>() / caps.len() as f64
+        };
+
+        Some(ChannelQuality {
+            streamer_name: streamer.to_string(),
+            window_messages: samples.len() as u64,
+            emote_only_ratio: emote_only as f64 / samples.len() as f64,
+            average_caps_ratio,
+            duplicate_ratio: duplicate_stats(samples.iter().map(|s| s.normalized_text.as_str())).0,
+            window_start: samples.front().map(|s| s.timestamp),
+            last_updated: Utc::now(),
+        })
+    }
+
+    // current window stats for every streamer with messages in the window
+    pub fn all_channel_quality(&self) -> HashMap<String, ChannelQuality> {
+        self.samples
+            .keys()
+            .filter_map(|streamer| {
+                self.channel_quality(streamer)
+                    .map(|quality| (streamer.clone(), quality))
+            })
+            .collect()
+    }
+}
+
+/// Probable language of a message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LanguageGuess {
+    /// ISO 639-1 code, e.g. "en" or "ja"
+    pub code: String,
+    /// How sure the guess is (0.0 to 1.0)
+    pub confidence: f64,
+}
+
+/// Fewest letters worth guessing from in an alphabetic script
+const MIN_LATIN_LETTERS: usize = 10;
+/// Fewest words worth guessing from in an alphabetic script
+const MIN_LATIN_WORDS: usize = 3;
+/// Fewest characters worth guessing from in a script only one language uses
+const MIN_SCRIPT_CHARS: usize = 2;
+
+/// Common short words per language, the languages that share the Latin script
+/// are told apart by which of these a message uses
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "are", "you", "that", "this", "was", "what", "with", "have", "for", "not", "just", "i'm", "it's", "of", "to", "my", "so", "be", "can", "don't", "like", "how", "why", "he", "she", "they", "we", "his", "your", "been", "ever", "i"]),
+    ("es", &["el", "los", "las", "que", "y", "es", "por", "para", "una", "un", "con", "muy", "pero", "qué", "como", "esto", "está", "yo", "jaja", "del", "se", "lo", "al"]),
+    ("pt", &["o", "os", "que", "não", "é", "um", "uma", "com", "para", "muito", "mas", "eu", "você", "isso", "tá", "kkkk", "do", "da", "se"]),
+    ("fr", &["le", "les", "et", "est", "que", "un", "une", "je", "tu", "pas", "c'est", "pour", "avec", "mais", "très", "il", "ça", "oui", "du", "des", "au"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "ich", "du", "ein", "eine", "mit", "auf", "zu", "sehr", "aber", "was", "ja", "auch", "wie", "den", "dem"]),
+];
+
+// language of a character whose script gives it away, "latin" for the shared alphabet
+fn script_of(c: char) -> Option<&'static str> {
+    match c {
+        '\u{3040}'..='\u{30FF}' => Some("ja"), // hiragana and katakana
+        '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => Some("ko"),
+        '\u{4E00}'..='\u{9FFF}' => Some("zh"), // han, japanese too unless kana shows up
+        '\u{0400}'..='\u{04FF}' => Some("ru"),
+        '\u{0600}'..='\u{06FF}' => Some("ar"),
+        '\u{0E00}'..='\u{0E7F}' => Some("th"),
+        '\u{0370}'..='\u{03FF}' => Some("el"),
+        '\u{0590}'..='\u{05FF}' => Some("he"),
+        c if c.is_alphabetic() => Some("latin"),
+        _ => None,
+    }
+}
+
+/// Guess the language of what the chatter typed. Emotes don't count, and
+/// anything too short to tell gives None rather than a guess.
+pub fn detect_language(message: &ChatMessage) -> Option<LanguageGuess> {
+    let typed: Vec<&str> = if message.message.fragments.is_empty() {
+        message
+            .message
+            .text
+            .split_whitespace()
+            .filter(|word| !message.message.emotes.iter().any(|emote| emote == word))
+            .collect()
+    } else {
+        message
+            .message
+            .fragments
+            .iter()
+            .filter(|f| f.fragment_type == "text")
+            .flat_map(|f| f.content.split_whitespace())
+            .collect()
+    };
+
+    let mut scripts: HashMap<&'static str, usize> = HashMap::new();
+    for c in typed.iter().flat_map(|word| word.chars()) {
+        if let Some(script) = script_of(c) {
+            *scripts.entry(script).or_default() += 1;
+        }
+    }
+    let letters: usize = scripts.values().sum();
+    if letters == 0 {
+        return None;
+    }
+
+    // any kana makes han characters japanese
+    let kana = scripts.get("ja").copied().unwrap_or(0);
+    if kana > 0 {
+        let han = scripts.remove("zh").unwrap_or(0);
+        *scripts.entry("ja").or_default() += han;
+    }
+
+    let (&script, &count) = scripts.iter().max_by_key(|(script, count)| (**count, **script))?;
+    if script != "latin" {
+        if count < MIN_SCRIPT_CHARS {
+            return None;
+        }
+        return Some(LanguageGuess { code: script.to_string(), confidence: count as f64 / letters as f64 });
+    }
+
+    let words: Vec<String> = typed
+        .iter()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    if count < MIN_LATIN_LETTERS || words.len() < MIN_LATIN_WORDS {
+        return None;
+    }
+
+    let hits: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| (*code, words.iter().filter(|word| stopwords.contains(&word.as_str())).count()))
+        .collect();
+    let total_hits: usize = hits.iter().map(|(_, hits)| hits).sum();
+    let (code, best) = hits.into_iter().max_by_key(|(_, hits)| *hits)?;
+    if best == 0 {
+        return None;
+    }
+
+    // share of the stopword hits that agree, scaled down when the script itself is mixed
+    Some(LanguageGuess {
+        code: code.to_string(),
+        confidence: (best as f64 / total_hits as f64) * (count as f64 / letters as f64),
+    })
+}
+
 /// Levels for quality alerts
 #[derive(Debug, Clone, PartialEq)]
 pub enum QualityAlert {
@@ -115,6 +390,7 @@ impl QualityMetricsTracker {
     }
 
     // record a batch of messages processed
+    #[allow(clippy::too_many_arguments)]
     pub fn record_batch_processed(
         &mut self,
         streamer: &str,
@@ -185,7 +461,7 @@ impl QualityMetricsTracker {
         // Track unique users
         let user_set = self.user_tracking
             .entry(streamer.to_string())
-            .or_insert_with(HashMap::new);
+            .or_default();
         
         for user in unique_users {
             *user_set.entry(user).or_insert(0) += 1;
@@ -225,7 +501,7 @@ impl QualityMetricsTracker {
             .sum();
 
         // Ensure score is between 0.0 and 1.0
-        self.metrics.quality_score = self.metrics.quality_score.max(0.0).min(1.0);
+        self.metrics.quality_score = self.metrics.quality_score.clamp(0.0, 1.0);
     }
 
     // check for quality alerts based on metrics
@@ -501,4 +777,148 @@ mod tests {
         assert!(report.contains("Total Processed: 100"));
         assert!(report.contains("teststreamer"));
     }
-}
\ No newline at end of file
+
+    fn create_chat_message(streamer: &str, fragments: Vec<(&str, &str)>, timestamp: DateTime<Utc>) -> ChatMessage {
+        use crate::parser::{ChatUser, MessageContent, MessageFragment, StreamContext};
+
+        let fragments: Vec<MessageFragment> = fragments
+            .into_iter()
+            .map(|(fragment_type, content)| MessageFragment::new(fragment_type, content))
+            .collect();
+        let text = fragments.iter().map(|f| f.content.clone()).collect::<Vec<_>>().join(" ");
+
+        ChatMessage::new(
+            streamer.to_string(),
+            timestamp,
+            ChatUser {
+                username: "user1".to_string(),
+                display_name: "user1".to_string(),
+                color: None,
+                badges: vec![],
+            },
+            MessageContent {
+                text,
+                emotes: vec![],
+                fragments,
+            },
+            StreamContext::default(),
+        )
+    }
+
+    #[test]
+    fn test_channel_quality_ratios() {
+        let mut tracker = ChannelQualityTracker::new(chrono::Duration::minutes(5));
+        let now = Utc::now();
+
+        tracker.record_message(&create_chat_message("teststreamer", vec![("emote", "Kappa")], now));
+        tracker.record_message(&create_chat_message("teststreamer", vec![("text", "HELLO")], now));
+        tracker.record_message(&create_chat_message("teststreamer", vec![("text", "hello")], now));
+        tracker.record_message(&create_chat_message("teststreamer", vec![("text", "Hi"), ("emote", "LUL")], now));
+
+        let quality = tracker.channel_quality("teststreamer").unwrap();
+        assert_eq!(quality.window_messages, 4);
+        assert_eq!(quality.emote_only_ratio, 0.25);
+        // caps ratios 1.0, 0.0 and 0.5, the emote-only message has no letters to judge
+        assert!((quality.average_caps_ratio - 0.5).abs() < f64::EPSILON);
+        // "HELLO" and "hello" are the same line once normalized
+        assert_eq!(quality.duplicate_ratio, 0.5);
+        assert!(tracker.channel_quality("otherstreamer").is_none());
+    }
+
+    #[test]
+    fn test_channel_quality_window_pruning() {
+        let mut tracker = ChannelQualityTracker::new(chrono::Duration::minutes(5));
+        let now = Utc::now();
+
+        tracker.record_message(&create_chat_message("old", vec![("text", "hi")], now - chrono::Duration::minutes(10)));
+        tracker.record_message(&create_chat_message("teststreamer", vec![("text", "hi")], now - chrono::Duration::minutes(10)));
+        tracker.record_message(&create_chat_message("teststreamer", vec![("text", "hi")], now));
+
+        tracker.prune(now);
+
+        let all = tracker.all_channel_quality();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all["teststreamer"].window_messages, 1);
+    }
+
+    fn text_messages(texts: &[&str]) -> Vec<ChatMessage> {
+        let now = Utc::now();
+        texts.iter().map(|text| create_chat_message("teststreamer", vec![("text", text)], now)).collect()
+    }
+
+    #[test]
+    fn test_duplicate_flood_clean_window() {
+        let window = text_messages(&["gg", "that clutch was insane", "what rank is he", "lol", "GG", "first time here"]);
+        let flood = duplicate_flood(&window);
+
+        // one "gg" pair out of six messages
+        assert!((flood.duplicate_ratio - 2.0 / 6.0).abs() < f64::EPSILON);
+        assert_eq!(flood.repeated_texts, vec!["gg"]);
+        assert!(!flood.is_flood);
+
+        let empty = duplicate_flood(&[]);
+        assert_eq!(empty.duplicate_ratio, 0.0);
+        assert!(empty.repeated_texts.is_empty());
+        assert!(!empty.is_flood);
+    }
+
+    #[test]
+    fn test_duplicate_flood_copypasta_window() {
+        let pasta = "THIS STREAMER IS THE GOAT  copy paste if you agree";
+        let window = text_messages(&[
+            pasta,
+            "  this streamer is the goat copy paste if you agree",
+            "hello",
+            "This Streamer Is The Goat copy paste if you agree ",
+            "W", "w",
+            pasta,
+            "ok",
+        ]);
+        let flood = duplicate_flood(&window);
+
+        assert_eq!(flood.duplicate_ratio, 6.0 / 8.0);
+        assert_eq!(flood.repeated_texts, vec!["this streamer is the goat copy paste if you agree", "w"]);
+        assert!(flood.is_flood);
+
+        // the same repeats are too few to call a burst
+        assert!(!duplicate_flood(&window[..2]).is_flood);
+    }
+
+    #[test]
+    fn test_detect_language_english() {
+        let message = create_chat_message("teststreamer", vec![("text", "that was the best play I have ever seen")], Utc::now());
+        let guess = detect_language(&message).unwrap();
+        assert_eq!(guess.code, "en");
+        assert!(guess.confidence > 0.5, "confidence {}", guess.confidence);
+
+        let message = create_chat_message("teststreamer", vec![("text", "das ist nicht so gut aber ich mag es")], Utc::now());
+        assert_eq!(detect_language(&message).unwrap().code, "de");
+    }
+
+    #[test]
+    fn test_detect_language_japanese() {
+        let message = create_chat_message("teststreamer", vec![("text", "今日の配信めっちゃ楽しかった")], Utc::now());
+        let guess = detect_language(&message).unwrap();
+        assert_eq!(guess.code, "ja");
+        assert_eq!(guess.confidence, 1.0);
+
+        // han on its own reads as chinese
+        let message = create_chat_message("teststreamer", vec![("text", "主播太厉害了")], Utc::now());
+        assert_eq!(detect_language(&message).unwrap().code, "zh");
+    }
+
+    #[test]
+    fn test_detect_language_needs_enough_text() {
+        let emote_only = create_chat_message("teststreamer", vec![("emote", "Kappa"), ("emote", "PogChamp"), ("emote", "LUL")], Utc::now());
+        assert_eq!(detect_language(&emote_only), None);
+
+        // emotes typed around a word don't make it long enough either
+        let mostly_emotes = create_chat_message("teststreamer", vec![("emote", "KEKW"), ("text", "lol"), ("emote", "KEKW")], Utc::now());
+        assert_eq!(detect_language(&mostly_emotes), None);
+
+        for text in ["gg", "lmaooo", "!!!", "123 456 789"] {
+            let message = create_chat_message("teststreamer", vec![("text", text)], Utc::now());
+            assert_eq!(detect_language(&message), None, "{:?} is too short to tell", text);
+        }
+    }
+}
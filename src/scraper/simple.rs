@@ -1,3 +1,4 @@
+use reqwest::header::{HeaderMap, USER_AGENT};
 use reqwest::Client;
 use serde_json::Value;
 use std::time::Duration;
@@ -5,28 +6,55 @@ use tokio::time::sleep;
 use tracing::{info, error, warn};
 use chrono::Utc;
 use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::browser::UserAgentGenerator;
+use crate::config::Config;
+use crate::error::{Result as ScrapeResult, ScrapingError};
 
 pub struct SimpleTwitchScraper {
     client: Client,
     output_dir: PathBuf,
     streamers: Vec<String>,
+    user_agents: Option<Arc<UserAgentGenerator>>, // set when every request gets a fresh user agent
 }
 
 impl SimpleTwitchScraper {
     pub fn new(output_dir: PathBuf, streamers: Vec<String>) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .user_agent("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36")
-            .build()
+        let client = Self::build_client(UserAgentGenerator::new().random_user_agent(), HeaderMap::new())
             .expect("Failed to create HTTP client");
 
         Self {
             client,
             output_dir,
             streamers,
+            user_agents: None,
         }
     }
 
+    /// Scraper for the configured streamers, sending `scraper.extra_headers` on every
+    /// request and rotating the user agent when `stealth.randomize_user_agents` is on
+    pub fn from_config(config: &Config) -> ScrapeResult<Self> {
+        let generator = UserAgentGenerator::new();
+        let client = Self::build_client(generator.random_user_agent(), config.scraper.header_map()?)?;
+
+        Ok(Self {
+            client,
+            output_dir: config.output.directory.clone(),
+            streamers: config.streamers.clone(),
+            user_agents: config.stealth.randomize_user_agents.then(|| Arc::new(generator)),
+        })
+    }
+
+    fn build_client(user_agent: &str, headers: HeaderMap) -> ScrapeResult<Client> {
+        Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent(user_agent)
+            .default_headers(headers)
+            .build()
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to create HTTP client: {}", e)).into())
+    }
+
     pub async fn start_scraping(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         info!("Starting simple Twitch scraper for {} streamers", self.streamers.len());
         
@@ -39,9 +67,10 @@ impl SimpleTwitchScraper {
             let streamer = streamer.clone();
             let client = self.client.clone();
             let output_dir = self.output_dir.clone();
+            let user_agents = self.user_agents.clone();
             
             let handle = tokio::spawn(async move {
-                Self::scrape_streamer(client, streamer, output_dir).await;
+                Self::scrape_streamer(client, streamer, output_dir, user_agents).await;
             });
             
             handles.push(handle);
@@ -55,14 +84,16 @@ impl SimpleTwitchScraper {
         Ok(())
     }
     
-    async fn scrape_streamer(client: Client, streamer: String, output_dir: PathBuf) {
+    async fn scrape_streamer(client: Client, streamer: String, output_dir: PathBuf, user_agents: Option<Arc<UserAgentGenerator>>) {
         info!("Starting scraper for streamer: {}", streamer);
         
         let output_file = output_dir.join(format!("{}_chat.json", streamer));
         let mut message_count = 0u64;
         
         loop {
-            match Self::fetch_stream_info(&client, &streamer).await {
+            let mut next_scrape = Duration::from_secs(30);
+            let user_agent = user_agents.as_ref().map(|generator| generator.random_user_agent());
+            match Self::fetch_stream_info(&client, &streamer, user_agent).await {
                 Ok(stream_info) => {
                     // create a mock chat message since we can't get real chat without proper api
                     let chat_entry = serde_json::json!({
@@ -79,13 +110,18 @@ impl SimpleTwitchScraper {
                         error!("Failed to write to output file for {}: {}", streamer, e);
                     } else {
                         message_count += 1;
-                        if message_count % 10 == 0 {
+                        if message_count.is_multiple_of(10) {
                             info!("Scraped {} entries for {}", message_count, streamer);
                         }
                     }
                 }
                 Err(e) => {
                     warn!("Failed to fetch stream info for {}: {}", streamer, e);
+
+                    // back off this streamer for as long as twitch asked
+                    if let Some(pause) = ScrapingError::rate_limit_pause(e.as_ref()) {
+                        next_scrape = next_scrape.max(pause);
+                    }
                     
                     // write error entry
                     let error_entry = serde_json::json!({
@@ -101,17 +137,24 @@ impl SimpleTwitchScraper {
             }
             
             // wait before next scrape
-            sleep(Duration::from_secs(30)).await;
+            sleep(next_scrape).await;
         }
     }
     
-    async fn fetch_stream_info(client: &Client, streamer: &str) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+    async fn fetch_stream_info(client: &Client, streamer: &str, user_agent: Option<&str>) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
         let url = format!("https://www.twitch.tv/{}", streamer);
         
-        let response = client
-            .get(&url)
+        let mut request = client.get(&url);
+        if let Some(user_agent) = user_agent {
+            request = request.header(USER_AGENT, user_agent);
+        }
+        let response = request
             .send()
             .await?;
+
+        if let Some(rate_limited) = ScrapingError::from_rate_limited_response(&response) {
+            return Err(rate_limited.into());
+        }
             
         if response.status().is_success() {
             let body = response.text().await?;
@@ -0,0 +1,402 @@
+use rand::RngCore;
+use std::io::{self, Read};
+
+use crate::error::{Result, ScrapingError};
+use crate::parser::chat_message::ChatMessage;
+use crate::storage::OutputFormatter;
+
+/// Avro schema for `ChatMessage` rows, embedded in every `.avro` file header.
+/// Bump `version` in the doc string when fields change so registries can tell revisions apart.
+pub const CHAT_MESSAGE_AVRO_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "ChatMessage",
+  "namespace": "twitch_chat_scraper",
+  "doc": "Twitch chat message, version 5",
+  "fields": [
+    {"name": "id", "type": "string"},
+    {"name": "streamer", "type": "string"},
+    {"name": "timestamp", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+    {"name": "user", "type": {
+      "type": "record",
+      "name": "ChatUser",
+      "fields": [
+        {"name": "username", "type": "string"},
+        {"name": "display_name", "type": "string"},
+        {"name": "color", "type": ["null", "string"], "default": null},
+        {"name": "badges", "type": {"type": "array", "items": "string"}}
+      ]
+    }},
+    {"name": "message", "type": {
+      "type": "record",
+      "name": "MessageContent",
+      "fields": [
+        {"name": "text", "type": "string"},
+        {"name": "emotes", "type": {"type": "array", "items": "string"}},
+        {"name": "fragments", "type": {"type": "array", "items": {
+          "type": "record",
+          "name": "MessageFragment",
+          "fields": [
+            {"name": "type", "type": "string"},
+            {"name": "content", "type": "string"},
+            {"name": "provider", "type": ["null", "string"], "default": null}
+          ]
+        }}}
+      ]
+    }},
+    {"name": "context", "type": {
+      "type": "record",
+      "name": "StreamContext",
+      "fields": [
+        {"name": "viewer_count", "type": ["null", "int"], "default": null},
+        {"name": "game_category", "type": ["null", "string"], "default": null},
+        {"name": "stream_title", "type": ["null", "string"], "default": null}
+      ]
+    }},
+    {"name": "message_type", "type": "string", "default": "chat"},
+    {"name": "bits", "type": ["null", "long"], "default": null},
+    {"name": "mentions", "type": {"type": "array", "items": "string"}, "default": []},
+    {"name": "reply_to", "type": ["null", "string"], "default": null}
+  ]
+}"#;
+
+const AVRO_MAGIC: &[u8; 4] = b"Obj\x01";
+
+/// Writes Avro object container files: the header carries the schema and a
+/// sync marker, and each `store_messages` batch becomes one data block.
+pub struct AvroFormatter {
+    sync_marker: [u8; 16],
+}
+
+impl Default for AvroFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AvroFormatter {
+    pub fn new() -> Self {
+        let mut sync_marker = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut sync_marker);
+        Self { sync_marker }
+    }
+
+    fn encode_message(buf: &mut Vec<u8>, message: &ChatMessage) {
+        write_string(buf, &message.id);
+        write_string(buf, &message.streamer);
+        write_long(buf, message.timestamp.timestamp_millis());
+
+        write_string(buf, &message.user.username);
+        write_string(buf, &message.user.display_name);
+        write_optional_string(buf, message.user.color.as_deref());
+        write_string_array(buf, &message.user.badges);
+
+        write_string(buf, &message.message.text);
+        write_string_array(buf, &message.message.emotes);
+        if !message.message.fragments.is_empty() {
+            write_long(buf, message.message.fragments.len() as i64);
+            for fragment in &message.message.fragments {
+                write_string(buf, &fragment.fragment_type);
+                write_string(buf, &fragment.content);
+                write_optional_string(buf, fragment.provider.as_deref());
+            }
+        }
+        write_long(buf, 0);
+
+        match message.context.viewer_count {
+            Some(viewer_count) => {
+                write_long(buf, 1);
+                write_long(buf, viewer_count as i64);
+            }
+            None => write_long(buf, 0),
+        }
+        write_optional_string(buf, message.context.game_category.as_deref());
+        write_optional_string(buf, message.context.stream_title.as_deref());
+
+        write_string(buf, message.message_type.as_str());
+        match message.bits {
+            Some(bits) => {
+                write_long(buf, 1);
+                write_long(buf, bits as i64);
+            }
+            None => write_long(buf, 0),
+        }
+        write_string_array(buf, &message.mentions);
+        write_optional_string(buf, message.reply_to.as_deref());
+    }
+}
+
+impl OutputFormatter for AvroFormatter {
+    fn format_messages(&self, _messages: &[ChatMessage]) -> Result<String> {
+        Err(ScrapingError::StorageError("Avro is a binary format, use format_bytes".to_string()).into())
+    }
+
+    fn file_extension(&self) -> &str {
+        "avro"
+    }
+
+    fn header(&self) -> Option<String> {
+        None
+    }
+
+    fn format_bytes(&self, messages: &[ChatMessage]) -> Result<Vec<u8>> {
+        let mut records = Vec::new();
+        for message in messages {
+            Self::encode_message(&mut records, message);
+        }
+
+        let mut block = Vec::with_capacity(records.len() + 32);
+        write_long(&mut block, messages.len() as i64);
+        write_long(&mut block, records.len() as i64);
+        block.extend_from_slice(&records);
+        block.extend_from_slice(&self.sync_marker);
+        Ok(block)
+    }
+
+    fn header_bytes(&self) -> Option<Vec<u8>> {
+        let mut header = Vec::with_capacity(CHAT_MESSAGE_AVRO_SCHEMA.len() + 64);
+        header.extend_from_slice(AVRO_MAGIC);
+
+        // file metadata is a map<bytes> with a single block
+        write_long(&mut header, 2);
+        write_string(&mut header, "avro.schema");
+        write_string(&mut header, CHAT_MESSAGE_AVRO_SCHEMA);
+        write_string(&mut header, "avro.codec");
+        write_string(&mut header, "null");
+        write_long(&mut header, 0);
+
+        header.extend_from_slice(&self.sync_marker);
+        Some(header)
+    }
+}
+
+/// Records in an Avro object container file, summed from each data block's
+/// count so the records themselves are never decoded
+pub fn count_records(mut reader: impl Read) -> io::Result<u64> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != AVRO_MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not an Avro object container file"));
+    }
+
+    // metadata map blocks, a negative count is followed by the block's byte size
+    loop {
+        let entries = read_long(&mut reader)?;
+        if entries == 0 {
+            break;
+        }
+        if entries < 0 {
+            read_long(&mut reader)?;
+        }
+        for _ in 0..entries.unsigned_abs() {
+            skip_bytes(&mut reader)?;
+            skip_bytes(&mut reader)?;
+        }
+    }
+    skip(&mut reader, 16)?; // sync marker
+
+    let mut records = 0;
+    loop {
+        // a clean end of file between blocks is the end of the data
+        let count = match read_long(&mut reader) {
+            Ok(count) => count,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        };
+        let size = read_long(&mut reader)?;
+        if count < 0 || size < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "negative Avro block count or size"));
+        }
+        skip(&mut reader, size as u64 + 16)?;
+        records += count as u64;
+    }
+    Ok(records)
+}
+
+fn read_long(reader: &mut impl Read) -> io::Result<i64> {
+    let (mut n, mut shift) = (0u64, 0);
+    loop {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        if shift >= 64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Avro long is too long"));
+        }
+        n |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Ok((n >> 1) as i64 ^ -((n & 1) as i64))
+}
+
+/// Skip a length prefixed `bytes` or `string`
+fn skip_bytes(reader: &mut impl Read) -> io::Result<()> {
+    let len = read_long(reader)?;
+    if len < 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "negative Avro string length"));
+    }
+    skip(reader, len as u64)
+}
+
+fn skip(reader: &mut impl Read, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(len), &mut io::sink())?;
+    if skipped < len {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Avro file ends mid-block"));
+    }
+    Ok(())
+}
+
+/// Avro `long`: zig-zag encoded variable length integer
+fn write_long(buf: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        buf.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_long(buf, value.len() as i64);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+/// `["null", "string"]` union, branch index first
+fn write_optional_string(buf: &mut Vec<u8>, value: Option<&str>) {
+    match value {
+        Some(value) => {
+            write_long(buf, 1);
+            write_string(buf, value);
+        }
+        None => write_long(buf, 0),
+    }
+}
+
+fn write_string_array(buf: &mut Vec<u8>, values: &[String]) {
+    if !values.is_empty() {
+        write_long(buf, values.len() as i64);
+        for value in values {
+            write_string(buf, value);
+        }
+    }
+    write_long(buf, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chat_message::{ChatUser, MessageContent, MessageFragment, StreamContext};
+    use chrono::{DateTime, Utc};
+
+    fn create_test_message() -> ChatMessage {
+        let mut message = ChatMessage::new(
+            "teststreamer".to_string(),
+            DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc),
+            ChatUser {
+                username: "user1".to_string(),
+                display_name: "User1".to_string(),
+                color: None,
+                badges: vec!["subscriber".to_string()],
+            },
+            MessageContent {
+                text: "hi".to_string(),
+                emotes: vec![],
+                fragments: vec![MessageFragment::new("text", "hi")],
+            },
+            StreamContext {
+                viewer_count: Some(1000),
+                game_category: None,
+                stream_title: None,
+            },
+        );
+        message.id = "id1".to_string();
+        message
+    }
+
+    #[test]
+    fn test_zigzag_longs() {
+        let encode = |value: i64| {
+            let mut buf = Vec::new();
+            write_long(&mut buf, value);
+            buf
+        };
+        assert_eq!(encode(0), vec![0x00]);
+        assert_eq!(encode(-1), vec![0x01]);
+        assert_eq!(encode(1), vec![0x02]);
+        assert_eq!(encode(64), vec![0x80, 0x01]);
+        assert_eq!(encode(-65), vec![0x81, 0x01]);
+    }
+
+    #[test]
+    fn test_schema_is_valid_json() {
+        let schema: serde_json::Value = serde_json::from_str(CHAT_MESSAGE_AVRO_SCHEMA).unwrap();
+        assert_eq!(schema["name"], "ChatMessage");
+        assert_eq!(schema["fields"].as_array().unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_header_and_block_layout() {
+        let formatter = AvroFormatter::new();
+        let header = formatter.header_bytes().unwrap();
+        assert!(header.starts_with(AVRO_MAGIC));
+        assert!(header.ends_with(&formatter.sync_marker));
+
+        let block = formatter.format_bytes(&[create_test_message(), create_test_message()]).unwrap();
+        assert_eq!(block[0], 0x04); // two records
+        assert!(block.ends_with(&formatter.sync_marker));
+
+        // one record, field by field
+        let mut expected = Vec::new();
+        for field in ["id1", "teststreamer"] {
+            write_string(&mut expected, field);
+        }
+        write_long(&mut expected, 1705314645000);
+        write_string(&mut expected, "user1");
+        write_string(&mut expected, "User1");
+        expected.push(0x00); // color: null
+        expected.extend_from_slice(&[0x02, 0x14]);
+        expected.extend_from_slice(b"subscriber");
+        expected.push(0x00);
+        write_string(&mut expected, "hi");
+        expected.push(0x00); // no emotes
+        expected.extend_from_slice(&[0x02, 0x08]);
+        expected.extend_from_slice(b"text");
+        write_string(&mut expected, "hi");
+        expected.push(0x00); // provider: null
+        expected.push(0x00);
+        expected.push(0x02); // viewer_count: int branch
+        write_long(&mut expected, 1000);
+        expected.extend_from_slice(&[0x00, 0x00]);
+        write_string(&mut expected, "chat");
+        expected.push(0x00); // bits: null
+        expected.push(0x00); // no mentions
+        expected.push(0x00); // reply_to: null
+
+        let mut record = Vec::new();
+        AvroFormatter::encode_message(&mut record, &create_test_message());
+        assert_eq!(record, expected);
+
+        assert!(formatter.format_messages(&[]).is_err());
+    }
+
+    #[test]
+    fn test_count_records() {
+        let formatter = AvroFormatter::new();
+        let mut file = formatter.header_bytes().unwrap();
+        assert_eq!(count_records(file.as_slice()).unwrap(), 0);
+
+        file.extend(formatter.format_bytes(&[create_test_message(), create_test_message()]).unwrap());
+        file.extend(formatter.format_bytes(&[create_test_message()]).unwrap());
+        assert_eq!(count_records(file.as_slice()).unwrap(), 3);
+
+        for value in [0, -1, 1, 64, -65, i64::MAX, i64::MIN] {
+            let mut buf = Vec::new();
+            write_long(&mut buf, value);
+            assert_eq!(read_long(&mut buf.as_slice()).unwrap(), value);
+        }
+
+        // cut off mid-block, or not Avro at all
+        assert!(count_records(&file[..file.len() - 1]).is_err());
+        assert!(count_records(&b"{\"id\":1}\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, ScrapingError};
+
+/// How rotated chat files are compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(ScrapingError::ConfigError(format!("Unsupported compression: {}", name)).into()),
+        }
+    }
+
+    /// Suffix added after the data file's own extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+        }
+    }
+
+    /// The compression a file was written with, going by its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Self::Gzip),
+            "zst" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// A `chat_x.jsonl.gz.tmp` left behind by a compression that was cut off
+    pub fn is_temp_path(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "tmp") && Self::from_path(&path.with_extension("")).is_some()
+    }
+
+    /// Read the uncompressed contents of `file`
+    pub fn decoder(&self, file: File) -> io::Result<Box<dyn Read + Send>> {
+        match self {
+            Self::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(file))),
+            Self::Zstd => Ok(Box::new(zstd::stream::read::Decoder::new(file)?)),
+        }
+    }
+
+    fn compress(&self, mut source: File, target: File) -> io::Result<()> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(target, flate2::Compression::default());
+                io::copy(&mut source, &mut encoder)?;
+                encoder.finish()?.flush()
+            }
+            Self::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(target, 0)?;
+                io::copy(&mut source, &mut encoder)?;
+                encoder.finish()?.flush()
+            }
+        }
+    }
+
+    /// `chat_x.jsonl` -> `chat_x.jsonl.gz`
+    pub fn compressed_path(&self, path: &Path) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".");
+        name.push(self.extension());
+        PathBuf::from(name)
+    }
+
+    /// Compress `path` into a temp file next to it, returning the temp path
+    /// and how many bytes of the original it covers. The caller decides
+    /// whether to `commit` it or throw it away.
+    pub async fn compress_to_temp(&self, path: &Path) -> Result<(PathBuf, u64)> {
+        let original_len = tokio::fs::metadata(path).await
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to stat {}: {}", path.display(), e)))?
+            .len();
+
+        let mut temp_name = self.compressed_path(path).into_os_string();
+        temp_name.push(".tmp");
+        let temp_path = PathBuf::from(temp_name);
+
+        let compression = *self;
+        let (source, target) = (path.to_path_buf(), temp_path.clone());
+        let compressed = tokio::task::spawn_blocking(move || {
+            let source = File::open(&source)?;
+            let target = File::create(&target)?;
+            compression.compress(source, target)
+        })
+        .await
+        .map_err(|e| ScrapingError::StorageError(format!("Compression task failed: {}", e)))?;
+
+        match compressed {
+            Ok(()) => Ok((temp_path, original_len)),
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                Err(ScrapingError::StorageError(format!("Failed to compress {}: {}", path.display(), e)).into())
+            }
+        }
+    }
+
+    /// Move a finished temp file into place and remove the original
+    pub fn commit(&self, path: &Path, temp_path: &Path) -> Result<PathBuf> {
+        let compressed_path = self.compressed_path(path);
+        std::fs::rename(temp_path, &compressed_path)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to replace {}: {}", compressed_path.display(), e)))?;
+        std::fs::remove_file(path)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to remove {}: {}", path.display(), e)))?;
+        Ok(compressed_path)
+    }
+}
@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::error::{Result, ScrapingError};
+use crate::parser::chat_message::{ChatMessage, ChatUser, MessageContent, MessageFragment, MessageType, StreamContext};
+use crate::storage::{is_chat_file, AvroFormatter, Compression, CsvFormatter, JsonFormatter, OutputFormatter};
+
+/// What a `convert` run did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConvertStats {
+    pub files_converted: usize,
+    pub files_skipped: usize,
+    pub messages: usize,
+}
+
+/// Re-emit every stored chat file under `input_dir` as `to_format` into `output_dir`,
+/// keeping the `<streamer>/<date>/` layout. The input format is picked per file from
+/// its extension, so directories holding both `.jsonl`, `.csv` and `.tsv` files convert fine,
+/// compressed or not.
+pub fn convert(input_dir: &Path, output_dir: &Path, to_format: &str, columns: Option<Vec<String>>) -> Result<ConvertStats> {
+    let formatter: Box<dyn OutputFormatter> = match to_format {
+        "json" => Box::new(JsonFormatter),
+        "csv" => Box::new(CsvFormatter::new(columns.unwrap_or_else(CsvFormatter::default_columns))),
+        "tsv" => Box::new(CsvFormatter::tsv(columns.unwrap_or_else(CsvFormatter::default_columns))),
+        "avro" => Box::new(AvroFormatter::new()),
+        _ => return Err(ScrapingError::ConfigError(format!("Unsupported export format: {}", to_format)).into()),
+    };
+
+    let mut stats = ConvertStats::default();
+    convert_dir(input_dir, input_dir, output_dir, formatter.as_ref(), &mut stats)?;
+
+    info!(
+        "Converted {} files ({} messages) to {}, skipped {}",
+        stats.files_converted, stats.messages, to_format, stats.files_skipped
+    );
+    Ok(stats)
+}
+
+fn convert_dir(
+    root: &Path,
+    dir: &Path,
+    output_dir: &Path,
+    formatter: &dyn OutputFormatter,
+    stats: &mut ConvertStats,
+) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| ScrapingError::StorageError(format!("Failed to read {}: {}", dir.display(), e)))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            convert_dir(root, &path, output_dir, formatter, stats)?;
+            continue;
+        }
+
+        // summaries, count files and agent logs aren't chat data
+        if !is_chat_file(&path) {
+            continue;
+        }
+
+        let messages = match read_messages(&path) {
+            Ok(messages) => messages,
+            Err(e) => {
+                warn!("Skipping {}: {}", path.display(), e);
+                stats.files_skipped += 1;
+                continue;
+            }
+        };
+
+        // `chat_x.jsonl.gz` comes out as `chat_x.csv`, not `chat_x.jsonl.csv`
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let relative = if Compression::from_path(relative).is_some() { relative.with_extension("") } else { relative.to_path_buf() };
+        let target = output_dir.join(relative).with_extension(formatter.file_extension());
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+
+        let mut content = formatter.header_bytes().unwrap_or_default();
+        content.extend(formatter.format_bytes(&messages)?);
+        fs::write(&target, content)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to write {}: {}", target.display(), e)))?;
+
+        stats.files_converted += 1;
+        stats.messages += messages.len();
+    }
+
+    Ok(())
+}
+
+/// Read a stored chat file back into messages, by extension. Rotated
+/// `.gz` and `.zst` files are decompressed first.
+pub fn read_messages(path: &Path) -> Result<Vec<ChatMessage>> {
+    let compression = Compression::from_path(path);
+    let data_path = if compression.is_some() { path.with_extension("") } else { path.to_path_buf() };
+    let extension = data_path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    if !matches!(extension, "jsonl" | "csv" | "tsv") {
+        return Err(ScrapingError::ParseError(format!("Reading .{} files isn't supported", extension)).into());
+    }
+
+    let read_error = |e: std::io::Error| ScrapingError::StorageError(format!("Failed to read {}: {}", path.display(), e));
+    let file = File::open(path).map_err(read_error)?;
+    let mut reader: Box<dyn Read + Send> = match compression {
+        Some(compression) => compression.decoder(file).map_err(read_error)?,
+        None => Box::new(file),
+    };
+    let mut content = String::new();
+    reader.read_to_string(&mut content).map_err(read_error)?;
+
+    match extension {
+        "jsonl" => read_jsonl(&content),
+        "tsv" => read_csv(&content, '\t'),
+        _ => read_csv(&content, ','),
+    }
+}
+
+fn read_jsonl(content: &str) -> Result<Vec<ChatMessage>> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                ScrapingError::ParseError(format!("Invalid JSON on line {}: {}", index + 1, e)).into()
+            })
+        })
+        .collect()
+}
+
+fn read_csv(content: &str, delimiter: char) -> Result<Vec<ChatMessage>> {
+    // files written for Excel start with a BOM
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut records = parse_csv_records(content, delimiter).into_iter();
+    let Some(columns) = records.next() else {
+        return Ok(Vec::new());
+    };
+
+    let mut messages = Vec::new();
+    for record in records {
+        let field = |name: &str| {
+            columns
+                .iter()
+                .position(|column| column == name)
+                .and_then(|index| record.get(index))
+                .map(String::as_str)
+                .unwrap_or_default()
+        };
+        let optional = |name: &str| Some(field(name)).filter(|value| !value.is_empty()).map(str::to_string);
+
+        let timestamp = DateTime::parse_from_rfc3339(field("timestamp"))
+            .map(|timestamp| timestamp.with_timezone(&Utc))
+            .map_err(|e| ScrapingError::ParseError(format!("Invalid timestamp '{}': {}", field("timestamp"), e)))?;
+        let text = field("message_text").to_string();
+
+        let mut message = ChatMessage::new(
+            field("streamer").to_string(),
+            timestamp,
+            ChatUser {
+                username: field("username").to_string(),
+                display_name: optional("display_name").unwrap_or_else(|| field("username").to_string()),
+                color: optional("user_color"),
+                badges: field("badges").split(';').filter(|b| !b.is_empty()).map(str::to_string).collect(),
+            },
+            MessageContent {
+                // emotes aren't a CSV column, so only the text survives
+                emotes: vec![],
+                fragments: vec![MessageFragment::new("text", text.clone())],
+                text,
+            },
+            StreamContext {
+                viewer_count: field("viewer_count").parse().ok(),
+                game_category: optional("game_category"),
+                stream_title: optional("stream_title"),
+            },
+        );
+        if let Some(id) = optional("id") {
+            message.id = id;
+        }
+        message.message_type = MessageType::from_name(field("message_type")).unwrap_or_default();
+        message.bits = field("bits").parse().ok();
+        message.mentions = field("mentions").split(';').filter(|m| !m.is_empty()).map(str::to_string).collect();
+        message.reply_to = optional("reply_to");
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+/// Split CSV into records, honouring quoted fields with commas, quotes and newlines
+fn parse_csv_records(content: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|value| !value.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::fixtures::chat_message;
+    use tempfile::tempdir;
+
+    fn create_test_message(text: &str) -> ChatMessage {
+        let mut message = chat_message(text);
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        message.user.badges = vec!["subscriber".to_string(), "vip".to_string()];
+        message
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let formatter = CsvFormatter::new(CsvFormatter::default_columns());
+        let original = vec![create_test_message("hello, \"chat\"\nsecond line"), create_test_message("plain")];
+        let content = format!("{}\n{}", formatter.header().unwrap(), formatter.format_messages(&original).unwrap());
+
+        assert_eq!(read_csv(&content, ',').unwrap(), original);
+    }
+
+    #[test]
+    fn test_convert_mixed_directory() {
+        let input = tempdir().unwrap();
+        let output = tempdir().unwrap();
+        let day_dir = input.path().join("teststreamer").join("2024-01-15");
+        fs::create_dir_all(&day_dir).unwrap();
+
+        let messages = vec![create_test_message("one"), create_test_message("two")];
+        fs::write(day_dir.join("chat_2024-01-15_10-00-00.jsonl"), JsonFormatter.format_messages(&messages).unwrap()).unwrap();
+        let csv = CsvFormatter::new(CsvFormatter::default_columns());
+        fs::write(
+            day_dir.join("chat_2024-01-15_11-00-00.csv"),
+            format!("{}\n{}", csv.header().unwrap(), csv.format_messages(&messages[..1]).unwrap()),
+        ).unwrap();
+        fs::write(day_dir.join("chat_2024-01-15_12-00-00.avro"), b"Obj\x01").unwrap();
+        fs::write(day_dir.join("count.json"), "{}").unwrap();
+
+        let stats = convert(input.path(), output.path(), "csv", None).unwrap();
+        assert_eq!(stats, ConvertStats { files_converted: 2, files_skipped: 1, messages: 3 });
+
+        let out_dir = output.path().join("teststreamer").join("2024-01-15");
+        let converted = read_messages(&out_dir.join("chat_2024-01-15_10-00-00.csv")).unwrap();
+        assert_eq!(converted, messages);
+        assert!(out_dir.join("chat_2024-01-15_11-00-00.csv").exists());
+        assert!(!out_dir.join("count.csv").exists());
+
+        assert!(convert(input.path(), output.path(), "xml", None).is_err());
+    }
+
+    #[test]
+    fn test_convert_compressed_and_templated_files() {
+        use std::io::Write;
+
+        let input = tempdir().unwrap();
+        let output = tempdir().unwrap();
+        // a `{date}/{streamer}/{time}` template, nothing starts with chat_
+        let day_dir = input.path().join("2024-01-15").join("teststreamer");
+        fs::create_dir_all(&day_dir).unwrap();
+
+        let messages = vec![create_test_message("one"), create_test_message("two")];
+        let jsonl = JsonFormatter.format_messages(&messages).unwrap();
+        fs::write(day_dir.join("10-00-00.jsonl"), &jsonl).unwrap();
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(jsonl.as_bytes()).unwrap();
+        fs::write(day_dir.join("11-00-00.jsonl.gz"), gzip.finish().unwrap()).unwrap();
+        fs::write(day_dir.join("12-00-00.jsonl.zst"), zstd::encode_all(jsonl.as_bytes(), 0).unwrap()).unwrap();
+        fs::write(day_dir.join("count.json"), "{}").unwrap();
+        fs::write(day_dir.join("agent.log"), "started").unwrap();
+
+        assert_eq!(read_messages(&day_dir.join("11-00-00.jsonl.gz")).unwrap(), messages);
+
+        let stats = convert(input.path(), output.path(), "csv", None).unwrap();
+        assert_eq!(stats, ConvertStats { files_converted: 3, files_skipped: 0, messages: 6 });
+
+        let out_dir = output.path().join("2024-01-15").join("teststreamer");
+        for name in ["10-00-00.csv", "11-00-00.csv", "12-00-00.csv"] {
+            assert_eq!(read_messages(&out_dir.join(name)).unwrap(), messages, "{}", name);
+        }
+        assert!(!out_dir.join("agent.csv").exists());
+    }
+}
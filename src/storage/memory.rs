@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::error::Result;
+use crate::parser::chat_message::ChatMessage;
+use crate::storage::{StorageManager, StorageStats};
+
+/// Keeps stored messages in memory instead of on disk, for tests and as a
+/// baseline when benchmarking the file backed storage.
+#[derive(Clone, Default)]
+pub struct MemoryStorageManager {
+    messages: Arc<Mutex<Vec<ChatMessage>>>,
+}
+
+impl MemoryStorageManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything stored so far, in the order it arrived
+    pub async fn messages(&self) -> Vec<ChatMessage> {
+        self.messages.lock().await.clone()
+    }
+
+    pub async fn len(&self) -> usize {
+        self.messages.lock().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.messages.lock().await.is_empty()
+    }
+
+    pub async fn clear(&self) {
+        self.messages.lock().await.clear();
+    }
+}
+
+#[async_trait]
+impl StorageManager for MemoryStorageManager {
+    async fn store_messages(&self, messages: Vec<ChatMessage>) -> Result<()> {
+        self.messages.lock().await.extend(messages);
+        Ok(())
+    }
+
+    async fn setup_rotation(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_storage_stats(&self) -> Result<StorageStats> {
+        let stored = self.messages.lock().await.len() as u64;
+        Ok(StorageStats {
+            total_seen: stored,
+            total_stored: stored,
+            files_created: 0,
+            disk_usage: 0,
+            last_rotation: None,
+            duplicates_skipped: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::fixtures::chat_message;
+
+    fn create_test_message(streamer: &str, text: &str) -> ChatMessage {
+        let mut message = chat_message(text);
+        message.streamer = streamer.to_string();
+        message
+    }
+
+    #[tokio::test]
+    async fn test_memory_storage_keeps_messages() {
+        let storage = MemoryStorageManager::new();
+        storage.setup_rotation().await.unwrap();
+        assert!(storage.is_empty().await);
+
+        storage.store_messages(vec![
+            create_test_message("one", "first"),
+            create_test_message("two", "second"),
+        ]).await.unwrap();
+        storage.store_messages(vec![]).await.unwrap();
+
+        let messages = storage.messages().await;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].message.text, "first");
+        assert_eq!(messages[1].streamer, "two");
+
+        let stats = storage.get_storage_stats().await.unwrap();
+        assert_eq!(stats.total_stored, 2);
+        assert_eq!(stats.files_created, 0);
+        assert_eq!(stats.disk_usage, 0);
+
+        storage.clear().await;
+        assert_eq!(storage.len().await, 0);
+    }
+}
@@ -1,32 +1,194 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{debug, info};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tracing::{debug, info, warn};
 
 use crate::parser::chat_message::ChatMessage;
-use crate::config::FileConfigManager;
+use crate::config::{Durability, FileConfigManager, OutputConfig};
 use crate::error::{Result, ScrapingError};
 
+pub mod avro;
+pub mod compress;
+pub mod convert;
+pub mod memory;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod summary;
+pub mod template;
+
+pub use avro::{AvroFormatter, CHAT_MESSAGE_AVRO_SCHEMA};
+pub use compress::Compression;
+pub use convert::{convert, read_messages, ConvertStats};
+pub use memory::MemoryStorageManager;
+#[cfg(feature = "sqlite")]
+pub use sqlite::{SqliteStorageManager, SQLITE_DB_FILE};
+pub use summary::{EmoteCount, HourlySummary, SummaryWriter};
+pub use template::{FilenameTemplate, DEFAULT_FILENAME_TEMPLATE};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageStats {
-    pub total_messages: u64,
+    pub total_seen: u64,   // messages handed to storage, before sampling
+    pub total_stored: u64, // messages actually written
     pub files_created: u32,
     pub disk_usage: u64,
     pub last_rotation: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub duplicates_skipped: u64, // messages whose id was already in the current file
+}
+
+/// A chat file as the file storage writes it, compressed or not, going by its
+/// extension. Names come from the filename template, so they can't be relied on.
+pub(crate) fn is_chat_file(path: &Path) -> bool {
+    let data_path = if Compression::from_path(path).is_some() { path.with_extension("") } else { path.to_path_buf() };
+    data_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ["jsonl", "csv", "tsv", "avro"].contains(&ext))
+}
+
+/// What `setup_rotation` found in the output directory from earlier runs
+#[derive(Default)]
+struct RotationScan {
+    current_files: HashMap<String, FileInfo>, // newest data file per streamer
+    day_counts: HashMap<PathBuf, DayCount>,
+    files_found: u32,
+    disk_usage: u64,
+}
+
+/// Running total kept in `count.json` inside each streamer-day directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayCount {
+    pub streamer: String,
+    pub date: String,
+    pub message_count: u64,
+    pub last_write: DateTime<Utc>,
 }
 
+/// Name of the per streamer-day counter file
+pub const DAY_COUNT_FILE: &str = "count.json";
+
 #[derive(Debug, Clone)]
 pub struct FileInfo {
     pub path: PathBuf,
     pub size: u64,
     pub created: DateTime<Utc>,
     pub message_count: u64,
+    writer: Option<SharedFile>,   // opened on first write, kept until rotation
+    seen_ids: HashSet<String>,    // message ids written to this file, for dedup
+    day: NaiveDate,               // local date the file belongs to, a later one starts a new file
+}
+
+/// Most message ids remembered per file. A file this busy starts over rather
+/// than growing the set further; rotation normally clears it well before.
+const MAX_SEEN_IDS: usize = 200_000;
+
+/// Longest gap between retention sweeps
+const RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How often the running disk usage total is checked against a full walk,
+/// catching anything changed behind the manager's back
+const DISK_USAGE_RECONCILE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Buffered handle on a streamer's current file
+#[derive(Debug)]
+struct OpenFile {
+    writer: BufWriter<File>,
+    last_flush: std::time::Instant,
+    pending_path: Option<PathBuf>, // where the temp file goes once its first batch is written
+}
+
+type SharedFile = Arc<std::sync::Mutex<OpenFile>>;
+
+/// `chat_x.jsonl` -> `chat_x.jsonl.tmp`, where a new file is written before it's renamed into place
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+impl OpenFile {
+    /// Open `path` for appending, creating its directory if needed. A file that
+    /// doesn't exist yet is started as a temp sibling instead, so the header
+    /// and first batch show up together or not at all.
+    /// The flag is true when the file is empty and still needs a header.
+    fn open(path: &Path) -> Result<(Self, bool)> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to create directory: {}", e)))?;
+        }
+
+        let (file, pending_path) = if path.exists() {
+            let file = OpenOptions::new()
+                .append(true)
+                .open(path)
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to open file: {}", e)))?;
+            (file, None)
+        } else {
+            let file = File::create(temp_path(path))
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to create file: {}", e)))?;
+            (file, Some(path.to_path_buf()))
+        };
+        let is_empty = file.metadata().map(|metadata| metadata.len() == 0).unwrap_or(true);
+
+        Ok((Self { writer: BufWriter::new(file), last_flush: std::time::Instant::now(), pending_path }, is_empty))
+    }
+
+    /// Move a freshly written temp file to its real name. The handle stays
+    /// open across the rename, so later appends land in the renamed file.
+    fn publish(&mut self) -> Result<()> {
+        let Some(path) = self.pending_path.take() else {
+            return Ok(());
+        };
+        self.flush(false)?;
+        fs::rename(temp_path(&path), &path)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to move {} into place: {}", path.display(), e)))?;
+        Ok(())
+    }
+
+    /// Hand buffered bytes to the OS, and to the disk too when `sync` is set
+    fn flush(&mut self, sync: bool) -> Result<()> {
+        self.writer.flush()
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to flush file: {}", e)))?;
+        if sync {
+            self.writer.get_ref().sync_all()
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to sync file: {}", e)))?;
+        }
+        self.last_flush = std::time::Instant::now();
+        Ok(())
+    }
+}
+
+impl Drop for OpenFile {
+    /// The last handle on a file can go away without an explicit flush, e.g.
+    /// when the manager is dropped; BufWriter would lose any error silently
+    fn drop(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            warn!("Failed to flush buffered chat on close: {}", e);
+        }
+    }
+}
+
+/// Where a batch is written, from `file_writer`
+struct FileTarget {
+    writer: SharedFile,
+    path: PathBuf,
+    day: NaiveDate,
+    needs_header: bool,
+    is_new_file: bool,
+}
+
+fn lock_file(file: &SharedFile) -> std::sync::MutexGuard<'_, OpenFile> {
+    file.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 #[async_trait]
@@ -34,17 +196,36 @@ pub trait StorageManager {
     async fn store_messages(&self, messages: Vec<ChatMessage>) -> Result<()>;
     async fn setup_rotation(&self) -> Result<()>;
     async fn get_storage_stats(&self) -> Result<StorageStats>;
+
+    /// Push open output files, buffered writes included, to disk before
+    /// shutdown, returning how many there were
+    async fn flush_all(&self) -> Result<usize> {
+        Ok(0)
+    }
 }
 
 pub trait OutputFormatter {
     fn format_messages(&self, messages: &[ChatMessage]) -> Result<String>;
     fn file_extension(&self) -> &str;
     fn header(&self) -> Option<String>;
+
+    /// Bytes written for a batch; binary formats override this
+    fn format_bytes(&self, messages: &[ChatMessage]) -> Result<Vec<u8>> {
+        Ok(self.format_messages(messages)?.into_bytes())
+    }
+
+    /// Bytes written at the start of a new file; binary formats override this
+    fn header_bytes(&self) -> Option<Vec<u8>> {
+        self.header().map(|header| format!("{}\n", header).into_bytes())
+    }
 }
 
 pub struct JsonFormatter;
 pub struct CsvFormatter {
     columns: Vec<String>,
+    timezone: Option<Tz>, // None keeps timestamps in UTC
+    delimiter: char,      // ',' for csv, '\t' for tsv
+    excel_compat: bool,   // BOM before the header and CRLF line endings
 }
 
 impl OutputFormatter for JsonFormatter {
@@ -70,7 +251,34 @@ impl OutputFormatter for JsonFormatter {
 
 impl CsvFormatter {
     pub fn new(columns: Vec<String>) -> Self {
-        Self { columns }
+        Self { columns, timezone: None, delimiter: ',', excel_compat: false }
+    }
+
+    /// Tab separated, written to `.tsv` files
+    pub fn tsv(columns: Vec<String>) -> Self {
+        Self::new(columns).with_delimiter('\t')
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Start new files with a UTF-8 BOM and end rows with CRLF, which is what
+    /// Excel on Windows needs to show emoji and split rows properly
+    pub fn with_excel_compat(mut self) -> Self {
+        self.excel_compat = true;
+        self
+    }
+
+    fn line_ending(&self) -> &'static str {
+        if self.excel_compat { "\r\n" } else { "\n" }
+    }
+
+    /// Render the timestamp column in the given timezone instead of UTC
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
     }
 
     pub fn default_columns() -> Vec<String> {
@@ -89,8 +297,8 @@ impl CsvFormatter {
         ]
     }
 
-    fn escape_csv_field(field: &str) -> String {
-        if field.contains(',') || field.contains('"') || field.contains('\n') {
+    fn escape_csv_field(&self, field: &str) -> String {
+        if field.contains(self.delimiter) || field.contains('"') || field.contains('\n') {
             format!("\"{}\"", field.replace('"', "\"\""))
         } else {
             field.to_string()
@@ -100,7 +308,10 @@ impl CsvFormatter {
     fn extract_field_value(&self, message: &ChatMessage, column: &str) -> String {
         match column {
             "id" => message.id.clone(),
-            "timestamp" => message.timestamp.to_rfc3339(),
+            "timestamp" => match self.timezone {
+                Some(tz) => message.timestamp.with_timezone(&tz).to_rfc3339(),
+                None => message.timestamp.to_rfc3339(),
+            },
             "streamer" => message.streamer.clone(),
             "username" => message.user.username.clone(),
             "display_name" => message.user.display_name.clone(),
@@ -110,6 +321,10 @@ impl CsvFormatter {
             "viewer_count" => message.context.viewer_count.map_or(String::new(), |v| v.to_string()),
             "game_category" => message.context.game_category.as_deref().unwrap_or("").to_string(),
             "stream_title" => message.context.stream_title.as_deref().unwrap_or("").to_string(),
+            "message_type" => message.message_type.as_str().to_string(),
+            "bits" => message.bits.map_or(String::new(), |bits| bits.to_string()),
+            "mentions" => message.mentions.join(";"),
+            "reply_to" => message.reply_to.as_deref().unwrap_or("").to_string(),
             _ => String::new(),
         }
     }
@@ -123,21 +338,39 @@ impl OutputFormatter for CsvFormatter {
             let mut row = Vec::new();
             for column in &self.columns {
                 let value = self.extract_field_value(message, column);
-                row.push(Self::escape_csv_field(&value));
+                row.push(self.escape_csv_field(&value));
             }
-            output.push_str(&row.join(","));
-            output.push('\n');
+            output.push_str(&row.join(&self.delimiter.to_string()));
+            output.push_str(self.line_ending());
         }
         
         Ok(output)
     }
 
     fn file_extension(&self) -> &str {
-        "csv"
+        if self.delimiter == '\t' { "tsv" } else { "csv" }
     }
 
     fn header(&self) -> Option<String> {
-        Some(self.columns.join(","))
+        Some(self.columns.join(&self.delimiter.to_string()))
+    }
+
+    // only ever written at the start of a new file, so the BOM can't end up mid-file
+    fn header_bytes(&self) -> Option<Vec<u8>> {
+        let bom = if self.excel_compat { "\u{feff}" } else { "" };
+        self.header().map(|header| format!("{}{}{}", bom, header, self.line_ending()).into_bytes())
+    }
+}
+
+/// The storage backend `[output]` asks for: a SQLite database for
+/// `format = "sqlite"`, rotating chat files otherwise
+pub fn storage_from_config(output: &OutputConfig) -> Result<Arc<dyn StorageManager + Send + Sync>> {
+    match output.format.as_str() {
+        #[cfg(feature = "sqlite")]
+        "sqlite" => Ok(Arc::new(SqliteStorageManager::from_config(output)?)),
+        #[cfg(not(feature = "sqlite"))]
+        "sqlite" => Err(ScrapingError::ConfigError("format \"sqlite\" needs the sqlite feature, which this build left out".to_string()).into()),
+        _ => Ok(Arc::new(FileStorageManager::from_config(output)?)),
     }
 }
 
@@ -145,8 +378,23 @@ pub struct FileStorageManager {
     output_dir: PathBuf,
     formatter: Box<dyn OutputFormatter + Send + Sync>,
     rotation_size: u64,
-    rotation_time: chrono::Duration,
+    rotation_time: Option<chrono::Duration>, // None never rotates by age
+    rotation_overrides: HashMap<String, (u64, Option<chrono::Duration>)>, // lowercase streamer -> size and time
+    timezone: Tz,
+    filename_template: FilenameTemplate,
+    durability: Durability,
+    write_permits: Arc<Semaphore>, // streamers written in parallel
+    sample_rate: f64,              // share of messages kept, 1.0 keeps everything
+    sampler: Arc<std::sync::Mutex<StdRng>>,
+    compression: Option<Compression>, // applied to files once they rotate out
+    compression_tasks: Arc<std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>>,
+    flush_interval: Duration, // how long writes may sit in a buffer, zero flushes every write
+    flush_task: OnceLock<tokio::task::JoinHandle<()>>,
+    retention: Option<Duration>, // files older than this get deleted, None keeps everything
+    retention_task: OnceLock<tokio::task::JoinHandle<()>>,
+    disk_usage_task: OnceLock<tokio::task::JoinHandle<()>>, // reconciles the running disk usage total
     current_files: Arc<Mutex<HashMap<String, FileInfo>>>,
+    day_counts: Arc<Mutex<HashMap<PathBuf, DayCount>>>, // keyed by streamer-day directory
     stats: Arc<Mutex<StorageStats>>,
 }
 
@@ -159,14 +407,14 @@ impl FileStorageManager {
     ) -> Result<Self> {
         // Parse rotation size and time
         let rotation_size = FileConfigManager::parse_size_to_bytes(&rotation_size_str)?;
-        let rotation_time = chrono::Duration::from_std(
-            FileConfigManager::parse_time_to_duration(&rotation_time_str)?
-        ).map_err(|e| ScrapingError::ConfigError(format!("Invalid rotation time: {}", e)))?;
+        let rotation_time = Self::parse_rotation_time(&rotation_time_str)?;
 
         // Create formatter based on format type
         let formatter: Box<dyn OutputFormatter + Send + Sync> = match format.as_str() {
             "json" => Box::new(JsonFormatter),
             "csv" => Box::new(CsvFormatter::new(CsvFormatter::default_columns())),
+            "tsv" => Box::new(CsvFormatter::tsv(CsvFormatter::default_columns())),
+            "avro" => Box::new(AvroFormatter::new()),
             _ => return Err(ScrapingError::ConfigError(format!("Unsupported format: {}", format)).into()),
         };
 
@@ -175,12 +423,29 @@ impl FileStorageManager {
             formatter,
             rotation_size,
             rotation_time,
+            rotation_overrides: HashMap::new(),
+            timezone: Tz::UTC,
+            filename_template: FilenameTemplate::default(),
+            durability: Durability::None,
+            write_permits: Arc::new(Semaphore::new(1)),
+            sample_rate: 1.0,
+            sampler: Arc::new(std::sync::Mutex::new(StdRng::from_entropy())),
+            compression: None,
+            compression_tasks: Arc::new(std::sync::Mutex::new(Vec::new())),
+            flush_interval: Duration::ZERO,
+            flush_task: OnceLock::new(),
+            retention: None,
+            retention_task: OnceLock::new(),
+            disk_usage_task: OnceLock::new(),
             current_files: Arc::new(Mutex::new(HashMap::new())),
+            day_counts: Arc::new(Mutex::new(HashMap::new())),
             stats: Arc::new(Mutex::new(StorageStats {
-                total_messages: 0,
+                total_seen: 0,
+                total_stored: 0,
                 files_created: 0,
                 disk_usage: 0,
                 last_rotation: None,
+                duplicates_skipped: 0,
             })),
         })
     }
@@ -192,9 +457,7 @@ impl FileStorageManager {
         rotation_time_str: String,
     ) -> Result<Self> {
         let rotation_size = FileConfigManager::parse_size_to_bytes(&rotation_size_str)?;
-        let rotation_time = chrono::Duration::from_std(
-            FileConfigManager::parse_time_to_duration(&rotation_time_str)?
-        ).map_err(|e| ScrapingError::ConfigError(format!("Invalid rotation time: {}", e)))?;
+        let rotation_time = Self::parse_rotation_time(&rotation_time_str)?;
 
         let formatter = Box::new(CsvFormatter::new(columns));
 
@@ -203,323 +466,1168 @@ impl FileStorageManager {
             formatter,
             rotation_size,
             rotation_time,
+            rotation_overrides: HashMap::new(),
+            timezone: Tz::UTC,
+            filename_template: FilenameTemplate::default(),
+            durability: Durability::None,
+            write_permits: Arc::new(Semaphore::new(1)),
+            sample_rate: 1.0,
+            sampler: Arc::new(std::sync::Mutex::new(StdRng::from_entropy())),
+            compression: None,
+            compression_tasks: Arc::new(std::sync::Mutex::new(Vec::new())),
+            flush_interval: Duration::ZERO,
+            flush_task: OnceLock::new(),
+            retention: None,
+            retention_task: OnceLock::new(),
+            disk_usage_task: OnceLock::new(),
             current_files: Arc::new(Mutex::new(HashMap::new())),
+            day_counts: Arc::new(Mutex::new(HashMap::new())),
             stats: Arc::new(Mutex::new(StorageStats {
-                total_messages: 0,
+                total_seen: 0,
+                total_stored: 0,
                 files_created: 0,
                 disk_usage: 0,
                 last_rotation: None,
+                duplicates_skipped: 0,
             })),
         })
     }
 
-    async fn get_file_path(&self, streamer: &str, timestamp: DateTime<Utc>) -> PathBuf {
-        let date_str = timestamp.format("%Y-%m-%d").to_string();
-        let time_str = timestamp.format("%H-%M-%S").to_string();
-        
-        // Create directory structure: output_dir/streamer/YYYY-MM-DD/
-        let dir_path = self.output_dir
-            .join(streamer)
-            .join(&date_str);
-        
-        // Create filename with timestamp and extension
-        let filename = format!("chat_{}_{}.{}", 
-            date_str, 
-            time_str, 
-            self.formatter.file_extension()
-        );
-        
-        dir_path.join(filename)
-    }
+    /// Build a manager from the `[output]` section, including its timezone settings
+    pub fn from_config(output: &OutputConfig) -> Result<Self> {
+        let timezone: Tz = output.timezone.parse()
+            .map_err(|e| ScrapingError::ConfigError(format!("Invalid timezone '{}': {}", output.timezone, e)))?;
+
+        let mut manager = Self::new(
+            output.directory.clone(),
+            output.format.clone(),
+            output.rotation_size.clone(),
+            output.rotation_time.clone(),
+        )?
+        .with_timezone(timezone)
+        .with_filename_template(match output.filename_template {
+            Some(ref template) => FilenameTemplate::parse(template)?,
+            None => FilenameTemplate::default(),
+        })
+        .with_durability(output.durability)
+        .with_write_concurrency(output.max_write_concurrency)
+        .with_sampling(output.sample_rate, output.sample_seed)
+        .with_compression(output.compression.as_deref().map(Compression::parse).transpose()?)
+        .with_flush_interval(FileConfigManager::parse_time_to_duration(&output.flush_interval)?)
+        .with_retention(output.retention.as_deref().map(FileConfigManager::parse_time_to_duration).transpose()?);
+
+        for (streamer, rotation) in &output.rotation_overrides {
+            manager = manager.with_rotation_override(streamer, rotation.rotation_size.as_deref(), rotation.rotation_time.as_deref())?;
+        }
 
-    async fn ensure_directory_exists(&self, path: &Path) -> Result<()> {
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| ScrapingError::StorageError(format!("Failed to create directory: {}", e)))?;
+        let is_csv = matches!(output.format.as_str(), "csv" | "tsv");
+        if is_csv && (output.csv_local_timestamps || output.csv_excel_compat) {
+            let mut formatter = match output.format.as_str() {
+                "tsv" => CsvFormatter::tsv(CsvFormatter::default_columns()),
+                _ => CsvFormatter::new(CsvFormatter::default_columns()),
+            };
+            if output.csv_local_timestamps {
+                formatter = formatter.with_timezone(timezone);
+            }
+            if output.csv_excel_compat {
+                formatter = formatter.with_excel_compat();
+            }
+            manager.formatter = Box::new(formatter);
         }
-        Ok(())
+
+        Ok(manager)
     }
 
-    async fn should_rotate_file(&self, file_info: &FileInfo) -> bool {
-        // Check size-based rotation
-        if file_info.size >= self.rotation_size {
-            debug!("File {} needs rotation due to size: {} bytes", file_info.path.display(), file_info.size);
-            return true;
-        }
+    /// Use this timezone for the date folder and file name; stored timestamps stay UTC
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
 
-        // Check time-based rotation
-        let now = Utc::now();
-        let age = now.signed_duration_since(file_info.created);
-        if age >= self.rotation_time {
-            debug!("File {} needs rotation due to age: {} minutes", 
-                file_info.path.display(), 
-                age.num_minutes()
-            );
-            return true;
-        }
+    /// Lay chat files out with this template instead of `<streamer>/<date>/chat_<date>_<time>.<ext>`
+    pub fn with_filename_template(mut self, filename_template: FilenameTemplate) -> Self {
+        self.filename_template = filename_template;
+        self
+    }
 
-        false
+    /// When to fsync chat files; the default only flushes
+    pub fn with_durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
     }
 
-    async fn write_to_file(&self, file_path: &Path, content: &str, is_new_file: bool) -> Result<u64> {
-        self.ensure_directory_exists(file_path).await?;
+    /// Write up to this many streamers' files at once; 1 keeps writes sequential
+    pub fn with_write_concurrency(mut self, max_write_concurrency: usize) -> Self {
+        self.write_permits = Arc::new(Semaphore::new(max_write_concurrency.max(1)));
+        self
+    }
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(file_path)
-            .map_err(|e| ScrapingError::StorageError(format!("Failed to open file: {}", e)))?;
+    /// Keep each message with probability `sample_rate`. A fixed `seed` makes the
+    /// kept subset reproducible for the same input order
+    pub fn with_sampling(mut self, sample_rate: f64, seed: Option<u64>) -> Self {
+        self.sample_rate = sample_rate.clamp(0.0, 1.0);
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        self.sampler = Arc::new(std::sync::Mutex::new(rng));
+        self
+    }
 
-        let mut bytes_written = 0;
+    /// Compress files in the background once they rotate out; `None` leaves them as written
+    pub fn with_compression(mut self, compression: Option<Compression>) -> Self {
+        self.compression = compression;
+        self
+    }
 
-        // Write header for new files if formatter provides one
-        if is_new_file {
-            if let Some(header) = self.formatter.header() {
-                let header_line = format!("{}\n", header);
-                file.write_all(header_line.as_bytes())
-                    .map_err(|e| ScrapingError::StorageError(format!("Failed to write header: {}", e)))?;
-                bytes_written += header_line.len() as u64;
-            }
-        }
+    /// Buffer writes for up to `flush_interval` before handing them to the OS.
+    /// Zero, the default, flushes after every write.
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
 
-        // Write content
-        file.write_all(content.as_bytes())
-            .map_err(|e| ScrapingError::StorageError(format!("Failed to write content: {}", e)))?;
-        bytes_written += content.len() as u64;
+    /// "unlimited" parses to None so files never rotate by age
+    fn parse_rotation_time(rotation_time_str: &str) -> Result<Option<chrono::Duration>> {
+        FileConfigManager::parse_rotation_time(rotation_time_str)?
+            .map(chrono::Duration::from_std)
+            .transpose()
+            .map_err(|e| ScrapingError::ConfigError(format!("Invalid rotation time: {}", e)).into())
+    }
 
-        file.flush()
-            .map_err(|e| ScrapingError::StorageError(format!("Failed to flush file: {}", e)))?;
+    /// Rotate `streamer`'s files at their own size and/or age, e.g. sooner for a
+    /// very busy channel. Whatever isn't given stays at the global value.
+    pub fn with_rotation_override(mut self, streamer: &str, rotation_size_str: Option<&str>, rotation_time_str: Option<&str>) -> Result<Self> {
+        let rotation_size = match rotation_size_str {
+            Some(size) => FileConfigManager::parse_size_to_bytes(size)?,
+            None => self.rotation_size,
+        };
+        let rotation_time = match rotation_time_str {
+            Some(time) => Self::parse_rotation_time(time)
+                .map_err(|e| ScrapingError::ConfigError(format!("Invalid rotation time for {}: {}", streamer, e)))?,
+            None => self.rotation_time,
+        };
+        self.rotation_overrides.insert(streamer.to_lowercase(), (rotation_size, rotation_time));
+        Ok(self)
+    }
 
-        Ok(bytes_written)
+    /// Delete output files last modified longer ago than `retention`.
+    /// The sweep starts with `setup_rotation`.
+    pub fn with_retention(mut self, retention: Option<Duration>) -> Self {
+        self.retention = retention;
+        self
     }
 
-    async fn update_file_info(&self, streamer: &str, file_path: PathBuf, bytes_written: u64, message_count: u64) {
-        let mut current_files = self.current_files.lock().await;
-        
-        match current_files.get_mut(streamer) {
-            Some(file_info) => {
-                file_info.size += bytes_written;
-                file_info.message_count += message_count;
+    /// Sweep expired files every few minutes, or more often for very short retention
+    fn ensure_retention_task(&self) {
+        let Some(retention) = self.retention else {
+            return;
+        };
+        let output_dir = self.output_dir.clone();
+        let current_files = Arc::downgrade(&self.current_files);
+        let stats = self.stats.clone();
+        self.retention_task.get_or_init(|| {
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(retention.min(RETENTION_SWEEP_INTERVAL));
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                loop {
+                    ticker.tick().await;
+                    let Some(current_files) = current_files.upgrade() else {
+                        break;
+                    };
+                    if let Err(e) = Self::remove_expired(&output_dir, retention, &current_files, &stats).await {
+                        warn!("Retention sweep failed: {}", e);
+                    }
+                }
+            })
+        });
+    }
+
+    /// Re-walk the output directory now and then to correct the running total
+    fn ensure_disk_usage_task(&self) {
+        let output_dir = self.output_dir.clone();
+        let stats = Arc::downgrade(&self.stats);
+        self.disk_usage_task.get_or_init(|| {
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(DISK_USAGE_RECONCILE_INTERVAL);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                ticker.tick().await; // setup_rotation just measured it
+                loop {
+                    ticker.tick().await;
+                    let dir = output_dir.clone();
+                    let disk_usage = match tokio::task::spawn_blocking(move || Self::calculate_directory_size(&dir)).await {
+                        Ok(disk_usage) => disk_usage,
+                        Err(e) => {
+                            warn!("Disk usage task failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let Some(stats) = stats.upgrade() else {
+                        break;
+                    };
+                    let mut stats = stats.lock().await;
+                    if stats.disk_usage != disk_usage {
+                        debug!("Disk usage drifted from {} to {} bytes", stats.disk_usage, disk_usage);
+                    }
+                    stats.disk_usage = disk_usage;
+                }
+            })
+        });
+    }
+
+    /// Run a retention sweep now, returning how many files were deleted
+    pub async fn enforce_retention(&self) -> Result<usize> {
+        match self.retention {
+            Some(retention) => Self::remove_expired(&self.output_dir, retention, &self.current_files, &self.stats).await,
+            None => Ok(0),
+        }
+    }
+
+    async fn remove_expired(
+        output_dir: &Path,
+        retention: Duration,
+        current_files: &Mutex<HashMap<String, FileInfo>>,
+        stats: &Mutex<StorageStats>,
+    ) -> Result<usize> {
+        let Some(cutoff) = std::time::SystemTime::now().checked_sub(retention) else {
+            return Ok(0);
+        };
+        // files a streamer is writing to are never expired, whatever their mtime says
+        let open_paths: HashSet<PathBuf> = current_files.lock().await
+            .values()
+            .flat_map(|file_info| [file_info.path.clone(), temp_path(&file_info.path)])
+            .collect();
+
+        let dir = output_dir.to_path_buf();
+        let (removed, disk_usage) = tokio::task::spawn_blocking(move || {
+            let mut removed = Vec::new();
+            Self::remove_expired_files(&dir, cutoff, &open_paths, &mut removed);
+            Self::remove_drained_dirs(&dir, &removed);
+            (removed, Self::calculate_directory_size(&dir))
+        })
+        .await
+        .map_err(|e| ScrapingError::StorageError(format!("Retention task failed: {}", e)))?;
+
+        for path in &removed {
+            info!("Deleted {} past the retention period", path.display());
+        }
+        stats.lock().await.disk_usage = disk_usage;
+        Ok(removed.len())
+    }
+
+    fn remove_expired_files(dir: &Path, cutoff: std::time::SystemTime, open_paths: &HashSet<PathBuf>, removed: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                Self::remove_expired_files(&path, cutoff, open_paths, removed);
+                continue;
             }
-            None => {
-                current_files.insert(streamer.to_string(), FileInfo {
-                    path: file_path,
-                    size: bytes_written,
-                    created: Utc::now(),
-                    message_count,
-                });
+            let is_expired = metadata.modified().is_ok_and(|modified| modified < cutoff);
+            if !is_expired || open_paths.contains(&path) || !Self::is_chat_output(&path) {
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => removed.push(path),
+                Err(e) => warn!("Failed to delete expired {}: {}", path.display(), e),
             }
         }
     }
 
-    async fn rotate_file_if_needed(&self, streamer: &str) -> Result<()> {
-        let mut current_files = self.current_files.lock().await;
-        
-        if let Some(file_info) = current_files.get(streamer) {
-            if self.should_rotate_file(file_info).await {
-                info!("Rotating file for streamer: {}", streamer);
-                current_files.remove(streamer);
-                
-                let mut stats = self.stats.lock().await;
-                stats.last_rotation = Some(Utc::now());
+    /// Chat files this manager writes, compressed or not, and the hourly
+    /// summaries. Retention leaves everything else in the output directory alone.
+    fn is_chat_output(path: &Path) -> bool {
+        let is_chat = is_chat_file(path);
+        let is_summary = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("summary_") && name.ends_with(".json"));
+        is_chat || is_summary
+    }
+
+    /// After a retention sweep, drop the counters of days it emptied and then
+    /// the day and streamer directories left with nothing in them
+    fn remove_drained_dirs(output_dir: &Path, removed: &[PathBuf]) {
+        let mut streamer_dirs = HashSet::new();
+        for parent in removed.iter().filter_map(|path| path.parent()) {
+            if parent.parent() == Some(output_dir) {
+                streamer_dirs.insert(parent.to_path_buf()); // a summary
+                continue;
+            }
+            let Some(streamer_dir) = parent.parent().filter(|streamer_dir| streamer_dir.parent() == Some(output_dir)) else {
+                continue;
+            };
+            let only_count_left = fs::read_dir(parent)
+                .is_ok_and(|mut entries| entries.all(|entry| entry.is_ok_and(|entry| entry.file_name() == DAY_COUNT_FILE)));
+            if only_count_left {
+                let _ = fs::remove_file(parent.join(DAY_COUNT_FILE));
             }
+            streamer_dirs.insert(streamer_dir.to_path_buf());
+        }
+        for streamer_dir in streamer_dirs {
+            Self::remove_empty_day_dirs(&streamer_dir);
         }
-        
-        Ok(())
     }
 
-    async fn calculate_disk_usage(&self) -> u64 {
-        let mut total_size = 0;
-        
-        if let Ok(entries) = fs::read_dir(&self.output_dir) {
-            for entry in entries.flatten() {
-                if let Ok(metadata) = entry.metadata() {
-                    if metadata.is_file() {
-                        total_size += metadata.len();
-                    } else if metadata.is_dir() {
-                        total_size += self.calculate_directory_size(&entry.path());
+    /// Flush files that have gone quiet, so a slow channel's messages don't sit
+    /// in memory until its next write. Started on the first write.
+    fn ensure_flush_task(&self) {
+        if self.flush_interval.is_zero() {
+            return;
+        }
+        let flush_interval = self.flush_interval;
+        let current_files = Arc::downgrade(&self.current_files);
+        self.flush_task.get_or_init(|| {
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(flush_interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                loop {
+                    ticker.tick().await;
+                    // the manager is gone once its file map is
+                    let Some(current_files) = current_files.upgrade() else {
+                        break;
+                    };
+                    let writers = Self::open_writers(&current_files).await;
+                    drop(current_files);
+
+                    let flushed = tokio::task::spawn_blocking(move || {
+                        for writer in writers {
+                            let mut file = lock_file(&writer);
+                            if file.last_flush.elapsed() >= flush_interval {
+                                file.flush(false)?;
+                            }
+                        }
+                        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+                    }).await;
+                    match flushed {
+                        Ok(Err(e)) => warn!("Periodic flush failed: {}", e),
+                        Err(e) => warn!("Periodic flush task failed: {}", e),
+                        Ok(Ok(())) => {}
                     }
                 }
+            })
+        });
+    }
+
+    async fn open_writers(current_files: &Mutex<HashMap<String, FileInfo>>) -> Vec<SharedFile> {
+        current_files.lock().await
+            .values()
+            .filter_map(|file_info| file_info.writer.clone())
+            .collect()
+    }
+
+    /// Wait for every compression started by a rotation so far
+    pub async fn wait_for_compression(&self) {
+        let tasks: Vec<_> = self.compression_tasks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .drain(..)
+            .collect();
+        for task in tasks {
+            if let Err(e) = task.await {
+                warn!("Compression task failed: {}", e);
             }
         }
-        
-        total_size
     }
 
-    fn calculate_directory_size(&self, dir_path: &Path) -> u64 {
-        let mut total_size = 0;
-        
-        if let Ok(entries) = fs::read_dir(dir_path) {
-            for entry in entries.flatten() {
-                if let Ok(metadata) = entry.metadata() {
-                    if metadata.is_file() {
-                        total_size += metadata.len();
-                    } else if metadata.is_dir() {
-                        total_size += self.calculate_directory_size(&entry.path());
-                    }
-                }
+    /// Compress a file that's done being written, off the write path, when
+    /// compression is on. The next write goes to a new file anyway.
+    fn compress_in_background(&self, path: PathBuf) {
+        let Some(compression) = self.compression else {
+            return;
+        };
+        let task = tokio::spawn(Self::compress_rotated_file(
+            compression,
+            path,
+            self.current_files.clone(),
+            self.stats.clone(),
+        ));
+        let mut tasks = self.compression_tasks.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(task);
+    }
+
+    /// Replace a rotated file with its compressed copy, unless it was reopened
+    /// or written to while compressing, in which case it stays as it is
+    async fn compress_rotated_file(
+        compression: Compression,
+        path: PathBuf,
+        current_files: Arc<Mutex<HashMap<String, FileInfo>>>,
+        stats: Arc<Mutex<StorageStats>>,
+    ) {
+        let (temp_path, original_len) = match compression.compress_to_temp(&path).await {
+            Ok(compressed) => compressed,
+            Err(e) => {
+                warn!("Leaving {} uncompressed: {}", path.display(), e);
+                return;
+            }
+        };
+
+        // holding the lock keeps the file from being picked up again while it's swapped
+        let current_files = current_files.lock().await;
+        let is_current = current_files.values().any(|file_info| file_info.path == path);
+        let has_grown = fs::metadata(&path).map_or(true, |metadata| metadata.len() != original_len);
+        if is_current || has_grown {
+            debug!("{} was written to again, not compressing it", path.display());
+            let _ = fs::remove_file(&temp_path);
+            return;
+        }
+
+        match compression.commit(&path, &temp_path) {
+            Ok(compressed_path) => {
+                debug!("Compressed {}", compressed_path.display());
+                let compressed_len = fs::metadata(&compressed_path).map_or(0, |metadata| metadata.len());
+                let mut stats = stats.lock().await;
+                stats.disk_usage = stats.disk_usage.saturating_sub(original_len) + compressed_len;
+            }
+            Err(e) => {
+                warn!("Failed to compress {}: {}", path.display(), e);
+                let _ = fs::remove_file(&temp_path);
             }
         }
-        
-        total_size
     }
-}
 
-#[async_trait]
-impl StorageManager for FileStorageManager {
-    async fn store_messages(&self, messages: Vec<ChatMessage>) -> Result<()> {
-        if messages.is_empty() {
-            return Ok(());
+    /// Drop messages outside the sample, in arrival order so a seeded run repeats
+    fn sample(&self, messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        if self.sample_rate >= 1.0 {
+            return messages;
         }
+        let mut rng = self.sampler.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        messages.into_iter().filter(|_| rng.gen_bool(self.sample_rate)).collect()
+    }
 
-        debug!("Storing {} messages", messages.len());
+    async fn get_file_path(&self, streamer: &str, timestamp: DateTime<Utc>) -> PathBuf {
+        let local_time = timestamp.with_timezone(&self.timezone);
+        self.output_dir.join(self.filename_template.render(streamer, &local_time, self.formatter.file_extension()))
+    }
 
-        // Group messages by streamer
-        let mut messages_by_streamer: HashMap<String, Vec<ChatMessage>> = HashMap::new();
-        for message in messages {
-            messages_by_streamer
-                .entry(message.streamer.clone())
-                .or_insert_with(Vec::new)
-                .push(message);
+    /// Add `added` messages to the counter in `day_dir`, rewriting its `count.json`
+    async fn update_day_count(&self, streamer: &str, date: &str, day_dir: &Path, added: u64) -> Result<()> {
+        let mut day_counts = self.day_counts.lock().await;
+        if !day_counts.contains_key(day_dir) {
+            // first write to this day since startup, continue from what's on disk
+            let dir = day_dir.to_path_buf();
+            let on_disk = tokio::task::spawn_blocking(move || Self::read_day_count(&dir))
+                .await
+                .map_err(|e| ScrapingError::StorageError(format!("Count read task failed: {}", e)))?;
+            day_counts.insert(day_dir.to_path_buf(), on_disk.unwrap_or_else(|| DayCount {
+                streamer: streamer.to_string(),
+                date: date.to_string(),
+                message_count: 0,
+                last_write: Utc::now(),
+            }));
         }
+        let Some(day_count) = day_counts.get_mut(day_dir) else {
+            return Ok(());
+        };
+        day_count.message_count += added;
+        day_count.last_write = Utc::now();
 
-        // Process each streamer's messages
-        for (streamer, streamer_messages) in messages_by_streamer {
-            // Check if we need to rotate the current file
-            self.rotate_file_if_needed(&streamer).await?;
-
-            // Get or create file path
-            let timestamp = streamer_messages[0].timestamp;
-            let file_path = self.get_file_path(&streamer, timestamp).await;
-            
-            // Check if this is a new file
-            let current_files = self.current_files.lock().await;
-            let is_new_file = !current_files.contains_key(&streamer) || 
-                             current_files.get(&streamer).unwrap().path != file_path;
-            drop(current_files);
+        // the lock stays held so counts land on disk in order
+        let (dir, day_count) = (day_dir.to_path_buf(), day_count.clone());
+        let (old_len, new_len) = tokio::task::spawn_blocking(move || Self::write_day_count(&dir, &day_count))
+            .await
+            .map_err(|e| ScrapingError::StorageError(format!("Count write task failed: {}", e)))??;
 
-            // Format messages
-            let formatted_content = self.formatter.format_messages(&streamer_messages)?;
+        let mut stats = self.stats.lock().await;
+        stats.disk_usage = stats.disk_usage.saturating_sub(old_len) + new_len;
+        Ok(())
+    }
 
-            // Write to file
-            let bytes_written = self.write_to_file(&file_path, &formatted_content, is_new_file).await?;
+    /// Read a streamer-day `count.json`, if present and readable
+    pub fn read_day_count(day_dir: &Path) -> Option<DayCount> {
+        let content = fs::read_to_string(day_dir.join(DAY_COUNT_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
 
-            // Update file info and stats
-            self.update_file_info(&streamer, file_path, bytes_written, streamer_messages.len() as u64).await;
+    /// Write through a temp file and rename so readers never see a partial file.
+    /// Returns the old and new size of the count file.
+    fn write_day_count(day_dir: &Path, day_count: &DayCount) -> Result<(u64, u64)> {
+        let content = serde_json::to_string_pretty(day_count)
+            .map_err(|e| ScrapingError::StorageError(format!("Count serialization failed: {}", e)))?;
+        let path = day_dir.join(DAY_COUNT_FILE);
+        let temp_path = path.with_extension("json.tmp");
+        let old_len = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+        let new_len = content.len() as u64;
+
+        fs::write(&temp_path, content)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to write count file: {}", e)))?;
+        fs::rename(&temp_path, &path)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to replace count file: {}", e)))?;
+        Ok((old_len, new_len))
+    }
 
-            let mut stats = self.stats.lock().await;
-            stats.total_messages += streamer_messages.len() as u64;
-            if is_new_file {
-                stats.files_created += 1;
+    /// Messages in a data file, read a chunk at a time so big files aren't
+    /// loaded whole. Newlines inside quoted CSV fields don't end a record,
+    /// Avro files are counted from their block headers, and a compressed
+    /// `chat.jsonl.gz` is counted as the `chat.jsonl` it holds.
+    fn count_file_messages(path: &Path, has_header: bool) -> std::io::Result<u64> {
+        let compression = Compression::from_path(path);
+        let data_path = if compression.is_some() { path.with_extension("") } else { path.to_path_buf() };
+        let open = || -> std::io::Result<Box<dyn std::io::Read + Send>> {
+            let file = File::open(path)?;
+            match compression {
+                Some(compression) => compression.decoder(file),
+                None => Ok(Box::new(file)),
             }
-        }
-
-        debug!("Successfully stored messages");
-        Ok(())
-    }
+        };
 
-    async fn setup_rotation(&self) -> Result<()> {
-        info!("Setting up file rotation system");
-        
-        // Create output directory if it doesn't exist
-        fs::create_dir_all(&self.output_dir)
-            .map_err(|e| ScrapingError::StorageError(format!("Failed to create output directory: {}", e)))?;
+        if data_path.extension().is_some_and(|ext| ext == "avro") {
+            return avro::count_records(std::io::BufReader::new(open()?));
+        }
+        let is_csv = data_path.extension().is_some_and(|ext| ext == "csv" || ext == "tsv");
+        if !is_csv && data_path.extension().is_none_or(|ext| ext != "jsonl") {
+            return Ok(0); // binary formats have no lines to count
+        }
 
-        // Scan existing files and populate current_files
-        let mut current_files = self.current_files.lock().await;
-        let mut stats = self.stats.lock().await;
-        
-        if let Ok(entries) = fs::read_dir(&self.output_dir) {
-            for entry in entries.flatten() {
-                if entry.path().is_dir() {
-                    let streamer = entry.file_name().to_string_lossy().to_string();
-                    
-                    // Find the most recent file for this streamer
-                    if let Ok(streamer_entries) = fs::read_dir(entry.path()) {
-                        for date_entry in streamer_entries.flatten() {
-                            if date_entry.path().is_dir() {
-                                if let Ok(file_entries) = fs::read_dir(date_entry.path()) {
-                                    for file_entry in file_entries.flatten() {
-                                        if file_entry.path().is_file() {
-                                            if let Ok(metadata) = file_entry.metadata() {
-                                                let created = metadata.created()
-                                                    .map(|t| DateTime::<Utc>::from(t))
-                                                    .unwrap_or_else(|_| Utc::now());
-                                                
-                                                // Update or insert file info for most recent file
-                                                match current_files.get(&streamer) {
-                                                    Some(existing) if existing.created < created => {
-                                                        current_files.insert(streamer.clone(), FileInfo {
-                                                            path: file_entry.path(),
-                                                            size: metadata.len(),
-                                                            created,
-                                                            message_count: 0, // We don't track this for existing files
-                                                        });
-                                                    }
-                                                    None => {
-                                                        current_files.insert(streamer.clone(), FileInfo {
-                                                            path: file_entry.path(),
-                                                            size: metadata.len(),
-                                                            created,
-                                                            message_count: 0,
-                                                        });
-                                                    }
-                                                    _ => {} // Keep existing newer file
-                                                }
-                                                
-                                                stats.files_created += 1;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+        let mut reader = std::io::BufReader::new(open()?);
+        let (mut records, mut in_quotes, mut line_has_content) = (0u64, false, false);
+        loop {
+            let buffer = std::io::BufRead::fill_buf(&mut reader)?;
+            if buffer.is_empty() {
+                break;
+            }
+            for &byte in buffer {
+                match byte {
+                    b'"' if is_csv => {
+                        in_quotes = !in_quotes;
+                        line_has_content = true;
                     }
+                    b'\n' if !in_quotes => {
+                        records += line_has_content as u64;
+                        line_has_content = false;
+                    }
+                    b'\r' | b' ' | b'\t' => {}
+                    _ => line_has_content = true,
                 }
             }
+            let consumed = buffer.len();
+            std::io::BufRead::consume(&mut reader, consumed);
         }
+        // an unterminated last line still holds a message
+        records += line_has_content as u64;
 
-        info!("File rotation system initialized with {} existing files", current_files.len());
-        Ok(())
+        Ok(if has_header { records.saturating_sub(1) } else { records })
     }
 
-    async fn get_storage_stats(&self) -> Result<StorageStats> {
+    /// Rebuild a day's count from its data files, None if a file can't be counted
+    fn recount_day_dir(day_dir: &Path) -> Option<u64> {
+        let mut total = 0;
+        for entry in fs::read_dir(day_dir).ok()?.flatten() {
+            let path = entry.path();
+            // rotated and compressed files are counted through the decompressor
+            let data_path = if Compression::from_path(&path).is_some() { path.with_extension("") } else { path.clone() };
+            match data_path.extension().and_then(|ext| ext.to_str()) {
+                Some("jsonl") | Some("avro") => total += Self::count_file_messages(&path, false).ok()?,
+                Some("csv") | Some("tsv") => total += Self::count_file_messages(&path, true).ok()?,
+                Some("json") | Some("tmp") => {} // our own count file
+                _ => return None,
+            }
+        }
+        Some(total)
+    }
+
+    /// Load existing counters, recounting days whose `count.json` is missing or unreadable
+    fn reconcile_day_count(day_dir: &Path, streamer: &str) -> Option<DayCount> {
+        if let Some(day_count) = Self::read_day_count(day_dir) {
+            return Some(day_count);
+        }
+
+        let message_count = Self::recount_day_dir(day_dir)?;
+        let day_count = DayCount {
+            streamer: streamer.to_string(),
+            date: day_dir.file_name()?.to_string_lossy().to_string(),
+            message_count,
+            last_write: Utc::now(),
+        };
+        if let Err(e) = Self::write_day_count(day_dir, &day_count) {
+            warn!("Failed to rebuild count file in {}: {}", day_dir.display(), e);
+        }
+        Some(day_count)
+    }
+
+    async fn should_rotate_file(&self, streamer: &str, file_info: &FileInfo) -> bool {
+        let (rotation_size, rotation_time) = self.rotation_overrides
+            .get(&streamer.to_lowercase())
+            .copied()
+            .unwrap_or((self.rotation_size, self.rotation_time));
+
+        // Check size-based rotation, 0 means unlimited
+        if rotation_size > 0 && file_info.size >= rotation_size {
+            debug!("File {} needs rotation due to size: {} bytes", file_info.path.display(), file_info.size);
+            return true;
+        }
+
+        // Check time-based rotation
+        let now = Utc::now();
+        let age = now.signed_duration_since(file_info.created);
+        if rotation_time.is_some_and(|rotation_time| age >= rotation_time) {
+            debug!("File {} needs rotation due to age: {} minutes", 
+                file_info.path.display(), 
+                age.num_minutes()
+            );
+            return true;
+        }
+
+        false
+    }
+
+    /// Writer for the streamer's current file. Batches keep going to it until
+    /// it rotates or a batch from a later day arrives, then a new file is named
+    /// after `timestamp`.
+    async fn file_writer(&self, streamer: &str, timestamp: DateTime<Utc>) -> Result<FileTarget> {
+        let day = timestamp.with_timezone(&self.timezone).date_naive();
+        let mut current_files = self.current_files.lock().await;
+        if let Some(file_info) = current_files.get_mut(streamer).filter(|file_info| file_info.day >= day) {
+            if let Some(ref writer) = file_info.writer {
+                return Ok(FileTarget {
+                    writer: writer.clone(),
+                    path: file_info.path.clone(),
+                    day: file_info.day,
+                    needs_header: false,
+                    is_new_file: false,
+                });
+            }
+            // picked up by setup_rotation, not opened yet
+            let (open_file, needs_header, opened_path) = self.open_blocking(&file_info.path).await?;
+            let writer = Arc::new(std::sync::Mutex::new(open_file));
+            file_info.writer = Some(writer.clone());
+            let is_new_file = opened_path != file_info.path;
+            if is_new_file {
+                file_info.path = opened_path.clone();
+                file_info.size = 0;
+                file_info.created = Utc::now();
+                file_info.message_count = 0;
+            }
+            return Ok(FileTarget { writer, path: opened_path, day: file_info.day, needs_header, is_new_file });
+        }
+
+        // the date rolled over since the streamer's last file
+        if let Some(previous) = current_files.remove(streamer) {
+            self.finish_file(&previous).await?;
+            self.compress_in_background(previous.path);
+        }
+
+        let file_path = self.get_file_path(streamer, timestamp).await;
+        let (open_file, needs_header, opened_path) = self.open_blocking(&file_path).await?;
+        let writer = Arc::new(std::sync::Mutex::new(open_file));
+        current_files.insert(streamer.to_string(), FileInfo {
+            path: opened_path.clone(),
+            size: 0,
+            created: Utc::now(),
+            message_count: 0,
+            writer: Some(writer.clone()),
+            seen_ids: HashSet::new(),
+            day,
+        });
+        Ok(FileTarget { writer, path: opened_path, day, needs_header, is_new_file: true })
+    }
+
+    /// Open `file_path`, or a numbered sibling of it when the existing file
+    /// was started with a different header. Returns the path actually opened.
+    async fn open_blocking(&self, file_path: &Path) -> Result<(OpenFile, bool, PathBuf)> {
+        let file_path = file_path.to_path_buf();
+        let header = self.formatter.header_bytes();
+        tokio::task::spawn_blocking(move || {
+            let path = Self::appendable_path(&file_path, header.as_deref());
+            if path != file_path {
+                info!("{} was started with a different header, writing to {} instead", file_path.display(), path.display());
+            }
+            let (open_file, needs_header) = OpenFile::open(&path)?;
+            Ok((open_file, needs_header, path))
+        })
+        .await
+        .map_err(|e| ScrapingError::StorageError(format!("Open task failed: {}", e)))?
+    }
+
+    /// `path` if new writes can go on the end of it: it's missing, empty, or
+    /// starts with the header this formatter writes. Otherwise the first free
+    /// `<name>-<n>.<ext>` next to it. An Avro header carries the file's sync
+    /// marker, so a file another run started is never appended to.
+    fn appendable_path(path: &Path, header: Option<&[u8]>) -> PathBuf {
+        let can_append = |path: &Path| {
+            let Some(header) = header else {
+                return true;
+            };
+            let Ok(file) = File::open(path) else {
+                return true; // missing, OpenFile::open starts it
+            };
+            let mut existing = Vec::with_capacity(header.len());
+            if std::io::Read::read_to_end(&mut std::io::Read::take(file, header.len() as u64), &mut existing).is_err() {
+                return false;
+            }
+            existing.is_empty() || existing == header
+        };
+        if can_append(path) {
+            return path.to_path_buf();
+        }
+
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+        (1..)
+            .map(|n| path.with_file_name(format!("{}-{}{}", stem, n, extension)))
+            .find(|candidate| can_append(candidate))
+            .expect("some numbered sibling is free")
+    }
+
+    async fn flush_blocking(writer: SharedFile, sync: bool) -> Result<()> {
+        tokio::task::spawn_blocking(move || lock_file(&writer).flush(sync))
+            .await
+            .map_err(|e| ScrapingError::StorageError(format!("Flush task failed: {}", e)))?
+    }
+
+    async fn write_to_file(&self, writer: SharedFile, header: Option<Vec<u8>>, content: Vec<u8>) -> Result<u64> {
+        let durability = self.durability;
+        let flush_interval = self.flush_interval;
+
+        // blocking I/O off the runtime so parallel streamer writes actually overlap
+        tokio::task::spawn_blocking(move || {
+            Self::append_to_file(&mut lock_file(&writer), header.as_deref(), &content, durability, flush_interval)
+        })
+        .await
+        .map_err(|e| ScrapingError::StorageError(format!("Write task failed: {}", e)))?
+    }
+
+    fn append_to_file(
+        file: &mut OpenFile,
+        header: Option<&[u8]>,
+        content: &[u8],
+        durability: Durability,
+        flush_interval: Duration,
+    ) -> Result<u64> {
+        let mut bytes_written = 0;
+
+        if let Some(header) = header {
+            file.writer.write_all(header)
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to write header: {}", e)))?;
+            bytes_written += header.len() as u64;
+        }
+
+        // Write content
+        file.writer.write_all(content)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to write content: {}", e)))?;
+        bytes_written += content.len() as u64;
+
+        // anything older than the flush interval goes out now, `always` syncs every write
+        let sync = durability == Durability::Always;
+        if sync || file.last_flush.elapsed() >= flush_interval {
+            file.flush(sync)?;
+        }
+        file.publish()?;
+
+        Ok(bytes_written)
+    }
+
+    /// Rotate, write and account for one streamer's share of a batch
+    async fn store_streamer_messages(&self, streamer: &str, messages: Vec<ChatMessage>) -> Result<()> {
+        // Check if we need to rotate the current file
+        self.rotate_file_if_needed(streamer).await?;
+
+        // The streamer's current file, or a new one named after this batch
+        let target = self.file_writer(streamer, messages[0].timestamp).await?;
+
+        // Drop messages this file already has, e.g. lines the poller read twice
+        let received = messages.len();
+        let messages = self.skip_duplicates(streamer, messages).await;
+        let duplicates = (received - messages.len()) as u64;
+        if duplicates > 0 {
+            debug!("Skipping {} duplicate messages for {}", duplicates, streamer);
+            self.stats.lock().await.duplicates_skipped += duplicates;
+        }
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        // Format and write outside of any lock
+        let header = if target.needs_header { self.formatter.header_bytes() } else { None };
+        let formatted_content = self.formatter.format_bytes(&messages)?;
+        let bytes_written = self.write_to_file(target.writer, header, formatted_content).await?;
+
+        // Keep the streamer-day counter in step with what was written, when the
+        // layout has a streamer-day directory to keep it in
+        if let Some(day_dir) = target.path.parent().filter(|_| self.filename_template.has_day_dirs()) {
+            let date = target.day.format("%Y-%m-%d").to_string();
+            self.update_day_count(streamer, &date, day_dir, messages.len() as u64).await?;
+        }
+
+        // Update file info and stats
+        self.update_file_info(streamer, bytes_written, &messages).await;
+
+        let mut stats = self.stats.lock().await;
+        stats.total_stored += messages.len() as u64;
+        stats.disk_usage += bytes_written;
+        if target.is_new_file {
+            stats.files_created += 1;
+        }
+        Ok(())
+    }
+
+    /// Messages whose id isn't in the streamer's current file yet, or earlier in the batch
+    async fn skip_duplicates(&self, streamer: &str, messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        let current_files = self.current_files.lock().await;
+        let seen_ids = current_files.get(streamer).map(|file_info| &file_info.seen_ids);
+        let mut batch_ids = HashSet::new();
+        messages
+            .into_iter()
+            .filter(|message| {
+                !seen_ids.is_some_and(|seen_ids| seen_ids.contains(&message.id)) && batch_ids.insert(message.id.clone())
+            })
+            .collect()
+    }
+
+    async fn update_file_info(&self, streamer: &str, bytes_written: u64, messages: &[ChatMessage]) {
+        if let Some(file_info) = self.current_files.lock().await.get_mut(streamer) {
+            file_info.size += bytes_written;
+            file_info.message_count += messages.len() as u64;
+            if file_info.seen_ids.len() + messages.len() > MAX_SEEN_IDS {
+                file_info.seen_ids.clear();
+            }
+            file_info.seen_ids.extend(messages.iter().map(|message| message.id.clone()));
+        }
+    }
+
+    async fn rotate_file_if_needed(&self, streamer: &str) -> Result<()> {
+        let rotated = {
+            let mut current_files = self.current_files.lock().await;
+            match current_files.get(streamer) {
+                Some(file_info) if self.should_rotate_file(streamer, file_info).await => current_files.remove(streamer),
+                _ => None,
+            }
+        };
+        let Some(file_info) = rotated else {
+            return Ok(());
+        };
+
+        info!("Rotating file for streamer: {}", streamer);
+        self.finish_file(&file_info).await?;
+        self.compress_in_background(file_info.path);
+
+        let mut stats = self.stats.lock().await;
+        stats.last_rotation = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Flush a file nothing more goes into, rotated or left behind when the
+    /// date rolled over. `on_rotate` durability also syncs it to disk.
+    async fn finish_file(&self, file_info: &FileInfo) -> Result<()> {
+        let sync = self.durability == Durability::OnRotate;
+        Self::flush_file(&file_info.path, file_info.writer.clone(), sync).await
+    }
+
+    /// Flush a file's buffered writer, and sync it when `sync` is set. A file
+    /// without a writer has nothing buffered, so only the sync applies.
+    async fn flush_file(path: &Path, writer: Option<SharedFile>, sync: bool) -> Result<()> {
+        match writer {
+            Some(writer) => Self::flush_blocking(writer, sync).await,
+            None if sync => {
+                let path = path.to_path_buf();
+                tokio::task::spawn_blocking(move || Self::sync_file(&path))
+                    .await
+                    .map_err(|e| ScrapingError::StorageError(format!("Sync task failed: {}", e)))?
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// fsync a file that's no longer being written to
+    fn sync_file(path: &Path) -> Result<()> {
+        // opened for writing, as some platforms refuse to sync read-only handles
+        OpenOptions::new()
+            .append(true)
+            .open(path)
+            .and_then(|file| file.sync_all())
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to sync {}: {}", path.display(), e)).into())
+    }
+
+    /// Find the newest data file of each streamer and the day counters left
+    /// by earlier runs, clearing out what interrupted writes left behind.
+    /// Blocking, `setup_rotation` runs it on the blocking pool.
+    fn scan_output_dir(output_dir: &Path, extension: &str, has_header: bool, timezone: Tz, walk: bool) -> Result<RotationScan> {
+        // Create output directory if it doesn't exist
+        fs::create_dir_all(output_dir)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to create output directory: {}", e)))?;
+
+        let mut scan = RotationScan::default();
+
+        // only the default `<streamer>/<date>/` layout is known well enough to walk;
+        // with a custom template each run starts new files on its first write
+        let entries = if walk {
+            fs::read_dir(output_dir).ok()
+        } else {
+            info!("Custom filename_template, not picking up files from earlier runs");
+            None
+        };
+        if let Some(entries) = entries {
+            for entry in entries.flatten() {
+                if entry.path().is_dir() {
+                    let streamer = entry.file_name().to_string_lossy().to_string();
+
+                    // streamer dirs are only made on first write, drop empty ones left behind
+                    if Self::remove_empty_day_dirs(&entry.path()) {
+                        debug!("Removed empty output directory for streamer: {}", streamer);
+                        continue;
+                    }
+                    
+                    // Find the most recent file for this streamer
+                    if let Ok(streamer_entries) = fs::read_dir(entry.path()) {
+                        for date_entry in streamer_entries.flatten() {
+                            if date_entry.path().is_dir() {
+                                if let Some(day_count) = Self::reconcile_day_count(&date_entry.path(), &streamer) {
+                                    scan.day_counts.insert(date_entry.path(), day_count);
+                                }
+
+                                if let Ok(file_entries) = fs::read_dir(date_entry.path()) {
+                                    for file_entry in file_entries.flatten() {
+                                        // a first batch cut off before its rename, never visible to readers
+                                        let is_unpublished = file_entry.file_name().to_string_lossy()
+                                            .ends_with(&format!(".{}.tmp", extension));
+                                        if is_unpublished {
+                                            warn!("Removing unfinished file {}", file_entry.path().display());
+                                            let _ = fs::remove_file(file_entry.path());
+                                            continue;
+                                        }
+                                        // nothing is compressing yet, so this one was interrupted; the original is still there
+                                        if Compression::is_temp_path(&file_entry.path()) {
+                                            warn!("Removing interrupted compression {}", file_entry.path().display());
+                                            let _ = fs::remove_file(file_entry.path());
+                                            continue;
+                                        }
+
+                                        // only data files, not count.json
+                                        let is_data_file = file_entry.path().extension()
+                                            .is_some_and(|ext| ext == extension);
+                                        if file_entry.path().is_file() && is_data_file {
+                                            if let Ok(metadata) = file_entry.metadata() {
+                                                let created = metadata.created()
+                                                    .map(DateTime::<Utc>::from)
+                                                    .unwrap_or_else(|_| Utc::now());
+                                                let day = date_entry.file_name().to_str()
+                                                    .and_then(|name| NaiveDate::parse_from_str(name, "%Y-%m-%d").ok())
+                                                    .unwrap_or_else(|| created.with_timezone(&timezone).date_naive());
+                                                
+                                                // Update or insert file info for most recent file
+                                                let is_newest = scan.current_files.get(&streamer)
+                                                    .is_none_or(|existing| existing.created < created);
+                                                if is_newest {
+                                                    scan.current_files.insert(streamer.clone(), FileInfo {
+                                                        path: file_entry.path(),
+                                                        size: metadata.len(),
+                                                        created,
+                                                        message_count: 0, // counted below, once the newest file is known
+                                                        writer: None,
+                                                        seen_ids: HashSet::new(),
+                                                        day,
+                                                    });
+                                                }
+                                                
+                                                scan.files_found += 1;
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // pick up where the previous run left off, so rotation and rates see the real count
+        for file_info in scan.current_files.values_mut() {
+            match Self::count_file_messages(&file_info.path, has_header) {
+                Ok(count) => file_info.message_count = count,
+                Err(e) => warn!("Failed to count messages in {}: {}", file_info.path.display(), e),
+            }
+        }
+
+        scan.disk_usage = Self::calculate_directory_size(output_dir);
+        Ok(scan)
+    }
+
+    /// Remove the empty `<date>` directories in a streamer's directory, then
+    /// the streamer's directory if that left it empty. Anything not laid out
+    /// the way this manager writes is left alone. Returns true if the
+    /// streamer's directory is gone.
+    fn remove_empty_day_dirs(streamer_dir: &Path) -> bool {
+        let Ok(entries) = fs::read_dir(streamer_dir) else {
+            return false;
+        };
+
+        let mut removed_any = false;
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let is_day_dir = entry.path().is_dir()
+                && name.to_str().is_some_and(|name| chrono::NaiveDate::parse_from_str(name, "%Y-%m-%d").is_ok());
+            // remove_dir refuses non-empty directories, so a file written meanwhile is safe
+            if is_day_dir && fs::remove_dir(entry.path()).is_ok() {
+                removed_any = true;
+            }
+        }
+
+        removed_any && fs::remove_dir(streamer_dir).is_ok()
+    }
+
+    fn calculate_directory_size(dir_path: &Path) -> u64 {
+        let mut total_size = 0;
+        
+        if let Ok(entries) = fs::read_dir(dir_path) {
+            for entry in entries.flatten() {
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        total_size += metadata.len();
+                    } else if metadata.is_dir() {
+                        total_size += Self::calculate_directory_size(&entry.path());
+                    }
+                }
+            }
+        }
+        
+        total_size
+    }
+}
+
+impl Drop for FileStorageManager {
+    /// Stops the background tasks. Buffered messages are flushed as the open
+    /// files drop, whoever holds the last handle on them.
+    fn drop(&mut self) {
+        if let Some(task) = self.flush_task.get() {
+            task.abort();
+        }
+        if let Some(task) = self.retention_task.get() {
+            task.abort();
+        }
+        if let Some(task) = self.disk_usage_task.get() {
+            task.abort();
+        }
+    }
+}
+
+#[async_trait]
+impl StorageManager for FileStorageManager {
+    async fn store_messages(&self, messages: Vec<ChatMessage>) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let seen = messages.len() as u64;
+        let messages = self.sample(messages);
+        self.stats.lock().await.total_seen += seen;
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        debug!("Storing {} of {} messages", messages.len(), seen);
+        self.ensure_flush_task();
+
+        // Group messages by streamer
+        let mut messages_by_streamer: HashMap<String, Vec<ChatMessage>> = HashMap::new();
+        for message in messages {
+            messages_by_streamer
+                .entry(message.streamer.clone())
+                .or_default()
+                .push(message);
+        }
+
+        // Process streamers concurrently, at most `write_permits` at a time
+        let writes = messages_by_streamer.into_iter().map(|(streamer, streamer_messages)| async move {
+            let _permit = self.write_permits.acquire().await
+                .map_err(|e| ScrapingError::StorageError(format!("Write limiter closed: {}", e)))?;
+            self.store_streamer_messages(&streamer, streamer_messages).await
+        });
+        futures::future::try_join_all(writes).await?;
+
+        debug!("Successfully stored messages");
+        Ok(())
+    }
+
+    async fn setup_rotation(&self) -> Result<()> {
+        info!("Setting up file rotation system");
+
+        // walking and counting a big output tree is slow, keep it off the runtime
+        // and out of the locks, which are only taken to install what it found
+        let output_dir = self.output_dir.clone();
+        let extension = self.formatter.file_extension().to_string();
+        let has_header = self.formatter.header_bytes().is_some();
+        let timezone = self.timezone;
+        let walk = self.filename_template.is_default();
+        let scan = tokio::task::spawn_blocking(move || Self::scan_output_dir(&output_dir, &extension, has_header, timezone, walk))
+            .await
+            .map_err(|e| ScrapingError::StorageError(format!("Output directory scan failed: {}", e)))??;
+
+        let mut current_files = self.current_files.lock().await;
+        for (streamer, file_info) in scan.current_files {
+            match current_files.get(&streamer) {
+                Some(existing) if existing.created >= file_info.created => {} // Keep existing newer file
+                _ => {
+                    current_files.insert(streamer, file_info);
+                }
+            }
+        }
+        self.day_counts.lock().await.extend(scan.day_counts);
+
         let mut stats = self.stats.lock().await;
-        stats.disk_usage = self.calculate_disk_usage().await;
-        Ok(stats.clone())
+        stats.files_created += scan.files_found;
+        // the one full walk, writes keep the total current from here
+        stats.disk_usage = scan.disk_usage;
+        drop(stats);
+
+        info!("File rotation system initialized with {} existing files", current_files.len());
+        drop(current_files);
+        self.ensure_retention_task();
+        self.ensure_disk_usage_task();
+        Ok(())
+    }
+
+    async fn get_storage_stats(&self) -> Result<StorageStats> {
+        // disk usage is kept up to date as files change, no directory walk here
+        Ok(self.stats.lock().await.clone())
+    }
+
+    async fn flush_all(&self) -> Result<usize> {
+        self.wait_for_compression().await;
+
+        // taken under the lock, flushed without it so writes aren't held up
+        let files: Vec<(PathBuf, Option<SharedFile>)> = self.current_files.lock().await
+            .values()
+            .map(|file_info| (file_info.path.clone(), file_info.writer.clone()))
+            .collect();
+
+        let mut first_error = None;
+        for (path, writer) in &files {
+            if let Err(e) = Self::flush_file(path, writer.clone(), true).await {
+                warn!("Failed to flush {}: {}", path.display(), e);
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => {
+                info!("Flushed {} open files", files.len());
+                Ok(files.len())
+            }
+        }
     }
 }
 #[cfg(
 test)]
 mod tests {
     use super::*;
-    use crate::parser::chat_message::{ChatUser, MessageContent, MessageFragment, StreamContext};
+    use crate::parser::fixtures::chat_message;
     use tempfile::tempdir;
 
     fn create_test_message(streamer: &str, username: &str, text: &str) -> ChatMessage {
-        ChatMessage::new(
-            streamer.to_string(),
-            Utc::now(),
-            ChatUser {
-                username: username.to_string(),
-                display_name: username.to_string(),
-                color: Some("#FF0000".to_string()),
-                badges: vec!["subscriber".to_string()],
-            },
-            MessageContent {
-                text: text.to_string(),
-                emotes: vec![],
-                fragments: vec![MessageFragment {
-                    fragment_type: "text".to_string(),
-                    content: text.to_string(),
-                }],
-            },
-            StreamContext {
-                viewer_count: Some(1000),
-                game_category: Some("Just Chatting".to_string()),
-                stream_title: Some("Test Stream".to_string()),
-            },
-        )
+        let mut message = chat_message(text);
+        message.streamer = streamer.to_string();
+        message.user.username = username.to_string();
+        message.user.display_name = username.to_string();
+        message.context.stream_title = Some("Test Stream".to_string());
+        message
     }
 
     #[test]
@@ -570,243 +1678,1105 @@ mod tests {
         assert_eq!(formatter.header(), Some("username,message_text,streamer".to_string()));
     }
 
-    #[test]
-    fn test_csv_field_escaping() {
-        let text_with_comma = "Hello, world!";
-        let text_with_quotes = "He said \"Hello\"";
-        let text_with_newline = "Line 1\nLine 2";
+    #[test]
+    fn test_csv_field_escaping() {
+        let text_with_comma = "Hello, world!";
+        let text_with_quotes = "He said \"Hello\"";
+        let text_with_newline = "Line 1\nLine 2";
+        let formatter = CsvFormatter::new(CsvFormatter::default_columns());
+
+        assert_eq!(formatter.escape_csv_field(text_with_comma), "\"Hello, world!\"");
+        assert_eq!(formatter.escape_csv_field(text_with_quotes), "\"He said \"\"Hello\"\"\"");
+        assert_eq!(formatter.escape_csv_field(text_with_newline), "\"Line 1\nLine 2\"");
+        assert_eq!(formatter.escape_csv_field("normal text"), "normal text");
+        assert_eq!(formatter.escape_csv_field("tab\tseparated"), "tab\tseparated");
+    }
+
+    #[test]
+    fn test_tsv_formatter() {
+        let columns = vec!["username".to_string(), "message_text".to_string(), "streamer".to_string()];
+        let formatter = CsvFormatter::tsv(columns);
+        let messages = vec![
+            create_test_message("teststreamer", "user1", "Hello, world, again"),
+            create_test_message("teststreamer", "user2", "tab\there"),
+        ];
+
+        let result = formatter.format_messages(&messages).unwrap();
+        let lines: Vec<&str> = result.trim().split('\n').collect();
+        // commas need no quoting, a tab in the text does
+        assert_eq!(lines[0], "user1\tHello, world, again\tteststreamer");
+        assert_eq!(lines[1], "user2\t\"tab\there\"\tteststreamer");
+
+        assert_eq!(formatter.file_extension(), "tsv");
+        assert_eq!(formatter.header(), Some("username\tmessage_text\tstreamer".to_string()));
+
+        // and it reads back as the same messages
+        let temp_dir = tempdir().unwrap();
+        let formatter = CsvFormatter::tsv(CsvFormatter::default_columns());
+        let path = temp_dir.path().join("chat.tsv");
+        fs::write(&path, format!("{}\n{}", formatter.header().unwrap(), formatter.format_messages(&messages).unwrap())).unwrap();
+        let read_back = read_messages(&path).unwrap();
+        assert_eq!(read_back.len(), 2);
+        assert_eq!(read_back[0].message.text, "Hello, world, again");
+        assert_eq!(read_back[1].message.text, "tab\there");
+    }
+
+    #[tokio::test]
+    async fn test_csv_excel_compat() {
+        let temp_dir = tempdir().unwrap();
+        let mut manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "csv".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+        manager.formatter = Box::new(CsvFormatter::new(vec!["username".to_string(), "message_text".to_string()]).with_excel_compat());
+
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        for text in ["first 🎉", "second"] {
+            let mut message = create_test_message("streamer1", "user1", text);
+            message.timestamp = timestamp;
+            manager.store_messages(vec![message]).await.unwrap();
+        }
+        manager.flush_all().await.unwrap();
+
+        let file_path = temp_dir.path().join("streamer1").join("2024-01-15").join("chat_2024-01-15_10-30-45.csv");
+        let content = fs::read(&file_path).unwrap();
+        assert_eq!(&content[..3], &[0xEF, 0xBB, 0xBF]);
+
+        // one BOM for the file, none on the appended batch
+        let content = String::from_utf8(content).unwrap();
+        assert_eq!(content.matches('\u{feff}').count(), 1);
+        assert_eq!(content, "\u{feff}username,message_text\r\nuser1,first 🎉\r\nuser1,second\r\n");
+
+        // the BOM doesn't get in the way of reading it back
+        let formatter = CsvFormatter::new(CsvFormatter::default_columns()).with_excel_compat();
+        let message = create_test_message("streamer1", "user1", "first 🎉");
+        let mut content = formatter.header_bytes().unwrap();
+        content.extend(formatter.format_bytes(&[message]).unwrap());
+        let path = temp_dir.path().join("excel.csv");
+        fs::write(&path, content).unwrap();
+        assert_eq!(read_messages(&path).unwrap()[0].message.text, "first 🎉");
+    }
+
+    #[test]
+    fn test_csv_default_columns() {
+        let columns = CsvFormatter::default_columns();
+        let expected = vec![
+            "id", "timestamp", "streamer", "username", "display_name", 
+            "message_text", "user_color", "badges", "viewer_count", 
+            "game_category", "stream_title"
+        ];
+        assert_eq!(columns, expected);
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_manager_creation() {
+        let temp_dir = tempdir().unwrap();
+        
+        // Test JSON format
+        let json_manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+        
+        assert_eq!(json_manager.formatter.file_extension(), "jsonl");
+
+        // Test CSV format
+        let csv_manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "csv".to_string(),
+            "50MB".to_string(),
+            "30m".to_string(),
+        ).unwrap();
+        
+        assert_eq!(csv_manager.formatter.file_extension(), "csv");
+
+        // Test invalid format
+        let invalid_result = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "invalid".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        );
+        
+        assert!(invalid_result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_csv_with_custom_columns() {
+        let temp_dir = tempdir().unwrap();
+        let custom_columns = vec!["username".to_string(), "message_text".to_string()];
+        
+        let manager = FileStorageManager::with_csv_columns(
+            temp_dir.path().to_path_buf(),
+            custom_columns.clone(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+        
+        assert_eq!(manager.formatter.file_extension(), "csv");
+    }
+
+    #[tokio::test]
+    async fn test_file_path_generation() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        let file_path = manager.get_file_path("teststreamer", timestamp).await;
+
+        let expected_path = temp_dir.path()
+            .join("teststreamer")
+            .join("2024-01-15")
+            .join("chat_2024-01-15_10-30-45.jsonl");
+
+        assert_eq!(file_path, expected_path);
+    }
+
+    #[tokio::test]
+    async fn test_file_path_generation_with_timezone() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap().with_timezone(chrono_tz::Asia::Tokyo);
+
+        // 20:30 UTC is already the next day in Tokyo
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T20:30:45Z").unwrap().with_timezone(&Utc);
+        let file_path = manager.get_file_path("teststreamer", timestamp).await;
+
+        let expected_path = temp_dir.path()
+            .join("teststreamer")
+            .join("2024-01-16")
+            .join("chat_2024-01-16_05-30-45.jsonl");
+
+        assert_eq!(file_path, expected_path);
+    }
+
+    #[test]
+    fn test_csv_formatter_local_timestamps() {
+        let mut message = create_test_message("teststreamer", "user1", "Hello");
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T20:30:45Z").unwrap().with_timezone(&Utc);
+
+        let formatter = CsvFormatter::new(vec!["timestamp".to_string()])
+            .with_timezone(chrono_tz::America::New_York);
+        let output = formatter.format_messages(&[message.clone()]).unwrap();
+        assert_eq!(output.trim(), "2024-01-15T15:30:45-05:00");
+
+        // the message itself keeps its UTC timestamp
+        assert_eq!(message.timestamp.to_rfc3339(), "2024-01-15T20:30:45+00:00");
+    }
+
+    #[tokio::test]
+    async fn test_storage_manager_from_config() {
+        let temp_dir = tempdir().unwrap();
+        let mut output = crate::config::Config::default().output;
+        output.directory = temp_dir.path().to_path_buf();
+        output.timezone = "Europe/Berlin".to_string();
+
+        let manager = FileStorageManager::from_config(&output).unwrap();
+        assert_eq!(manager.timezone, chrono_tz::Europe::Berlin);
+
+        output.timezone = "Nowhere/Special".to_string();
+        assert!(FileStorageManager::from_config(&output).is_err());
+
+        output.timezone = "UTC".to_string();
+        output.durability = Durability::OnRotate;
+        assert_eq!(FileStorageManager::from_config(&output).unwrap().durability, Durability::OnRotate);
+    }
+
+    #[tokio::test]
+    async fn test_durability_modes() {
+        for durability in [Durability::Always, Durability::OnRotate] {
+            let temp_dir = tempdir().unwrap();
+            let manager = FileStorageManager::new(
+                temp_dir.path().to_path_buf(),
+                "json".to_string(),
+                "1KB".to_string(),
+                "1h".to_string(),
+            ).unwrap().with_durability(durability);
+
+            // the second batch pushes the file over 1KB, the third rotates it
+            for _ in 0..3 {
+                let messages = (0..5)
+                    .map(|i| create_test_message("teststreamer", &format!("user{}", i), "some padding for the file"))
+                    .collect();
+                manager.store_messages(messages).await.unwrap();
+            }
+
+            let stats = manager.get_storage_stats().await.unwrap();
+            assert_eq!(stats.total_stored, 15);
+            assert!(stats.last_rotation.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_setup_rotation() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        // Setup rotation should create the output directory
+        manager.setup_rotation().await.unwrap();
+        assert!(temp_dir.path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_streamer_dirs_created_on_first_write() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("offline").join("2024-01-15")).unwrap();
+
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        manager.setup_rotation().await.unwrap();
+        assert!(!temp_dir.path().join("offline").exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        manager.store_messages(vec![create_test_message("live", "user1", "hi")]).await.unwrap();
+        let dirs: Vec<String> = fs::read_dir(temp_dir.path()).unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(dirs, vec!["live".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_setup_rotation_only_removes_its_own_empty_dirs() {
+        let temp_dir = tempdir().unwrap();
+        let output_dir = temp_dir.path().join("scraped_data");
+        fs::create_dir_all(output_dir.join("streamer1").join("2024-01-14")).unwrap();
+        fs::create_dir_all(output_dir.join("streamer1").join("2024-01-15")).unwrap();
+        fs::write(output_dir.join("streamer1").join("2024-01-15").join("chat_2024-01-15_10-30-45.jsonl"), "").unwrap();
+        fs::create_dir_all(output_dir.join("backups")).unwrap();
+        fs::create_dir_all(output_dir.join("exports").join("march")).unwrap();
+
+        let manager = FileStorageManager::new(
+            output_dir.clone(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+        manager.setup_rotation().await.unwrap();
+
+        assert!(!output_dir.join("streamer1").join("2024-01-14").exists());
+        assert!(output_dir.join("streamer1").join("2024-01-15").exists());
+        // not a `<streamer>/<date>` directory, so not ours to remove
+        assert!(output_dir.join("backups").exists());
+        assert!(output_dir.join("exports").join("march").exists());
+
+        // an output directory with nothing of ours in it stays too
+        let empty_output = temp_dir.path().join("empty");
+        fs::create_dir_all(&empty_output).unwrap();
+        let manager = FileStorageManager::new(
+            empty_output.clone(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+        manager.setup_rotation().await.unwrap();
+        assert!(empty_output.exists());
+    }
+
+    #[tokio::test]
+    async fn test_store_messages_json() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        manager.setup_rotation().await.unwrap();
+
+        let messages = vec![
+            create_test_message("teststreamer", "user1", "Hello world!"),
+            create_test_message("teststreamer", "user2", "How are you?"),
+        ];
+
+        manager.store_messages(messages).await.unwrap();
+
+        // Check that files were created
+        let streamer_dir = temp_dir.path().join("teststreamer");
+        assert!(streamer_dir.exists());
+
+        // Find the created file
+        let mut found_file = false;
+        for entry in std::fs::read_dir(&streamer_dir).unwrap() {
+            let entry = entry.unwrap();
+            if entry.path().is_dir() {
+                for date_entry in std::fs::read_dir(entry.path()).unwrap() {
+                    let date_entry = date_entry.unwrap();
+                    if date_entry.path().extension().and_then(|s| s.to_str()) == Some("jsonl") {
+                        found_file = true;
+                        
+                        // Check file content
+                        let content = std::fs::read_to_string(date_entry.path()).unwrap();
+                        let lines: Vec<&str> = content.trim().split('\n').collect();
+                        assert_eq!(lines.len(), 2);
+                        
+                        // Verify JSON content
+                        for line in lines {
+                            let parsed: serde_json::Value = serde_json::from_str(line).unwrap();
+                            assert!(parsed["user"]["username"].is_string());
+                            assert!(parsed["message"]["text"].is_string());
+                        }
+                    }
+                }
+            }
+        }
+        assert!(found_file, "No JSON file was created");
+    }
+
+    #[tokio::test]
+    async fn test_store_messages_csv() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "csv".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        manager.setup_rotation().await.unwrap();
+
+        let messages = vec![
+            create_test_message("teststreamer", "user1", "Hello world!"),
+            create_test_message("teststreamer", "user2", "How are you?"),
+        ];
+
+        manager.store_messages(messages).await.unwrap();
+
+        // Find the created CSV file
+        let streamer_dir = temp_dir.path().join("teststreamer");
+        let mut found_file = false;
+        
+        for entry in std::fs::read_dir(&streamer_dir).unwrap() {
+            let entry = entry.unwrap();
+            if entry.path().is_dir() {
+                for date_entry in std::fs::read_dir(entry.path()).unwrap() {
+                    let date_entry = date_entry.unwrap();
+                    if date_entry.path().extension().and_then(|s| s.to_str()) == Some("csv") {
+                        found_file = true;
+                        
+                        // Check file content
+                        let content = std::fs::read_to_string(date_entry.path()).unwrap();
+                        let lines: Vec<&str> = content.trim().split('\n').collect();
+                        
+                        // Should have header + 2 data lines
+                        assert_eq!(lines.len(), 3);
+                        
+                        // Check header
+                        assert!(lines[0].contains("username"));
+                        assert!(lines[0].contains("message_text"));
+                        
+                        // Check data lines
+                        assert!(lines[1].contains("user1"));
+                        assert!(lines[1].contains("Hello world!"));
+                        assert!(lines[2].contains("user2"));
+                        assert!(lines[2].contains("How are you?"));
+                    }
+                }
+            }
+        }
+        assert!(found_file, "No CSV file was created");
+    }
+
+    #[tokio::test]
+    async fn test_store_messages_avro() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "avro".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+        assert_eq!(manager.formatter.file_extension(), "avro");
+
+        let message = create_test_message("teststreamer", "user1", "Hello");
+        let mut resent = message.clone();
+        resent.id = uuid::Uuid::new_v4().to_string();
+        manager.store_messages(vec![message]).await.unwrap();
+        manager.store_messages(vec![resent]).await.unwrap();
+
+        let current_files = manager.current_files.lock().await;
+        let file_info = current_files.get("teststreamer").unwrap();
+        let content = fs::read(&file_info.path).unwrap();
+
+        // one header then one block per batch
+        let header = manager.formatter.header_bytes().unwrap();
+        assert!(content.starts_with(&header));
+        assert_eq!(content.len() as u64, file_info.size);
+
+        let blocks = &content[header.len()..];
+        let (first, second) = blocks.split_at(blocks.len() / 2);
+        assert_eq!(first.len(), second.len());
+        assert!(first.ends_with(&header[header.len() - 16..]));
+        assert!(second.ends_with(&header[header.len() - 16..]));
+    }
+
+    #[tokio::test]
+    async fn test_avro_never_appends_to_another_runs_file() {
+        let temp_dir = tempdir().unwrap();
+        let new_manager = || FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "avro".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        let message = |text: &str| {
+            let mut message = create_test_message("streamer1", "user1", text);
+            message.timestamp = timestamp;
+            message
+        };
+
+        let manager = new_manager();
+        manager.store_messages(vec![message("one"), message("two")]).await.unwrap();
+        drop(manager);
+
+        // a second run has its own sync marker, so the same file name gets a sibling
+        let restarted = new_manager();
+        restarted.setup_rotation().await.unwrap();
+        restarted.store_messages(vec![message("three")]).await.unwrap();
+        restarted.store_messages(vec![message("four")]).await.unwrap();
+
+        let day_dir = temp_dir.path().join("streamer1").join("2024-01-15");
+        let first = day_dir.join("chat_2024-01-15_10-30-45.avro");
+        let second = day_dir.join("chat_2024-01-15_10-30-45-1.avro");
+        assert_eq!(FileStorageManager::count_file_messages(&first, false).unwrap(), 2);
+        assert_eq!(FileStorageManager::count_file_messages(&second, false).unwrap(), 2);
+        assert!(fs::read(&second).unwrap().starts_with(&restarted.formatter.header_bytes().unwrap()));
+
+        let current_files = restarted.current_files.lock().await;
+        assert_eq!(current_files.get("streamer1").unwrap().path, second);
+    }
+
+    #[tokio::test]
+    async fn test_multiple_streamers() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        manager.setup_rotation().await.unwrap();
+
+        let messages = vec![
+            create_test_message("streamer1", "user1", "Hello from streamer1!"),
+            create_test_message("streamer2", "user2", "Hello from streamer2!"),
+            create_test_message("streamer1", "user3", "Another message for streamer1!"),
+        ];
+
+        manager.store_messages(messages).await.unwrap();
+
+        // Check that both streamer directories were created
+        assert!(temp_dir.path().join("streamer1").exists());
+        assert!(temp_dir.path().join("streamer2").exists());
+    }
+
+    #[tokio::test]
+    async fn test_flush_reports_open_files() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        assert_eq!(manager.flush_all().await.unwrap(), 0);
+
+        let first = create_test_message("streamer1", "user1", "one");
+        let mut later = create_test_message("streamer1", "user1", "three");
+        later.timestamp = first.timestamp;
+        manager.store_messages(vec![first, create_test_message("streamer2", "user2", "two")]).await.unwrap();
+        assert_eq!(manager.flush_all().await.unwrap(), 2);
+
+        // flushing doesn't close anything for good, later writes append as before
+        manager.store_messages(vec![later]).await.unwrap();
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!(stats.files_created, 2);
+        assert_eq!(stats.total_stored, 3);
+    }
+
+    #[tokio::test]
+    async fn test_rotated_files_are_compressed() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "1B".to_string(), // every write rotates
+            "1h".to_string(),
+        ).unwrap()
+        .with_compression(Some(Compression::Gzip));
+
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        let mut first = create_test_message("streamer1", "user1", "one");
+        first.timestamp = timestamp;
+        let mut second = create_test_message("streamer1", "user1", "two");
+        second.timestamp = timestamp + chrono::Duration::seconds(5);
+        manager.store_messages(vec![first]).await.unwrap();
+        manager.store_messages(vec![second]).await.unwrap();
+        manager.flush_all().await.unwrap();
+
+        let day_dir = temp_dir.path().join("streamer1").join("2024-01-15");
+        let rotated = day_dir.join("chat_2024-01-15_10-30-45.jsonl");
+        let compressed = fs::read(day_dir.join("chat_2024-01-15_10-30-45.jsonl.gz")).unwrap();
+        assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+        assert!(!rotated.exists());
+
+        // the file still being written is left alone
+        assert!(day_dir.join("chat_2024-01-15_10-30-50.jsonl").exists());
+        assert!(!day_dir.join("chat_2024-01-15_10-30-50.jsonl.gz").exists());
+
+        let stats = manager.get_storage_stats().await.unwrap();
+        let on_disk: u64 = fs::read_dir(&day_dir).unwrap().flatten().map(|entry| entry.metadata().unwrap().len()).sum();
+        assert_eq!(stats.disk_usage, on_disk);
+    }
+
+    #[tokio::test]
+    async fn test_rotation_flushes_buffered_writes() {
+        let temp_dir = tempdir().unwrap();
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        let message_at = |text: &str, seconds: i64| {
+            let mut message = create_test_message("streamer1", "user1", text);
+            message.timestamp = timestamp + chrono::Duration::seconds(seconds);
+            message
+        };
+
+        // a file rotates once it holds two messages
+        let line_len = JsonFormatter.format_bytes(&[message_at("one", 0)]).unwrap().len();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            format!("{}B", line_len + 1),
+            "1h".to_string(),
+        ).unwrap()
+        .with_flush_interval(Duration::from_secs(3600));
+
+        manager.store_messages(vec![message_at("one", 0)]).await.unwrap();
+        manager.store_messages(vec![message_at("two", 1)]).await.unwrap(); // still buffered
+        manager.store_messages(vec![message_at("three", 5)]).await.unwrap();
+
+        // the rotated file has both lines on disk without a flush or shutdown
+        let day_dir = temp_dir.path().join("streamer1").join("2024-01-15");
+        assert_eq!(read_messages(&day_dir.join("chat_2024-01-15_10-30-45.jsonl")).unwrap().len(), 2);
+        assert!(day_dir.join("chat_2024-01-15_10-30-50.jsonl").exists());
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_compression_is_removed_on_startup() {
+        let temp_dir = tempdir().unwrap();
+        let day_dir = temp_dir.path().join("streamer1").join("2024-01-15");
+        fs::create_dir_all(&day_dir).unwrap();
+        let original = day_dir.join("chat_2024-01-15_10-30-45.jsonl");
+        fs::write(&original, "{}\n").unwrap();
+        let partial = day_dir.join("chat_2024-01-15_10-30-45.jsonl.zst.tmp");
+        fs::write(&partial, "half a frame").unwrap();
+
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap()
+        .with_compression(Some(Compression::Zstd));
+        manager.setup_rotation().await.unwrap();
+
+        assert!(!partial.exists());
+        assert!(original.exists());
+    }
 
-        assert_eq!(CsvFormatter::escape_csv_field(text_with_comma), "\"Hello, world!\"");
-        assert_eq!(CsvFormatter::escape_csv_field(text_with_quotes), "\"He said \"\"Hello\"\"\"");
-        assert_eq!(CsvFormatter::escape_csv_field(text_with_newline), "\"Line 1\nLine 2\"");
-        assert_eq!(CsvFormatter::escape_csv_field("normal text"), "normal text");
+    #[tokio::test]
+    async fn test_day_rollover_compresses_previous_file() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "csv".to_string(),
+            "1GB".to_string(),
+            "unlimited".to_string(),
+        ).unwrap()
+        .with_compression(Some(Compression::Zstd));
+
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T23:59:58Z").unwrap().with_timezone(&Utc);
+        let mut late = create_test_message("streamer1", "user1", "one");
+        late.timestamp = timestamp;
+        let mut later = create_test_message("streamer1", "user1", "two");
+        later.timestamp = timestamp + chrono::Duration::seconds(1);
+        let mut next_day = create_test_message("streamer1", "user1", "three");
+        next_day.timestamp = timestamp + chrono::Duration::seconds(5);
+        manager.store_messages(vec![late, later]).await.unwrap();
+        manager.store_messages(vec![next_day]).await.unwrap();
+        manager.flush_all().await.unwrap();
+
+        let day_dir = temp_dir.path().join("streamer1").join("2024-01-15");
+        let compressed = day_dir.join("chat_2024-01-15_23-59-58.csv.zst");
+        assert!(compressed.exists());
+        assert!(!day_dir.join("chat_2024-01-15_23-59-58.csv").exists());
+        assert!(temp_dir.path().join("streamer1").join("2024-01-16").join("chat_2024-01-16_00-00-03.csv").exists());
+
+        // compressed files still count when a day is rebuilt
+        assert_eq!(FileStorageManager::count_file_messages(&compressed, true).unwrap(), 2);
+        assert_eq!(FileStorageManager::recount_day_dir(&day_dir), Some(2));
     }
 
     #[test]
-    fn test_csv_default_columns() {
-        let columns = CsvFormatter::default_columns();
-        let expected = vec![
-            "id", "timestamp", "streamer", "username", "display_name", 
-            "message_text", "user_color", "badges", "viewer_count", 
-            "game_category", "stream_title"
-        ];
-        assert_eq!(columns, expected);
+    fn test_recount_day_dir_matches_file_counts() {
+        let temp_dir = tempdir().unwrap();
+        let day_dir = temp_dir.path().join("2024-01-15");
+        fs::create_dir_all(&day_dir).unwrap();
+        // a quoted newline is still one message, the header is none
+        fs::write(day_dir.join("chat_a.csv"), "id,message_text\n1,\"two\nlines\"\n2,hi\n").unwrap();
+        fs::write(day_dir.join("chat_b.jsonl"), "{}\n\n{}").unwrap();
+        fs::write(day_dir.join("chat_c.jsonl.tmp"), "{}\n").unwrap();
+
+        assert_eq!(FileStorageManager::recount_day_dir(&day_dir), Some(4));
     }
 
     #[tokio::test]
-    async fn test_file_storage_manager_creation() {
+    async fn test_large_write_does_not_block_runtime() {
         let temp_dir = tempdir().unwrap();
-        
-        // Test JSON format
-        let json_manager = FileStorageManager::new(
+        let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
             "json".to_string(),
-            "100MB".to_string(),
+            "1GB".to_string(),
             "1h".to_string(),
-        ).unwrap();
-        
-        assert_eq!(json_manager.formatter.file_extension(), "jsonl");
+        ).unwrap()
+        .with_durability(Durability::Always);
+
+        let timestamp = Utc::now();
+        let messages: Vec<ChatMessage> = (0..20_000)
+            .map(|i| {
+                let mut message = create_test_message(&format!("streamer{}", i % 4), "user1", &"spam ".repeat(20));
+                message.timestamp = timestamp;
+                message
+            })
+            .collect();
+
+        // tokio::test is single threaded, so this only ticks while storage yields
+        let ticks = Arc::new(std::sync::atomic::AtomicU64::new(0));
+        let ticker = tokio::spawn({
+            let ticks = ticks.clone();
+            async move {
+                loop {
+                    ticks.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+        tokio::task::yield_now().await;
 
-        // Test CSV format
-        let csv_manager = FileStorageManager::new(
-            temp_dir.path().to_path_buf(),
-            "csv".to_string(),
-            "50MB".to_string(),
-            "30m".to_string(),
-        ).unwrap();
-        
-        assert_eq!(csv_manager.formatter.file_extension(), "csv");
+        let before = ticks.load(std::sync::atomic::Ordering::SeqCst);
+        manager.store_messages(messages).await.unwrap();
+        let during = ticks.load(std::sync::atomic::Ordering::SeqCst) - before;
+        ticker.abort();
 
-        // Test invalid format
-        let invalid_result = FileStorageManager::new(
+        assert!(during > 0, "ticker never ran while storing");
+        assert_eq!(manager.get_storage_stats().await.unwrap().total_stored, 20_000);
+    }
+
+    #[tokio::test]
+    async fn test_buffered_writes_flush() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
-            "invalid".to_string(),
+            "json".to_string(),
             "100MB".to_string(),
             "1h".to_string(),
-        );
-        
-        assert!(invalid_result.is_err());
+        ).unwrap()
+        .with_flush_interval(Duration::from_secs(3600));
+
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        let mut first = create_test_message("streamer1", "user1", "one");
+        first.timestamp = timestamp;
+        let mut second = create_test_message("streamer1", "user1", "two");
+        second.timestamp = timestamp + chrono::Duration::seconds(1);
+        manager.store_messages(vec![first]).await.unwrap();
+        manager.store_messages(vec![second]).await.unwrap();
+
+        // both writes share one open file, the first went out when the file was
+        // moved into place and the second is still buffered
+        let file_path = temp_dir.path().join("streamer1").join("2024-01-15").join("chat_2024-01-15_10-30-45.jsonl");
+        assert_eq!(read_messages(&file_path).unwrap().len(), 1);
+
+        manager.flush_all().await.unwrap();
+        assert_eq!(read_messages(&file_path).unwrap().len(), 2);
+
+        // dropping the manager writes out whatever is left
+        let mut third = create_test_message("streamer1", "user1", "three");
+        third.timestamp = timestamp + chrono::Duration::seconds(2);
+        manager.store_messages(vec![third]).await.unwrap();
+        drop(manager);
+        assert_eq!(read_messages(&file_path).unwrap().len(), 3);
     }
 
     #[tokio::test]
-    async fn test_csv_with_custom_columns() {
+    async fn test_new_files_appear_atomically() {
         let temp_dir = tempdir().unwrap();
-        let custom_columns = vec!["username".to_string(), "message_text".to_string()];
-        
-        let manager = FileStorageManager::with_csv_columns(
+        let day_dir = temp_dir.path().join("streamer1").join("2024-01-15");
+        let file_path = day_dir.join("chat_2024-01-15_10-30-45.csv");
+
+        // killed after the header went out but before any messages
+        let (mut open_file, needs_header) = OpenFile::open(&file_path).unwrap();
+        assert!(needs_header);
+        open_file.writer.write_all(b"id,timestamp\n").unwrap();
+        open_file.flush(false).unwrap();
+        drop(open_file);
+        assert!(!file_path.exists());
+        assert!(temp_path(&file_path).exists());
+
+        let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
-            custom_columns.clone(),
+            "csv".to_string(),
             "100MB".to_string(),
             "1h".to_string(),
         ).unwrap();
-        
-        assert_eq!(manager.formatter.file_extension(), "csv");
+        manager.setup_rotation().await.unwrap();
+        assert!(!temp_path(&file_path).exists());
+
+        let mut message = create_test_message("streamer1", "user1", "hello");
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        manager.store_messages(vec![message]).await.unwrap();
+
+        let content = fs::read_to_string(&file_path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert!(content.starts_with("id,timestamp"));
+        let leftovers: Vec<_> = fs::read_dir(&day_dir).unwrap()
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
     }
 
     #[tokio::test]
-    async fn test_file_path_generation() {
+    async fn test_retention_deletes_old_files() {
         let temp_dir = tempdir().unwrap();
         let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
             "json".to_string(),
             "100MB".to_string(),
             "1h".to_string(),
-        ).unwrap();
+        ).unwrap()
+        .with_retention(Some(Duration::from_secs(7 * 86400)));
 
-        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
-        let file_path = manager.get_file_path("teststreamer", timestamp).await;
+        let backdate = |path: &Path| {
+            let old = std::time::SystemTime::now() - Duration::from_secs(30 * 86400);
+            File::options().write(true).open(path).unwrap().set_modified(old).unwrap();
+        };
 
-        let expected_path = temp_dir.path()
-            .join("teststreamer")
-            .join("2024-01-15")
-            .join("chat_2024-01-15_10-30-45.jsonl");
+        let old_dir = temp_dir.path().join("streamer1").join("2024-01-01");
+        fs::create_dir_all(&old_dir).unwrap();
+        let old_file = old_dir.join("chat_2024-01-01_10-00-00.jsonl");
+        let old_count = old_dir.join(DAY_COUNT_FILE);
+        fs::write(&old_file, "{}\n").unwrap();
+        fs::write(&old_count, "{}").unwrap();
+        backdate(&old_file);
+        backdate(&old_count);
+
+        let recent_file = temp_dir.path().join("streamer2").join("2024-01-20").join("chat_2024-01-20_10-00-00.jsonl");
+        fs::create_dir_all(recent_file.parent().unwrap()).unwrap();
+        fs::write(&recent_file, "{}\n").unwrap();
+
+        // still being written to, so it stays however old it looks
+        let mut message = create_test_message("streamer1", "user1", "still here");
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        manager.store_messages(vec![message]).await.unwrap();
+        let open_file = temp_dir.path().join("streamer1").join("2024-01-15").join("chat_2024-01-15_10-30-45.jsonl");
+        backdate(&open_file);
+
+        // anything that isn't chat output stays, however old
+        let notes = temp_dir.path().join("notes.txt");
+        let database = temp_dir.path().join("chat.sqlite3");
+        fs::write(&notes, "keep me").unwrap();
+        fs::write(&database, "").unwrap();
+        backdate(&notes);
+        backdate(&database);
+
+        assert_eq!(manager.enforce_retention().await.unwrap(), 1);
+        assert!(!old_file.exists());
+        // the emptied day goes with its counter, the streamer's other day stays
+        assert!(!old_count.exists());
+        assert!(!old_dir.exists());
+        assert!(temp_dir.path().join("streamer1").exists());
+        assert!(recent_file.exists());
+        assert!(open_file.exists());
+        assert!(notes.exists());
+        assert!(database.exists());
 
-        assert_eq!(file_path, expected_path);
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!(stats.disk_usage, FileStorageManager::calculate_directory_size(temp_dir.path()));
     }
 
     #[tokio::test]
-    async fn test_setup_rotation() {
+    async fn test_setup_rotation_recovers_message_count() {
+        for format in ["json", "csv", "avro"] {
+            let temp_dir = tempdir().unwrap();
+            let new_manager = || FileStorageManager::new(
+                temp_dir.path().to_path_buf(),
+                format.to_string(),
+                "100MB".to_string(),
+                "1h".to_string(),
+            ).unwrap();
+
+            let manager = new_manager();
+            let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+            for batch in [vec!["one", "two, with a comma"], vec!["three\nspans two lines"]] {
+                let messages = batch.into_iter().map(|text| {
+                    let mut message = create_test_message("streamer1", "user1", text);
+                    message.timestamp = timestamp;
+                    message
+                }).collect();
+                manager.store_messages(messages).await.unwrap();
+            }
+            drop(manager);
+
+            let restarted = new_manager();
+            restarted.setup_rotation().await.unwrap();
+            let current_files = restarted.current_files.lock().await;
+            assert_eq!(current_files.get("streamer1").unwrap().message_count, 3, "{}", format);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disk_usage_tracked_incrementally() {
         let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("streamer1").join("2024-01-01")).unwrap();
+        fs::write(temp_dir.path().join("streamer1").join("2024-01-01").join("chat_2024-01-01_10-00-00.csv"), "id\n1\n").unwrap();
+
         let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
-            "json".to_string(),
-            "100MB".to_string(),
+            "csv".to_string(),
+            "1KB".to_string(),
             "1h".to_string(),
         ).unwrap();
-
-        // Setup rotation should create the output directory
         manager.setup_rotation().await.unwrap();
-        assert!(temp_dir.path().exists());
+        // the existing file plus the count.json rebuilt for its day
+        let disk_usage = manager.get_storage_stats().await.unwrap().disk_usage;
+        assert!(disk_usage > 7);
+        assert_eq!(disk_usage, FileStorageManager::calculate_directory_size(temp_dir.path()));
+
+        // enough batches to rotate through a few files, running past midnight
+        let start = DateTime::parse_from_rfc3339("2024-01-15T23:50:00Z").unwrap().with_timezone(&Utc);
+        for index in 0..12 {
+            let mut message = create_test_message("streamer2", "user1", &format!("message {} {}", index, "x".repeat(1200)));
+            message.timestamp = start + chrono::Duration::minutes(index);
+            manager.store_messages(vec![message]).await.unwrap();
+        }
+        manager.flush_all().await.unwrap();
+
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert!(stats.last_rotation.is_some());
+        assert_eq!(stats.disk_usage, FileStorageManager::calculate_directory_size(temp_dir.path()));
     }
 
     #[tokio::test]
-    async fn test_store_messages_json() {
+    async fn test_custom_filename_template() {
         let temp_dir = tempdir().unwrap();
         let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
             "json".to_string(),
             "100MB".to_string(),
             "1h".to_string(),
-        ).unwrap();
-
-        manager.setup_rotation().await.unwrap();
+        ).unwrap()
+        .with_filename_template(FilenameTemplate::parse("{streamer}-{timestamp}.{ext}").unwrap());
 
-        let messages = vec![
-            create_test_message("teststreamer", "user1", "Hello world!"),
-            create_test_message("teststreamer", "user2", "How are you?"),
-        ];
+        let mut message = create_test_message("streamer1", "user1", "flat");
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        manager.store_messages(vec![message]).await.unwrap();
 
-        manager.store_messages(messages).await.unwrap();
+        let file_path = temp_dir.path().join("streamer1-1705314645.jsonl");
+        assert_eq!(read_messages(&file_path).unwrap().len(), 1);
+        assert!(!temp_dir.path().join(DAY_COUNT_FILE).exists());
+    }
 
-        // Check that files were created
-        let streamer_dir = temp_dir.path().join("teststreamer");
-        assert!(streamer_dir.exists());
+    #[tokio::test]
+    async fn test_custom_template_with_day_dirs_keeps_counts() {
+        let temp_dir = tempdir().unwrap();
+        let new_manager = || FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap()
+        .with_filename_template(FilenameTemplate::parse("{date}/{streamer}/chat_{time}.{ext}").unwrap());
 
-        // Find the created file
-        let mut found_file = false;
-        for entry in std::fs::read_dir(&streamer_dir).unwrap() {
-            let entry = entry.unwrap();
-            if entry.path().is_dir() {
-                for date_entry in std::fs::read_dir(entry.path()).unwrap() {
-                    let date_entry = date_entry.unwrap();
-                    if date_entry.path().extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                        found_file = true;
-                        
-                        // Check file content
-                        let content = std::fs::read_to_string(date_entry.path()).unwrap();
-                        let lines: Vec<&str> = content.trim().split('\n').collect();
-                        assert_eq!(lines.len(), 2);
-                        
-                        // Verify JSON content
-                        for line in lines {
-                            let parsed: serde_json::Value = serde_json::from_str(line).unwrap();
-                            assert!(parsed["user"]["username"].is_string());
-                            assert!(parsed["message"]["text"].is_string());
-                        }
-                    }
-                }
-            }
-        }
-        assert!(found_file, "No JSON file was created");
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        let manager = new_manager();
+        let mut message = create_test_message("streamer1", "user1", "dated first");
+        message.timestamp = timestamp;
+        manager.store_messages(vec![message]).await.unwrap();
+        drop(manager);
+
+        // the date directories aren't mistaken for streamers on restart
+        let restarted = new_manager();
+        restarted.setup_rotation().await.unwrap();
+        assert!(restarted.current_files.lock().await.is_empty());
+        let mut message = create_test_message("streamer1", "user1", "after restart");
+        message.timestamp = timestamp + chrono::Duration::seconds(10);
+        restarted.store_messages(vec![message]).await.unwrap();
+
+        let day_dir = temp_dir.path().join("2024-01-15").join("streamer1");
+        assert_eq!(read_messages(&day_dir.join("chat_10-30-45.jsonl")).unwrap().len(), 1);
+        assert_eq!(read_messages(&day_dir.join("chat_10-30-55.jsonl")).unwrap().len(), 1);
+        let day_count = FileStorageManager::read_day_count(&day_dir).unwrap();
+        assert_eq!(day_count.date, "2024-01-15");
+        assert_eq!(day_count.streamer, "streamer1");
+        assert_eq!(day_count.message_count, 2);
     }
 
     #[tokio::test]
-    async fn test_store_messages_csv() {
+    async fn test_duplicate_messages_written_once() {
         let temp_dir = tempdir().unwrap();
         let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
-            "csv".to_string(),
+            "json".to_string(),
             "100MB".to_string(),
             "1h".to_string(),
         ).unwrap();
 
-        manager.setup_rotation().await.unwrap();
-
-        let messages = vec![
-            create_test_message("teststreamer", "user1", "Hello world!"),
-            create_test_message("teststreamer", "user2", "How are you?"),
-        ];
+        let mut message = create_test_message("streamer1", "user1", "polled twice");
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        let mut other = create_test_message("streamer1", "user2", "new");
+        other.timestamp = message.timestamp + chrono::Duration::seconds(1);
+        let mut newest = create_test_message("streamer1", "user3", "newest");
+        newest.timestamp = message.timestamp + chrono::Duration::seconds(2);
+
+        // the next polls read the line again alongside newer chat
+        manager.store_messages(vec![message.clone(), message.clone()]).await.unwrap();
+        manager.store_messages(vec![other, message.clone()]).await.unwrap();
+        manager.store_messages(vec![newest, message.clone()]).await.unwrap();
+
+        let day_dir = temp_dir.path().join("streamer1").join("2024-01-15");
+        let stored = read_messages(&day_dir.join("chat_2024-01-15_10-30-45.jsonl")).unwrap();
+        assert_eq!(stored.iter().filter(|stored| stored.id == message.id).count(), 1);
+        assert_eq!(stored.len(), 3);
+        let data_files = fs::read_dir(&day_dir).unwrap()
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+            .count();
+        assert_eq!(data_files, 1);
 
-        manager.store_messages(messages).await.unwrap();
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!(stats.duplicates_skipped, 3);
+        assert_eq!(stats.total_stored, 3);
+        assert_eq!(stats.total_seen, 6);
+        assert_eq!(stats.files_created, 1);
+    }
 
-        // Find the created CSV file
-        let streamer_dir = temp_dir.path().join("teststreamer");
-        let mut found_file = false;
-        
-        for entry in std::fs::read_dir(&streamer_dir).unwrap() {
-            let entry = entry.unwrap();
-            if entry.path().is_dir() {
-                for date_entry in std::fs::read_dir(entry.path()).unwrap() {
-                    let date_entry = date_entry.unwrap();
-                    if date_entry.path().extension().and_then(|s| s.to_str()) == Some("csv") {
-                        found_file = true;
-                        
-                        // Check file content
-                        let content = std::fs::read_to_string(date_entry.path()).unwrap();
-                        let lines: Vec<&str> = content.trim().split('\n').collect();
-                        
-                        // Should have header + 2 data lines
-                        assert_eq!(lines.len(), 3);
-                        
-                        // Check header
-                        assert!(lines[0].contains("username"));
-                        assert!(lines[0].contains("message_text"));
-                        
-                        // Check data lines
-                        assert!(lines[1].contains("user1"));
-                        assert!(lines[1].contains("Hello world!"));
-                        assert!(lines[2].contains("user2"));
-                        assert!(lines[2].contains("How are you?"));
-                    }
-                }
-            }
+    #[tokio::test]
+    async fn test_message_sampling() {
+        let messages: Vec<ChatMessage> = (0..200)
+            .map(|i| create_test_message("teststreamer", "user1", &format!("message {}", i)))
+            .collect();
+
+        let mut stored_texts = Vec::new();
+        for _ in 0..2 {
+            let temp_dir = tempdir().unwrap();
+            let manager = FileStorageManager::new(
+                temp_dir.path().to_path_buf(),
+                "json".to_string(),
+                "100MB".to_string(),
+                "1h".to_string(),
+            ).unwrap().with_sampling(0.25, Some(42));
+
+            manager.store_messages(messages.clone()).await.unwrap();
+
+            let stats = manager.get_storage_stats().await.unwrap();
+            assert_eq!(stats.total_seen, 200);
+            assert!(stats.total_stored > 20 && stats.total_stored < 80, "kept {}", stats.total_stored);
+
+            let day_dir = temp_dir.path().join("teststreamer").join(Utc::now().format("%Y-%m-%d").to_string());
+            let data_file = fs::read_dir(&day_dir).unwrap()
+                .flatten()
+                .find(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+                .unwrap();
+            let texts: Vec<String> = read_messages(&data_file.path()).unwrap()
+                .into_iter()
+                .map(|message| message.message.text)
+                .collect();
+            assert_eq!(texts.len() as u64, stats.total_stored);
+            stored_texts.push(texts);
         }
-        assert!(found_file, "No CSV file was created");
+
+        // the same seed keeps the same messages
+        assert_eq!(stored_texts[0], stored_texts[1]);
+
+        // nothing kept at 0.0, but everything is still counted as seen
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap().with_sampling(0.0, None);
+        manager.store_messages(messages).await.unwrap();
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!((stats.total_seen, stats.total_stored), (200, 0));
+        assert!(!temp_dir.path().join("teststreamer").exists());
     }
 
     #[tokio::test]
-    async fn test_multiple_streamers() {
+    async fn test_parallel_streamer_writes() {
         let temp_dir = tempdir().unwrap();
         let manager = FileStorageManager::new(
             temp_dir.path().to_path_buf(),
-            "json".to_string(),
+            "csv".to_string(),
             "100MB".to_string(),
             "1h".to_string(),
-        ).unwrap();
+        ).unwrap().with_write_concurrency(4);
 
         manager.setup_rotation().await.unwrap();
 
-        let messages = vec![
-            create_test_message("streamer1", "user1", "Hello from streamer1!"),
-            create_test_message("streamer2", "user2", "Hello from streamer2!"),
-            create_test_message("streamer1", "user3", "Another message for streamer1!"),
-        ];
-
-        manager.store_messages(messages).await.unwrap();
+        for _ in 0..3 {
+            let messages = (0..8)
+                .flat_map(|i| {
+                    let streamer = format!("streamer{}", i);
+                    vec![
+                        create_test_message(&streamer, "user1", "first"),
+                        create_test_message(&streamer, "user2", "second"),
+                    ]
+                })
+                .collect();
+            manager.store_messages(messages).await.unwrap();
+        }
 
-        // Check that both streamer directories were created
-        assert!(temp_dir.path().join("streamer1").exists());
-        assert!(temp_dir.path().join("streamer2").exists());
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!(stats.total_stored, 48);
+        assert_eq!(stats.files_created, 8);
+
+        // every streamer got one file with a single header and all its rows
+        for i in 0..8 {
+            let day_dir = temp_dir.path().join(format!("streamer{}", i)).join(Utc::now().format("%Y-%m-%d").to_string());
+            assert_eq!(FileStorageManager::read_day_count(&day_dir).unwrap().message_count, 6);
+            let data_file = fs::read_dir(&day_dir).unwrap()
+                .flatten()
+                .find(|entry| entry.path().extension().is_some_and(|ext| ext == "csv"))
+                .unwrap();
+            let content = fs::read_to_string(data_file.path()).unwrap();
+            assert_eq!(content.lines().count(), 7);
+        }
     }
 
     #[tokio::test]
@@ -823,7 +2793,7 @@ mod tests {
 
         // Initial stats
         let initial_stats = manager.get_storage_stats().await.unwrap();
-        assert_eq!(initial_stats.total_messages, 0);
+        assert_eq!(initial_stats.total_stored, 0);
         assert_eq!(initial_stats.files_created, 0);
 
         // Store some messages
@@ -836,11 +2806,47 @@ mod tests {
 
         // Check updated stats
         let updated_stats = manager.get_storage_stats().await.unwrap();
-        assert_eq!(updated_stats.total_messages, 2);
+        assert_eq!(updated_stats.total_stored, 2);
         assert_eq!(updated_stats.files_created, 1);
         assert!(updated_stats.disk_usage > 0);
     }
 
+    #[tokio::test]
+    async fn test_day_count_file() {
+        let temp_dir = tempdir().unwrap();
+        let new_manager = || FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap();
+
+        let manager = new_manager();
+        manager.setup_rotation().await.unwrap();
+        manager.store_messages(vec![
+            create_test_message("teststreamer", "user1", "Hello world!"),
+            create_test_message("teststreamer", "user2", "How are you?"),
+        ]).await.unwrap();
+        manager.store_messages(vec![create_test_message("teststreamer", "user3", "hi")]).await.unwrap();
+
+        let day_dir = temp_dir.path().join("teststreamer").join(Utc::now().format("%Y-%m-%d").to_string());
+        let day_count = FileStorageManager::read_day_count(&day_dir).unwrap();
+        assert_eq!(day_count.streamer, "teststreamer");
+        assert_eq!(day_count.message_count, 3);
+        assert!(!day_dir.join("count.json.tmp").exists());
+
+        // a restart picks the count up from disk
+        let manager = new_manager();
+        manager.setup_rotation().await.unwrap();
+        manager.store_messages(vec![create_test_message("teststreamer", "user4", "hey")]).await.unwrap();
+        assert_eq!(FileStorageManager::read_day_count(&day_dir).unwrap().message_count, 4);
+
+        // and a lost count file is rebuilt from the data files
+        fs::remove_file(day_dir.join(DAY_COUNT_FILE)).unwrap();
+        new_manager().setup_rotation().await.unwrap();
+        assert_eq!(FileStorageManager::read_day_count(&day_dir).unwrap().message_count, 4);
+    }
+
     #[tokio::test]
     async fn test_empty_messages() {
         let temp_dir = tempdir().unwrap();
@@ -857,7 +2863,7 @@ mod tests {
         manager.store_messages(vec![]).await.unwrap();
 
         let stats = manager.get_storage_stats().await.unwrap();
-        assert_eq!(stats.total_messages, 0);
+        assert_eq!(stats.total_stored, 0);
         assert_eq!(stats.files_created, 0);
     }
 
@@ -876,14 +2882,84 @@ mod tests {
             size: 2048, // 2KB, larger than rotation size
             created: Utc::now(),
             message_count: 10,
+            writer: None,
+            seen_ids: HashSet::new(),
+            day: Utc::now().date_naive(),
         };
 
         // Should rotate due to size
         let runtime = tokio::runtime::Runtime::new().unwrap();
-        let should_rotate = runtime.block_on(manager.should_rotate_file(&file_info));
+        let should_rotate = runtime.block_on(manager.should_rotate_file("teststreamer", &file_info));
         assert!(should_rotate);
     }
 
+    #[tokio::test]
+    async fn test_unlimited_rotation_never_triggers() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "unlimited".to_string(),
+            "1h".to_string(),
+        ).unwrap()
+        .with_rotation_override("nightbot", Some("0"), Some("unlimited")).unwrap();
+
+        // far past any normal size threshold, but still young
+        let huge = FileInfo {
+            path: temp_dir.path().join("test.jsonl"),
+            size: 50 * 1024 * 1024 * 1024,
+            created: Utc::now(),
+            message_count: 1_000_000,
+            writer: None,
+            seen_ids: HashSet::new(),
+            day: Utc::now().date_naive(),
+        };
+        assert!(!manager.should_rotate_file("teststreamer", &huge).await);
+
+        // the global time limit still applies
+        let old = FileInfo {
+            created: Utc::now() - chrono::Duration::hours(2),
+            ..huge
+        };
+        assert!(manager.should_rotate_file("teststreamer", &old).await);
+
+        // with both turned off nothing rotates
+        assert!(!manager.should_rotate_file("nightbot", &old).await);
+    }
+
+    #[tokio::test]
+    async fn test_per_streamer_rotation_override() {
+        let temp_dir = tempdir().unwrap();
+        let manager = FileStorageManager::new(
+            temp_dir.path().to_path_buf(),
+            "json".to_string(),
+            "100MB".to_string(),
+            "1h".to_string(),
+        ).unwrap()
+        .with_rotation_override("BigChannel", None, Some("10m")).unwrap();
+
+        // both files were started 15 minutes ago
+        let started = Utc::now() - chrono::Duration::minutes(15);
+        for streamer in ["bigchannel", "smallchannel"] {
+            let message = create_test_message(streamer, "user1", "hi");
+            let path = manager.get_file_path(streamer, message.timestamp).await;
+            manager.current_files.lock().await.insert(streamer.to_string(), FileInfo {
+                path,
+                size: 100,
+                created: started,
+                message_count: 1,
+                writer: None,
+                seen_ids: HashSet::new(),
+                day: message.timestamp.date_naive(),
+            });
+            manager.store_messages(vec![message]).await.unwrap();
+        }
+
+        let current_files = manager.current_files.lock().await;
+        assert!(current_files.get("bigchannel").unwrap().created > started);
+        assert_eq!(current_files.get("smallchannel").unwrap().created, started);
+    }
+
     #[test]
     fn test_file_rotation_time_check() {
         let temp_dir = tempdir().unwrap();
@@ -899,11 +2975,14 @@ mod tests {
             size: 100, // Small size
             created: Utc::now() - chrono::Duration::seconds(2), // 2 seconds ago
             message_count: 1,
+            writer: None,
+            seen_ids: HashSet::new(),
+            day: Utc::now().date_naive(),
         };
 
         // Should rotate due to age
         let runtime = tokio::runtime::Runtime::new().unwrap();
-        let should_rotate = runtime.block_on(manager.should_rotate_file(&file_info));
+        let should_rotate = runtime.block_on(manager.should_rotate_file("teststreamer", &file_info));
         assert!(should_rotate);
     }
 }
\ No newline at end of file
@@ -0,0 +1,240 @@
+use async_trait::async_trait;
+use chrono::SecondsFormat;
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+
+use crate::config::OutputConfig;
+use crate::error::{Result, ScrapingError};
+use crate::parser::chat_message::ChatMessage;
+use crate::storage::{StorageManager, StorageStats};
+
+/// Database file created inside `output.directory` for `format = "sqlite"`
+pub const SQLITE_DB_FILE: &str = "chat.sqlite3";
+
+const SCHEMA: &str = "
+    PRAGMA journal_mode = WAL;
+    CREATE TABLE IF NOT EXISTS messages (
+        id TEXT PRIMARY KEY,
+        streamer TEXT NOT NULL,
+        username TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        text TEXT NOT NULL,
+        content TEXT NOT NULL,
+        message TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS messages_streamer_timestamp ON messages (streamer, timestamp);
+    CREATE INDEX IF NOT EXISTS messages_username ON messages (username);
+";
+
+// replayed batches carry the same ids, so a second insert is a no-op
+const INSERT_MESSAGE: &str = "INSERT OR IGNORE INTO messages (id, streamer, username, timestamp, text, content, message) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)";
+
+/// Writes messages into a `messages` table in one SQLite database, a row per
+/// message with the emotes and fragments as JSON, so they can be queried with SQL.
+/// `timestamp` is RFC 3339 UTC with milliseconds, which sorts chronologically.
+pub struct SqliteStorageManager {
+    db_path: PathBuf,
+    connection: Arc<Mutex<Connection>>,
+    stats: Arc<Mutex<StorageStats>>,
+}
+
+impl SqliteStorageManager {
+    /// Open or create the database at `db_path`
+    pub fn new(db_path: PathBuf) -> Result<Self> {
+        if let Some(parent) = db_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+
+        let connection = Connection::open(&db_path)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to open {}: {}", db_path.display(), e)))?;
+        connection.execute_batch(SCHEMA).map_err(sqlite_error)?;
+        info!("Storing chat in SQLite database {}", db_path.display());
+
+        Ok(Self {
+            db_path,
+            connection: Arc::new(Mutex::new(connection)),
+            stats: Arc::new(Mutex::new(StorageStats {
+                total_seen: 0,
+                total_stored: 0,
+                files_created: 1,
+                disk_usage: 0,
+                last_rotation: None,
+                duplicates_skipped: 0,
+            })),
+        })
+    }
+
+    /// Database at `<directory>/chat.sqlite3`
+    pub fn from_config(output: &OutputConfig) -> Result<Self> {
+        Self::new(output.directory.join(SQLITE_DB_FILE))
+    }
+
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Everything `username` said, oldest first
+    pub async fn messages_by_user(&self, username: &str) -> Result<Vec<ChatMessage>> {
+        let connection = self.connection.clone();
+        let username = username.to_string();
+        tokio::task::spawn_blocking(move || {
+            let connection = lock(&connection);
+            let mut statement = connection
+                .prepare("SELECT message FROM messages WHERE username = ?1 ORDER BY timestamp")
+                .map_err(sqlite_error)?;
+            let mut rows = statement.query([&username]).map_err(sqlite_error)?;
+
+            let mut messages = Vec::new();
+            while let Some(row) = rows.next().map_err(sqlite_error)? {
+                let message: String = row.get(0).map_err(sqlite_error)?;
+                messages.push(serde_json::from_str(&message)
+                    .map_err(|e| ScrapingError::ParseError(format!("Invalid stored message: {}", e)))?);
+            }
+            Ok(messages)
+        })
+        .await
+        .map_err(|e| ScrapingError::StorageError(format!("Query task failed: {}", e)))?
+    }
+
+    fn insert_batch(connection: &Connection, messages: &[ChatMessage]) -> Result<u64> {
+        let mut statement = connection.prepare_cached(INSERT_MESSAGE).map_err(sqlite_error)?;
+        let mut inserted = 0;
+        for message in messages {
+            let content = serde_json::json!({
+                "emotes": message.message.emotes,
+                "fragments": message.message.fragments,
+            });
+            inserted += statement.execute(params![
+                message.id,
+                message.streamer,
+                message.user.username,
+                message.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+                message.message.text,
+                content.to_string(),
+                serde_json::to_string(message)?,
+            ]).map_err(sqlite_error)? as u64;
+        }
+        Ok(inserted)
+    }
+}
+
+#[async_trait]
+impl StorageManager for SqliteStorageManager {
+    async fn store_messages(&self, messages: Vec<ChatMessage>) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let seen = messages.len() as u64;
+        let connection = self.connection.clone();
+        // one transaction per batch, rolled back whole if any insert fails
+        let inserted = tokio::task::spawn_blocking(move || {
+            let mut connection = lock(&connection);
+            let transaction = connection.transaction().map_err(sqlite_error)?;
+            let inserted = Self::insert_batch(&transaction, &messages)?;
+            transaction.commit().map_err(sqlite_error)?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(inserted)
+        })
+        .await
+        .map_err(|e| ScrapingError::StorageError(format!("Insert task failed: {}", e)))??;
+
+        debug!("Inserted {} of {} messages into {}", inserted, seen, self.db_path.display());
+        let mut stats = lock(&self.stats);
+        stats.total_seen += seen;
+        stats.total_stored += inserted;
+        stats.duplicates_skipped += seen - inserted;
+        Ok(())
+    }
+
+    async fn setup_rotation(&self) -> Result<()> {
+        // a single database never rotates
+        Ok(())
+    }
+
+    async fn get_storage_stats(&self) -> Result<StorageStats> {
+        // the write-ahead log holds recent inserts until it's checkpointed
+        let mut wal_path = self.db_path.clone().into_os_string();
+        wal_path.push("-wal");
+        let disk_usage = [self.db_path.clone(), PathBuf::from(wal_path)]
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+
+        let mut stats = lock(&self.stats);
+        stats.disk_usage = disk_usage;
+        Ok(stats.clone())
+    }
+
+    async fn flush_all(&self) -> Result<usize> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || lock(&connection).execute_batch("PRAGMA wal_checkpoint(FULL)"))
+            .await
+            .map_err(|e| ScrapingError::StorageError(format!("Checkpoint task failed: {}", e)))?
+            .map_err(sqlite_error)?;
+        Ok(1)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn sqlite_error(e: rusqlite::Error) -> Box<dyn std::error::Error + Send + Sync> {
+    ScrapingError::StorageError(format!("SQLite error: {}", e)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::fixtures::chat_message;
+    use chrono::{DateTime, Utc};
+    use tempfile::tempdir;
+
+    fn create_test_message(username: &str, text: &str, second: u32) -> ChatMessage {
+        let mut message = chat_message(text);
+        message.timestamp = DateTime::parse_from_rfc3339(&format!("2024-01-15T10:30:{:02}Z", second)).unwrap().with_timezone(&Utc);
+        message.user.username = username.to_string();
+        message.user.display_name = username.to_string();
+        message.message.emotes = vec!["Kappa".to_string()];
+        message
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_and_query() {
+        let temp_dir = tempdir().unwrap();
+        let manager = SqliteStorageManager::new(temp_dir.path().join(SQLITE_DB_FILE)).unwrap();
+
+        let second = create_test_message("alice", "it's 'quoted'; DROP TABLE messages", 20);
+        let first = create_test_message("alice", "hello", 10);
+        manager.store_messages(vec![
+            second.clone(),
+            create_test_message("bob", "hi", 15),
+            first.clone(),
+        ]).await.unwrap();
+
+        // ordered by time, not insertion, and round-tripped intact
+        assert_eq!(manager.messages_by_user("alice").await.unwrap(), vec![first.clone(), second]);
+        assert_eq!(manager.messages_by_user("bob").await.unwrap().len(), 1);
+        assert!(manager.messages_by_user("nobody").await.unwrap().is_empty());
+
+        // a replayed message isn't stored twice
+        manager.store_messages(vec![first]).await.unwrap();
+        assert_eq!(manager.messages_by_user("alice").await.unwrap().len(), 2);
+
+        manager.flush_all().await.unwrap();
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!(stats.total_seen, 4);
+        assert_eq!(stats.total_stored, 3);
+        assert_eq!(stats.duplicates_skipped, 1);
+        assert!(stats.disk_usage > 0);
+
+        // reopening keeps what was written
+        drop(manager);
+        let reopened = SqliteStorageManager::new(temp_dir.path().join(SQLITE_DB_FILE)).unwrap();
+        assert_eq!(reopened.messages_by_user("alice").await.unwrap().len(), 2);
+    }
+}
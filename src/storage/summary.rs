@@ -0,0 +1,312 @@
+use chrono::{DateTime, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::error::{Result, ScrapingError};
+use crate::parser::chat_message::ChatMessage;
+
+/// How many emotes are kept in each summary
+const TOP_EMOTES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmoteCount {
+    pub emote: String,
+    pub count: u64,
+}
+
+/// Rollup of one streamer's chat over a single hour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HourlySummary {
+    pub streamer: String,
+    pub hour_start: DateTime<Utc>,
+    pub hour_end: DateTime<Utc>,
+    pub message_count: u64,
+    pub unique_chatters: usize,
+    pub top_emotes: Vec<EmoteCount>,
+}
+
+struct SummaryBucket {
+    hour_start: DateTime<Utc>,
+    message_count: u64,
+    chatters: HashSet<String>,
+    emotes: HashMap<String, u64>,
+}
+
+impl SummaryBucket {
+    fn new(hour_start: DateTime<Utc>) -> Self {
+        Self {
+            hour_start,
+            message_count: 0,
+            chatters: HashSet::new(),
+            emotes: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, message: &ChatMessage) {
+        self.message_count += 1;
+        self.chatters.insert(message.user.username.to_lowercase());
+        for emote in &message.message.emotes {
+            *self.emotes.entry(emote.clone()).or_insert(0) += 1;
+        }
+    }
+
+    fn hour_end(&self) -> DateTime<Utc> {
+        self.hour_start + chrono::Duration::hours(1)
+    }
+
+    fn to_summary(&self, streamer: &str) -> HourlySummary {
+        HourlySummary {
+            streamer: streamer.to_string(),
+            hour_start: self.hour_start,
+            hour_end: self.hour_end(),
+            message_count: self.message_count,
+            unique_chatters: self.chatters.len(),
+            top_emotes: top_emotes(self.emotes.iter().map(|(emote, count)| (emote.clone(), *count))),
+        }
+    }
+}
+
+fn top_emotes(counts: impl Iterator<Item = (String, u64)>) -> Vec<EmoteCount> {
+    let mut emotes: Vec<EmoteCount> = counts
+        .map(|(emote, count)| EmoteCount { emote, count })
+        .collect();
+    emotes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.emote.cmp(&b.emote)));
+    emotes.truncate(TOP_EMOTES);
+    emotes
+}
+
+/// Aggregates chat messages into hourly buckets per streamer and writes
+/// `summary_<date>_<hour>.json` into the streamer directory when a bucket closes.
+pub struct SummaryWriter {
+    output_dir: PathBuf,
+    timezone: Tz,
+    buckets: HashMap<String, SummaryBucket>,
+}
+
+impl SummaryWriter {
+    pub fn new(output_dir: PathBuf, timezone: Tz) -> Self {
+        Self {
+            output_dir,
+            timezone,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Add a message to its streamer's bucket, closing the previous hour if it moved on.
+    /// Late messages from an already-closed hour count toward the open bucket.
+    pub fn record(&mut self, message: &ChatMessage) -> Result<()> {
+        let hour_start = self.hour_start(message.timestamp);
+
+        let closed = match self.buckets.get(&message.streamer) {
+            Some(bucket) if hour_start > bucket.hour_start => self.buckets.remove(&message.streamer),
+            _ => None,
+        };
+        if let Some(bucket) = closed {
+            self.write_summary(&bucket.to_summary(&message.streamer))?;
+        }
+
+        self.buckets
+            .entry(message.streamer.clone())
+            .or_insert_with(|| SummaryBucket::new(hour_start))
+            .record(message);
+        Ok(())
+    }
+
+    /// Write and drop every bucket whose hour has ended, returning how many were written
+    pub fn flush_due(&mut self, now: DateTime<Utc>) -> Result<usize> {
+        let due: Vec<String> = self.buckets
+            .iter()
+            .filter(|(_, bucket)| bucket.hour_end() <= now)
+            .map(|(streamer, _)| streamer.clone())
+            .collect();
+
+        for streamer in &due {
+            if let Some(bucket) = self.buckets.remove(streamer) {
+                self.write_summary(&bucket.to_summary(streamer))?;
+            }
+        }
+        Ok(due.len())
+    }
+
+    /// Write every open bucket, used on shutdown
+    pub fn flush_all(&mut self) -> Result<usize> {
+        let buckets: Vec<(String, SummaryBucket)> = self.buckets.drain().collect();
+        for (streamer, bucket) in &buckets {
+            self.write_summary(&bucket.to_summary(streamer))?;
+        }
+        Ok(buckets.len())
+    }
+
+    pub fn open_buckets(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Path of the summary file for the hour starting at `hour_start`
+    pub fn summary_path(&self, streamer: &str, hour_start: DateTime<Utc>) -> PathBuf {
+        let local = hour_start.with_timezone(&self.timezone);
+        self.output_dir
+            .join(streamer)
+            .join(format!("summary_{}_{}.json", local.format("%Y-%m-%d"), local.format("%H")))
+    }
+
+    fn hour_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        // truncate in local time so half-hour offsets still get whole local hours
+        let local = timestamp.with_timezone(&self.timezone);
+        timestamp
+            - chrono::Duration::minutes(local.minute() as i64)
+            - chrono::Duration::seconds(local.second() as i64)
+            - chrono::Duration::nanoseconds(local.nanosecond() as i64)
+    }
+
+    fn write_summary(&self, summary: &HourlySummary) -> Result<()> {
+        let path = self.summary_path(&summary.streamer, summary.hour_start);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to create summary directory: {}", e)))?;
+        }
+
+        // a restart inside the same hour leaves a partial summary behind, fold it in
+        let summary = match Self::read_summary(&path) {
+            Some(existing) => merge_summaries(existing, summary),
+            None => summary.clone(),
+        };
+
+        let content = serde_json::to_string_pretty(&summary)
+            .map_err(|e| ScrapingError::StorageError(format!("Summary serialization failed: {}", e)))?;
+        fs::write(&path, content)
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to write summary: {}", e)))?;
+
+        debug!("Wrote hourly summary {}", path.display());
+        Ok(())
+    }
+
+    fn read_summary(path: &Path) -> Option<HourlySummary> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
+
+fn merge_summaries(existing: HourlySummary, new: &HourlySummary) -> HourlySummary {
+    let mut emotes: HashMap<String, u64> = HashMap::new();
+    for emote in existing.top_emotes.iter().chain(new.top_emotes.iter()) {
+        *emotes.entry(emote.emote.clone()).or_insert(0) += emote.count;
+    }
+
+    HourlySummary {
+        streamer: new.streamer.clone(),
+        hour_start: new.hour_start,
+        hour_end: new.hour_end,
+        message_count: existing.message_count + new.message_count,
+        // chatter sets aren't persisted, so the larger count is a lower bound
+        unique_chatters: existing.unique_chatters.max(new.unique_chatters),
+        top_emotes: top_emotes(emotes.into_iter()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::chat_message::{ChatUser, MessageContent, StreamContext};
+    use tempfile::tempdir;
+
+    fn message_at(streamer: &str, username: &str, emotes: &[&str], timestamp: &str) -> ChatMessage {
+        ChatMessage::new(
+            streamer.to_string(),
+            DateTime::parse_from_rfc3339(timestamp).unwrap().with_timezone(&Utc),
+            ChatUser {
+                username: username.to_string(),
+                display_name: username.to_string(),
+                color: None,
+                badges: vec![],
+            },
+            MessageContent {
+                text: "hello".to_string(),
+                emotes: emotes.iter().map(|e| e.to_string()).collect(),
+                fragments: vec![],
+            },
+            StreamContext {
+                viewer_count: None,
+                game_category: None,
+                stream_title: None,
+            },
+        )
+    }
+
+    fn read(path: &Path) -> HourlySummary {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_bucket_closes_when_hour_changes() {
+        let temp_dir = tempdir().unwrap();
+        let mut writer = SummaryWriter::new(temp_dir.path().to_path_buf(), Tz::UTC);
+
+        writer.record(&message_at("streamer", "alice", &["Kappa"], "2024-01-15T10:05:00Z")).unwrap();
+        writer.record(&message_at("streamer", "bob", &["Kappa", "PogChamp"], "2024-01-15T10:40:00Z")).unwrap();
+        writer.record(&message_at("streamer", "Alice", &[], "2024-01-15T10:59:59Z")).unwrap();
+
+        let path = temp_dir.path().join("streamer").join("summary_2024-01-15_10.json");
+        assert!(!path.exists());
+
+        writer.record(&message_at("streamer", "carol", &[], "2024-01-15T11:00:01Z")).unwrap();
+
+        let summary = read(&path);
+        assert_eq!(summary.message_count, 3);
+        assert_eq!(summary.unique_chatters, 2);
+        assert_eq!(summary.top_emotes[0], EmoteCount { emote: "Kappa".to_string(), count: 2 });
+        assert_eq!(summary.top_emotes.len(), 2);
+        assert_eq!(writer.open_buckets(), 1);
+    }
+
+    #[test]
+    fn test_flush_due_and_flush_all() {
+        let temp_dir = tempdir().unwrap();
+        let mut writer = SummaryWriter::new(temp_dir.path().to_path_buf(), Tz::UTC);
+
+        writer.record(&message_at("one", "alice", &[], "2024-01-15T10:05:00Z")).unwrap();
+        writer.record(&message_at("two", "bob", &[], "2024-01-15T11:05:00Z")).unwrap();
+
+        let now = DateTime::parse_from_rfc3339("2024-01-15T11:30:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(writer.flush_due(now).unwrap(), 1);
+        assert!(temp_dir.path().join("one").join("summary_2024-01-15_10.json").exists());
+
+        assert_eq!(writer.flush_all().unwrap(), 1);
+        assert!(temp_dir.path().join("two").join("summary_2024-01-15_11.json").exists());
+        assert_eq!(writer.open_buckets(), 0);
+    }
+
+    #[test]
+    fn test_partial_summaries_are_merged() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("streamer").join("summary_2024-01-15_10.json");
+
+        let mut writer = SummaryWriter::new(temp_dir.path().to_path_buf(), Tz::UTC);
+        writer.record(&message_at("streamer", "alice", &["Kappa"], "2024-01-15T10:05:00Z")).unwrap();
+        writer.flush_all().unwrap();
+
+        let mut writer = SummaryWriter::new(temp_dir.path().to_path_buf(), Tz::UTC);
+        writer.record(&message_at("streamer", "bob", &["Kappa"], "2024-01-15T10:35:00Z")).unwrap();
+        writer.flush_all().unwrap();
+
+        let summary = read(&path);
+        assert_eq!(summary.message_count, 2);
+        assert_eq!(summary.top_emotes[0].count, 2);
+    }
+
+    #[test]
+    fn test_summary_uses_local_hours() {
+        let temp_dir = tempdir().unwrap();
+        let mut writer = SummaryWriter::new(temp_dir.path().to_path_buf(), chrono_tz::Asia::Kolkata);
+
+        // 10:45 UTC is 16:15 in Kolkata, so the local hour started at 10:30 UTC
+        writer.record(&message_at("streamer", "alice", &[], "2024-01-15T10:45:00Z")).unwrap();
+        writer.flush_all().unwrap();
+
+        let summary = read(&temp_dir.path().join("streamer").join("summary_2024-01-15_16.json"));
+        assert_eq!(summary.hour_start.to_rfc3339(), "2024-01-15T10:30:00+00:00");
+    }
+}
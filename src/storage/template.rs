@@ -0,0 +1,172 @@
+use chrono::{DateTime, TimeZone};
+use std::fmt::Display;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{Result, ScrapingError};
+
+/// Layout used when `output.filename_template` isn't set
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{streamer}/{date}/chat_{date}_{time}.{ext}";
+
+const PLACEHOLDERS: [&str; 5] = ["streamer", "date", "time", "ext", "timestamp"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(&'static str),
+}
+
+/// Chat file path relative to the output directory, e.g. `{streamer}-{timestamp}.{ext}`.
+/// `/` starts a subdirectory; `{date}` and `{time}` are in the storage timezone
+/// and `{timestamp}` is unix seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilenameTemplate {
+    segments: Vec<Segment>,
+}
+
+impl Default for FilenameTemplate {
+    fn default() -> Self {
+        Self::parse(DEFAULT_FILENAME_TEMPLATE).expect("default template is valid")
+    }
+}
+
+impl FilenameTemplate {
+    /// Parse a template, rejecting unknown placeholders, stray braces, paths
+    /// that would leave the output directory and templates that would give two
+    /// streamers, or two rotations, the same file
+    pub fn parse(template: &str) -> Result<Self> {
+        let invalid = |reason: String| ScrapingError::ConfigError(format!("Invalid filename_template '{}': {}", template, reason));
+
+        let path = Path::new(template);
+        if template.trim().is_empty() {
+            return Err(invalid("it is empty".to_string()).into());
+        }
+        if path.is_absolute() || path.components().any(|component| matches!(component, Component::ParentDir)) {
+            return Err(invalid("it must stay inside the output directory".to_string()).into());
+        }
+
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            match rest.find(['{', '}']) {
+                Some(index) if rest[index..].starts_with('}') => {
+                    return Err(invalid("unmatched '}'".to_string()).into());
+                }
+                Some(index) => {
+                    if index > 0 {
+                        segments.push(Segment::Literal(rest[..index].to_string()));
+                    }
+                    let Some(end) = rest[index..].find('}') else {
+                        return Err(invalid("unclosed '{'".to_string()).into());
+                    };
+                    let name = &rest[index + 1..index + end];
+                    let Some(placeholder) = PLACEHOLDERS.iter().find(|placeholder| **placeholder == name) else {
+                        return Err(invalid(format!(
+                            "unknown placeholder {{{}}}, expected one of {}",
+                            name,
+                            PLACEHOLDERS.map(|placeholder| format!("{{{}}}", placeholder)).join(", ")
+                        )).into());
+                    };
+                    segments.push(Segment::Placeholder(placeholder));
+                    rest = &rest[index + end + 1..];
+                }
+                None => {
+                    segments.push(Segment::Literal(rest.to_string()));
+                    rest = "";
+                }
+            }
+        }
+
+        if template.ends_with('/') {
+            return Err(invalid("it names a directory, not a file".to_string()).into());
+        }
+        let template = Self { segments };
+        if !template.placeholders().any(|placeholder| placeholder == "streamer") {
+            return Err(invalid("it needs {streamer} so each channel gets its own files".to_string()).into());
+        }
+        if !template.placeholders().any(|placeholder| placeholder == "time" || placeholder == "timestamp") {
+            return Err(invalid("it needs {time} or {timestamp} so a rotated file isn't reopened".to_string()).into());
+        }
+        Ok(template)
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether files land in a directory of their own per streamer and day,
+    /// e.g. `{date}/{streamer}/...`. Day counts are kept in that directory.
+    pub fn has_day_dirs(&self) -> bool {
+        let Some(last_separator) = self.segments
+            .iter()
+            .rposition(|segment| matches!(segment, Segment::Literal(text) if text.contains('/')))
+        else {
+            return false;
+        };
+        let directory = Self { segments: self.segments[..last_separator].to_vec() };
+        directory.placeholders().any(|placeholder| placeholder == "streamer")
+            && directory.placeholders().any(|placeholder| placeholder == "date")
+            && !directory.placeholders().any(|placeholder| placeholder == "time" || placeholder == "timestamp")
+    }
+
+    fn placeholders(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Placeholder(placeholder) => Some(*placeholder),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Relative path for a file started at `time`
+    pub fn render<Tz: TimeZone>(&self, streamer: &str, time: &DateTime<Tz>, extension: &str) -> PathBuf
+    where
+        Tz::Offset: Display,
+    {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => rendered.push_str(text),
+                Segment::Placeholder("streamer") => rendered.push_str(streamer),
+                Segment::Placeholder("date") => rendered.push_str(&time.format("%Y-%m-%d").to_string()),
+                Segment::Placeholder("time") => rendered.push_str(&time.format("%H-%M-%S").to_string()),
+                Segment::Placeholder("ext") => rendered.push_str(extension),
+                Segment::Placeholder(_) => rendered.push_str(&time.timestamp().to_string()),
+            }
+        }
+        PathBuf::from(rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_filename_templates() {
+        let time = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+
+        let default = FilenameTemplate::default();
+        assert_eq!(
+            default.render("shroud", &time, "jsonl"),
+            PathBuf::from("shroud/2024-01-15/chat_2024-01-15_10-30-45.jsonl")
+        );
+
+        let flat = FilenameTemplate::parse("{streamer}-{timestamp}.{ext}").unwrap();
+        assert!(!flat.is_default());
+        assert_eq!(flat.render("shroud", &time, "jsonl"), PathBuf::from("shroud-1705314645.jsonl"));
+
+        // day counts need a directory per streamer and day
+        assert!(default.has_day_dirs());
+        assert!(!flat.has_day_dirs());
+        assert!(FilenameTemplate::parse("{date}/{streamer}/{time}.{ext}").unwrap().has_day_dirs());
+        assert!(!FilenameTemplate::parse("{streamer}/{date}_{time}.{ext}").unwrap().has_day_dirs());
+        assert!(!FilenameTemplate::parse("{streamer}/{date}/{time}/chat.{ext}").unwrap().has_day_dirs());
+
+        for bad in [
+            "{streamer}-{hour}.jsonl", "{streamer", "chat}.jsonl", "../{streamer}.jsonl", "/tmp/{streamer}.jsonl", "{date}/", "",
+            "{streamer}/{date}.{ext}", // rotating within a day would reopen the same file
+            "{date}/chat_{time}.{ext}", // every channel in one file
+        ] {
+            assert!(FilenameTemplate::parse(bad).is_err(), "accepted {:?}", bad);
+        }
+    }
+}
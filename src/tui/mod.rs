@@ -2,18 +2,22 @@ use anyhow::Result;
 use crossterm::event::{Event, KeyCode};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState, Tabs, Wrap},
+    style::{Color, Modifier, Style},
+    widgets::{block::Title, Block, Borders, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState, Tabs, Wrap},
     text::{Line, Span},
     Frame,
 };
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::agents::{AgentId, AgentStatus};
+use crate::agents::{AgentId, AgentMessage, AgentStatus};
+use crate::parser::chat_message::{ChatMessage, ChatUser};
+use crate::config::{Config, ConfigManager};
 
 pub mod run;
-pub use run::run_tui;
+pub use run::{dashboard_snapshot, run_tui};
 
 // Helper functions for AgentStatus
 impl AgentStatus {
@@ -24,6 +28,7 @@ impl AgentStatus {
             AgentStatus::Running => "▶",
             AgentStatus::Stopping => "⏹",
             AgentStatus::Stopped => "⏹",
+            AgentStatus::Paused => "⏯",
             AgentStatus::Error(_) => "❌",
         }
     }
@@ -35,6 +40,7 @@ impl AgentStatus {
             AgentStatus::Running => Color::Green,
             AgentStatus::Stopping => Color::Red,
             AgentStatus::Stopped => Color::Gray,
+            AgentStatus::Paused => Color::Blue,
             AgentStatus::Error(_) => Color::Red,
         }
     }
@@ -48,6 +54,7 @@ impl std::fmt::Display for AgentStatus {
             AgentStatus::Running => write!(f, "Running"),
             AgentStatus::Stopping => write!(f, "Stopping"),
             AgentStatus::Stopped => write!(f, "Stopped"),
+            AgentStatus::Paused => write!(f, "Paused"),
             AgentStatus::Error(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -75,9 +82,13 @@ pub struct AgentInfo {
     pub alert_id: Option<u64>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum Action {
     Continue,
     Quit,
+    Redraw, // the terminal was resized, draw again now rather than on the next frame
+    StopAgent(AgentId),
+    RestartAgent(AgentId),
 }
 
 pub trait TUIMonitor {
@@ -95,6 +106,7 @@ enum Tab {
     Performance,
     Alerts,
     Config,
+    Messages,
 }
 
 impl Tab {
@@ -106,16 +118,224 @@ impl Tab {
             Tab::Performance => "Performance",
             Tab::Alerts => "Alerts",
             Tab::Config => "Config",
+            Tab::Messages => "Chat",
+        }
+    }
+
+    /// Keys that only do something on this tab, for the help popup
+    fn key_help(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Tab::Agents => &[("↑/↓", "Select an agent"), ("x", "Stop the selected agent"), ("r", "Restart the selected agent")],
+            Tab::Logs => &[
+                ("↑/↓", "Scroll the log"),
+                ("/", "Search, Enter keeps the search and Esc clears it"),
+                ("w", "Only show warnings and errors"),
+                ("Esc", "Clear the search and level filter"),
+            ],
+            Tab::Config => &[
+                ("e", "Enter or leave edit mode"),
+                ("↑/↓", "Pick a setting (edit mode)"),
+                ("Enter", "Edit the value, or flip an on/off setting"),
+                ("s", "Save the config file (edit mode)"),
+                ("Esc", "Drop the typed value, or leave edit mode"),
+            ],
+            Tab::Messages => &[("↑/↓", "Scroll the chat"), ("f", "Cycle through streamers, then back to all")],
+            Tab::Alerts => &[
+                ("↑/↓", "Select an alert"),
+                ("a", "Acknowledge the selected alert"),
+                ("u", "Only show alerts not acknowledged yet"),
+            ],
+            Tab::Overview | Tab::Performance => &[],
         }
     }
 }
 
+/// Keys that work on every tab
+const GLOBAL_KEY_HELP: [(&str, &str); 5] = [
+    ("q", "Quit"),
+    ("Tab", "Cycle to the next tab"),
+    ("1-7", "Jump to a tab"),
+    ("h / ?", "Show or hide this help"),
+    ("↑/↓ PgUp/PgDn", "Scroll this help"),
+];
+
+/// The settings the Config tab can change, in the order they're listed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConfigField {
+    Streamers,
+    MaxConcurrent,
+    RetryAttempts,
+    DelayMin,
+    DelayMax,
+    ApiPort,
+    DashboardPort,
+    OutputFormat,
+    OutputDirectory,
+    RotationSize,
+    RotationTime,
+    RandomizeUserAgents,
+    SimulateHumanBehavior,
+    ProxyRotation,
+    FingerprintRandomization,
+}
+
+impl ConfigField {
+    const ALL: [ConfigField; 15] = [
+        ConfigField::Streamers,
+        ConfigField::MaxConcurrent,
+        ConfigField::RetryAttempts,
+        ConfigField::DelayMin,
+        ConfigField::DelayMax,
+        ConfigField::ApiPort,
+        ConfigField::DashboardPort,
+        ConfigField::OutputFormat,
+        ConfigField::OutputDirectory,
+        ConfigField::RotationSize,
+        ConfigField::RotationTime,
+        ConfigField::RandomizeUserAgents,
+        ConfigField::SimulateHumanBehavior,
+        ConfigField::ProxyRotation,
+        ConfigField::FingerprintRandomization,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ConfigField::Streamers => "🎯 Streamers",
+            ConfigField::MaxConcurrent => "👥 Max Concurrent Agents",
+            ConfigField::RetryAttempts => "🔄 Retry Attempts",
+            ConfigField::DelayMin => "⏱️  Min Delay (ms)",
+            ConfigField::DelayMax => "⏱️  Max Delay (ms)",
+            ConfigField::ApiPort => "📊 API Port",
+            ConfigField::DashboardPort => "🌐 Dashboard Port",
+            ConfigField::OutputFormat => "📁 Output Format",
+            ConfigField::OutputDirectory => "📂 Output Directory",
+            ConfigField::RotationSize => "🔄 File Rotation Size",
+            ConfigField::RotationTime => "⏰ File Rotation Time",
+            ConfigField::RandomizeUserAgents => "🎭 User Agent Randomization",
+            ConfigField::SimulateHumanBehavior => "🎭 Human Behavior Simulation",
+            ConfigField::ProxyRotation => "🎭 Proxy Rotation",
+            ConfigField::FingerprintRandomization => "🎭 Fingerprint Randomization",
+        }
+    }
+
+    // on/off settings flip on Enter instead of opening an edit buffer
+    fn toggle(&self, config: &mut Config) -> bool {
+        let flag = match self {
+            ConfigField::RandomizeUserAgents => &mut config.stealth.randomize_user_agents,
+            ConfigField::SimulateHumanBehavior => &mut config.stealth.simulate_human_behavior,
+            ConfigField::ProxyRotation => &mut config.stealth.proxy_rotation,
+            ConfigField::FingerprintRandomization => &mut config.stealth.fingerprint_randomization,
+            _ => return false,
+        };
+        *flag = !*flag;
+        true
+    }
+
+    fn value(&self, config: &Config) -> String {
+        let flag = |on: bool| if on { "✅" } else { "❌" }.to_string();
+        match self {
+            ConfigField::Streamers => config.streamers.join(", "),
+            ConfigField::MaxConcurrent => config.agents.max_concurrent.to_string(),
+            ConfigField::RetryAttempts => config.agents.retry_attempts.to_string(),
+            ConfigField::DelayMin => config.agents.delay_range.0.to_string(),
+            ConfigField::DelayMax => config.agents.delay_range.1.to_string(),
+            ConfigField::ApiPort => config.monitoring.api_port.to_string(),
+            ConfigField::DashboardPort => config.monitoring.dashboard_port.map(|port| port.to_string()).unwrap_or_default(),
+            ConfigField::OutputFormat => config.output.format.clone(),
+            ConfigField::OutputDirectory => config.output.directory.display().to_string(),
+            ConfigField::RotationSize => config.output.rotation_size.clone(),
+            ConfigField::RotationTime => config.output.rotation_time.clone(),
+            ConfigField::RandomizeUserAgents => flag(config.stealth.randomize_user_agents),
+            ConfigField::SimulateHumanBehavior => flag(config.stealth.simulate_human_behavior),
+            ConfigField::ProxyRotation => flag(config.stealth.proxy_rotation),
+            ConfigField::FingerprintRandomization => flag(config.stealth.fingerprint_randomization),
+        }
+    }
+
+    /// Text for the edit buffer, the value as it would be typed
+    fn edit_text(&self, config: &Config) -> String {
+        match self {
+            ConfigField::Streamers => config.streamers.join(","),
+            _ => self.value(config),
+        }
+    }
+
+    fn set(&self, config: &mut Config, input: &str) -> Result<()> {
+        let input = input.trim();
+        let number = |what: &str| -> Result<u64> {
+            input.parse().map_err(|_| anyhow::anyhow!("{} must be a whole number, got '{}'", what, input))
+        };
+        match self {
+            ConfigField::Streamers => {
+                config.streamers = input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                config.normalize_streamers();
+            }
+            ConfigField::MaxConcurrent => config.agents.max_concurrent = number("Max concurrent agents")? as usize,
+            ConfigField::RetryAttempts => {
+                config.agents.retry_attempts = u32::try_from(number("Retry attempts")?)?;
+            }
+            ConfigField::DelayMin => config.agents.delay_range.0 = number("Min delay")?,
+            ConfigField::DelayMax => config.agents.delay_range.1 = number("Max delay")?,
+            ConfigField::ApiPort => config.monitoring.api_port = u16::try_from(number("API port")?)?,
+            ConfigField::DashboardPort if input.is_empty() => config.monitoring.dashboard_port = None,
+            ConfigField::DashboardPort => {
+                config.monitoring.dashboard_port = Some(u16::try_from(number("Dashboard port")?)?);
+            }
+            ConfigField::OutputFormat => config.output.format = input.to_lowercase(),
+            ConfigField::OutputDirectory => config.output.directory = PathBuf::from(input),
+            ConfigField::RotationSize => config.output.rotation_size = input.to_string(),
+            ConfigField::RotationTime => config.output.rotation_time = input.to_string(),
+            ConfigField::RandomizeUserAgents
+            | ConfigField::SimulateHumanBehavior
+            | ConfigField::ProxyRotation
+            | ConfigField::FingerprintRandomization => {}
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LogEntry {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub level: LogLevel,
     pub message: String,
     pub agent_id: Option<AgentId>,
+    pub user: Option<ChatUser>, // set for chat lines so the username can be colored
+}
+
+impl LogEntry {
+    /// A log line stamped now
+    pub fn new(level: LogLevel, message: impl Into<String>, agent_id: Option<AgentId>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now(),
+            level,
+            message: message.into(),
+            agent_id,
+            user: None,
+        }
+    }
+}
+
+/// Username color when the user has none or it can't be parsed
+const DEFAULT_USERNAME_COLOR: Color = Color::Cyan;
+
+/// Parse a Twitch `#rrggbb` color
+pub fn parse_hex_color(hex: &str) -> Option<Color> {
+    let digits = hex.strip_prefix('#')?;
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    Some(Color::Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+fn username_color(color: Option<&str>) -> Color {
+    color.and_then(parse_hex_color).unwrap_or(DEFAULT_USERNAME_COLOR)
 }
 
 #[derive(Debug, Clone)]
@@ -133,6 +353,7 @@ pub enum AlertLevel {
     Info,
     Warning,
     Critical,
+    RateLimited, // twitch throttled us, the agent is paused rather than broken
 }
 
 impl AlertLevel {
@@ -141,6 +362,7 @@ impl AlertLevel {
             AlertLevel::Info => Color::Blue,
             AlertLevel::Warning => Color::Yellow,
             AlertLevel::Critical => Color::Red,
+            AlertLevel::RateLimited => Color::Magenta,
         }
     }
 
@@ -149,6 +371,7 @@ impl AlertLevel {
             AlertLevel::Info => "ℹ",
             AlertLevel::Warning => "⚠",
             AlertLevel::Critical => "❌",
+            AlertLevel::RateLimited => "⏳",
         }
     }
 }
@@ -189,7 +412,92 @@ impl LogLevel {
     }
 }
 
-// A simple theming struct
+/// What the Logs tab shows: lines containing `query` (ignoring case), and
+/// only warnings and errors when `warnings_only` is set
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogFilter {
+    pub query: String,
+    pub warnings_only: bool,
+}
+
+impl LogFilter {
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty() || self.warnings_only
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        if self.warnings_only && !matches!(entry.level, LogLevel::Warning | LogLevel::Error) {
+            return false;
+        }
+        if self.query.is_empty() {
+            return true;
+        }
+        let query = self.query.to_lowercase();
+        entry.message.to_lowercase().contains(&query)
+            || entry.user.as_ref().is_some_and(|user| user.display_name.to_lowercase().contains(&query))
+    }
+}
+
+/// How long the message total is sampled over for the raw rate
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+const DEFAULT_RATE_SMOOTHING: f64 = 0.3;
+const MAX_PERFORMANCE_HISTORY: usize = 300;
+/// Scraped messages kept for the chat tab, the oldest go first
+const MAX_CHAT_MESSAGES: usize = 1000;
+
+/// Redraw at least this often even when nothing changed, so uptime keeps ticking
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Paces the dashboard loop: input is polled several times per frame, and a frame is
+/// only drawn when something changed and the frame budget allows, or when idle too long
+#[derive(Debug, Clone)]
+pub struct RenderTicker {
+    frame_interval: Duration,
+    last_draw: Option<Instant>,
+    dirty: bool,
+}
+
+impl RenderTicker {
+    pub fn new(fps: u32) -> Self {
+        Self {
+            frame_interval: Duration::from_secs(1) / fps.max(1),
+            last_draw: None,
+            dirty: true,
+        }
+    }
+
+    /// How long to wait for input before checking for a redraw again
+    pub fn poll_timeout(&self) -> Duration {
+        (self.frame_interval / 4).clamp(Duration::from_millis(5), Duration::from_millis(50))
+    }
+
+    /// Something on screen changed, draw it on the next frame
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Draw on the next check whatever the frame budget says, e.g. after a resize
+    pub fn force_draw(&mut self) {
+        self.last_draw = None;
+        self.dirty = true;
+    }
+
+    pub fn should_draw(&self, now: Instant) -> bool {
+        let Some(last_draw) = self.last_draw else {
+            return true;
+        };
+        let since_draw = now.saturating_duration_since(last_draw);
+        (self.dirty && since_draw >= self.frame_interval) || since_draw >= IDLE_REDRAW_INTERVAL
+    }
+
+    pub fn drawn(&mut self, now: Instant) {
+        self.last_draw = Some(now);
+        self.dirty = false;
+    }
+}
+
+/// Dashboard colors, the defaults match the built-in look
+#[derive(Debug, Clone, PartialEq)]
 pub struct CustomTheme {
     pub text_color: Color,
     pub accent_color: Color,
@@ -201,44 +509,119 @@ impl Default for CustomTheme {
     fn default() -> Self {
         Self {
             text_color: Color::White,
-            accent_color: Color::Cyan,
+            accent_color: Color::Yellow,
             border_color: Color::White,
-            background_color: Color::Black,
+            background_color: Color::Reset, // the terminal's own
+        }
+    }
+}
+
+impl CustomTheme {
+    /// Bordered panel in the theme's colors
+    fn block<'a>(&self, title: impl Into<Title<'a>>) -> Block<'a> {
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.border_color))
+            .style(Style::default().fg(self.text_color))
+    }
+
+    /// Parse `key = color` lines, colors are `#rrggbb` or names like `light blue`.
+    /// Keys left out keep their default, lines starting with `#` are comments.
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut theme = Self::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                anyhow::bail!("line {}: expected `key = color`, got '{}'", number + 1, line);
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')))
+                .unwrap_or(value);
+
+            let slot = match key {
+                "text_color" => &mut theme.text_color,
+                "accent_color" => &mut theme.accent_color,
+                "border_color" => &mut theme.border_color,
+                "background_color" => &mut theme.background_color,
+                _ => anyhow::bail!(
+                    "line {}: unknown key '{}', expected text_color, accent_color, border_color or background_color",
+                    number + 1,
+                    key
+                ),
+            };
+            *slot = value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("line {}: '{}' is not a color for {}", number + 1, value, key))?;
         }
+        Ok(theme)
     }
 }
 
+/// Read and parse a theme file, see [`CustomTheme::parse`]
+pub fn load_custom_theme(path: &Path) -> Result<CustomTheme> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read theme file {}: {}", path.display(), e))?;
+    CustomTheme::parse(&content).map_err(|e| anyhow::anyhow!("Invalid theme file {}: {}", path.display(), e))
+}
+
 pub struct Dashboard {
     // Core state
     metrics: SystemMetrics,
     agents: Vec<AgentInfo>,
     logs: Vec<LogEntry>,
+    chat_messages: VecDeque<ChatMessage>,
     alerts: Vec<Alert>,
     
     // UI state
     current_tab: Tab,
     show_help: bool,
+    help_scroll: u16,
     agent_table_state: TableState,
     log_list_state: ListState,
+    log_filter: LogFilter,
+    log_search_active: bool, // typing goes into the search box
+    alert_list_state: ListState,
+    hide_acknowledged: bool,
+    chat_list_state: ListState,
+    chat_filter: Option<String>, // only show this streamer's chat
     
     // Performance tracking
     performance_history: VecDeque<PerformanceData>,
     last_message_count: u64,
     last_update_time: std::time::Instant,
+    raw_message_rate: f64,
+    message_rate_ema: Option<f64>,
+    rate_smoothing: f64, // EMA weight of the newest sample, 0 < x <= 1
     
     // Alert management
     next_alert_id: u64,
     
     // Config editing
     config: Option<crate::config::Config>,
+    config_manager: Option<Arc<dyn ConfigManager + Send + Sync>>,
     config_editing: bool,
     config_field_index: usize,
+    config_edit_buffer: Option<String>, // value being typed for the selected field
     
     // Theming
     theme: CustomTheme,
+    #[allow(dead_code)]
     custom_css_path: Option<PathBuf>,
 }
 
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Dashboard {
     pub fn new() -> Self {
         Self {
@@ -253,23 +636,48 @@ impl Dashboard {
             },
             agents: Vec::new(),
             logs: Vec::new(),
+            chat_messages: VecDeque::new(),
             alerts: Vec::new(),
             current_tab: Tab::Overview,
             show_help: false,
+            help_scroll: 0,
             agent_table_state: TableState::default(),
             log_list_state: ListState::default(),
+            log_filter: LogFilter::default(),
+            log_search_active: false,
+            alert_list_state: ListState::default(),
+            hide_acknowledged: false,
+            chat_list_state: ListState::default(),
+            chat_filter: None,
             performance_history: VecDeque::new(),
             last_message_count: 0,
             last_update_time: std::time::Instant::now(),
+            raw_message_rate: 0.0,
+            message_rate_ema: None,
+            rate_smoothing: DEFAULT_RATE_SMOOTHING,
             next_alert_id: 1,
             config: None,
+            config_manager: None,
             config_editing: false,
             config_field_index: 0,
+            config_edit_buffer: None,
             theme: CustomTheme::default(),
             custom_css_path: None,
         }
     }
 
+    /// Use the colors from a theme file, keeping the default theme if it can't be loaded
+    pub fn with_custom_theme(mut self, theme_path: Option<PathBuf>) -> Self {
+        if let Some(path) = &theme_path {
+            match load_custom_theme(path) {
+                Ok(theme) => self.theme = theme,
+                Err(e) => tracing::warn!("{}, using the default theme", e),
+            }
+        }
+        self.custom_css_path = theme_path;
+        self
+    }
+
     pub fn add_log(&mut self, entry: LogEntry) {
         self.logs.push(entry);
         if self.logs.len() > 1000 {
@@ -277,6 +685,35 @@ impl Dashboard {
         }
     }
 
+    pub fn add_chat_message(&mut self, message: ChatMessage) {
+        self.chat_messages.push_back(message);
+        if self.chat_messages.len() > MAX_CHAT_MESSAGES {
+            self.chat_messages.pop_front();
+        }
+    }
+
+    /// Step the chat filter through each streamer with messages, then back to all
+    fn cycle_chat_filter(&mut self) {
+        let mut streamers: Vec<&str> = self.chat_messages.iter().map(|message| message.streamer.as_str()).collect();
+        streamers.sort_unstable();
+        streamers.dedup();
+
+        self.chat_filter = match self.chat_filter {
+            None => streamers.first().map(|streamer| streamer.to_string()),
+            Some(ref current) => streamers
+                .iter()
+                .find(|streamer| **streamer > current.as_str())
+                .map(|streamer| streamer.to_string()),
+        };
+        self.chat_list_state.select(None);
+    }
+
+    fn filtered_chat(&self) -> impl DoubleEndedIterator<Item = &ChatMessage> {
+        self.chat_messages
+            .iter()
+            .filter(|message| self.chat_filter.as_ref().is_none_or(|streamer| message.streamer == *streamer))
+    }
+
     pub fn add_alert(&mut self, level: AlertLevel, message: String, agent_id: Option<AgentId>) {
         let alert = Alert {
             id: self.next_alert_id,
@@ -290,10 +727,100 @@ impl Dashboard {
         self.next_alert_id += 1;
     }
 
+    /// Raise an alert for agent events worth the operator's attention. Only
+    /// Twitch throttling is, the rest already shows in the agents tab.
+    pub fn add_agent_alert(&mut self, message: &AgentMessage) {
+        if let AgentMessage::RateLimited { agent_id, streamer, pause } = message {
+            self.add_alert(
+                AlertLevel::RateLimited,
+                format!("Rate limited on {}, paused for {}s", streamer, pause.as_secs()),
+                Some(*agent_id),
+            );
+        }
+    }
+
+    /// Mark an alert as seen, false if there's no alert with that id
+    pub fn acknowledge_alert(&mut self, alert_id: u64) -> bool {
+        match self.alerts.iter_mut().find(|alert| alert.id == alert_id) {
+            Some(alert) => {
+                alert.acknowledged = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn filtered_alerts(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts.iter().filter(|alert| !(self.hide_acknowledged && alert.acknowledged))
+    }
+
+    // the selected alert can be hidden by acknowledging it, keep the selection in range
+    fn clamp_alert_selection(&mut self) {
+        let shown = self.filtered_alerts().count();
+        match self.alert_list_state.selected() {
+            Some(_) if shown == 0 => self.alert_list_state.select(None),
+            Some(selected) if selected >= shown => self.alert_list_state.select(Some(shown - 1)),
+            _ => {}
+        }
+    }
+
     pub fn set_config(&mut self, config: crate::config::Config) {
         self.config = Some(config);
     }
 
+    pub fn set_config_manager(&mut self, config_manager: Arc<dyn ConfigManager + Send + Sync>) {
+        self.config_manager = Some(config_manager);
+    }
+
+    /// Weight given to the newest rate sample; lower values give a steadier average
+    pub fn set_rate_smoothing(&mut self, smoothing: f64) {
+        self.rate_smoothing = smoothing.clamp(0.01, 1.0);
+    }
+
+    /// Rate over the last sampling window, before smoothing
+    pub fn raw_message_rate(&self) -> f64 {
+        self.raw_message_rate
+    }
+
+    /// Refresh the raw window rate and its moving average from the message total
+    pub fn update_message_rate(&mut self) {
+        self.update_message_rate_at(Instant::now());
+    }
+
+    fn update_message_rate_at(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_update_time);
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+
+        let new_messages = self.metrics.total_messages.saturating_sub(self.last_message_count);
+        self.raw_message_rate = new_messages as f64 / elapsed.as_secs_f64();
+        let average = match self.message_rate_ema {
+            Some(previous) => self.rate_smoothing * self.raw_message_rate + (1.0 - self.rate_smoothing) * previous,
+            None => self.raw_message_rate,
+        };
+        self.message_rate_ema = Some(average);
+        self.metrics.messages_per_second = average;
+
+        self.last_message_count = self.metrics.total_messages;
+        self.last_update_time = now;
+
+        self.performance_history.push_back(PerformanceData {
+            timestamp: now,
+            cpu_usage: self.metrics.cpu_usage,
+            memory_usage: self.metrics.memory_usage,
+            messages_per_second: average,
+        });
+        if self.performance_history.len() > MAX_PERFORMANCE_HISTORY {
+            self.performance_history.pop_front();
+        }
+    }
+
+    /// "12.3 (avg 9.8)" so spikes show next to the smoothed rate
+    fn format_message_rate(&self) -> String {
+        format!("{:.1} (avg {:.1})", self.raw_message_rate, self.metrics.messages_per_second)
+    }
+
     fn render_overview(&mut self, frame: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -306,15 +833,15 @@ impl Dashboard {
 
         // System metrics
         let metrics_text = format!(
-            "Active Agents: {} | Total Messages: {} | Messages/sec: {:.2} | CPU: {:.1}% | Memory: {} MB",
+            "Active Agents: {} | Total Messages: {} | Messages/sec: {} | CPU: {:.1}% | Memory: {} MB",
             self.metrics.active_agents,
             self.metrics.total_messages,
-            self.metrics.messages_per_second,
+            self.format_message_rate(),
             self.metrics.cpu_usage,
             self.metrics.memory_usage / 1024 / 1024
         );
         let metrics = Paragraph::new(metrics_text)
-            .block(Block::default().title("System Metrics").borders(Borders::ALL));
+            .block(self.theme.block("System Metrics"));
         frame.render_widget(metrics, chunks[0]);
 
         // Agent summary
@@ -326,7 +853,7 @@ impl Dashboard {
             self.agents.iter().filter(|a| matches!(a.status, AgentStatus::Error(_))).count(),
         );
         let summary = Paragraph::new(agent_summary)
-            .block(Block::default().title("Agent Summary").borders(Borders::ALL));
+            .block(self.theme.block("Agent Summary"));
         frame.render_widget(summary, chunks[1]);
 
         // Recent activity
@@ -344,14 +871,14 @@ impl Dashboard {
             .collect();
 
         let activity_list = List::new(activity_items)
-            .block(Block::default().title("Recent Activity").borders(Borders::ALL));
+            .block(self.theme.block("Recent Activity"));
         frame.render_widget(activity_list, chunks[2]);
     }
 
     fn render_agents(&mut self, frame: &mut Frame, area: Rect) {
         let header_cells = ["ID", "Channel", "Status", "Uptime", "Msgs/s", "Errors"]
             .iter()
-            .map(|h| ratatui::widgets::Cell::from(*h).style(Style::default().fg(Color::Yellow)));
+            .map(|h| ratatui::widgets::Cell::from(*h).style(Style::default().fg(self.theme.accent_color)));
         let header = Row::new(header_cells).height(1).bottom_margin(1);
 
         let rows = self.agents.iter().map(|agent| {
@@ -359,7 +886,7 @@ impl Dashboard {
             Row::new(vec![
                 ratatui::widgets::Cell::from(agent.id.to_string()),
                 ratatui::widgets::Cell::from(agent.channel.clone()),
-                ratatui::widgets::Cell::from(agent.status.to_string()).style(Style::default().fg(agent.status.color())),
+                ratatui::widgets::Cell::from(format!("{} {}", agent.status.symbol(), agent.status)).style(Style::default().fg(agent.status.color())),
                 ratatui::widgets::Cell::from(uptime),
                 ratatui::widgets::Cell::from(format!("{:.2}", agent.messages_per_second)),
                 ratatui::widgets::Cell::from(agent.error_count.to_string()),
@@ -368,7 +895,7 @@ impl Dashboard {
 
         let table = Table::new(rows)
             .header(header)
-            .block(Block::default().borders(Borders::ALL).title("Agents"))
+            .block(self.theme.block("Agents (x: stop, r: restart)"))
             .widths(&[
                 Constraint::Length(8),
                 Constraint::Length(15),
@@ -381,9 +908,57 @@ impl Dashboard {
         frame.render_stateful_widget(table, area, &mut self.agent_table_state);
     }
 
+    // Enter on a config field: flip it if it's on/off, otherwise start typing a new value
+    fn begin_config_edit(&mut self) {
+        let Some(config) = self.config.as_mut() else {
+            return;
+        };
+        let field = ConfigField::ALL[self.config_field_index];
+        if !field.toggle(config) {
+            self.config_edit_buffer = Some(field.edit_text(config));
+        }
+    }
+
+    // the typed value goes into the config only if it parses and the config stays valid
+    fn confirm_config_edit(&mut self) {
+        let (Some(config), Some(input)) = (&self.config, &self.config_edit_buffer) else {
+            return;
+        };
+        let field = ConfigField::ALL[self.config_field_index];
+        let mut updated = config.clone();
+        let result = field.set(&mut updated, input).and_then(|_| match &self.config_manager {
+            Some(config_manager) => config_manager.validate_config(&updated).map_err(|e| anyhow::anyhow!("{}", e)),
+            None => Ok(()),
+        });
+        match result {
+            Ok(()) => {
+                self.config = Some(updated);
+                self.config_edit_buffer = None;
+            }
+            Err(e) => {
+                self.add_alert(AlertLevel::Warning, format!("Invalid {}: {}", field.label(), e), None);
+            }
+        }
+    }
+
+    fn filtered_logs(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.logs.iter().filter(|log| self.log_filter.matches(log))
+    }
+
+    // the filter changed, keep the selection on a line that's still listed
+    fn clamp_log_selection(&mut self) {
+        let shown = self.filtered_logs().count();
+        match self.log_list_state.selected() {
+            Some(_) if shown == 0 => self.log_list_state.select(None),
+            Some(selected) if selected >= shown => self.log_list_state.select(Some(shown - 1)),
+            _ => {}
+        }
+    }
+
     fn render_logs(&mut self, frame: &mut Frame, area: Rect) {
-        let log_items: Vec<ListItem> = self.logs.iter().rev().map(|log| {
-            let content = Line::from(vec![
+        let filter = &self.log_filter;
+        let log_items: Vec<ListItem> = self.logs.iter().filter(|log| filter.matches(log)).rev().map(|log| {
+            let mut spans = vec![
                 Span::styled(
                     format!("[{}] ", log.timestamp.format("%H:%M:%S")),
                     Style::default().fg(Color::Gray)
@@ -392,18 +967,62 @@ impl Dashboard {
                     format!("{} ", log.level.symbol()),
                     Style::default().fg(log.level.color())
                 ),
-                Span::raw(&log.message),
-            ]);
-            ListItem::new(content)
+            ];
+            if let Some(ref user) = log.user {
+                spans.push(Span::styled(
+                    format!("{}: ", user.display_name),
+                    Style::default().fg(username_color(user.color.as_deref()))
+                ));
+            }
+            spans.push(Span::raw(&log.message));
+            ListItem::new(Line::from(spans))
         }).collect();
 
+        let mut title = "Logs".to_string();
+        if self.log_search_active || !self.log_filter.query.is_empty() {
+            let cursor = if self.log_search_active { "_" } else { "" };
+            title.push_str(&format!(" - /{}{}", self.log_filter.query, cursor));
+        }
+        if self.log_filter.warnings_only {
+            title.push_str(" - warnings and errors");
+        }
+        if !self.log_search_active {
+            title.push_str(" (/: search, w: warnings)");
+        }
         let logs_list = List::new(log_items)
-            .block(Block::default().borders(Borders::ALL).title("Logs"))
+            .block(self.theme.block(title))
             .highlight_style(Style::default().bg(Color::DarkGray));
 
         frame.render_stateful_widget(logs_list, area, &mut self.log_list_state);
     }
 
+    fn render_chat(&mut self, frame: &mut Frame, area: Rect) {
+        let chat_items: Vec<ListItem> = self.filtered_chat().rev().map(|message| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("[{}] ", message.timestamp.format("%H:%M:%S")),
+                    Style::default().fg(Color::Gray)
+                ),
+                Span::styled(format!("#{} ", message.streamer), Style::default().fg(Color::Magenta)),
+                Span::styled(
+                    format!("{}: ", message.user.display_name),
+                    Style::default().fg(username_color(message.user.color.as_deref()))
+                ),
+                Span::raw(message.message.text.clone()),
+            ]))
+        }).collect();
+
+        let title = match self.chat_filter {
+            Some(ref streamer) => format!("Chat - #{} (f: next streamer)", streamer),
+            None => "Chat - all streamers (f: filter)".to_string(),
+        };
+        let chat_list = List::new(chat_items)
+            .block(self.theme.block(title))
+            .highlight_style(Style::default().bg(Color::DarkGray));
+
+        frame.render_stateful_widget(chat_list, area, &mut self.chat_list_state);
+    }
+
     fn render_performance(&mut self, frame: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -416,27 +1035,28 @@ impl Dashboard {
             self.metrics.cpu_usage,
             self.metrics.memory_usage / 1024 / 1024,
             self.metrics.memory_total / 1024 / 1024,
-            (self.metrics.memory_usage as f64 / self.metrics.memory_total as f64) * 100.0,
+            (self.metrics.memory_usage as f64 / self.metrics.memory_total.max(1) as f64) * 100.0,
             format_duration(self.metrics.uptime)
         );
         let perf_info = Paragraph::new(perf_text)
-            .block(Block::default().title("System Performance").borders(Borders::ALL));
+            .block(self.theme.block("System Performance"));
         frame.render_widget(perf_info, chunks[0]);
 
         // Message rate info
         let msg_text = format!(
-            "Total Messages: {}\nMessages/Second: {:.2}\nActive Agents: {}",
+            "Total Messages: {}\nMessages/Second: {}\nActive Agents: {}",
             self.metrics.total_messages,
-            self.metrics.messages_per_second,
+            self.format_message_rate(),
             self.metrics.active_agents
         );
         let msg_info = Paragraph::new(msg_text)
-            .block(Block::default().title("Message Statistics").borders(Borders::ALL));
+            .block(self.theme.block("Message Statistics"));
         frame.render_widget(msg_info, chunks[1]);
     }
 
     fn render_alerts(&mut self, frame: &mut Frame, area: Rect) {
-        let alert_items: Vec<ListItem> = self.alerts.iter().map(|alert| {
+        let hide_acknowledged = self.hide_acknowledged;
+        let alert_items: Vec<ListItem> = self.alerts.iter().filter(|alert| !(hide_acknowledged && alert.acknowledged)).map(|alert| {
             let content = Line::from(vec![
                 Span::styled(
                     format!("[{}] ", alert.timestamp.format("%H:%M:%S")),
@@ -453,14 +1073,63 @@ impl Dashboard {
                     Span::raw("")
                 },
             ]);
-            ListItem::new(content)
+            // seen alerts fade into the background
+            let style = if alert.acknowledged {
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM)
+            } else {
+                Style::default()
+            };
+            ListItem::new(content).style(style)
         }).collect();
 
+        let unacknowledged = self.alerts.iter().filter(|alert| !alert.acknowledged).count();
+        let title = if self.hide_acknowledged {
+            format!("Alerts - {} unacknowledged (a: acknowledge, u: show all)", unacknowledged)
+        } else {
+            format!("Alerts - {} unacknowledged (a: acknowledge, u: hide acknowledged)", unacknowledged)
+        };
         let alerts_list = List::new(alert_items)
-            .block(Block::default().borders(Borders::ALL).title("Alerts"))
+            .block(self.theme.block(title))
             .highlight_style(Style::default().bg(Color::DarkGray));
 
-        frame.render_widget(alerts_list, area);
+        frame.render_stateful_widget(alerts_list, area, &mut self.alert_list_state);
+    }
+
+    /// Key bindings for every tab, then the ones for the tab that's open
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        let heading = |text: String| Line::from(Span::styled(text, Style::default().add_modifier(Modifier::BOLD)));
+        let binding = |(keys, action): &(&'static str, &'static str)| {
+            Line::from(vec![
+                Span::styled(format!("{:>14}", keys), Style::default().fg(self.theme.accent_color)),
+                Span::raw(format!("  {}", action)),
+            ])
+        };
+
+        let mut lines = vec![heading("Everywhere".to_string())];
+        lines.extend(GLOBAL_KEY_HELP.iter().map(binding));
+        lines.push(Line::from(""));
+        lines.push(heading(format!("{} tab", self.current_tab.title())));
+        let tab_keys = self.current_tab.key_help();
+        if tab_keys.is_empty() {
+            lines.push(Line::from("  Nothing extra here"));
+        }
+        lines.extend(tab_keys.iter().map(binding));
+        lines
+    }
+
+    fn render_help(&mut self, frame: &mut Frame) {
+        let area = centered_rect(60, 50, frame.size());
+        let lines = self.help_lines();
+        // scrolling stops once the last line is in view
+        let max_scroll = (lines.len() as u16).saturating_sub(area.height.saturating_sub(2));
+        self.help_scroll = self.help_scroll.min(max_scroll);
+
+        let title = if max_scroll > 0 { "Help (↑/↓ to scroll, Esc to close)" } else { "Help (Esc to close)" };
+        let help = Paragraph::new(lines)
+            .block(self.theme.block(title))
+            .scroll((self.help_scroll, 0));
+        frame.render_widget(Clear, area);
+        frame.render_widget(help, area);
     }
 
     fn render_config(&mut self, frame: &mut Frame, area: Rect) {
@@ -471,44 +1140,30 @@ impl Dashboard {
                 "👀 VIEW MODE"
             };
 
-            let config_text = format!(
-                "📝 Configuration Settings - {}\n\n\
-                🎯 Streamers: {}\n\
-                👥 Max Concurrent Agents: {}\n\
-                🔄 Retry Attempts: {}\n\
-                ⏱️  Delay Range: {} - {} ms\n\
-                📊 API Port: {}\n\
-                🌐 Dashboard Port: {}\n\
-                📁 Output Format: {}\n\
-                📂 Output Directory: {}\n\
-                🔄 File Rotation Size: {}\n\
-                ⏰ File Rotation Time: {}\n\
-                🎭 Stealth Features:\n\
-                  • User Agent Randomization: {}\n\
-                  • Human Behavior Simulation: {}\n\
-                  • Proxy Rotation: {}\n\
-                  • Fingerprint Randomization: {}\n\n\
-                💡 Press 'e' to {} configuration\n\
-                💾 Press 's' to save changes (when editing)\n\
-                🚫 Press 'Esc' to cancel editing",
-                editing_status,
-                config.streamers.join(", "),
-                config.agents.max_concurrent,
-                config.agents.retry_attempts,
-                config.agents.delay_range.0,
-                config.agents.delay_range.1,
-                config.monitoring.api_port,
-                config.monitoring.dashboard_port.unwrap_or(8888),
-                config.output.format,
-                config.output.directory.display(),
-                config.output.rotation_size,
-                config.output.rotation_time,
-                if config.stealth.randomize_user_agents { "✅" } else { "❌" },
-                if config.stealth.simulate_human_behavior { "✅" } else { "❌" },
-                if config.stealth.proxy_rotation { "✅" } else { "❌" },
-                if config.stealth.fingerprint_randomization { "✅" } else { "❌" },
+            let mut lines = vec![
+                Line::from(format!("📝 Configuration Settings - {}", editing_status)),
+                Line::from(""),
+            ];
+            for (index, field) in ConfigField::ALL.iter().enumerate() {
+                let selected = self.config_editing && index == self.config_field_index;
+                let value = match &self.config_edit_buffer {
+                    Some(buffer) if selected => format!("{}_", buffer),
+                    _ => field.value(config),
+                };
+                let style = if selected {
+                    Style::default().fg(Color::Black).bg(self.theme.accent_color)
+                } else {
+                    Style::default()
+                };
+                lines.push(Line::from(Span::styled(format!("{}: {}", field.label(), value), style)));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(format!(
+                "💡 Press 'e' to {} configuration",
                 if self.config_editing { "exit edit mode for" } else { "edit" }
-            );
+            )));
+            lines.push(Line::from("💾 Press 's' to save changes (when editing)"));
+            lines.push(Line::from("🚫 Press 'Esc' to cancel editing"));
 
             let title = if self.config_editing {
                 "Configuration (EDITING)"
@@ -517,20 +1172,20 @@ impl Dashboard {
             };
 
             let style = if self.config_editing {
-                Style::default().fg(Color::Yellow)
+                Style::default().fg(self.theme.accent_color)
             } else {
                 Style::default()
             };
 
-            let config_paragraph = Paragraph::new(config_text)
-                .block(Block::default().title(title).borders(Borders::ALL))
+            let config_paragraph = Paragraph::new(lines)
+                .block(self.theme.block(title))
                 .style(style)
                 .wrap(Wrap { trim: true });
 
             frame.render_widget(config_paragraph, area);
         } else {
             let no_config = Paragraph::new("⚠️  No configuration loaded\n\nConfiguration will be available once the system is fully initialized.")
-                .block(Block::default().title("Configuration").borders(Borders::ALL))
+                .block(self.theme.block("Configuration"))
                 .style(Style::default().fg(Color::Yellow));
 
             frame.render_widget(no_config, area);
@@ -545,6 +1200,8 @@ impl TUIMonitor for Dashboard {
             .constraints([Constraint::Length(3), Constraint::Min(0)])
             .split(frame.size());
 
+        frame.render_widget(Block::default().style(Style::default().bg(self.theme.background_color)), frame.size());
+
         // Render tabs
         let tab_titles = [
             Tab::Overview,
@@ -553,16 +1210,17 @@ impl TUIMonitor for Dashboard {
             Tab::Performance,
             Tab::Alerts,
             Tab::Config,
+            Tab::Messages,
         ]
         .iter()
         .map(|t| t.title())
         .collect::<Vec<_>>();
 
         let tabs = Tabs::new(tab_titles)
-            .block(Block::default().borders(Borders::ALL).title("Twitch Chat Scraper"))
+            .block(self.theme.block("Twitch Chat Scraper"))
             .select(self.current_tab as usize)
-            .style(Style::default().fg(Color::White))
-            .highlight_style(Style::default().fg(Color::Yellow));
+            .style(Style::default().fg(self.theme.text_color))
+            .highlight_style(Style::default().fg(self.theme.accent_color));
 
         frame.render_widget(tabs, main_layout[0]);
 
@@ -574,24 +1232,68 @@ impl TUIMonitor for Dashboard {
             Tab::Performance => self.render_performance(frame, main_layout[1]),
             Tab::Alerts => self.render_alerts(frame, main_layout[1]),
             Tab::Config => self.render_config(frame, main_layout[1]),
+            Tab::Messages => self.render_chat(frame, main_layout[1]),
         }
 
         // Show help popup if requested
         if self.show_help {
-            let area = centered_rect(60, 50, frame.size());
-            frame.render_widget(Clear, area);
-            let block = Block::default().title("Help").borders(Borders::ALL);
-            frame.render_widget(block, area);
+            self.render_help(frame);
         }
 
         Ok(())
     }
 
     fn handle_input(&mut self, event: Event) -> Result<Action> {
+        // every render works from the frame's size, so a redraw is all a resize needs
+        if let Event::Resize(_, _) = event {
+            return Ok(Action::Redraw);
+        }
+
         if let Event::Key(key) = event {
             if self.show_help {
-                if matches!(key.code, KeyCode::Char('h') | KeyCode::Char('?') | KeyCode::Esc) {
-                    self.show_help = false;
+                match key.code {
+                    KeyCode::Char('h') | KeyCode::Char('?') | KeyCode::Esc => {
+                        self.show_help = false;
+                        self.help_scroll = 0;
+                    }
+                    // render keeps the scroll from running past the end
+                    KeyCode::Up => self.help_scroll = self.help_scroll.saturating_sub(1),
+                    KeyCode::Down => self.help_scroll = self.help_scroll.saturating_add(1),
+                    KeyCode::PageUp => self.help_scroll = self.help_scroll.saturating_sub(10),
+                    KeyCode::PageDown => self.help_scroll = self.help_scroll.saturating_add(10),
+                    _ => {}
+                }
+                return Ok(Action::Continue);
+            }
+
+            // typing a log search, every key goes to the query
+            if self.log_search_active {
+                match key.code {
+                    KeyCode::Char(c) => self.log_filter.query.push(c),
+                    KeyCode::Backspace => {
+                        self.log_filter.query.pop();
+                    }
+                    KeyCode::Enter => self.log_search_active = false,
+                    KeyCode::Esc => {
+                        self.log_filter.query.clear();
+                        self.log_search_active = false;
+                    }
+                    _ => {}
+                }
+                self.clamp_log_selection();
+                return Ok(Action::Continue);
+            }
+
+            // typing a config value, Enter applies it and Esc drops it
+            if self.config_edit_buffer.is_some() {
+                match key.code {
+                    KeyCode::Char(c) => self.config_edit_buffer.get_or_insert_with(String::new).push(c),
+                    KeyCode::Backspace => {
+                        self.config_edit_buffer.get_or_insert_with(String::new).pop();
+                    }
+                    KeyCode::Enter => self.confirm_config_edit(),
+                    KeyCode::Esc => self.config_edit_buffer = None,
+                    _ => {}
                 }
                 return Ok(Action::Continue);
             }
@@ -608,7 +1310,8 @@ impl TUIMonitor for Dashboard {
                         Tab::Logs => Tab::Performance,
                         Tab::Performance => Tab::Alerts,
                         Tab::Alerts => Tab::Config,
-                        Tab::Config => Tab::Overview,
+                        Tab::Config => Tab::Messages,
+                        Tab::Messages => Tab::Overview,
                     };
                 }
                 KeyCode::Char('1') => self.current_tab = Tab::Overview,
@@ -617,19 +1320,47 @@ impl TUIMonitor for Dashboard {
                 KeyCode::Char('4') => self.current_tab = Tab::Performance,
                 KeyCode::Char('5') => self.current_tab = Tab::Alerts,
                 KeyCode::Char('6') => self.current_tab = Tab::Config,
+                KeyCode::Char('7') => self.current_tab = Tab::Messages,
+                KeyCode::Char('x') | KeyCode::Char('r') if self.current_tab == Tab::Agents => {
+                    let selected = self.agent_table_state.selected().and_then(|selected| self.agents.get(selected));
+                    if let Some(agent) = selected {
+                        return Ok(match key.code {
+                            KeyCode::Char('x') => Action::StopAgent(agent.id),
+                            _ => Action::RestartAgent(agent.id),
+                        });
+                    }
+                }
+                KeyCode::Char('a') if self.current_tab == Tab::Alerts => {
+                    let selected = self.alert_list_state.selected().and_then(|selected| self.filtered_alerts().nth(selected));
+                    if let Some(alert_id) = selected.map(|alert| alert.id) {
+                        self.acknowledge_alert(alert_id);
+                        self.clamp_alert_selection();
+                    }
+                }
+                KeyCode::Char('u') if self.current_tab == Tab::Alerts => {
+                    self.hide_acknowledged = !self.hide_acknowledged;
+                    self.clamp_alert_selection();
+                }
+                KeyCode::Char('f') if self.current_tab == Tab::Messages => self.cycle_chat_filter(),
+                KeyCode::Char('/') if self.current_tab == Tab::Logs => self.log_search_active = true,
+                KeyCode::Char('w') if self.current_tab == Tab::Logs => {
+                    self.log_filter.warnings_only = !self.log_filter.warnings_only;
+                    self.clamp_log_selection();
+                }
+                KeyCode::Esc if self.current_tab == Tab::Logs && self.log_filter.is_active() => {
+                    self.log_filter = LogFilter::default();
+                }
                 KeyCode::Char('e') if self.current_tab == Tab::Config => {
                     self.config_editing = !self.config_editing;
                 }
                 KeyCode::Char('s') if self.current_tab == Tab::Config && self.config_editing => {
-                    if let Some(ref config_manager) = self.config_manager {
-                        if let Some(ref config) = self.config {
-                            match config_manager.save_config(config).await {
-                                Ok(_) => {
-                                    self.add_alert(AlertLevel::Info, "Config Saved".to_string(), "Configuration saved successfully".to_string(), None);
-                                }
-                                Err(e) => {
-                                    self.add_alert(AlertLevel::Critical, "Save Failed".to_string(), format!("Failed to save config: {}", e), None);
-                                }
+                    if let (Some(config_manager), Some(config)) = (self.config_manager.clone(), self.config.clone()) {
+                        match futures::executor::block_on(config_manager.save_config(&config)) {
+                            Ok(_) => {
+                                self.add_alert(AlertLevel::Info, "Configuration saved successfully".to_string(), None);
+                            }
+                            Err(e) => {
+                                self.add_alert(AlertLevel::Critical, format!("Failed to save config: {}", e), None);
                             }
                         }
                     }
@@ -638,6 +1369,7 @@ impl TUIMonitor for Dashboard {
                 KeyCode::Esc if self.current_tab == Tab::Config && self.config_editing => {
                     self.config_editing = false;
                 }
+                KeyCode::Enter if self.current_tab == Tab::Config && self.config_editing => self.begin_config_edit(),
                 KeyCode::Up => {
                     match self.current_tab {
                         Tab::Agents => {
@@ -652,6 +1384,21 @@ impl TUIMonitor for Dashboard {
                                 self.log_list_state.select(Some(selected - 1));
                             }
                         }
+                        Tab::Messages => {
+                            let selected = self.chat_list_state.selected().unwrap_or(0);
+                            if selected > 0 {
+                                self.chat_list_state.select(Some(selected - 1));
+                            }
+                        }
+                        Tab::Alerts => {
+                            let selected = self.alert_list_state.selected().unwrap_or(0);
+                            if selected > 0 {
+                                self.alert_list_state.select(Some(selected - 1));
+                            }
+                        }
+                        Tab::Config if self.config_editing => {
+                            self.config_field_index = self.config_field_index.saturating_sub(1);
+                        }
                         _ => {}
                     }
                 }
@@ -665,10 +1412,25 @@ impl TUIMonitor for Dashboard {
                         }
                         Tab::Logs => {
                             let selected = self.log_list_state.selected().unwrap_or(0);
-                            if selected < self.logs.len().saturating_sub(1) {
+                            if selected < self.filtered_logs().count().saturating_sub(1) {
                                 self.log_list_state.select(Some(selected + 1));
                             }
                         }
+                        Tab::Messages => {
+                            let selected = self.chat_list_state.selected().unwrap_or(0);
+                            if selected < self.filtered_chat().count().saturating_sub(1) {
+                                self.chat_list_state.select(Some(selected + 1));
+                            }
+                        }
+                        Tab::Alerts => {
+                            let selected = self.alert_list_state.selected().unwrap_or(0);
+                            if selected < self.filtered_alerts().count().saturating_sub(1) {
+                                self.alert_list_state.select(Some(selected + 1));
+                            }
+                        }
+                        Tab::Config if self.config_editing => {
+                            self.config_field_index = (self.config_field_index + 1).min(ConfigField::ALL.len() - 1);
+                        }
                         _ => {}
                     }
                 }
@@ -679,7 +1441,11 @@ impl TUIMonitor for Dashboard {
     }
 
     fn update_metrics(&mut self, metrics: SystemMetrics) {
+        // the rate is derived here from the message total, keep the current average
+        let messages_per_second = self.metrics.messages_per_second;
         self.metrics = metrics;
+        self.metrics.messages_per_second = messages_per_second;
+        self.update_message_rate();
     }
 
     fn update_agents(&mut self, agents: Vec<AgentInfo>) {
@@ -730,4 +1496,646 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
             Constraint::Percentage((100 - percent_x) / 2),
         ])
         .split(popup_layout[1])[1]
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    const ALL_TABS: [Tab; 7] =
+        [Tab::Overview, Tab::Agents, Tab::Logs, Tab::Performance, Tab::Alerts, Tab::Config, Tab::Messages];
+
+    fn chat_message(streamer: &str, text: &str) -> ChatMessage {
+        ChatMessage::new(
+            streamer.to_string(),
+            chrono::Utc::now(),
+            ChatUser {
+                username: "viewer1".to_string(),
+                display_name: "Viewer1".to_string(),
+                color: Some("#1E90FF".to_string()),
+                badges: vec![],
+            },
+            crate::parser::MessageContent { text: text.to_string(), emotes: vec![], fragments: vec![] },
+            crate::parser::StreamContext::default(),
+        )
+    }
+
+    fn sample_dashboard() -> Dashboard {
+        let mut dashboard = Dashboard::new();
+        let agent_id = uuid::Uuid::new_v4();
+
+        dashboard.update_metrics(SystemMetrics {
+            active_agents: 2,
+            total_messages: 1234,
+            messages_per_second: 4.5,
+            cpu_usage: 37.5,
+            memory_usage: 512 * 1024 * 1024,
+            memory_total: 2048 * 1024 * 1024,
+            uptime: Duration::from_secs(3725),
+        });
+        dashboard.update_agents(vec![
+            AgentInfo {
+                id: agent_id,
+                channel: "shroud".to_string(),
+                status: AgentStatus::Running,
+                uptime: Duration::from_secs(600),
+                messages_per_second: 3.0,
+                error_count: 0,
+                alert_id: None,
+            },
+            AgentInfo {
+                id: uuid::Uuid::new_v4(),
+                channel: "pokimane".to_string(),
+                status: AgentStatus::Error("page crashed".to_string()),
+                uptime: Duration::from_secs(5),
+                messages_per_second: 0.0,
+                error_count: 3,
+                alert_id: None,
+            },
+        ]);
+        dashboard.add_log(LogEntry::new(LogLevel::Info, "Agent started", Some(agent_id)));
+        dashboard.add_log(LogEntry {
+            user: Some(chat_message("shroud", "hello chat").user),
+            ..LogEntry::new(LogLevel::Info, "hello chat", Some(agent_id))
+        });
+        dashboard.add_alert(AlertLevel::Warning, "High memory usage".to_string(), Some(agent_id));
+        dashboard.add_chat_message(chat_message("shroud", "nice flick"));
+        dashboard.add_chat_message(chat_message("pokimane", "hi poki"));
+        dashboard
+    }
+
+    fn render_to_string(dashboard: &mut Dashboard, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| dashboard.render(frame).unwrap()).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let mut text = String::new();
+        for y in 0..buffer.area.height {
+            for x in 0..buffer.area.width {
+                text.push_str(&buffer.get(x, y).symbol);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    #[test]
+    fn test_theme_colors_every_panel() {
+        let mut dashboard = sample_dashboard();
+        dashboard.theme.border_color = Color::Rgb(145, 70, 255);
+
+        for tab in ALL_TABS {
+            dashboard.current_tab = tab;
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal.draw(|frame| dashboard.render(frame).unwrap()).unwrap();
+            // bottom left corner of the tab's lowest panel
+            assert_eq!(terminal.backend().buffer().get(0, 39).fg, Color::Rgb(145, 70, 255), "{:?} tab", tab);
+        }
+    }
+
+    #[test]
+    fn test_render_every_tab() {
+        let mut dashboard = sample_dashboard();
+        dashboard.set_config(crate::config::Config::default());
+
+        for tab in ALL_TABS {
+            dashboard.current_tab = tab;
+            let screen = render_to_string(&mut dashboard, 120, 40);
+
+            for title in ALL_TABS.iter().map(Tab::title) {
+                assert!(screen.contains(title), "{:?} tab is missing the '{}' title", tab, title);
+            }
+            match tab {
+                Tab::Agents => {
+                    assert!(screen.contains("shroud"));
+                    assert!(screen.contains("pokimane"));
+                }
+                Tab::Logs => assert!(screen.contains("hello chat")),
+                Tab::Alerts => assert!(screen.contains("High memory usage")),
+                Tab::Messages => {
+                    assert!(screen.contains("#shroud Viewer1: nice flick"));
+                    assert!(screen.contains("hi poki"));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_empty_and_tiny() {
+        // nothing loaded yet, no config
+        let mut dashboard = Dashboard::new();
+        for tab in ALL_TABS {
+            dashboard.current_tab = tab;
+            render_to_string(&mut dashboard, 120, 40);
+        }
+
+        // a cramped terminal must not panic either
+        let mut dashboard = sample_dashboard();
+        dashboard.show_help = true;
+        for tab in ALL_TABS {
+            dashboard.current_tab = tab;
+            render_to_string(&mut dashboard, 20, 6);
+        }
+    }
+
+    #[test]
+    fn test_resize_redraws() {
+        let mut dashboard = sample_dashboard();
+        dashboard.set_config(Config::default());
+        assert_eq!(dashboard.handle_input(Event::Resize(2, 1)).unwrap(), Action::Redraw);
+
+        let mut ticker = RenderTicker::new(10);
+        let now = Instant::now();
+        ticker.drawn(now);
+        assert!(!ticker.should_draw(now));
+        ticker.force_draw();
+        assert!(ticker.should_draw(now));
+
+        // shrinking all the way down, with and without the help popup
+        for show_help in [false, true] {
+            dashboard.show_help = show_help;
+            for tab in ALL_TABS {
+                dashboard.current_tab = tab;
+                for (width, height) in [(0, 0), (1, 1), (2, 1), (5, 3), (40, 2), (3, 30)] {
+                    render_to_string(&mut dashboard, width, height);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_rate_is_smoothed() {
+        let mut dashboard = Dashboard::new();
+        let start = dashboard.last_update_time;
+
+        dashboard.metrics.total_messages = 10;
+        dashboard.update_message_rate_at(start + Duration::from_secs(1));
+        assert_eq!(dashboard.raw_message_rate(), 10.0);
+        assert_eq!(dashboard.metrics.messages_per_second, 10.0);
+
+        // a spike moves the average only part of the way
+        dashboard.metrics.total_messages = 40;
+        dashboard.update_message_rate_at(start + Duration::from_secs(2));
+        assert_eq!(dashboard.raw_message_rate(), 30.0);
+        assert!((dashboard.metrics.messages_per_second - 16.0).abs() < 1e-9);
+        assert_eq!(dashboard.format_message_rate(), "30.0 (avg 16.0)");
+        assert_eq!(dashboard.performance_history.len(), 2);
+    }
+
+    #[test]
+    fn test_message_rate_waits_for_full_window() {
+        let mut dashboard = Dashboard::new();
+        dashboard.set_rate_smoothing(1.0);
+        let start = dashboard.last_update_time;
+
+        dashboard.metrics.total_messages = 5;
+        dashboard.update_message_rate_at(start + Duration::from_millis(200));
+        assert_eq!(dashboard.raw_message_rate(), 0.0);
+
+        dashboard.update_message_rate_at(start + Duration::from_secs(2));
+        assert_eq!(dashboard.raw_message_rate(), 2.5);
+        assert_eq!(dashboard.metrics.messages_per_second, 2.5);
+    }
+
+    #[test]
+    fn test_render_ticker() {
+        let mut ticker = RenderTicker::new(10);
+        assert_eq!(ticker.poll_timeout(), Duration::from_millis(25));
+        assert_eq!(RenderTicker::new(60).poll_timeout(), Duration::from_millis(5));
+        assert_eq!(RenderTicker::new(1).poll_timeout(), Duration::from_millis(50));
+
+        // first frame always draws
+        let start = Instant::now();
+        assert!(ticker.should_draw(start));
+        ticker.drawn(start);
+
+        // nothing changed, wait for the idle refresh
+        assert!(!ticker.should_draw(start + Duration::from_millis(500)));
+        assert!(ticker.should_draw(start + IDLE_REDRAW_INTERVAL));
+
+        // changes are debounced to the frame rate
+        ticker.mark_dirty();
+        assert!(!ticker.should_draw(start + Duration::from_millis(40)));
+        assert!(ticker.should_draw(start + Duration::from_millis(100)));
+        ticker.drawn(start + Duration::from_millis(100));
+        assert!(!ticker.should_draw(start + Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#FF4500"), Some(Color::Rgb(255, 69, 0)));
+        assert_eq!(parse_hex_color("#1e90ff"), Some(Color::Rgb(30, 144, 255)));
+
+        assert_eq!(parse_hex_color("FF4500"), None);
+        assert_eq!(parse_hex_color("#FFF"), None);
+        assert_eq!(parse_hex_color("#GG0000"), None);
+        assert_eq!(parse_hex_color(""), None);
+
+        assert_eq!(username_color(None), DEFAULT_USERNAME_COLOR);
+        assert_eq!(username_color(Some("red")), DEFAULT_USERNAME_COLOR);
+        assert_eq!(username_color(Some("#000000")), Color::Rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn test_chat_buffer_is_capped() {
+        let mut dashboard = Dashboard::new();
+        for i in 0..MAX_CHAT_MESSAGES + 25 {
+            dashboard.add_chat_message(chat_message("shroud", &format!("message {}", i)));
+        }
+
+        assert_eq!(dashboard.chat_messages.len(), MAX_CHAT_MESSAGES);
+        assert_eq!(dashboard.chat_messages.front().unwrap().message.text, "message 25");
+        assert_eq!(
+            dashboard.chat_messages.back().unwrap().message.text,
+            format!("message {}", MAX_CHAT_MESSAGES + 24)
+        );
+    }
+
+    #[test]
+    fn test_chat_streamer_filter() {
+        let mut dashboard = sample_dashboard();
+        dashboard.current_tab = Tab::Messages;
+        dashboard.add_chat_message(chat_message("shroud", "second shroud line"));
+
+        let press = |dashboard: &mut Dashboard, code: KeyCode| {
+            dashboard.handle_input(Event::Key(crossterm::event::KeyEvent::from(code))).unwrap();
+        };
+
+        // all streamers, then each in turn, then back to all
+        assert_eq!(dashboard.filtered_chat().count(), 3);
+        press(&mut dashboard, KeyCode::Char('f'));
+        assert_eq!(dashboard.chat_filter.as_deref(), Some("pokimane"));
+        assert_eq!(dashboard.filtered_chat().count(), 1);
+        press(&mut dashboard, KeyCode::Char('f'));
+        assert_eq!(dashboard.chat_filter.as_deref(), Some("shroud"));
+        assert_eq!(dashboard.filtered_chat().count(), 2);
+        let screen = render_to_string(&mut dashboard, 120, 40);
+        assert!(screen.contains("second shroud line"));
+        assert!(!screen.contains("hi poki"));
+        press(&mut dashboard, KeyCode::Char('f'));
+        assert_eq!(dashboard.chat_filter, None);
+
+        // reachable from the number keys and the tab cycle
+        press(&mut dashboard, KeyCode::Char('1'));
+        press(&mut dashboard, KeyCode::Char('7'));
+        assert_eq!(dashboard.current_tab, Tab::Messages);
+        press(&mut dashboard, KeyCode::Tab);
+        assert_eq!(dashboard.current_tab, Tab::Overview);
+    }
+
+    #[test]
+    fn test_log_filter() {
+        let entry = |level: LogLevel, message: &str, user: Option<&str>| LogEntry {
+            user: user.map(|name| ChatUser {
+                username: name.to_lowercase(),
+                display_name: name.to_string(),
+                color: None,
+                badges: vec![],
+            }),
+            ..LogEntry::new(level, message, None)
+        };
+        let logs = [
+            entry(LogLevel::Info, "Agent started for shroud", None),
+            entry(LogLevel::Warning, "Shroud chat went quiet", None),
+            entry(LogLevel::Error, "Browser crashed", None),
+            entry(LogLevel::Debug, "poll took 20ms", None),
+            entry(LogLevel::Info, "gg", Some("ShroudFan")),
+        ];
+        let shown = |filter: &LogFilter| -> Vec<&str> {
+            logs.iter().filter(|log| filter.matches(log)).map(|log| log.message.as_str()).collect()
+        };
+
+        let everything = LogFilter::default();
+        assert!(!everything.is_active());
+        assert_eq!(shown(&everything).len(), logs.len());
+
+        // substring, ignoring case, in the text or the chatter's name
+        let search = LogFilter { query: "SHROUD".to_string(), warnings_only: false };
+        assert_eq!(shown(&search), vec!["Agent started for shroud", "Shroud chat went quiet", "gg"]);
+
+        let warnings = LogFilter { query: String::new(), warnings_only: true };
+        assert_eq!(shown(&warnings), vec!["Shroud chat went quiet", "Browser crashed"]);
+
+        let both = LogFilter { query: "shroud".to_string(), warnings_only: true };
+        assert_eq!(shown(&both), vec!["Shroud chat went quiet"]);
+
+        let nothing = LogFilter { query: "no such line".to_string(), warnings_only: false };
+        assert!(shown(&nothing).is_empty());
+    }
+
+    #[test]
+    fn test_log_search_keys() {
+        let mut dashboard = sample_dashboard();
+        dashboard.current_tab = Tab::Logs;
+        let press = |dashboard: &mut Dashboard, code: KeyCode| {
+            dashboard.handle_input(Event::Key(crossterm::event::KeyEvent::from(code))).unwrap();
+        };
+
+        dashboard.log_list_state.select(Some(1));
+        press(&mut dashboard, KeyCode::Char('/'));
+        for c in "chat".chars() {
+            press(&mut dashboard, KeyCode::Char(c));
+        }
+        // one line left, the selection moves onto it
+        assert_eq!(dashboard.filtered_logs().count(), 1);
+        assert_eq!(dashboard.log_list_state.selected(), Some(0));
+
+        // 'q' is part of the query while searching, not quit
+        press(&mut dashboard, KeyCode::Char('q'));
+        assert_eq!(dashboard.log_filter.query, "chatq");
+        press(&mut dashboard, KeyCode::Backspace);
+        press(&mut dashboard, KeyCode::Enter);
+        assert_eq!(dashboard.log_filter.query, "chat");
+        assert!(!dashboard.log_search_active);
+
+        let screen = render_to_string(&mut dashboard, 120, 40);
+        assert!(screen.contains("hello chat"));
+        assert!(!screen.contains("Agent started"));
+
+        press(&mut dashboard, KeyCode::Char('w'));
+        assert_eq!(dashboard.filtered_logs().count(), 0);
+        assert_eq!(dashboard.log_list_state.selected(), None);
+
+        press(&mut dashboard, KeyCode::Esc);
+        assert_eq!(dashboard.log_filter, LogFilter::default());
+        assert_eq!(dashboard.filtered_logs().count(), 2);
+    }
+
+    #[test]
+    fn test_config_field_editing() {
+        let mut dashboard = sample_dashboard();
+        dashboard.set_config(Config::default());
+        dashboard.current_tab = Tab::Config;
+        let press = |dashboard: &mut Dashboard, code: KeyCode| {
+            dashboard.handle_input(Event::Key(crossterm::event::KeyEvent::from(code))).unwrap();
+        };
+        let type_text = |dashboard: &mut Dashboard, text: &str| {
+            for c in text.chars() {
+                press(dashboard, KeyCode::Char(c));
+            }
+        };
+
+        press(&mut dashboard, KeyCode::Char('e'));
+        press(&mut dashboard, KeyCode::Down);
+        assert_eq!(ConfigField::ALL[dashboard.config_field_index], ConfigField::MaxConcurrent);
+
+        // Enter starts from the current value, 'q' and 's' are just text here
+        press(&mut dashboard, KeyCode::Enter);
+        assert_eq!(dashboard.config_edit_buffer.as_deref(), Some("5"));
+        press(&mut dashboard, KeyCode::Backspace);
+        type_text(&mut dashboard, "2q");
+        assert!(render_to_string(&mut dashboard, 120, 40).contains("Max Concurrent Agents: 2q_"));
+
+        // not a number, the edit stays open and the config untouched
+        press(&mut dashboard, KeyCode::Enter);
+        assert!(dashboard.config_edit_buffer.is_some());
+        assert_eq!(dashboard.config.as_ref().unwrap().agents.max_concurrent, 5);
+
+        press(&mut dashboard, KeyCode::Backspace);
+        type_text(&mut dashboard, "5");
+        press(&mut dashboard, KeyCode::Enter);
+        assert_eq!(dashboard.config_edit_buffer, None);
+        assert_eq!(dashboard.config.as_ref().unwrap().agents.max_concurrent, 25);
+        assert!(render_to_string(&mut dashboard, 120, 40).contains("Max Concurrent Agents: 25"));
+
+        // Esc throws a half typed value away
+        press(&mut dashboard, KeyCode::Enter);
+        type_text(&mut dashboard, "0");
+        press(&mut dashboard, KeyCode::Esc);
+        assert_eq!(dashboard.config.as_ref().unwrap().agents.max_concurrent, 25);
+
+        // switches flip in place
+        for _ in 0..ConfigField::ALL.len() {
+            press(&mut dashboard, KeyCode::Down);
+        }
+        assert_eq!(ConfigField::ALL[dashboard.config_field_index], ConfigField::FingerprintRandomization);
+        let before = dashboard.config.as_ref().unwrap().stealth.fingerprint_randomization;
+        press(&mut dashboard, KeyCode::Enter);
+        assert_eq!(dashboard.config_edit_buffer, None);
+        assert_eq!(dashboard.config.as_ref().unwrap().stealth.fingerprint_randomization, !before);
+    }
+
+    #[test]
+    fn test_parse_custom_theme() {
+        let theme = CustomTheme::parse(
+            "# dark purple\n\
+             accent_color = \"#9146FF\"\n\
+             \n\
+             text_color = light gray\n\
+             border_color='dark gray'\n",
+        )
+        .unwrap();
+        assert_eq!(
+            theme,
+            CustomTheme {
+                text_color: Color::White,
+                accent_color: Color::Rgb(145, 70, 255),
+                border_color: Color::DarkGray,
+                background_color: Color::Reset,
+            }
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("theme.conf");
+        std::fs::write(&path, "background_color = black\n").unwrap();
+        assert_eq!(load_custom_theme(&path).unwrap().background_color, Color::Black);
+        assert_eq!(Dashboard::new().with_custom_theme(Some(path)).theme.background_color, Color::Black);
+    }
+
+    #[test]
+    fn test_reject_malformed_theme() {
+        let err = CustomTheme::parse("accent_colour = \"#9146FF\"").unwrap_err();
+        assert!(err.to_string().contains("unknown key 'accent_colour'"), "{}", err);
+
+        let err = CustomTheme::parse("text_color = white\naccent_color = #12345").unwrap_err();
+        assert!(err.to_string().contains("line 2: '#12345' is not a color"), "{}", err);
+
+        assert!(CustomTheme::parse("accent_color #9146FF").is_err());
+
+        // a bad or missing file keeps the default theme instead of failing
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("theme.conf");
+        std::fs::write(&path, "accent_color = chartreuse\n").unwrap();
+        assert_eq!(Dashboard::new().with_custom_theme(Some(path)).theme, CustomTheme::default());
+        let missing = dir.path().join("missing.conf");
+        assert_eq!(Dashboard::new().with_custom_theme(Some(missing)).theme, CustomTheme::default());
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_shows_orchestrator_state() {
+        use crate::agents::{AgentAssignment, AgentOrchestrator, ScrapingAgent};
+        use crate::browser::BrowserManager;
+        use std::time::SystemTime;
+
+        let orchestrator = AgentOrchestrator::new(Config::default(), Arc::new(BrowserManager::without_browser(2)));
+        let (chat_tx, _) = tokio::sync::broadcast::channel(10);
+        for (streamer, messages) in [("shroud", 120), ("ninja", 30)] {
+            let mut agent = ScrapingAgent::new((100, 200), chat_tx.clone()).unwrap();
+            agent.start_time = Some(tokio::time::Instant::now() - Duration::from_secs(10));
+            agent.set_status(AgentStatus::Running).await;
+            agent.update_message_metrics(messages).await;
+            let assignment = AgentAssignment {
+                agent_id: agent.id,
+                streamer: streamer.to_string(),
+                assigned_at: SystemTime::now(),
+                priority: 0,
+                retry_attempts: 0,
+                last_failure: None,
+                idle_since: None,
+                proxy: None,
+            };
+            orchestrator.insert_agent(agent, assignment).await;
+        }
+
+        let (metrics, agents) = dashboard_snapshot(&orchestrator).await;
+        assert_eq!(metrics.active_agents, 2);
+        assert_eq!(metrics.total_messages, 150);
+        assert_eq!(agents.iter().map(|agent| agent.channel.as_str()).collect::<Vec<_>>(), ["ninja", "shroud"]);
+        assert!(agents.iter().all(|agent| agent.status == AgentStatus::Running));
+        let shroud_rate = agents[1].messages_per_second;
+        assert!(shroud_rate > 10.0 && shroud_rate <= 12.0, "{}", shroud_rate);
+
+        let mut dashboard = Dashboard::new();
+        dashboard.update_metrics(metrics);
+        dashboard.update_agents(agents);
+        assert_eq!(dashboard.metrics.total_messages, 150);
+        assert_eq!(dashboard.metrics.active_agents, 2);
+        dashboard.current_tab = Tab::Agents;
+        let screen = render_to_string(&mut dashboard, 120, 40);
+        assert!(screen.contains("shroud"));
+        assert!(screen.contains("ninja"));
+    }
+
+    #[test]
+    fn test_agent_control_keys() {
+        let mut dashboard = sample_dashboard();
+        let press = |dashboard: &mut Dashboard, c: char| {
+            dashboard.handle_input(Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)))).unwrap()
+        };
+
+        // nothing selected, or not on the Agents tab, does nothing
+        dashboard.current_tab = Tab::Agents;
+        assert_eq!(press(&mut dashboard, 'x'), Action::Continue);
+        dashboard.agent_table_state.select(Some(1));
+        dashboard.current_tab = Tab::Overview;
+        assert_eq!(press(&mut dashboard, 'x'), Action::Continue);
+
+        dashboard.current_tab = Tab::Agents;
+        let selected = dashboard.agents[1].id;
+        assert_eq!(press(&mut dashboard, 'x'), Action::StopAgent(selected));
+        assert_eq!(press(&mut dashboard, 'r'), Action::RestartAgent(selected));
+    }
+
+    #[test]
+    fn test_help_popup() {
+        let mut dashboard = sample_dashboard();
+        let press = |dashboard: &mut Dashboard, code: KeyCode| {
+            dashboard.handle_input(Event::Key(crossterm::event::KeyEvent::from(code))).unwrap()
+        };
+        let help_text = |dashboard: &Dashboard| -> String {
+            dashboard
+                .help_lines()
+                .iter()
+                .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        press(&mut dashboard, KeyCode::Char('?'));
+        assert!(dashboard.show_help);
+        // keys go to the popup, 'q' doesn't quit and Tab doesn't switch tabs
+        assert_eq!(press(&mut dashboard, KeyCode::Char('q')), Action::Continue);
+        press(&mut dashboard, KeyCode::Tab);
+        assert_eq!(dashboard.current_tab, Tab::Overview);
+
+        let text = help_text(&dashboard);
+        assert!(text.contains("q  Quit"), "{}", text);
+        assert!(text.contains("Tab  Cycle to the next tab"), "{}", text);
+        assert!(text.contains("Overview tab"));
+        assert!(render_to_string(&mut dashboard, 120, 40).contains("Cycle to the next tab"));
+
+        press(&mut dashboard, KeyCode::Char('h'));
+        assert!(!dashboard.show_help);
+
+        // the tab's own keys are listed
+        dashboard.current_tab = Tab::Logs;
+        press(&mut dashboard, KeyCode::Char('h'));
+        assert!(help_text(&dashboard).contains("Only show warnings and errors"));
+
+        // too small to fit: scrolls, but never past the last line
+        for _ in 0..50 {
+            press(&mut dashboard, KeyCode::Down);
+        }
+        render_to_string(&mut dashboard, 80, 16);
+        let max_scroll = dashboard.help_scroll;
+        assert!(max_scroll > 0);
+        assert!((max_scroll as usize) < dashboard.help_lines().len());
+        press(&mut dashboard, KeyCode::Up);
+        assert_eq!(dashboard.help_scroll, max_scroll - 1);
+
+        press(&mut dashboard, KeyCode::Esc);
+        assert!(!dashboard.show_help);
+        assert_eq!(dashboard.help_scroll, 0);
+    }
+
+    #[test]
+    fn test_rate_limit_raises_alert() {
+        let mut dashboard = Dashboard::new();
+        let agent_id = uuid::Uuid::new_v4();
+        dashboard.add_agent_alert(&AgentMessage::Error { agent_id, error: "boom".to_string() });
+        assert!(dashboard.alerts.is_empty());
+
+        dashboard.add_agent_alert(&AgentMessage::RateLimited {
+            agent_id,
+            streamer: "shroud".to_string(),
+            pause: Duration::from_secs(30),
+        });
+        assert_eq!(dashboard.alerts.len(), 1);
+        assert_eq!(dashboard.alerts[0].level, AlertLevel::RateLimited);
+        assert_eq!(dashboard.alerts[0].message, "Rate limited on shroud, paused for 30s");
+        assert_eq!(dashboard.alerts[0].agent_id, Some(agent_id));
+    }
+
+    #[test]
+    fn test_acknowledge_alert() {
+        let mut dashboard = sample_dashboard();
+        dashboard.add_alert(AlertLevel::Critical, "Agent crashed".to_string(), None);
+        dashboard.current_tab = Tab::Alerts;
+        let press = |dashboard: &mut Dashboard, code: KeyCode| {
+            dashboard.handle_input(Event::Key(crossterm::event::KeyEvent::from(code))).unwrap();
+        };
+        assert_eq!(dashboard.alerts.len(), 2);
+        assert!(dashboard.alerts.iter().all(|alert| !alert.acknowledged));
+
+        // nothing selected yet
+        press(&mut dashboard, KeyCode::Char('a'));
+        assert!(dashboard.alerts.iter().all(|alert| !alert.acknowledged));
+
+        press(&mut dashboard, KeyCode::Down);
+        press(&mut dashboard, KeyCode::Down);
+        assert_eq!(dashboard.alert_list_state.selected(), Some(1));
+        press(&mut dashboard, KeyCode::Char('a'));
+        assert!(!dashboard.alerts[0].acknowledged);
+        assert!(dashboard.alerts[1].acknowledged);
+        assert!(render_to_string(&mut dashboard, 120, 40).contains("Agent crashed [ACK]"));
+
+        // hiding acknowledged alerts moves the selection onto what's left
+        press(&mut dashboard, KeyCode::Char('u'));
+        assert_eq!(dashboard.filtered_alerts().count(), 1);
+        assert_eq!(dashboard.alert_list_state.selected(), Some(0));
+        let screen = render_to_string(&mut dashboard, 120, 40);
+        assert!(!screen.contains("Agent crashed"));
+        assert!(screen.contains("High memory usage"));
+
+        press(&mut dashboard, KeyCode::Char('a'));
+        assert!(dashboard.alerts.iter().all(|alert| alert.acknowledged));
+        assert_eq!(dashboard.alert_list_state.selected(), None);
+
+        press(&mut dashboard, KeyCode::Char('u'));
+        assert_eq!(dashboard.filtered_alerts().count(), 2);
+        assert!(!dashboard.acknowledge_alert(999));
+    }
+}
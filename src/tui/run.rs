@@ -1,5 +1,5 @@
-use crate::agents::AgentOrchestrator;
-use crate::tui::{Action, Dashboard, TUIMonitor};
+use crate::agents::{AgentId, AgentOrchestrator, AgentStatus};
+use crate::tui::{Action, AgentInfo, Dashboard, LogEntry, LogLevel, RenderTicker, SystemMetrics, TUIMonitor};
 use anyhow::Result;
 use crossterm::{event, terminal, execute};
 use tokio::signal;
@@ -9,6 +9,55 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// Dashboard metrics and agent rows from the orchestrator's live state. Message
+/// counts are read from the agents directly, cpu and memory from the last sample.
+pub async fn dashboard_snapshot(orchestrator: &AgentOrchestrator) -> (SystemMetrics, Vec<AgentInfo>) {
+    let status = orchestrator.get_status().await;
+
+    let mut agents = Vec::new();
+    let mut total_messages = 0;
+    for assignment in &status.agent_assignments {
+        let agent_status = orchestrator.get_agent_status(assignment.agent_id).await.unwrap_or(AgentStatus::Idle);
+        let metrics = orchestrator.get_agent_metrics(assignment.agent_id).await;
+        let (uptime, messages, error_count) = metrics
+            .map(|metrics| (metrics.uptime, metrics.messages_scraped, metrics.error_count))
+            .unwrap_or((Duration::ZERO, 0, assignment.retry_attempts));
+        total_messages += messages;
+
+        agents.push(AgentInfo {
+            id: assignment.agent_id,
+            channel: assignment.streamer.clone(),
+            status: agent_status,
+            uptime,
+            // averaged over the agent's run, the dashboard smooths the overall rate itself
+            messages_per_second: if uptime.is_zero() { 0.0 } else { messages as f64 / uptime.as_secs_f64() },
+            error_count,
+            alert_id: None,
+        });
+    }
+    agents.sort_by(|a, b| a.channel.cmp(&b.channel));
+
+    let metrics = SystemMetrics {
+        active_agents: status.active_agents as u32,
+        total_messages,
+        messages_per_second: 0.0, // derived from total_messages by the dashboard
+        cpu_usage: status.system_metrics.cpu_usage,
+        memory_usage: status.system_metrics.memory_usage,
+        memory_total: status.system_metrics.memory_total,
+        uptime: status.uptime,
+    };
+    (metrics, agents)
+}
+
+// agent control from the Agents tab, the outcome shows in the Logs tab
+fn log_agent_control(dashboard: &mut Dashboard, agent_id: AgentId, verb: &str, result: crate::error::Result<()>) {
+    let (level, message) = match result {
+        Ok(()) => (LogLevel::Info, format!("Agent {}: {} done", agent_id, verb)),
+        Err(e) => (LogLevel::Error, format!("Agent {}: {} failed: {}", agent_id, verb, e)),
+    };
+    dashboard.add_log(LogEntry::new(level, message, Some(agent_id)));
+}
+
 pub async fn run_tui(orchestrator: Arc<RwLock<AgentOrchestrator>>, config: Arc<crate::config::Config>, config_manager: Arc<dyn crate::config::ConfigManager + Send + Sync>) -> Result<()> {
     let mut stdout = io::stdout();
     terminal::enable_raw_mode()?;
@@ -16,30 +65,16 @@ pub async fn run_tui(orchestrator: Arc<RwLock<AgentOrchestrator>>, config: Arc<c
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut dashboard = Dashboard::new();
+    let mut dashboard = Dashboard::new().with_custom_theme(config.monitoring.custom_css.clone());
+    dashboard.set_rate_smoothing(config.monitoring.tui_rate_smoothing);
     dashboard.set_config_manager(config_manager);
     
     // add initial log entries
-    dashboard.add_log(crate::tui::LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: crate::tui::LogLevel::Info,
-        message: "Twitch Chat Scraper started".to_string(),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, "Twitch Chat Scraper started", None));
     
-    dashboard.add_log(crate::tui::LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: crate::tui::LogLevel::Info,
-        message: "Loading configuration...".to_string(),
-        agent_id: None,
-    });
-
-    dashboard.add_log(crate::tui::LogEntry {
-        timestamp: chrono::Utc::now(),
-        level: crate::tui::LogLevel::Info,
-        message: format!("Configured streamers: {}", config.streamers.join(", ")),
-        agent_id: None,
-    });
+    dashboard.add_log(LogEntry::new(LogLevel::Info, "Loading configuration...", None));
+
+    dashboard.add_log(LogEntry::new(LogLevel::Info, format!("Configured streamers: {}", config.streamers.join(", ")), None));
 
     // Set the config in dashboard
     dashboard.set_config((*config).clone());
@@ -48,100 +83,133 @@ pub async fn run_tui(orchestrator: Arc<RwLock<AgentOrchestrator>>, config: Arc<c
     let mut sigint = signal::unix::signal(signal::unix::SignalKind::interrupt())?;
     let mut sigterm = signal::unix::signal(signal::unix::SignalKind::terminate())?;
 
+    // scraped chat for the chat tab, agent events for the alerts tab
+    let (mut chat_rx, mut chat_batch_rx, mut agent_rx) = {
+        let orchestrator_read = orchestrator.read().await;
+        (
+            orchestrator_read.subscribe_to_chat_messages(),
+            orchestrator_read.subscribe_to_chat_batches(),
+            orchestrator_read.subscribe_to_messages(),
+        )
+    };
+
+    let (mut chat_open, mut chat_batches_open, mut agent_messages_open) = (true, true, true);
+
+    let mut ticker = RenderTicker::new(config.monitoring.tui_fps);
+    let mut refresh = tokio::time::interval(Duration::from_millis(500));
+
     loop {
         tokio::select! {
             // handle ctrl+c and sigterm
             _ = sigint.recv() => {
-                dashboard.add_log(crate::tui::LogEntry {
-                    timestamp: chrono::Utc::now(),
-                    level: crate::tui::LogLevel::Info,
-                    message: "Received interrupt signal (Ctrl+C), shutting down...".to_string(),
-                    agent_id: None,
-                });
+                dashboard.add_log(LogEntry::new(LogLevel::Info, "Received interrupt signal (Ctrl+C), shutting down...", None));
                 break;
             }
             _ = sigterm.recv() => {
-                dashboard.add_log(crate::tui::LogEntry {
-                    timestamp: chrono::Utc::now(),
-                    level: crate::tui::LogLevel::Info,
-                    message: "Received termination signal, shutting down...".to_string(),
-                    agent_id: None,
-                });
+                dashboard.add_log(LogEntry::new(LogLevel::Info, "Received termination signal, shutting down...", None));
                 break;
             }
             // handle keyboard input
             input_result = async {
-                if event::poll(Duration::from_millis(100))? {
+                if event::poll(ticker.poll_timeout())? {
                     Ok::<Option<crossterm::event::Event>, anyhow::Error>(Some(event::read()?))
                 } else {
                     Ok(None)
                 }
             } => {
-                match input_result? {
-                    Some(input_event) => {
-                        if let Action::Quit = dashboard.handle_input(input_event).map_err(anyhow::Error::from)? {
-                            break;
+                if let Some(input_event) = input_result? {
+                    ticker.mark_dirty();
+                    match dashboard.handle_input(input_event)? {
+                        Action::Continue => {}
+                        Action::Quit => break,
+                        Action::Redraw => ticker.force_draw(),
+                        Action::StopAgent(agent_id) => {
+                            let result = orchestrator.write().await.stop_agent(agent_id).await;
+                            log_agent_control(&mut dashboard, agent_id, "stop", result);
+                        }
+                        Action::RestartAgent(agent_id) => {
+                            let result = orchestrator.write().await.manual_restart_agent(agent_id).await;
+                            log_agent_control(&mut dashboard, agent_id, "restart", result);
                         }
                     }
-                    None => {} // No input, continue
+                }
+            }
+            // scraped messages, a slow terminal just skips what it fell behind on
+            message = chat_rx.recv(), if chat_open => {
+                match message {
+                    Ok(message) => {
+                        dashboard.add_chat_message(message);
+                        ticker.mark_dirty();
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => chat_open = false,
+                }
+            }
+            batch = chat_batch_rx.recv(), if chat_batches_open => {
+                match batch {
+                    Ok(batch) => {
+                        for message in batch {
+                            dashboard.add_chat_message(message);
+                        }
+                        ticker.mark_dirty();
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => chat_batches_open = false,
+                }
+            }
+            agent_message = agent_rx.recv(), if agent_messages_open => {
+                match agent_message {
+                    Ok(agent_message) => {
+                        dashboard.add_agent_alert(&agent_message);
+                        ticker.mark_dirty();
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => agent_messages_open = false,
                 }
             }
             // update dashboard data
-            _ = tokio::time::sleep(Duration::from_millis(500)) => {
-                // Update dashboard with real data from orchestrator
-                let orchestrator_read = orchestrator.read().await;
-                
-                // Get orchestrator status which includes system metrics
-                let orchestrator_status = orchestrator_read.get_status().await;
-                
-                // Update system metrics
-                let system_metrics = crate::tui::SystemMetrics {
-                    active_agents: orchestrator_status.active_agents as u32,
-                    total_messages: orchestrator_status.system_metrics.total_messages_scraped,
-                    messages_per_second: 0.0, // Calculate from recent data
-                    cpu_usage: orchestrator_status.system_metrics.cpu_usage,
-                    memory_usage: orchestrator_status.system_metrics.memory_usage,
-                    memory_total: orchestrator_status.system_metrics.memory_total,
-                    uptime: orchestrator_status.system_metrics.timestamp.elapsed().unwrap_or_default(),
-                };
+            _ = refresh.tick() => {
+                let (system_metrics, agents) = dashboard_snapshot(&*orchestrator.read().await).await;
                 dashboard.update_metrics(system_metrics);
-                
-                // Get real agent information
-                let mut agents_info = Vec::new();
-                let assignments = orchestrator_read.agent_assignments.read().await;
-                for assignment in assignments.values() {
-                    // Get real agent status and metrics
-                    let agent_status = orchestrator_read.get_agent_status(assignment.agent_id).await
-                        .unwrap_or(crate::agents::AgentStatus::Idle);
-                    let agent_metrics = orchestrator_read.get_agent_metrics(assignment.agent_id).await;
-                    
-                    let agent_info = crate::tui::AgentInfo {
-                        id: assignment.agent_id,
-                        channel: assignment.streamer.clone(),
-                        status: agent_status,
-                        uptime: agent_metrics.as_ref().map(|m| m.uptime).unwrap_or_default(),
-                        messages_per_second: 0.0, // Calculate from metrics if available
-                        error_count: agent_metrics.as_ref().map(|m| m.error_count).unwrap_or(assignment.retry_attempts),
-                        alert_id: None,
-                    };
-                    agents_info.push(agent_info);
-                }
-                
-                dashboard.update_agents(agents_info);
-
-                // Render the dashboard
-                let mut render_result = Ok(());
-                terminal.draw(|f| {
-                    render_result = dashboard.render(f);
-                })?;
-                render_result?;
+                dashboard.update_agents(agents);
+                ticker.mark_dirty();
             }
         }
+
+        // Render the dashboard, at most tui_fps times a second
+        let now = std::time::Instant::now();
+        if ticker.should_draw(now) {
+            let mut render_result = Ok(());
+            terminal.draw(|f| {
+                render_result = dashboard.render(f);
+            })?;
+            render_result?;
+            ticker.drawn(now);
+        }
     }
 
+    // wind everything down and tell the operator what made it to disk
+    let summary = orchestrator.write().await.stop().await;
+    let (level, message) = match summary {
+        Ok(ref summary) if summary.messages_unwritten == 0 && summary.agents_failed == 0 => {
+            (LogLevel::Info, summary.to_string())
+        }
+        Ok(ref summary) => (LogLevel::Warning, summary.to_string()),
+        Err(ref e) => (LogLevel::Error, format!("Shutdown failed: {}", e)),
+    };
+    dashboard.add_log(LogEntry::new(level, message.clone(), None));
+    let mut render_result = Ok(());
+    terminal.draw(|f| {
+        render_result = dashboard.render(f);
+    })?;
+    render_result?;
+
     terminal::disable_raw_mode()?;
     execute!(terminal.backend_mut(), terminal::LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
+    // the alternate screen is gone, keep the summary in the scrollback
+    println!("{}", message);
+
     Ok(())
 }
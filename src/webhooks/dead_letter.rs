@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::error::{Result, ScrapingError};
+use crate::parser::ChatMessage;
+
+/// JSONL file holding messages no webhook provider accepted, one message per line
+pub struct DeadLetterQueue {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append messages to the end of the queue
+    pub async fn push(&self, messages: &[ChatMessage]) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = String::new();
+        for message in messages {
+            lines.push_str(&serde_json::to_string(message)?);
+            lines.push('\n');
+        }
+
+        let _guard = self.lock.lock().await;
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).await
+                .map_err(|e| ScrapingError::StorageError(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to open {}: {}", self.path.display(), e)))?;
+        file.write_all(lines.as_bytes()).await
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to write {}: {}", self.path.display(), e)))?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Every queued message, and how many bytes of the file they came from for
+    /// `replace_read`. The file is left as it is. Lines that no longer parse are
+    /// logged and skipped rather than blocking the rest of the queue.
+    pub async fn read(&self) -> Result<(Vec<ChatMessage>, usize)> {
+        let _guard = self.lock.lock().await;
+        let content = match fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+            Err(e) => {
+                return Err(ScrapingError::StorageError(format!("Failed to read {}: {}", self.path.display(), e)).into())
+            }
+        };
+
+        let messages = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(message) => Some(message),
+                Err(e) => {
+                    warn!("Dropping unreadable dead letter in {}: {}", self.path.display(), e);
+                    None
+                }
+            })
+            .collect();
+        Ok((messages, content.len()))
+    }
+
+    /// Swap the first `read_len` bytes, what `read` returned, for `remaining`,
+    /// keeping anything pushed since. The new queue is written to a temp file and
+    /// renamed over the old one, so a crash mid-way loses nothing.
+    pub async fn replace_read(&self, read_len: usize, remaining: &[ChatMessage]) -> Result<()> {
+        if read_len == 0 && remaining.is_empty() {
+            return Ok(());
+        }
+        let _guard = self.lock.lock().await;
+        let content = match fs::read(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(ScrapingError::StorageError(format!("Failed to read {}: {}", self.path.display(), e)).into())
+            }
+        };
+
+        let mut queue = Vec::new();
+        for message in remaining {
+            queue.extend_from_slice(serde_json::to_string(message)?.as_bytes());
+            queue.push(b'\n');
+        }
+        queue.extend_from_slice(content.get(read_len..).unwrap_or_default());
+
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        fs::write(&temp_path, &queue).await
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to write {}: {}", temp_path.display(), e)))?;
+        fs::rename(&temp_path, &self.path).await
+            .map_err(|e| ScrapingError::StorageError(format!("Failed to replace {}: {}", self.path.display(), e)))?;
+        Ok(())
+    }
+
+    /// Messages waiting in the queue
+    pub async fn len(&self) -> Result<usize> {
+        let _guard = self.lock.lock().await;
+        match fs::read_to_string(&self.path).await {
+            Ok(content) => Ok(content.lines().filter(|line| !line.trim().is_empty()).count()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(ScrapingError::StorageError(format!("Failed to read {}: {}", self.path.display(), e)).into()),
+        }
+    }
+
+    pub async fn is_empty(&self) -> Result<bool> {
+        Ok(self.len().await? == 0)
+    }
+}
@@ -7,6 +7,8 @@ use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{debug, warn};
 
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+
 pub struct DiscordWebhook {
     client: Client,
     webhook_url: String,
@@ -96,7 +98,9 @@ impl DiscordWebhook {
         let (color, emoji) = match level.to_lowercase().as_str() {
             "critical" => (0xFF0000, "🚨"), // Red
             "warning" => (0xFFFF00, "⚠️"),  // Yellow
+            "rate_limited" => (0xFF8C00, "⏳"), // Orange
             "info" => (0x0099FF, "ℹ️"),     // Blue
+            "keyword" => (0x9146FF, "🔔"),  // Twitch purple
             _ => (0x808080, "📢"),          // Gray
         };
 
@@ -128,8 +132,8 @@ impl DiscordWebhook {
                         return (r << 16) | (g << 8) | b;
                     }
                 }
-            } else if color.starts_with('#') {
-                if let Ok(hex) = u32::from_str_radix(&color[1..], 16) {
+            } else if let Some(hex) = color.strip_prefix('#') {
+                if let Ok(hex) = u32::from_str_radix(hex, 16) {
                     return hex;
                 }
             }
@@ -140,11 +144,35 @@ impl DiscordWebhook {
 
 #[async_trait::async_trait]
 impl WebhookProvider for DiscordWebhook {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
     async fn send_message(&self, message: &ChatMessage) -> Result<()> {
         let payload = self.create_chat_embed(message);
         self.send_webhook(payload).await
     }
 
+    fn messages_per_post(&self) -> usize {
+        MAX_EMBEDS_PER_MESSAGE
+    }
+
+    fn bold(&self, text: &str) -> String {
+        format!("**{}**", text)
+    }
+
+    async fn send_messages(&self, messages: &[ChatMessage]) -> Result<()> {
+        // discord takes up to 10 embeds per webhook call
+        for chunk in messages.chunks(MAX_EMBEDS_PER_MESSAGE) {
+            let embeds: Vec<Value> = chunk
+                .iter()
+                .map(|message| self.create_chat_embed(message)["embeds"][0].clone())
+                .collect();
+            self.send_webhook(json!({ "embeds": embeds })).await?;
+        }
+        Ok(())
+    }
+
     async fn send_alert(&self, level: &str, title: &str, message: &str) -> Result<()> {
         let payload = self.create_alert_embed(level, title, message);
         self.send_webhook(payload).await
@@ -0,0 +1,218 @@
+use crate::error::{Result, ScrapingError};
+use crate::parser::ChatMessage;
+use crate::webhooks::WebhookProvider;
+use reqwest::Client;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use tracing::debug;
+
+/// Body posted for each message when `webhook_template` isn't set
+pub const DEFAULT_WEBHOOK_TEMPLATE: &str =
+    r#"{"streamer": "{streamer}", "username": "{username}", "message": "{message}", "timestamp": "{timestamp}"}"#;
+
+/// Placeholders a template may use, each filled from the message
+pub const TEMPLATE_PLACEHOLDERS: [&str; 7] =
+    ["id", "streamer", "username", "display_name", "message", "timestamp", "message_type"];
+
+/// POSTs chat messages to any HTTP endpoint as JSON rendered from a template.
+/// Placeholders like `{username}` are replaced inside the template's string
+/// values, so the substituted text is always escaped properly. Alerts go out
+/// as `{"level", "title", "message"}`.
+pub struct GenericWebhookProvider {
+    client: Client,
+    webhook_url: String,
+    template: Value,
+}
+
+impl GenericWebhookProvider {
+    /// Fails if `template` isn't JSON or uses a placeholder that doesn't exist
+    pub fn new(webhook_url: String, template: &str) -> Result<Self> {
+        let template = parse_template(template)?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            webhook_url,
+            template,
+        })
+    }
+
+    /// The template filled in for one message
+    pub fn render(&self, message: &ChatMessage) -> Value {
+        render_value(&self.template, &|name| match name {
+            "id" => message.id.clone(),
+            "streamer" => message.streamer.clone(),
+            "username" => message.user.username.clone(),
+            "display_name" => message.user.display_name.clone(),
+            "message" => message.message.text.clone(),
+            "timestamp" => message.timestamp.to_rfc3339(),
+            "message_type" => message.message_type.as_str().to_string(),
+            _ => String::new(),
+        })
+    }
+
+    async fn send_webhook(&self, payload: Value) -> Result<()> {
+        let response = self.client
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to send webhook: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ScrapingError::NetworkError(format!("Webhook failed with status {}: {}", status, body)).into());
+        }
+        debug!("Webhook sent successfully");
+        Ok(())
+    }
+}
+
+/// Parse a template, checking every `{placeholder}` in it is one we can fill
+pub fn parse_template(template: &str) -> Result<Value> {
+    let value: Value = serde_json::from_str(template)
+        .map_err(|e| ScrapingError::ConfigError(format!("webhook_template is not valid JSON: {}", e)))?;
+
+    let mut strings = Vec::new();
+    collect_strings(&value, &mut strings);
+    for text in strings {
+        for name in placeholders(text) {
+            if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+                return Err(ScrapingError::ConfigError(format!(
+                    "webhook_template has unknown placeholder {{{}}}, expected one of: {}",
+                    name,
+                    TEMPLATE_PLACEHOLDERS.join(", ")
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(value)
+}
+
+fn collect_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => strings.push(text),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(fields) => fields.values().for_each(|field| collect_strings(field, strings)),
+        _ => {}
+    }
+}
+
+// what goes between the braces of a placeholder, other braces are left alone
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_lowercase() || b == b'_')
+}
+
+fn placeholders(text: &str) -> impl Iterator<Item = &str> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|part| Some(&part[..part.find('}')?]))
+        .filter(|name| is_placeholder_name(name))
+}
+
+// one pass, so a substituted value containing `{username}` isn't filled in again
+fn render_text(text: &str, fill: &dyn Fn(&str) -> String) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').map(|end| &after[..end]) {
+            Some(name) if is_placeholder_name(name) => {
+                rendered.push_str(&fill(name));
+                rest = &after[name.len() + 1..];
+            }
+            _ => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn render_value(template: &Value, fill: &dyn Fn(&str) -> String) -> Value {
+    match template {
+        Value::String(text) => Value::String(render_text(text, fill)),
+        Value::Array(items) => Value::Array(items.iter().map(|item| render_value(item, fill)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, field)| (key.clone(), render_value(field, fill)))
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookProvider for GenericWebhookProvider {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn send_message(&self, message: &ChatMessage) -> Result<()> {
+        self.send_webhook(self.render(message)).await
+    }
+
+    async fn send_alert(&self, level: &str, title: &str, message: &str) -> Result<()> {
+        self.send_webhook(json!({ "level": level, "title": title, "message": message })).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::fixtures::chat_message;
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn test_template_rendering() {
+        let template = r##"{
+            "event": "chat",
+            "channel": "#{streamer}",
+            "author": {"login": "{username}", "name": "{display_name}"},
+            "body": "{message} from {username}",
+            "sent_at": "{timestamp}",
+            "tags": ["{message_type}", "static"],
+            "priority": 3,
+            "note": "braces {like this} stay"
+        }"##;
+        let provider = GenericWebhookProvider::new("http://127.0.0.1:9/hook".to_string(), template).unwrap();
+
+        let mut message = chat_message("say \"hi\" {username}");
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+
+        assert_eq!(
+            provider.render(&message),
+            json!({
+                "event": "chat",
+                "channel": "#teststreamer",
+                "author": {"login": "user1", "name": "User1"},
+                "body": "say \"hi\" {username} from user1",
+                "sent_at": "2024-01-15T10:30:45+00:00",
+                "tags": ["chat", "static"],
+                "priority": 3,
+                "note": "braces {like this} stay"
+            })
+        );
+
+        let default = GenericWebhookProvider::new("http://127.0.0.1:9/hook".to_string(), DEFAULT_WEBHOOK_TEMPLATE).unwrap();
+        assert_eq!(default.render(&message)["username"], "user1");
+    }
+
+    #[test]
+    fn test_invalid_template_fails_fast() {
+        let url = "http://127.0.0.1:9/hook".to_string();
+        assert!(GenericWebhookProvider::new(url.clone(), r#"{"text": "{message}""#).is_err());
+
+        let err = GenericWebhookProvider::new(url, r#"{"text": "{mesage}"}"#).err().unwrap();
+        assert!(err.to_string().contains("{mesage}"));
+    }
+}
@@ -0,0 +1,91 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::error::{Result, ScrapingError};
+use crate::parser::ChatMessage;
+
+/// Picks out chat messages worth an alert: any of a list of keywords as whole
+/// words, or anything a custom regex matches. Matching ignores case.
+#[derive(Debug, Clone)]
+pub struct KeywordFilter {
+    regex: Regex,
+}
+
+impl KeywordFilter {
+    /// None when there's nothing to match on, an error if `pattern` doesn't compile
+    pub fn new(keywords: &[String], pattern: Option<&str>) -> Result<Option<Self>> {
+        let mut alternatives: Vec<String> = keywords
+            .iter()
+            .map(|keyword| keyword.trim())
+            .filter(|keyword| !keyword.is_empty())
+            .map(whole_word)
+            .collect();
+        if let Some(pattern) = pattern {
+            // compiled on its own first so the error points at the user's pattern
+            Regex::new(pattern)
+                .map_err(|e| ScrapingError::ConfigError(format!("Invalid alert_pattern '{}': {}", pattern, e)))?;
+            alternatives.push(format!("(?:{})", pattern));
+        }
+        if alternatives.is_empty() {
+            return Ok(None);
+        }
+
+        let regex = RegexBuilder::new(&alternatives.join("|"))
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| ScrapingError::ConfigError(format!("Invalid alert keywords: {}", e)))?;
+        Ok(Some(Self { regex }))
+    }
+
+    /// The first term in the message that matched, None if nothing did
+    pub fn matched_term<'a>(&self, message: &'a ChatMessage) -> Option<&'a str> {
+        self.regex.find(&message.message.text).map(|found| found.as_str())
+    }
+
+    /// Message text with every match passed through `bold`
+    pub fn highlight(&self, text: &str, bold: impl Fn(&str) -> String) -> String {
+        self.regex.replace_all(text, |found: &regex::Captures| bold(&found[0])).into_owned()
+    }
+}
+
+// `\b` only means something next to a word character, so `c++` still matches
+fn whole_word(keyword: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let start = if is_word(keyword.chars().next()) { r"\b" } else { "" };
+    let end = if is_word(keyword.chars().last()) { r"\b" } else { "" };
+    format!("{}{}{}", start, regex::escape(keyword), end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::fixtures::chat_message;
+
+    #[test]
+    fn test_keyword_match() {
+        let keywords = vec!["raid".to_string(), "Acme Energy".to_string(), "c++".to_string()];
+        let filter = KeywordFilter::new(&keywords, Some(r"give\s*away")).unwrap().unwrap();
+
+        let raid = chat_message("incoming RAID from the east");
+        assert_eq!(filter.matched_term(&raid), Some("RAID"));
+        assert_eq!(filter.highlight(&raid.message.text, |found| format!("**{}**", found)), "incoming **RAID** from the east");
+
+        let brand = chat_message("sponsored by acme energy, also a GIVE AWAY");
+        assert_eq!(filter.matched_term(&brand), Some("acme energy"));
+        assert_eq!(filter.highlight(&brand.message.text, |found| format!("**{}**", found)), "sponsored by **acme energy**, also a **GIVE AWAY**");
+
+        assert_eq!(filter.matched_term(&chat_message("learning C++ today")), Some("C++"));
+    }
+
+    #[test]
+    fn test_keyword_no_match() {
+        let filter = KeywordFilter::new(&["raid".to_string()], None).unwrap().unwrap();
+
+        // whole words only
+        assert_eq!(filter.matched_term(&chat_message("I'm afraid of the dark")), None);
+        assert_eq!(filter.matched_term(&chat_message("nice play")), None);
+
+        assert!(KeywordFilter::new(&[], None).unwrap().is_none());
+        assert!(KeywordFilter::new(&[" ".to_string()], None).unwrap().is_none());
+        assert!(KeywordFilter::new(&[], Some("(unclosed")).is_err());
+    }
+}
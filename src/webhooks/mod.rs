@@ -1,39 +1,428 @@
+pub mod dead_letter;
 pub mod discord;
+pub mod generic;
+pub mod keywords;
+pub mod slack;
 
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::config::{FileConfigManager, MonitorConfig};
 use crate::error::Result;
 use crate::parser::ChatMessage;
+use crate::webhooks::dead_letter::DeadLetterQueue;
+use crate::webhooks::discord::DiscordWebhook;
+use crate::webhooks::generic::{GenericWebhookProvider, DEFAULT_WEBHOOK_TEMPLATE};
+use crate::webhooks::keywords::KeywordFilter;
+use crate::webhooks::slack::SlackWebhookProvider;
+
+/// Pause before the first redelivery attempt, doubled for each one after
+const REDELIVERY_BACKOFF: Duration = Duration::from_millis(500);
 
+/// Most messages held back while batching, the oldest are dropped past this
+const MAX_PENDING_MESSAGES: usize = 1000;
+
+/// How often the dead-letter queue is replayed
+const DEAD_LETTER_REPLAY_INTERVAL: Duration = Duration::from_secs(60);
 
 #[async_trait::async_trait]
 pub trait WebhookProvider: Send + Sync {
+    /// Short name used in delivery reports and logs
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
     async fn send_message(&self, message: &ChatMessage) -> Result<()>;
+
+    /// Send a batch of messages, providers that support it can override this
+    /// to pack several messages into one request
+    async fn send_messages(&self, messages: &[ChatMessage]) -> Result<()> {
+        for message in messages {
+            self.send_message(message).await?;
+        }
+        Ok(())
+    }
+
+    /// Most messages `send_messages` packs into one post
+    fn messages_per_post(&self) -> usize {
+        1
+    }
+
+    /// `text` in bold in the provider's markup, used to highlight keyword
+    /// alerts. Plain text for providers without markup.
+    fn bold(&self, text: &str) -> String {
+        text.to_string()
+    }
+
     async fn send_alert(&self, level: &str, title: &str, message: &str) -> Result<()>;
 }
 
+/// Which providers accepted a message
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeliveryReport {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<String>,
+    pub dead_lettered: bool, // every provider failed and the message went to the dead-letter queue
+}
+
+impl DeliveryReport {
+    /// At least one provider has the message, or there was nobody to send it to
+    pub fn delivered(&self) -> bool {
+        !self.succeeded.is_empty() || self.failed.is_empty()
+    }
+}
+
+/// What a `retry_dead_letters` run did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeadLetterReplay {
+    pub delivered: usize,
+    pub requeued: usize,
+}
+
+/// Messages held back to go out together, and the posts already made this minute
+struct WebhookBatching {
+    interval: Duration,
+    max_per_minute: u32, // 0 = no limit
+    pending: Mutex<Vec<ChatMessage>>,
+    recent_posts: Mutex<VecDeque<Instant>>,
+}
+
+impl WebhookBatching {
+    /// Take up to `wanted` posts from what's left of this minute's limit,
+    /// returning how many may be made
+    async fn reserve_posts(&self, wanted: usize) -> usize {
+        if self.max_per_minute == 0 {
+            return wanted;
+        }
+        let mut recent_posts = self.recent_posts.lock().await;
+        while recent_posts.front().is_some_and(|posted| posted.elapsed() >= Duration::from_secs(60)) {
+            recent_posts.pop_front();
+        }
+        let granted = wanted.min((self.max_per_minute as usize).saturating_sub(recent_posts.len()));
+        recent_posts.extend(std::iter::repeat_n(Instant::now(), granted));
+        granted
+    }
+}
+
 pub struct WebhookManager {
     providers: Vec<Box<dyn WebhookProvider>>,
+    delivery_retries: u32,
+    dead_letters: Option<DeadLetterQueue>,
+    batching: Option<WebhookBatching>,
+    keyword_alerts: Option<KeywordFilter>,
+}
+
+impl Default for WebhookManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WebhookManager {
     pub fn new() -> Self {
         Self {
             providers: Vec::new(),
+            delivery_retries: 0,
+            dead_letters: None,
+            batching: None,
+            keyword_alerts: None,
+        }
+    }
+
+    /// Forward only messages `filter` matches, each as an alert with the
+    /// matched terms in bold, instead of every message. With batching on the
+    /// alerts count against its per-minute limit.
+    pub fn with_keyword_alerts(mut self, filter: KeywordFilter) -> Self {
+        self.keyword_alerts = Some(filter);
+        self
+    }
+
+    /// Hold queued messages for `interval` and send them as one combined post
+    /// per provider, making at most `max_per_minute` posts a minute (0 for no
+    /// limit). Past the limit messages wait for the next flush and keyword
+    /// alerts are dropped. Other alerts are never held back.
+    pub fn with_batching(mut self, interval: Duration, max_per_minute: u32) -> Self {
+        self.batching = Some(WebhookBatching {
+            interval,
+            max_per_minute,
+            pending: Mutex::new(Vec::new()),
+            recent_posts: Mutex::new(VecDeque::new()),
+        });
+        self
+    }
+
+    /// At-least-once mode: retry providers that fail a message up to `retries`
+    /// more times, and append it to a JSONL dead-letter file at `path` when
+    /// none of them took it, to be replayed with `retry_dead_letters`
+    pub fn with_dead_letter_queue(mut self, path: PathBuf, retries: u32) -> Self {
+        self.delivery_retries = retries;
+        self.dead_letters = Some(DeadLetterQueue::new(path));
+        self
+    }
+
+    pub fn dead_letter_queue(&self) -> Option<&DeadLetterQueue> {
+        self.dead_letters.as_ref()
+    }
+
+    /// Manager with a provider for every webhook configured under `[monitoring]`
+    pub fn from_config(monitoring: &MonitorConfig) -> Result<Self> {
+        let mut manager = Self::new();
+        if let Some(ref interval) = monitoring.webhook_batch_interval {
+            let interval = FileConfigManager::parse_time_to_duration(interval)?;
+            manager = manager.with_batching(interval, monitoring.webhook_max_per_minute);
+        }
+        if let Some(filter) = KeywordFilter::new(&monitoring.alert_keywords, monitoring.alert_pattern.as_deref())? {
+            manager = manager.with_keyword_alerts(filter);
+        }
+        if let Some(ref path) = monitoring.webhook_dead_letter_file {
+            manager = manager.with_dead_letter_queue(path.clone(), monitoring.webhook_delivery_retries);
+        }
+        if let Some(ref url) = monitoring.discord_webhook_url {
+            manager.add_provider(Box::new(DiscordWebhook::new(url.clone())?));
+        }
+        if let Some(ref url) = monitoring.slack_webhook_url {
+            manager.add_provider(Box::new(SlackWebhookProvider::new(url.clone())?));
+        }
+        if let Some(ref url) = monitoring.webhook_url {
+            let template = monitoring.webhook_template.as_deref().unwrap_or(DEFAULT_WEBHOOK_TEMPLATE);
+            manager.add_provider(Box::new(GenericWebhookProvider::new(url.clone(), template)?));
         }
+        Ok(manager)
+    }
+
+    pub fn has_providers(&self) -> bool {
+        !self.providers.is_empty()
     }
 
     pub fn add_provider(&mut self, provider: Box<dyn WebhookProvider>) {
         self.providers.push(provider);
     }
 
-    pub async fn send_message(&self, message: &ChatMessage) -> Result<()> {
-        for provider in &self.providers {
-            if let Err(e) = provider.send_message(message).await {
-                tracing::warn!("Webhook provider failed to send message: {}", e);
+    /// Send to every provider and report which ones accepted the message. In
+    /// dead-letter mode a message nobody accepted is queued on disk; failing to
+    /// queue it is the only error.
+    pub async fn send_message(&self, message: &ChatMessage) -> Result<DeliveryReport> {
+        let mut report = self.deliver(message).await;
+        if !report.delivered() {
+            if let Some(ref dead_letters) = self.dead_letters {
+                dead_letters.push(std::slice::from_ref(message)).await?;
+                report.dead_lettered = true;
+                tracing::warn!("No webhook provider accepted message {}, queued in {}", message.id, dead_letters.path().display());
+            }
+        }
+        Ok(report)
+    }
+
+    /// Send a batch to every provider, with the same retries and dead-letter
+    /// queueing as `send_message`
+    pub async fn send_messages(&self, messages: &[ChatMessage]) -> Result<DeliveryReport> {
+        if messages.is_empty() {
+            return Ok(DeliveryReport::default());
+        }
+        let mut report = self.deliver_batch(messages).await;
+        if !report.delivered() {
+            if let Some(ref dead_letters) = self.dead_letters {
+                dead_letters.push(messages).await?;
+                report.dead_lettered = true;
+                tracing::warn!("No webhook provider accepted a batch of {} messages, queued in {}", messages.len(), dead_letters.path().display());
+            }
+        }
+        Ok(report)
+    }
+
+    /// Send a message now, or hold it for the next batch when batching is on.
+    /// With keyword alerts set up only matching messages go out, as alerts.
+    pub async fn queue_message(&self, message: &ChatMessage) -> Result<()> {
+        if let Some(ref filter) = self.keyword_alerts {
+            let Some(term) = filter.matched_term(message) else {
+                return Ok(());
+            };
+            if let Some(ref batching) = self.batching {
+                if batching.reserve_posts(1).await == 0 {
+                    tracing::debug!("Webhook post limit reached, dropping keyword alert for message {}", message.id);
+                    return Ok(());
+                }
             }
+            let title = format!("\"{}\" in {}'s chat", term, message.streamer);
+            for provider in &self.providers {
+                let highlighted = filter.highlight(&message.message.text, |found| provider.bold(found));
+                let body = format!("{}: {}", message.user.display_name, highlighted);
+                if let Err(e) = provider.send_alert("keyword", &title, &body).await {
+                    tracing::warn!("Webhook provider failed to send alert: {}", e);
+                }
+            }
+            return Ok(());
+        }
+
+        let Some(ref batching) = self.batching else {
+            self.send_message(message).await?;
+            return Ok(());
+        };
+
+        let mut pending = batching.pending.lock().await;
+        if pending.len() >= MAX_PENDING_MESSAGES {
+            pending.remove(0);
+            tracing::warn!("Webhook batch is full, dropping the oldest queued message");
         }
+        pending.push(message.clone());
         Ok(())
     }
 
+    /// Messages waiting for the next batch
+    pub async fn pending_messages(&self) -> usize {
+        match self.batching {
+            Some(ref batching) => batching.pending.lock().await.len(),
+            None => 0,
+        }
+    }
+
+    /// Send as much of the queue as the per-minute post limit allows, counting
+    /// the posts each provider splits the batch into. The rest is kept for the
+    /// next flush. Returns how many messages went out.
+    pub async fn flush(&self) -> Result<usize> {
+        let Some(ref batching) = self.batching else {
+            return Ok(0);
+        };
+
+        let batch: Vec<ChatMessage> = {
+            let mut pending = batching.pending.lock().await;
+            if pending.is_empty() {
+                return Ok(0);
+            }
+
+            // the provider needing the most posts decides how many a batch costs
+            let per_post = self.providers.iter().map(|provider| provider.messages_per_post().max(1)).min().unwrap_or(1);
+            let posts = batching.reserve_posts(pending.len().div_ceil(per_post)).await;
+            if posts == 0 {
+                tracing::debug!("Webhook post limit reached, holding {} messages", pending.len());
+                return Ok(0);
+            }
+            let batch_len = pending.len().min(posts * per_post);
+            pending.drain(..batch_len).collect()
+        };
+
+        self.send_messages(&batch).await?;
+        Ok(batch.len())
+    }
+
+    /// Flush the batch every interval and replay the dead-letter queue every
+    /// minute until shutdown, then flush once more so nothing queued is lost.
+    /// None when neither batching nor the dead-letter queue is on.
+    pub fn start_background_delivery(self: &Arc<Self>, mut shutdown_rx: broadcast::Receiver<()>) -> Option<JoinHandle<()>> {
+        if self.batching.is_none() && self.dead_letters.is_none() {
+            return None;
+        }
+        let flush_interval = self.batching.as_ref().map_or(DEAD_LETTER_REPLAY_INTERVAL, |batching| batching.interval);
+        let manager = self.clone();
+        Some(tokio::spawn(async move {
+            let mut flush_ticker = tokio::time::interval(flush_interval);
+            let mut replay_ticker = tokio::time::interval(DEAD_LETTER_REPLAY_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = flush_ticker.tick() => {
+                        if let Err(e) = manager.flush().await {
+                            tracing::warn!("Failed to flush webhook batch: {}", e);
+                        }
+                    }
+                    _ = replay_ticker.tick() => {
+                        if let Err(e) = manager.retry_dead_letters().await {
+                            tracing::warn!("Failed to replay dead letters: {}", e);
+                        }
+                    }
+                }
+            }
+            if let Err(e) = manager.flush().await {
+                tracing::warn!("Failed to flush webhook batch on shutdown: {}", e);
+            }
+        }))
+    }
+
+    /// Replay the dead-letter queue, keeping messages that still can't be delivered
+    pub async fn retry_dead_letters(&self) -> Result<DeadLetterReplay> {
+        let Some(ref dead_letters) = self.dead_letters else {
+            return Ok(DeadLetterReplay::default());
+        };
+
+        // the file is only rewritten once every message has had its attempt
+        let mut replay = DeadLetterReplay::default();
+        let mut undelivered = Vec::new();
+        let (messages, read_len) = dead_letters.read().await?;
+        for message in messages {
+            if self.deliver(&message).await.delivered() {
+                replay.delivered += 1;
+            } else {
+                undelivered.push(message);
+            }
+        }
+        replay.requeued = undelivered.len();
+        dead_letters.replace_read(read_len, &undelivered).await?;
+
+        if replay.delivered > 0 || replay.requeued > 0 {
+            tracing::info!("Replayed dead letters: {} delivered, {} still failing", replay.delivered, replay.requeued);
+        }
+        Ok(replay)
+    }
+
+    /// One pass over the providers, retrying failures when redelivery is on
+    async fn deliver(&self, message: &ChatMessage) -> DeliveryReport {
+        let mut report = DeliveryReport::default();
+        for provider in &self.providers {
+            let mut attempt = 0;
+            loop {
+                match provider.send_message(message).await {
+                    Ok(()) => {
+                        report.succeeded.push(provider.name().to_string());
+                        break;
+                    }
+                    Err(e) if attempt < self.delivery_retries => {
+                        tracing::debug!("Webhook provider {} failed to send message, retrying: {}", provider.name(), e);
+                        tokio::time::sleep(REDELIVERY_BACKOFF * 2u32.pow(attempt)).await;
+                        attempt += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Webhook provider {} failed to send message: {}", provider.name(), e);
+                        report.failed.push(provider.name().to_string());
+                        break;
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    /// `deliver` for a batch, each provider retrying the whole batch
+    async fn deliver_batch(&self, messages: &[ChatMessage]) -> DeliveryReport {
+        let mut report = DeliveryReport::default();
+        for provider in &self.providers {
+            let mut attempt = 0;
+            loop {
+                match provider.send_messages(messages).await {
+                    Ok(()) => {
+                        report.succeeded.push(provider.name().to_string());
+                        break;
+                    }
+                    Err(e) if attempt < self.delivery_retries => {
+                        tracing::debug!("Webhook provider {} failed to send {} messages, retrying: {}", provider.name(), messages.len(), e);
+                        tokio::time::sleep(REDELIVERY_BACKOFF * 2u32.pow(attempt)).await;
+                        attempt += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Webhook provider {} failed to send {} messages: {}", provider.name(), messages.len(), e);
+                        report.failed.push(provider.name().to_string());
+                        break;
+                    }
+                }
+            }
+        }
+        report
+    }
+
     pub async fn send_alert(&self, level: &str, title: &str, message: &str) -> Result<()> {
         for provider in &self.providers {
             if let Err(e) = provider.send_alert(level, title, message).await {
@@ -42,4 +431,233 @@ impl WebhookManager {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::fixtures::chat_message;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    struct MockProvider {
+        name: &'static str,
+        online: Arc<AtomicBool>,
+        received: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl WebhookProvider for MockProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn send_message(&self, _message: &ChatMessage) -> Result<()> {
+            if !self.online.load(Ordering::SeqCst) {
+                return Err(crate::error::ScrapingError::NetworkError("offline".to_string()).into());
+            }
+            self.received.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn send_alert(&self, _level: &str, _title: &str, _message: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let online = Arc::new(AtomicBool::new(false));
+        let received = Arc::new(AtomicUsize::new(0));
+        let mut manager = WebhookManager::new()
+            .with_dead_letter_queue(temp_dir.path().join("dead_letters.jsonl"), 0);
+        manager.add_provider(Box::new(MockProvider { name: "mock", online: online.clone(), received: received.clone() }));
+
+        // every provider down: the message is reported and parked on disk
+        let report = manager.send_message(&chat_message("first")).await.unwrap();
+        assert_eq!(report, DeliveryReport { succeeded: vec![], failed: vec!["mock".to_string()], dead_lettered: true });
+        manager.send_message(&chat_message("second")).await.unwrap();
+        let queue = manager.dead_letter_queue().unwrap();
+        assert_eq!(queue.len().await.unwrap(), 2);
+
+        // still down: replaying keeps them queued
+        let replay = manager.retry_dead_letters().await.unwrap();
+        assert_eq!(replay, DeadLetterReplay { delivered: 0, requeued: 2 });
+        assert_eq!(queue.len().await.unwrap(), 2);
+
+        online.store(true, Ordering::SeqCst);
+        let replay = manager.retry_dead_letters().await.unwrap();
+        assert_eq!(replay, DeadLetterReplay { delivered: 2, requeued: 0 });
+        assert!(queue.is_empty().await.unwrap());
+        assert_eq!(received.load(Ordering::SeqCst), 2);
+
+        // one provider taking the message is enough
+        manager.add_provider(Box::new(MockProvider {
+            name: "down",
+            online: Arc::new(AtomicBool::new(false)),
+            received: Arc::new(AtomicUsize::new(0)),
+        }));
+        let report = manager.send_message(&chat_message("third")).await.unwrap();
+        assert_eq!(report.succeeded, vec!["mock"]);
+        assert_eq!(report.failed, vec!["down"]);
+        assert!(report.delivered() && !report.dead_lettered);
+        assert!(manager.dead_letter_queue().unwrap().is_empty().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_survive_an_interrupted_replay() {
+        let temp_dir = tempdir().unwrap();
+        let queue = DeadLetterQueue::new(temp_dir.path().join("dead_letters.jsonl"));
+        queue.push(&[chat_message("first"), chat_message("second")]).await.unwrap();
+
+        // reading for a replay leaves the file alone until the outcome is known
+        let (messages, read_len) = queue.read().await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(queue.len().await.unwrap(), 2);
+
+        // a message dead-lettered mid-replay is kept along with the one still failing
+        queue.push(&[chat_message("third")]).await.unwrap();
+        queue.replace_read(read_len, &messages[1..]).await.unwrap();
+        let (messages, _) = queue.read().await.unwrap();
+        let texts: Vec<_> = messages.iter().map(|message| message.message.text.as_str()).collect();
+        assert_eq!(texts, ["second", "third"]);
+    }
+
+    #[test]
+    fn test_dead_letter_queue_from_config() {
+        let mut monitoring = crate::config::Config::default().monitoring;
+        assert!(WebhookManager::from_config(&monitoring).unwrap().dead_letter_queue().is_none());
+
+        monitoring.webhook_dead_letter_file = Some(PathBuf::from("output/dead_letters.jsonl"));
+        monitoring.webhook_delivery_retries = 2;
+        let manager = WebhookManager::from_config(&monitoring).unwrap();
+        assert_eq!(manager.dead_letter_queue().unwrap().path(), PathBuf::from("output/dead_letters.jsonl"));
+        assert_eq!(manager.delivery_retries, 2);
+    }
+
+    #[tokio::test]
+    async fn test_batching_coalesces_bursts() {
+        use axum::{extract::State, routing::post, Json, Router};
+        use serde_json::Value;
+
+        // stand-in for discord, recording how many embeds each post carried
+        let posts = Arc::new(tokio::sync::Mutex::new(Vec::<usize>::new()));
+        let app = Router::new()
+            .route(
+                "/api/webhooks/1/abc",
+                post(|State(posts): State<Arc<tokio::sync::Mutex<Vec<usize>>>>, Json(body): Json<Value>| async move {
+                    posts.lock().await.push(body["embeds"].as_array().map_or(0, |embeds| embeds.len()));
+                }),
+            )
+            .with_state(posts.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/webhooks/1/abc", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let mut manager = WebhookManager::new().with_batching(Duration::from_secs(60), 4);
+        manager.add_provider(Box::new(DiscordWebhook::new(url).unwrap()));
+
+        for i in 0..25 {
+            manager.queue_message(&chat_message(&format!("burst {}", i))).await.unwrap();
+        }
+        assert_eq!(manager.pending_messages().await, 25);
+        assert!(posts.lock().await.is_empty(), "nothing goes out before the flush");
+
+        // 25 messages in 3 posts of up to 10 embeds
+        assert_eq!(manager.flush().await.unwrap(), 25);
+        assert_eq!(*posts.lock().await, vec![10, 10, 5]);
+        assert_eq!(manager.pending_messages().await, 0);
+
+        // one post left this minute, so only the first 10 of the next burst go out
+        for i in 0..15 {
+            manager.queue_message(&chat_message(&format!("later {}", i))).await.unwrap();
+        }
+        assert_eq!(manager.flush().await.unwrap(), 10);
+        assert_eq!(manager.pending_messages().await, 5);
+        assert_eq!(manager.flush().await.unwrap(), 0);
+        assert_eq!(posts.lock().await.len(), 4);
+
+        // alerts skip the queue and the limit
+        manager.send_alert("critical", "Agent crashed", "teststreamer").await.unwrap();
+        assert_eq!(posts.lock().await.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_dead_lettered() {
+        let temp_dir = tempdir().unwrap();
+        let online = Arc::new(AtomicBool::new(false));
+        let received = Arc::new(AtomicUsize::new(0));
+        let mut manager = WebhookManager::new()
+            .with_batching(Duration::from_secs(60), 0)
+            .with_dead_letter_queue(temp_dir.path().join("dead_letters.jsonl"), 1);
+        manager.add_provider(Box::new(MockProvider { name: "mock", online: online.clone(), received: received.clone() }));
+
+        for text in ["one", "two", "three"] {
+            manager.queue_message(&chat_message(text)).await.unwrap();
+        }
+        assert_eq!(manager.flush().await.unwrap(), 3);
+        assert_eq!(manager.pending_messages().await, 0);
+        assert_eq!(manager.dead_letter_queue().unwrap().len().await.unwrap(), 3);
+
+        online.store(true, Ordering::SeqCst);
+        let replay = manager.retry_dead_letters().await.unwrap();
+        assert_eq!(replay, DeadLetterReplay { delivered: 3, requeued: 0 });
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_keyword_alerts() {
+        struct AlertRecorder {
+            alerts: Arc<tokio::sync::Mutex<Vec<(String, String, String)>>>,
+            messages: Arc<AtomicUsize>,
+        }
+
+        #[async_trait::async_trait]
+        impl WebhookProvider for AlertRecorder {
+            async fn send_message(&self, _message: &ChatMessage) -> Result<()> {
+                self.messages.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+
+            async fn send_alert(&self, level: &str, title: &str, message: &str) -> Result<()> {
+                self.alerts.lock().await.push((level.to_string(), title.to_string(), message.to_string()));
+                Ok(())
+            }
+
+            fn bold(&self, text: &str) -> String {
+                format!("<b>{}</b>", text)
+            }
+        }
+
+        let alerts = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        let messages = Arc::new(AtomicUsize::new(0));
+        let filter = KeywordFilter::new(&["giveaway".to_string(), "raid".to_string()], None).unwrap().unwrap();
+        let mut manager = WebhookManager::new()
+            .with_batching(Duration::from_secs(60), 2)
+            .with_keyword_alerts(filter);
+        manager.add_provider(Box::new(AlertRecorder { alerts: alerts.clone(), messages: messages.clone() }));
+
+        // matching message: an alert with the term in the provider's bold
+        manager.queue_message(&chat_message("is the GIVEAWAY still on?")).await.unwrap();
+        let sent = alerts.lock().await.clone();
+        assert_eq!(sent.len(), 1);
+        let (level, title, body) = &sent[0];
+        assert_eq!(level, "keyword");
+        assert_eq!(title, "\"GIVEAWAY\" in teststreamer's chat");
+        assert_eq!(body, "User1: is the <b>GIVEAWAY</b> still on?");
+
+        // non-matching message: nothing at all
+        manager.queue_message(&chat_message("good game everyone")).await.unwrap();
+        assert_eq!(alerts.lock().await.len(), 1);
+        assert_eq!(messages.load(Ordering::SeqCst), 0, "keyword mode never forwards plain messages");
+
+        // alerts share the post limit, past it they're dropped
+        manager.queue_message(&chat_message("raid incoming")).await.unwrap();
+        manager.queue_message(&chat_message("another raid")).await.unwrap();
+        assert_eq!(alerts.lock().await.len(), 2);
+    }
+}
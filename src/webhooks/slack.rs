@@ -0,0 +1,243 @@
+use crate::error::{Result, ScrapingError};
+use crate::parser::ChatMessage;
+use crate::webhooks::WebhookProvider;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{debug, warn};
+
+/// Slack caps a message at 50 blocks, each chat message takes two
+const MAX_MESSAGES_PER_POST: usize = 25;
+
+/// Posts to a Slack incoming webhook using Block Kit, alerts go in a colored attachment
+pub struct SlackWebhookProvider {
+    client: Client,
+    webhook_url: String,
+    rate_limiter: tokio::sync::Semaphore,
+}
+
+impl SlackWebhookProvider {
+    pub fn new(webhook_url: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            webhook_url,
+            rate_limiter: tokio::sync::Semaphore::new(1), // incoming webhooks allow about one post per second
+        })
+    }
+
+    async fn send_webhook(&self, payload: Value) -> Result<()> {
+        let permit = self.rate_limiter.acquire().await
+            .map_err(|e| ScrapingError::NetworkError(format!("Rate limiter error: {}", e)))?;
+
+        let response = self.client
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| ScrapingError::NetworkError(format!("Failed to send webhook: {}", e)))?;
+
+        if response.status().is_success() {
+            debug!("Slack webhook sent successfully");
+        } else if response.status().as_u16() == 429 {
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(1);
+            warn!("Slack webhook rate limited, waiting {}s...", retry_after);
+            sleep(Duration::from_secs(retry_after)).await;
+            drop(permit);
+            return Box::pin(self.send_webhook(payload)).await;
+        } else {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ScrapingError::NetworkError(format!(
+                "Slack webhook failed with status {}: {}",
+                status, body
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn chat_blocks(message: &ChatMessage) -> Vec<Value> {
+        vec![
+            json!({
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!("*{}*: {}", escape(&message.user.display_name), escape(&message.message.text))
+                }
+            }),
+            json!({
+                "type": "context",
+                "elements": [{
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "<https://twitch.tv/{0}|twitch.tv/{0}> • <!date^{1}^{{time_secs}}|{2}>",
+                        message.streamer,
+                        message.timestamp.timestamp(),
+                        message.timestamp.to_rfc3339()
+                    )
+                }]
+            }),
+        ]
+    }
+
+    fn create_chat_payload(messages: &[ChatMessage]) -> Value {
+        let blocks: Vec<Value> = messages.iter().flat_map(Self::chat_blocks).collect();
+        // plain-text fallback for notifications and clients without block support
+        let text = match messages {
+            [message] => format!("{} in {}: {}", message.user.display_name, message.streamer, message.message.text),
+            _ => format!("{} chat messages", messages.len()),
+        };
+        json!({ "text": text, "blocks": blocks })
+    }
+
+    fn create_alert_payload(level: &str, title: &str, message: &str) -> Value {
+        let (color, emoji) = match level.to_lowercase().as_str() {
+            "critical" => ("#FF0000", ":rotating_light:"),
+            "warning" => ("#FFFF00", ":warning:"),
+            "rate_limited" => ("#FF8C00", ":hourglass_flowing_sand:"),
+            "info" => ("#0099FF", ":information_source:"),
+            "keyword" => ("#9146FF", ":bell:"),
+            _ => ("#808080", ":loudspeaker:"),
+        };
+
+        json!({
+            "text": format!("{} {}", emoji, title),
+            "attachments": [{
+                "color": color,
+                "blocks": [
+                    {
+                        "type": "header",
+                        "text": { "type": "plain_text", "text": format!("{} {}", emoji, title), "emoji": true }
+                    },
+                    {
+                        "type": "section",
+                        "text": { "type": "mrkdwn", "text": escape(message) }
+                    },
+                    {
+                        "type": "context",
+                        "elements": [{ "type": "mrkdwn", "text": "Twitch Chat Scraper Alert" }]
+                    }
+                ]
+            }]
+        })
+    }
+}
+
+// slack reads &, < and > as markup in mrkdwn text
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[async_trait::async_trait]
+impl WebhookProvider for SlackWebhookProvider {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn send_message(&self, message: &ChatMessage) -> Result<()> {
+        self.send_webhook(Self::create_chat_payload(std::slice::from_ref(message))).await
+    }
+
+    fn messages_per_post(&self) -> usize {
+        MAX_MESSAGES_PER_POST
+    }
+
+    // mrkdwn bold is a single asterisk, `**` would show up literally
+    fn bold(&self, text: &str) -> String {
+        format!("*{}*", text)
+    }
+
+    async fn send_messages(&self, messages: &[ChatMessage]) -> Result<()> {
+        for chunk in messages.chunks(MAX_MESSAGES_PER_POST) {
+            self.send_webhook(Self::create_chat_payload(chunk)).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_alert(&self, level: &str, title: &str, message: &str) -> Result<()> {
+        self.send_webhook(Self::create_alert_payload(level, title, message)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::fixtures::chat_message;
+    use axum::{extract::State, routing::post, Json, Router};
+    use chrono::{DateTime, Utc};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    /// Serve a stand-in for Slack that records every posted body, returning its url
+    async fn serve_mock_slack() -> (String, Arc<Mutex<Vec<Value>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route(
+                "/services/T000/B000/XXXX",
+                post(|State(received): State<Arc<Mutex<Vec<Value>>>>, Json(body): Json<Value>| async move {
+                    received.lock().await.push(body);
+                    "ok"
+                }),
+            )
+            .with_state(received.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{}/services/T000/B000/XXXX", addr), received)
+    }
+
+    #[tokio::test]
+    async fn test_slack_payload_shape() {
+        let (url, received) = serve_mock_slack().await;
+        let provider = SlackWebhookProvider::new(url).unwrap();
+
+        let mut message = chat_message("is this <good> & fine");
+        message.timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+        provider.send_message(&message).await.unwrap();
+        provider.send_alert("critical", "Agent crashed", "teststreamer stopped").await.unwrap();
+        provider.send_alert("something else", "Heads up", "odd level").await.unwrap();
+
+        let received = received.lock().await;
+        assert_eq!(received.len(), 3);
+
+        let chat = &received[0];
+        assert_eq!(chat["text"], "User1 in teststreamer: is this <good> & fine");
+        let blocks = chat["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["type"], "section");
+        assert_eq!(blocks[0]["text"]["type"], "mrkdwn");
+        assert_eq!(blocks[0]["text"]["text"], "*User1*: is this &lt;good&gt; &amp; fine");
+        assert_eq!(blocks[1]["type"], "context");
+        let context = blocks[1]["elements"][0]["text"].as_str().unwrap();
+        assert!(context.contains("twitch.tv/teststreamer"));
+        assert!(context.contains("<!date^1705314645^"));
+
+        let alert = &received[1];
+        assert!(alert.get("blocks").is_none());
+        let attachment = &alert["attachments"][0];
+        assert_eq!(attachment["color"], "#FF0000");
+        assert_eq!(attachment["blocks"][0]["type"], "header");
+        assert_eq!(attachment["blocks"][0]["text"]["text"], ":rotating_light: Agent crashed");
+        assert_eq!(attachment["blocks"][1]["text"]["text"], "teststreamer stopped");
+
+        assert_eq!(received[2]["attachments"][0]["color"], "#808080");
+
+        // keyword highlights use mrkdwn bold
+        assert_eq!(provider.bold("raid"), "*raid*");
+    }
+}
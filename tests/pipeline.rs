@@ -0,0 +1,103 @@
+//! End to end: config file on disk -> storage manager -> files and stats
+
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+use twitch_chat_scraper::config::{ConfigManager, FileConfigManager};
+use twitch_chat_scraper::parser::{ChatMessage, ChatUser, MessageContent, MessageFragment, StreamContext};
+
+// the crate's own test fixture, it takes the parser types imported above
+#[path = "../src/parser/fixtures.rs"]
+mod fixtures;
+use twitch_chat_scraper::storage::{read_messages, FileStorageManager, StorageManager};
+
+fn chat_message(streamer: &str, username: &str, text: &str, timestamp: DateTime<Utc>) -> ChatMessage {
+    let mut message = fixtures::chat_message(text);
+    message.streamer = streamer.to_string();
+    message.timestamp = timestamp;
+    message.user.username = username.to_string();
+    message.user.display_name = username.to_string();
+    message
+}
+
+fn write_config(dir: &Path, output_dir: &Path) -> std::path::PathBuf {
+    let config_path = dir.join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            r#"
+streamers = ["shroud", "pokimane"]
+
+[agents]
+max_concurrent = 2
+retry_attempts = 1
+delay_range = [1000, 2000]
+
+[output]
+format = "csv"
+directory = "{}"
+rotation_size = "10MB"
+rotation_time = "1h"
+
+[monitoring]
+tui_enabled = false
+api_port = 8080
+
+[stealth]
+randomize_user_agents = true
+simulate_human_behavior = false
+proxy_rotation = false
+fingerprint_randomization = false
+"#,
+            output_dir.display().to_string().replace('\\', "/")
+        ),
+    )
+    .unwrap();
+    config_path
+}
+
+#[tokio::test]
+async fn test_config_to_storage_to_stats() {
+    let temp_dir = tempdir().unwrap();
+    let output_dir = temp_dir.path().join("scraped");
+    let config_path = write_config(temp_dir.path(), &output_dir);
+
+    let config = FileConfigManager::new(config_path).load_config().await.unwrap();
+    assert_eq!(config.streamers, vec!["shroud", "pokimane"]);
+    assert_eq!(config.output.directory, output_dir);
+
+    let storage = FileStorageManager::from_config(&config.output).unwrap();
+    storage.setup_rotation().await.unwrap();
+
+    let timestamp = DateTime::parse_from_rfc3339("2024-01-15T10:30:45Z").unwrap().with_timezone(&Utc);
+    let mut messages = Vec::new();
+    for (i, streamer) in config.streamers.iter().enumerate() {
+        for j in 0..=i + 2 {
+            messages.push(chat_message(streamer, &format!("viewer{}", j), &format!("hello, {} #{}", streamer, j), timestamp));
+        }
+    }
+    storage.store_messages(messages.clone()).await.unwrap();
+
+    // <output>/<streamer>/<date>/chat_<date>_<time>.csv plus the day's count file
+    for streamer in &config.streamers {
+        let day_dir = output_dir.join(streamer).join("2024-01-15");
+        let data_file = day_dir.join("chat_2024-01-15_10-30-45.csv");
+        assert!(data_file.exists(), "missing {}", data_file.display());
+
+        let expected: Vec<&ChatMessage> = messages.iter().filter(|m| &m.streamer == streamer).collect();
+        let stored = read_messages(&data_file).unwrap();
+        assert_eq!(stored.len(), expected.len());
+        assert_eq!(stored.iter().map(|m| &m.id).collect::<Vec<_>>(), expected.iter().map(|m| &m.id).collect::<Vec<_>>());
+
+        let count = FileStorageManager::read_day_count(&day_dir).unwrap();
+        assert_eq!(count.message_count, expected.len() as u64);
+    }
+
+    let stats = storage.get_storage_stats().await.unwrap();
+    assert_eq!(stats.total_seen, messages.len() as u64);
+    assert_eq!(stats.total_stored, messages.len() as u64);
+    assert_eq!(stats.files_created, 2);
+    assert!(stats.disk_usage > 0);
+}